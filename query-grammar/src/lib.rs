@@ -1,15 +1,58 @@
 mod occur;
 mod query_grammar;
 mod user_input_ast;
-use combine::parser::Parser;
+use combine::easy;
+use combine::EasyParser;
 
 pub use crate::occur::Occur;
-use crate::query_grammar::parse_to_ast;
+use crate::query_grammar::{parse_to_ast, parse_to_ast_lenient};
 pub use crate::user_input_ast::{UserInputAST, UserInputBound, UserInputLeaf, UserInputLiteral};
 
-pub struct Error;
+/// Error returned when a query is not syntactically valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// Byte offset within the query at which parsing failed.
+    pub position: usize,
+    /// Descriptions of the tokens that were expected at `position`.
+    pub expected: Vec<String>,
+}
+
+/// A fragment of a query that could not be parsed,
+/// and was skipped by `parse_query_lenient`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LenientError {
+    /// Byte offset of the fragment within the query.
+    pub position: usize,
+    /// The skipped fragment.
+    pub fragment: String,
+}
 
+/// Parses a query.
+///
+/// The spans of the leaves of the returned AST are byte offsets within `query`.
 pub fn parse_query(query: &str) -> Result<UserInputAST, Error> {
-    let (user_input_ast, _remaining) = parse_to_ast().parse(query).map_err(|_| Error)?;
+    let (mut user_input_ast, _remaining) = parse_to_ast().easy_parse(query).map_err(|errors| {
+        let position = errors.position.translate_position(query);
+        let mut expected: Vec<String> = Vec::new();
+        for error in errors.errors {
+            if let easy::Error::Expected(info) = error {
+                let info = info.to_string();
+                if !expected.contains(&info) {
+                    expected.push(info);
+                }
+            }
+        }
+        Error { position, expected }
+    })?;
+    user_input_ast.translate_spans(query.as_ptr() as usize);
     Ok(user_input_ast)
 }
+
+/// Parses a query, skipping the parts of the query that are not valid.
+///
+/// Returns the best-effort AST, and the list of skipped fragments.
+pub fn parse_query_lenient(query: &str) -> (UserInputAST, Vec<LenientError>) {
+    let (mut user_input_ast, errors) = parse_to_ast_lenient(query);
+    user_input_ast.translate_spans(query.as_ptr() as usize);
+    (user_input_ast, errors)
+}
@@ -1,13 +1,22 @@
 use super::user_input_ast::{UserInputAST, UserInputBound, UserInputLeaf, UserInputLiteral};
+use crate::LenientError;
 use crate::Occur;
-use combine::error::StringStreamError;
+use combine::error::StreamError;
 use combine::parser::char::{char, digit, letter, space, spaces, string};
 use combine::parser::Parser;
+use combine::stream::{PointerOffset, Stream, StreamErrorFor};
 use combine::{
-    attempt, choice, eof, many, many1, one_of, optional, parser, satisfy, skip_many1, value,
+    attempt, choice, eof, many, many1, not_followed_by, one_of, optional, parser, position,
+    satisfy, skip_many1, value,
 };
 
-fn field<'a>() -> impl Parser<&'a str, Output = String> {
+/// A stream of `char` read from a `&str`, whose positions can be translated
+/// into byte offsets within the query.
+pub trait StrStream: Stream<Token = char, Position = PointerOffset<str>> {}
+
+impl<Input: Stream<Token = char, Position = PointerOffset<str>>> StrStream for Input {}
+
+fn field<Input: StrStream>() -> impl Parser<Input, Output = String> {
     (
         (letter().or(char('_'))),
         many(satisfy(|c: char| {
@@ -18,7 +27,7 @@ fn field<'a>() -> impl Parser<&'a str, Output = String> {
         .map(|(s1, s2): (char, String)| format!("{}{}", s1, s2))
 }
 
-fn word<'a>() -> impl Parser<&'a str, Output = String> {
+fn word<Input: StrStream>() -> impl Parser<Input, Output = String> {
     (
         satisfy(|c: char| {
             !c.is_whitespace()
@@ -30,17 +39,19 @@ fn word<'a>() -> impl Parser<&'a str, Output = String> {
     )
         .map(|(s1, s2): (char, String)| format!("{}{}", s1, s2))
         .and_then(|s: String| match s.as_str() {
-            "OR" | "AND " | "NOT" => Err(StringStreamError::UnexpectedParse),
+            "OR" | "AND " | "NOT" => Err(StreamErrorFor::<Input>::unexpected_static_message(
+                "reserved keyword",
+            )),
             _ => Ok(s),
         })
 }
 
-fn term_val<'a>() -> impl Parser<&'a str, Output = String> {
+fn term_val<Input: StrStream>() -> impl Parser<Input, Output = String> {
     let phrase = char('"').with(many1(satisfy(|c| c != '"'))).skip(char('"'));
     phrase.or(word())
 }
 
-fn term_query<'a>() -> impl Parser<&'a str, Output = UserInputLiteral> {
+fn term_query<Input: StrStream>() -> impl Parser<Input, Output = UserInputLiteral> {
     let term_val_with_field = negative_number().or(term_val());
     (field(), term_val_with_field).map(|(field_name, phrase)| UserInputLiteral {
         field_name: Some(field_name),
@@ -48,7 +59,7 @@ fn term_query<'a>() -> impl Parser<&'a str, Output = UserInputLiteral> {
     })
 }
 
-fn literal<'a>() -> impl Parser<&'a str, Output = UserInputLeaf> {
+fn literal<Input: StrStream>() -> impl Parser<Input, Output = UserInputLeaf> {
     let term_default_field = term_val().map(|phrase| UserInputLiteral {
         field_name: None,
         phrase,
@@ -58,7 +69,68 @@ fn literal<'a>() -> impl Parser<&'a str, Output = UserInputLeaf> {
         .map(UserInputLeaf::from)
 }
 
-fn negative_number<'a>() -> impl Parser<&'a str, Output = String> {
+/// Parses a query matching all documents having a value for a given field,
+/// in the form `field:*`.
+fn exists<Input: StrStream>() -> impl Parser<Input, Output = UserInputLeaf> {
+    (
+        field(),
+        char('*'),
+        not_followed_by(satisfy(|c: char| {
+            !c.is_whitespace() && ![')', '^'].contains(&c)
+        })),
+    )
+        .map(|(field, _, _)| UserInputLeaf::Exists { field })
+}
+
+/// Parses an unquoted term containing at least one `*` or `?` wildcard,
+/// optionally prefixed by a field, e.g. `title:foo*ba?`.
+fn wildcard<Input: StrStream>() -> impl Parser<Input, Output = UserInputLeaf> {
+    let pattern = word().and_then(|s: String| {
+        if s.contains(&['*', '?'][..]) {
+            Ok(s)
+        } else {
+            Err(StreamErrorFor::<Input>::expected_static_message(
+                "wildcard pattern",
+            ))
+        }
+    });
+    (optional(attempt(field())), pattern)
+        .map(|(field, pattern)| UserInputLeaf::Wildcard { field, pattern })
+}
+
+/// Splits a word of the form `term~` or `term~N` into the term and its edit distance.
+fn split_fuzzy_term(word: &str) -> Option<(String, Option<u8>)> {
+    if word.contains(&['*', '?'][..]) {
+        return None;
+    }
+    let tilde_pos = word.rfind('~')?;
+    let (term, distance) = (&word[..tilde_pos], &word[tilde_pos + 1..]);
+    if term.is_empty() {
+        return None;
+    }
+    let distance = if distance.is_empty() {
+        None
+    } else {
+        Some(distance.parse::<u8>().ok()?)
+    };
+    Some((term.to_string(), distance))
+}
+
+/// Parses an unquoted term followed by a `~` and an optional edit distance,
+/// optionally prefixed by a field, e.g. `title:roam~1` or `roam~`.
+fn fuzzy<Input: StrStream>() -> impl Parser<Input, Output = UserInputLeaf> {
+    let fuzzy_term = word().and_then(|s: String| {
+        split_fuzzy_term(&s)
+            .ok_or_else(|| StreamErrorFor::<Input>::expected_static_message("fuzzy term"))
+    });
+    (optional(attempt(field())), fuzzy_term).map(|(field, (term, distance))| UserInputLeaf::Fuzzy {
+        field,
+        term,
+        distance,
+    })
+}
+
+fn negative_number<Input: StrStream>() -> impl Parser<Input, Output = String> {
     (
         char('-'),
         many1(digit()),
@@ -73,7 +145,7 @@ fn negative_number<'a>() -> impl Parser<&'a str, Output = String> {
         })
 }
 
-fn spaces1<'a>() -> impl Parser<&'a str, Output = ()> {
+fn spaces1<Input: StrStream>() -> impl Parser<Input, Output = ()> {
     skip_many1(space())
 }
 
@@ -81,7 +153,7 @@ fn spaces1<'a>() -> impl Parser<&'a str, Output = ()> {
 /// Supports ranges like:
 /// [5 TO 10], {5 TO 10}, [* TO 10], [10 TO *], {10 TO *], >5, <=10
 /// [a TO *], [a TO c], [abc TO bcd}
-fn range<'a>() -> impl Parser<&'a str, Output = UserInputLeaf> {
+fn range<Input: StrStream>() -> impl Parser<Input, Output = UserInputLeaf> {
     let range_term_val = || {
         word()
             .or(negative_number())
@@ -156,33 +228,48 @@ fn negate(expr: UserInputAST) -> UserInputAST {
     expr.unary(Occur::MustNot)
 }
 
-fn leaf<'a>() -> impl Parser<&'a str, Output = UserInputAST> {
-    parser(|input| {
-        char('(')
+fn leaf<Input: StrStream>() -> impl Parser<Input, Output = UserInputAST> {
+    parser(|input: &mut Input| {
+        let leaf = char('(')
             .with(ast())
             .skip(char(')'))
-            .or(char('*').map(|_| UserInputAST::from(UserInputLeaf::All)))
+            .or(attempt(
+                char('*')
+                    .skip(not_followed_by(satisfy(|c: char| {
+                        !c.is_whitespace() && ![')', '^'].contains(&c)
+                    })))
+                    .map(|_| UserInputAST::from(UserInputLeaf::All)),
+            ))
             .or(attempt(
                 string("NOT").skip(spaces1()).with(leaf()).map(negate),
             ))
             .or(attempt(range().map(UserInputAST::from)))
-            .or(literal().map(UserInputAST::from))
+            .or(attempt(exists().map(UserInputAST::from)))
+            .or(attempt(fuzzy().map(UserInputAST::from)))
+            .or(attempt(wildcard().map(UserInputAST::from)))
+            .or(literal().map(UserInputAST::from));
+        (position(), leaf, position())
+            .map(
+                |(start, ast, end): (PointerOffset<str>, UserInputAST, PointerOffset<str>)| {
+                    ast.with_span(start.0..end.0)
+                },
+            )
             .parse_stream(input)
             .into_result()
     })
 }
 
-fn occur_symbol<'a>() -> impl Parser<&'a str, Output = Occur> {
+fn occur_symbol<Input: StrStream>() -> impl Parser<Input, Output = Occur> {
     char('-')
         .map(|_| Occur::MustNot)
         .or(char('+').map(|_| Occur::Must))
 }
 
-fn occur_leaf<'a>() -> impl Parser<&'a str, Output = (Option<Occur>, UserInputAST)> {
+fn occur_leaf<Input: StrStream>() -> impl Parser<Input, Output = (Option<Occur>, UserInputAST)> {
     (optional(occur_symbol()), boosted_leaf())
 }
 
-fn positive_float_number<'a>() -> impl Parser<&'a str, Output = f64> {
+fn positive_float_number<Input: StrStream>() -> impl Parser<Input, Output = f64> {
     (many1(digit()), optional((char('.'), many1(digit())))).map(
         |(int_part, decimal_part_opt): (String, Option<(char, String)>)| {
             let mut float_str = int_part;
@@ -195,11 +282,11 @@ fn positive_float_number<'a>() -> impl Parser<&'a str, Output = f64> {
     )
 }
 
-fn boost<'a>() -> impl Parser<&'a str, Output = f64> {
+fn boost<Input: StrStream>() -> impl Parser<Input, Output = f64> {
     (char('^'), positive_float_number()).map(|(_, boost)| boost)
 }
 
-fn boosted_leaf<'a>() -> impl Parser<&'a str, Output = UserInputAST> {
+fn boosted_leaf<Input: StrStream>() -> impl Parser<Input, Output = UserInputAST> {
     (leaf(), optional(boost())).map(|(leaf, boost_opt)| match boost_opt {
         Some(boost) if (boost - 1.0).abs() > std::f64::EPSILON => {
             UserInputAST::Boost(Box::new(leaf), boost)
@@ -214,7 +301,7 @@ enum BinaryOperand {
     And,
 }
 
-fn binary_operand<'a>() -> impl Parser<&'a str, Output = BinaryOperand> {
+fn binary_operand<Input: StrStream>() -> impl Parser<Input, Output = BinaryOperand> {
     string("AND")
         .with(value(BinaryOperand::And))
         .or(string("OR").with(value(BinaryOperand::Or)))
@@ -245,43 +332,144 @@ fn aggregate_binary_expressions(
     }
 }
 
-fn operand_leaf<'a>() -> impl Parser<&'a str, Output = (BinaryOperand, UserInputAST)> {
+fn aggregate_whitespace_separated_leaves(
+    subqueries: Vec<(Option<Occur>, UserInputAST)>,
+) -> UserInputAST {
+    if subqueries.len() == 1 {
+        let (occur_opt, ast) = subqueries.into_iter().next().unwrap();
+        match occur_opt.unwrap_or(Occur::Should) {
+            Occur::Must | Occur::Should => ast,
+            Occur::MustNot => UserInputAST::Clause(vec![(Some(Occur::MustNot), ast)]),
+        }
+    } else {
+        UserInputAST::Clause(subqueries.into_iter().collect())
+    }
+}
+
+fn operand_leaf<Input: StrStream>() -> impl Parser<Input, Output = (BinaryOperand, UserInputAST)> {
     (
         binary_operand().skip(spaces()),
         boosted_leaf().skip(spaces()),
     )
 }
 
-pub fn ast<'a>() -> impl Parser<&'a str, Output = UserInputAST> {
+pub fn ast<Input: StrStream>() -> impl Parser<Input, Output = UserInputAST> {
     let boolean_expr = (boosted_leaf().skip(spaces()), many1(operand_leaf()))
         .map(|(left, right)| aggregate_binary_expressions(left, right));
-    let whitespace_separated_leaves = many1(occur_leaf().skip(spaces().silent())).map(
-        |subqueries: Vec<(Option<Occur>, UserInputAST)>| {
-            if subqueries.len() == 1 {
-                let (occur_opt, ast) = subqueries.into_iter().next().unwrap();
-                match occur_opt.unwrap_or(Occur::Should) {
-                    Occur::Must | Occur::Should => ast,
-                    Occur::MustNot => UserInputAST::Clause(vec![(Some(Occur::MustNot), ast)]),
-                }
-            } else {
-                UserInputAST::Clause(subqueries.into_iter().collect())
-            }
-        },
-    );
+    let whitespace_separated_leaves =
+        many1(occur_leaf().skip(spaces().silent())).map(aggregate_whitespace_separated_leaves);
     let expr = attempt(boolean_expr).or(whitespace_separated_leaves);
     spaces().with(expr).skip(spaces())
 }
 
-pub fn parse_to_ast<'a>() -> impl Parser<&'a str, Output = UserInputAST> {
+pub fn parse_to_ast<Input: StrStream>() -> impl Parser<Input, Output = UserInputAST> {
     spaces()
         .with(optional(ast()).skip(eof()))
         .map(|opt_ast| opt_ast.unwrap_or_else(UserInputAST::empty_query))
 }
 
+/// Parses a query, recovering from syntax errors.
+///
+/// If the query is not valid, it is parsed as a sequence of clauses,
+/// optionally separated by `AND` / `OR` operators (a missing operator
+/// is considered to be an `OR`). The fragments of the query that cannot be
+/// parsed are skipped and reported as a `LenientError`. Their alphanumeric
+/// parts are searched as plain terms.
+pub fn parse_to_ast_lenient(query: &str) -> (UserInputAST, Vec<LenientError>) {
+    if let Ok((user_input_ast, _)) = parse_to_ast().parse(query) {
+        return (user_input_ast, Vec::new());
+    }
+    let mut errors: Vec<LenientError> = Vec::new();
+    let mut operands: Vec<(Option<BinaryOperand>, Option<Occur>, UserInputAST)> = Vec::new();
+    let mut pending_operator: Option<(BinaryOperand, LenientError)> = None;
+    let mut remaining = query.trim_start();
+    while !remaining.is_empty() {
+        let position = query.len() - remaining.len();
+        let operator = binary_operand()
+            .skip(not_followed_by(satisfy(|c: char| !c.is_whitespace())))
+            .parse(remaining);
+        if let Ok((operator, rest)) = operator {
+            let error = LenientError {
+                position,
+                fragment: remaining[..remaining.len() - rest.len()].to_string(),
+            };
+            if operands.is_empty() {
+                // An operator at the start of the query has no left operand.
+                errors.push(error);
+            } else if let Some((_, dangling_operator)) = pending_operator.replace((operator, error))
+            {
+                errors.push(dangling_operator);
+            }
+            remaining = rest.trim_start();
+            continue;
+        }
+        match occur_leaf().parse(remaining) {
+            Ok(((occur, ast), rest))
+                if rest.is_empty() || rest.starts_with(char::is_whitespace) =>
+            {
+                let operator = pending_operator.take().map(|(operator, _)| operator);
+                operands.push((operator, occur, ast));
+                remaining = rest.trim_start();
+            }
+            _ => {
+                let fragment_len = remaining
+                    .find(char::is_whitespace)
+                    .unwrap_or(remaining.len());
+                let (fragment, rest) = remaining.split_at(fragment_len);
+                if !["AND", "OR", "NOT"].contains(&fragment) {
+                    for word in fragment
+                        .split(|c: char| !c.is_alphanumeric())
+                        .filter(|word| !word.is_empty())
+                    {
+                        let operator = pending_operator.take().map(|(operator, _)| operator);
+                        let literal = UserInputLiteral {
+                            field_name: None,
+                            phrase: word.to_string(),
+                        };
+                        let start = word.as_ptr() as usize;
+                        let ast = UserInputAST::from(UserInputLeaf::from(literal))
+                            .with_span(start..start + word.len());
+                        operands.push((operator, None, ast));
+                    }
+                }
+                errors.push(LenientError {
+                    position,
+                    fragment: fragment.to_string(),
+                });
+                remaining = rest.trim_start();
+            }
+        }
+    }
+    if let Some((_, dangling_operator)) = pending_operator {
+        errors.push(dangling_operator);
+    }
+    let user_input_ast = if operands.is_empty() {
+        UserInputAST::empty_query()
+    } else if operands.iter().all(|(operator, _, _)| operator.is_none()) {
+        aggregate_whitespace_separated_leaves(
+            operands
+                .into_iter()
+                .map(|(_, occur, ast)| (occur, ast))
+                .collect(),
+        )
+    } else {
+        let mut operands = operands.into_iter().map(|(operator, occur, ast)| {
+            let ast = match occur {
+                Some(Occur::MustNot) => ast.unary(Occur::MustNot),
+                _ => ast,
+            };
+            (operator.unwrap_or(BinaryOperand::Or), ast)
+        });
+        let (_, left) = operands.next().unwrap();
+        aggregate_binary_expressions(left, operands.collect())
+    };
+    (user_input_ast, errors)
+}
+
 #[cfg(test)]
 mod test {
 
-    type TestParseResult = Result<(), StringStreamError>;
+    type TestParseResult = Result<(), combine::error::StringStreamError>;
 
     use super::*;
     use combine::parser::Parser;
@@ -521,6 +709,39 @@ mod test {
         test_parse_query_to_ast_helper("foo:[1 TO 5]", "foo:[\"1\" TO \"5\"]");
     }
 
+    #[test]
+    fn test_parse_query_exists() {
+        test_parse_query_to_ast_helper("abc:*", "abc:*");
+        test_parse_query_to_ast_helper("abc:* toto", "(*abc:* *\"toto\")");
+        test_parse_query_to_ast_helper("-abc:* +def:*^2", "(-abc:* +(def:*)^2)");
+        test_parse_query_to_ast_helper("(abc:*)", "abc:*");
+        test_parse_query_to_ast_helper("abc:*a", "abc:*a");
+    }
+
+    #[test]
+    fn test_parse_query_wildcard() {
+        test_parse_query_to_ast_helper("abc:a*b", "abc:a*b");
+        test_parse_query_to_ast_helper("a?c", "a?c");
+        test_parse_query_to_ast_helper("*bc", "*bc");
+        test_parse_query_to_ast_helper("ab* cd", "(*ab* *\"cd\")");
+        test_parse_query_to_ast_helper("-abc:ab?^2 d", "(-(abc:ab?)^2 *\"d\")");
+        test_parse_query_to_ast_helper("abc:\"a*b\"", "abc:\"a*b\"");
+        test_parse_query_to_ast_helper("* abc", "(** *\"abc\")");
+        test_parse_query_to_ast_helper("(*)", "*");
+    }
+
+    #[test]
+    fn test_parse_query_fuzzy() {
+        test_parse_query_to_ast_helper("roam~1", "\"roam\"~1");
+        test_parse_query_to_ast_helper("title:roam~", "title:\"roam\"~");
+        test_parse_query_to_ast_helper("title:roam~2^3", "(title:\"roam\"~2)^3");
+        test_parse_query_to_ast_helper("-roam~ b", "(-\"roam\"~ *\"b\")");
+        test_parse_query_to_ast_helper("a~b~1", "\"a~b\"~1");
+        test_parse_query_to_ast_helper("a~b", "\"a~b\"");
+        test_parse_query_to_ast_helper("~1", "\"~1\"");
+        test_parse_query_to_ast_helper("ro*m~1", "ro*m~1");
+    }
+
     #[test]
     fn test_parse_query_with_range() {
         test_parse_query_to_ast_helper("[1 TO 5]", "[\"1\" TO \"5\"]");
@@ -531,4 +752,81 @@ mod test {
         test_parse_query_to_ast_helper("foo:[1.1 TO *}", "foo:[\"1.1\" TO \"*\"}");
         test_is_parse_err("abc +    ");
     }
+
+    fn test_parse_query_lenient_helper(
+        query: &str,
+        expected: &str,
+        expected_errors: &[(usize, &str)],
+    ) {
+        let (user_input_ast, errors) = parse_to_ast_lenient(query);
+        assert_eq!(format!("{:?}", user_input_ast), expected);
+        let errors: Vec<(usize, &str)> = errors
+            .iter()
+            .map(|error| (error.position, error.fragment.as_str()))
+            .collect();
+        assert_eq!(&errors[..], expected_errors);
+    }
+
+    #[test]
+    fn test_parse_query_lenient() {
+        test_parse_query_lenient_helper("a b", "(*\"a\" *\"b\")", &[]);
+        test_parse_query_lenient_helper(
+            "\"unclosed phrase",
+            "(*\"unclosed\" *\"phrase\")",
+            &[(0, "\"unclosed")],
+        );
+        test_parse_query_lenient_helper("(a b", "(*\"a\" *\"b\")", &[(0, "(a")]);
+        test_parse_query_lenient_helper("-a) +b", "(*\"a\" +\"b\")", &[(0, "-a)")]);
+        test_parse_query_lenient_helper("a^ b", "(*\"a\" *\"b\")", &[(0, "a^")]);
+        test_parse_query_lenient_helper(
+            "title:[a TO",
+            "(*\"title\" *\"a\" *\"TO\")",
+            &[(0, "title:[a")],
+        );
+        test_parse_query_lenient_helper("OR a", "\"a\"", &[(0, "OR")]);
+        test_parse_query_lenient_helper("a AND OR b", "(?\"a\" ?\"b\")", &[(2, "AND")]);
+        test_parse_query_lenient_helper("a AND -b) c", "(?(+\"a\" +\"b\") ?\"c\")", &[(6, "-b)")]);
+        test_parse_query_lenient_helper(
+            "a AND (b OR c) d)",
+            "(?(+\"a\" +(?\"b\" ?\"c\")) ?\"d\")",
+            &[(15, "d)")],
+        );
+        test_parse_query_lenient_helper("NOT", "<emptyclause>", &[(0, "NOT")]);
+        test_parse_query_lenient_helper(" ))", "<emptyclause>", &[(1, "))")]);
+    }
+
+    fn leaf_spans(user_input_ast: &UserInputAST) -> Vec<std::ops::Range<usize>> {
+        match user_input_ast {
+            UserInputAST::Leaf(_, span) => vec![span.clone()],
+            UserInputAST::Clause(sub_queries) => sub_queries
+                .iter()
+                .flat_map(|(_, sub_query)| leaf_spans(sub_query))
+                .collect(),
+            UserInputAST::Boost(ast, _) => leaf_spans(ast),
+        }
+    }
+
+    #[test]
+    fn test_parse_query_spans() {
+        let user_input_ast = crate::parse_query("title:abc b^2 -(c NOT dé) [1 TO 2]").unwrap();
+        assert_eq!(
+            leaf_spans(&user_input_ast),
+            vec![0..9, 10..11, 16..17, 22..25, 27..35]
+        );
+        let (user_input_ast, _) = crate::parse_query_lenient("(a b) c)");
+        assert_eq!(leaf_spans(&user_input_ast), vec![1..2, 3..4, 6..7]);
+    }
+
+    #[test]
+    fn test_parse_query_error() {
+        let error = crate::parse_query("a^").err().unwrap();
+        assert_eq!(error.position, 2);
+        assert_eq!(error.expected, vec!["digit"]);
+        let error = crate::parse_query("a \"bc").err().unwrap();
+        assert_eq!(error.position, 5);
+        assert_eq!(error.expected, vec!["`\"`"]);
+        let error = crate::parse_query("(a b").err().unwrap();
+        assert_eq!(error.position, 4);
+        assert!(error.expected.contains(&"`)`".to_string()));
+    }
 }
@@ -1,5 +1,6 @@
 use std::fmt;
 use std::fmt::{Debug, Formatter};
+use std::ops::Range;
 
 use crate::Occur;
 
@@ -12,6 +13,18 @@ pub enum UserInputLeaf {
         lower: UserInputBound,
         upper: UserInputBound,
     },
+    Exists {
+        field: String,
+    },
+    Wildcard {
+        field: Option<String>,
+        pattern: String,
+    },
+    Fuzzy {
+        field: Option<String>,
+        term: String,
+        distance: Option<u8>,
+    },
 }
 
 impl Debug for UserInputLeaf {
@@ -32,6 +45,30 @@ impl Debug for UserInputLeaf {
                 Ok(())
             }
             UserInputLeaf::All => write!(formatter, "*"),
+            UserInputLeaf::Exists { ref field } => write!(formatter, "{}:*", field),
+            UserInputLeaf::Wildcard {
+                ref field,
+                ref pattern,
+            } => {
+                if let Some(ref field) = field {
+                    write!(formatter, "{}:", field)?;
+                }
+                write!(formatter, "{}", pattern)
+            }
+            UserInputLeaf::Fuzzy {
+                ref field,
+                ref term,
+                ref distance,
+            } => {
+                if let Some(ref field) = field {
+                    write!(formatter, "{}:", field)?;
+                }
+                write!(formatter, "\"{}\"~", term)?;
+                if let Some(distance) = distance {
+                    write!(formatter, "{}", distance)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -86,11 +123,36 @@ impl UserInputBound {
 
 pub enum UserInputAST {
     Clause(Vec<(Option<Occur>, UserInputAST)>),
-    Leaf(Box<UserInputLeaf>),
+    /// A leaf, along with the byte span of its clause within the query.
+    Leaf(Box<UserInputLeaf>, Range<usize>),
     Boost(Box<UserInputAST>, f64),
 }
 
 impl UserInputAST {
+    pub(crate) fn with_span(self, span: Range<usize>) -> UserInputAST {
+        match self {
+            UserInputAST::Leaf(leaf, _) => UserInputAST::Leaf(leaf, span),
+            ast => ast,
+        }
+    }
+
+    /// Spans are computed as the addresses of the parsed characters.
+    /// This translates them into byte offsets relative to `origin`,
+    /// the address of the beginning of the query.
+    pub(crate) fn translate_spans(&mut self, origin: usize) {
+        match self {
+            UserInputAST::Clause(sub_queries) => {
+                for (_, sub_query) in sub_queries {
+                    sub_query.translate_spans(origin);
+                }
+            }
+            UserInputAST::Leaf(_, span) => {
+                *span = span.start - origin..span.end - origin;
+            }
+            UserInputAST::Boost(ast, _) => ast.translate_spans(origin),
+        }
+    }
+
     pub fn unary(self, occur: Occur) -> UserInputAST {
         UserInputAST::Clause(vec![(Some(occur), self)])
     }
@@ -130,7 +192,7 @@ impl From<UserInputLiteral> for UserInputLeaf {
 
 impl From<UserInputLeaf> for UserInputAST {
     fn from(leaf: UserInputLeaf) -> UserInputAST {
-        UserInputAST::Leaf(Box::new(leaf))
+        UserInputAST::Leaf(Box::new(leaf), 0..0)
     }
 }
 
@@ -164,7 +226,7 @@ impl fmt::Debug for UserInputAST {
                 }
                 Ok(())
             }
-            UserInputAST::Leaf(ref subquery) => write!(formatter, "{:?}", subquery),
+            UserInputAST::Leaf(ref subquery, _) => write!(formatter, "{:?}", subquery),
             UserInputAST::Boost(ref leaf, boost) => write!(formatter, "({:?})^{}", leaf, boost),
         }
     }
@@ -0,0 +1,252 @@
+use crate::TantivyError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Named aggregations.
+///
+/// The results are returned under the same names.
+pub type Aggregations = BTreeMap<String, Aggregation>;
+
+/// An aggregation, either a bucket aggregation or a metric aggregation.
+///
+/// Aggregations are serialized into JSON objects, tagged by their `type`.
+/// For instance, `{"type": "avg", "field": "price"}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Aggregation {
+    /// Puts the documents into buckets defined by ranges of values.
+    Range(RangeAggregation),
+    /// Puts the documents into buckets of fixed width.
+    Histogram(HistogramAggregation),
+    /// Puts the documents into buckets of calendar intervals.
+    DateHistogram(DateHistogramAggregation),
+    /// Puts the documents into one bucket per distinct term.
+    Terms(TermsAggregation),
+    /// Minimum value of a field.
+    Min(MetricAggregation),
+    /// Maximum value of a field.
+    Max(MetricAggregation),
+    /// Average value of a field.
+    Avg(MetricAggregation),
+    /// Sum of the values of a field.
+    Sum(MetricAggregation),
+    /// Count, min, max, sum and average of the values of a field.
+    Stats(MetricAggregation),
+    /// Approximate percentiles of the values of a field.
+    Percentiles(PercentilesAggregation),
+}
+
+impl Aggregation {
+    /// Returns the name of the field the aggregation applies to.
+    pub fn field_name(&self) -> &str {
+        match self {
+            Aggregation::Range(range) => &range.field,
+            Aggregation::Histogram(histogram) => &histogram.field,
+            Aggregation::DateHistogram(date_histogram) => &date_histogram.field,
+            Aggregation::Terms(terms) => &terms.field,
+            Aggregation::Min(metric)
+            | Aggregation::Max(metric)
+            | Aggregation::Avg(metric)
+            | Aggregation::Sum(metric)
+            | Aggregation::Stats(metric) => &metric.field,
+            Aggregation::Percentiles(percentiles) => &percentiles.field,
+        }
+    }
+
+    /// Returns the sub-aggregations computed for each bucket.
+    ///
+    /// Metric aggregations do not have any sub-aggregation.
+    pub fn sub_aggregations(&self) -> Option<&Aggregations> {
+        match self {
+            Aggregation::Range(range) => Some(&range.sub_aggregations),
+            Aggregation::Histogram(histogram) => Some(&histogram.sub_aggregations),
+            Aggregation::DateHistogram(date_histogram) => Some(&date_histogram.sub_aggregations),
+            Aggregation::Terms(terms) => Some(&terms.sub_aggregations),
+            _ => None,
+        }
+    }
+
+    /// Checks the parameters of the aggregation which are not related to the schema.
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        match self {
+            Aggregation::Histogram(histogram)
+                if !(histogram.interval.is_finite() && histogram.interval > 0.0) =>
+            {
+                Err(TantivyError::InvalidArgument(format!(
+                    "The interval of a histogram must be strictly positive, got {}.",
+                    histogram.interval
+                )))
+            }
+            Aggregation::Percentiles(percentiles)
+                if percentiles
+                    .percents
+                    .iter()
+                    .any(|percent| !(0.0..=100.0).contains(percent)) =>
+            {
+                Err(TantivyError::InvalidArgument(format!(
+                    "Percents must be between 0 and 100, got {:?}.",
+                    percentiles.percents
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Range of values of a `RangeAggregation`.
+///
+/// `from` is inclusive, and `to` is exclusive. A missing bound is unbounded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RangeBound {
+    /// Inclusive lower bound.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<f64>,
+    /// Exclusive upper bound.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<f64>,
+}
+
+/// Puts the documents into one bucket per range of values of a numeric or date
+/// fast field.
+///
+/// Ranges may overlap: a document is counted in each of the ranges containing its value.
+/// Date values are expressed as timestamps, in seconds.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RangeAggregation {
+    /// Name of the field.
+    pub field: String,
+    /// Ranges of the buckets. Buckets are returned in the same order.
+    pub ranges: Vec<RangeBound>,
+    /// Aggregations computed for each bucket.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sub_aggregations: Aggregations,
+}
+
+/// Puts the documents into buckets of width `interval` of the values of
+/// a numeric fast field.
+///
+/// The key of the bucket of a value is `offset + interval * floor((value - offset) / interval)`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistogramAggregation {
+    /// Name of the field.
+    pub field: String,
+    /// Width of the buckets. Must be finite and strictly positive.
+    pub interval: f64,
+    /// Shifts the boundaries of the buckets.
+    #[serde(default)]
+    pub offset: f64,
+    /// Buckets with fewer documents are not returned.
+    ///
+    /// With the default, `0`, the empty buckets between the first and the last
+    /// non-empty buckets are returned as well.
+    ///
+    /// The aggregation fails if it would return more than 65,536 buckets.
+    #[serde(default)]
+    pub min_doc_count: u64,
+    /// Aggregations computed for each bucket.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sub_aggregations: Aggregations,
+}
+
+/// Calendar interval of a `DateHistogramAggregation`.
+///
+/// Intervals are computed in UTC. Weeks start on Monday.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateInterval {
+    /// One second.
+    Second,
+    /// One minute.
+    Minute,
+    /// One hour.
+    Hour,
+    /// One day.
+    Day,
+    /// One week, starting on Monday.
+    Week,
+    /// One calendar month.
+    Month,
+    /// Three calendar months, starting in January, April, July or October.
+    Quarter,
+    /// One calendar year.
+    Year,
+}
+
+/// Puts the documents into buckets of calendar intervals of the values of
+/// a date fast field.
+///
+/// The buckets are keyed by the RFC 3339 representation of their start.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DateHistogramAggregation {
+    /// Name of the field.
+    pub field: String,
+    /// Interval of the buckets.
+    pub interval: DateInterval,
+    /// Buckets with fewer documents are not returned.
+    ///
+    /// With the default, `0`, the empty buckets between the first and the last
+    /// non-empty buckets are returned as well.
+    ///
+    /// The aggregation fails if it would return more than 65,536 buckets.
+    #[serde(default)]
+    pub min_doc_count: u64,
+    /// Aggregations computed for each bucket.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sub_aggregations: Aggregations,
+}
+
+fn default_terms_size() -> usize {
+    10
+}
+
+fn default_terms_min_doc_count() -> u64 {
+    1
+}
+
+/// Puts the documents into one bucket per facet of a facet field.
+///
+/// Buckets are sorted by decreasing number of documents, then by facet.
+/// Only the `size` first buckets are returned.
+///
+/// The counts are exact: all of the facets are kept until the results of the segments
+/// are merged.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TermsAggregation {
+    /// Name of the field.
+    pub field: String,
+    /// Number of buckets returned. Defaults to 10.
+    #[serde(default = "default_terms_size")]
+    pub size: usize,
+    /// Buckets with fewer documents are not returned. Defaults to 1.
+    #[serde(default = "default_terms_min_doc_count")]
+    pub min_doc_count: u64,
+    /// Aggregations computed for each bucket.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sub_aggregations: Aggregations,
+}
+
+/// Metric aggregation over the values of a numeric or date fast field.
+///
+/// Date values are expressed as timestamps, in seconds.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetricAggregation {
+    /// Name of the field.
+    pub field: String,
+}
+
+fn default_percents() -> Vec<f64> {
+    vec![1.0, 5.0, 25.0, 50.0, 75.0, 95.0, 99.0]
+}
+
+/// Approximate percentiles of the values of a numeric or date fast field.
+///
+/// Percentiles are computed with a sketch with a relative accuracy of 1%.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PercentilesAggregation {
+    /// Name of the field.
+    pub field: String,
+    /// Requested percentiles, between 0 and 100.
+    /// Defaults to `[1, 5, 25, 50, 75, 95, 99]`.
+    #[serde(default = "default_percents")]
+    pub percents: Vec<f64>,
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Results of named aggregations, under the names of the request.
+pub type AggregationResults = BTreeMap<String, AggregationResult>;
+
+/// Result of an aggregation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AggregationResult {
+    /// Result of a bucket aggregation.
+    Buckets(BucketResult),
+    /// Result of a metric aggregation.
+    Metric(MetricResult),
+}
+
+/// Buckets returned by a bucket aggregation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BucketResult {
+    /// Buckets, in the order defined by the aggregation.
+    pub buckets: Vec<BucketEntry>,
+}
+
+/// Key of a bucket.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Key {
+    /// Numeric key, used by histograms.
+    F64(f64),
+    /// String key, used by ranges, date histograms and terms.
+    Str(String),
+}
+
+/// A bucket, and the results of its sub-aggregations.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BucketEntry {
+    /// Key of the bucket.
+    pub key: Key,
+    /// Inclusive lower bound of a range bucket.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<f64>,
+    /// Exclusive upper bound of a range bucket.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<f64>,
+    /// Number of documents in the bucket.
+    pub doc_count: u64,
+    /// Results of the sub-aggregations.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sub_aggregations: AggregationResults,
+}
+
+/// Result of a metric aggregation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MetricResult {
+    /// Result of a `stats` aggregation.
+    Stats(StatsResult),
+    /// Result of a `percentiles` aggregation.
+    Percentiles(PercentilesResult),
+    /// Result of a `min`, `max`, `avg` or `sum` aggregation.
+    ///
+    /// `min`, `max` and `avg` are `None` if there are no values.
+    Value {
+        /// Value of the metric.
+        value: Option<f64>,
+    },
+}
+
+/// Result of a `stats` aggregation.
+///
+/// `min`, `max` and `avg` are `None` if there are no values.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatsResult {
+    /// Number of values.
+    pub count: u64,
+    /// Minimum value.
+    pub min: Option<f64>,
+    /// Maximum value.
+    pub max: Option<f64>,
+    /// Sum of the values.
+    pub sum: f64,
+    /// Average value.
+    pub avg: Option<f64>,
+}
+
+/// Value of a percentile.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PercentileValue {
+    /// Requested percentile, between 0 and 100.
+    pub percent: f64,
+    /// Approximate value of the percentile, or `None` if there are no values.
+    pub value: Option<f64>,
+}
+
+/// Result of a `percentiles` aggregation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PercentilesResult {
+    /// Values of the percentiles, in the order of the request.
+    pub values: Vec<PercentileValue>,
+}
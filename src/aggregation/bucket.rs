@@ -0,0 +1,167 @@
+use crate::aggregation::agg_req::DateInterval;
+use chrono::{Datelike, TimeZone, Utc};
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+/// 1970-01-01 is a Thursday: weeks start 3 days before the epoch.
+const WEEK_OFFSET: i64 = -3 * DAY;
+
+/// Returns the key of a range bucket, `"from-to"`, with `*` standing for a missing bound.
+pub(crate) fn range_key(from: Option<f64>, to: Option<f64>) -> String {
+    let bound_str = |bound: Option<f64>| bound.map_or_else(|| "*".to_string(), |b| b.to_string());
+    format!("{}-{}", bound_str(from), bound_str(to))
+}
+
+/// Returns true iff `val` is in the range `[from, to)`.
+pub(crate) fn range_contains(from: Option<f64>, to: Option<f64>, val: f64) -> bool {
+    from.into_iter().all(|from| from <= val) && to.into_iter().all(|to| val < to)
+}
+
+/// Returns the index of the histogram bucket containing `val`.
+pub(crate) fn histogram_bucket(val: f64, interval: f64, offset: f64) -> i64 {
+    ((val - offset) / interval).floor() as i64
+}
+
+/// Returns the key of the histogram bucket of index `bucket`.
+pub(crate) fn histogram_key(bucket: i64, interval: f64, offset: f64) -> f64 {
+    bucket as f64 * interval + offset
+}
+
+fn fixed_width(interval: DateInterval) -> Option<i64> {
+    match interval {
+        DateInterval::Second => Some(1),
+        DateInterval::Minute => Some(MINUTE),
+        DateInterval::Hour => Some(HOUR),
+        DateInterval::Day => Some(DAY),
+        DateInterval::Week => Some(WEEK),
+        DateInterval::Month | DateInterval::Quarter | DateInterval::Year => None,
+    }
+}
+
+fn num_months(interval: DateInterval) -> u32 {
+    match interval {
+        DateInterval::Quarter => 3,
+        DateInterval::Year => 12,
+        _ => 1,
+    }
+}
+
+/// Timestamp of the start of the month `month0` (0-based, possibly above 11) of `year`.
+fn month_start(year: i32, month0: u32) -> i64 {
+    let year = year + (month0 / 12) as i32;
+    let month = month0 % 12 + 1;
+    Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0)
+        .single()
+        .map(|date_time| date_time.timestamp())
+        .unwrap_or(i64::MAX)
+}
+
+/// Returns the timestamp of the start of the date histogram bucket containing `timestamp`.
+pub(crate) fn date_bucket_start(timestamp: i64, interval: DateInterval) -> i64 {
+    if let Some(width) = fixed_width(interval) {
+        let offset = if interval == DateInterval::Week {
+            WEEK_OFFSET
+        } else {
+            0
+        };
+        return (timestamp - offset).div_euclid(width) * width + offset;
+    }
+    let date_time = if let Some(date_time) = Utc.timestamp_opt(timestamp, 0).single() {
+        date_time
+    } else {
+        return timestamp;
+    };
+    let num_months = num_months(interval);
+    let month0 = date_time.month0() / num_months * num_months;
+    month_start(date_time.year(), month0)
+}
+
+/// Returns the timestamp of the start of the date histogram bucket following
+/// the bucket starting at `bucket_start`.
+pub(crate) fn next_date_bucket_start(bucket_start: i64, interval: DateInterval) -> i64 {
+    if let Some(width) = fixed_width(interval) {
+        return bucket_start.saturating_add(width);
+    }
+    match Utc.timestamp_opt(bucket_start, 0).single() {
+        Some(date_time) => month_start(date_time.year(), date_time.month0() + num_months(interval)),
+        None => i64::MAX,
+    }
+}
+
+/// Returns the key of the date histogram bucket starting at `bucket_start`.
+pub(crate) fn date_key(bucket_start: i64) -> String {
+    Utc.timestamp_opt(bucket_start, 0)
+        .single()
+        .map(|date_time| date_time.to_rfc3339())
+        .unwrap_or_else(|| bucket_start.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{date_bucket_start, date_key, next_date_bucket_start, range_key};
+    use crate::aggregation::agg_req::DateInterval;
+    use chrono::{TimeZone, Utc};
+
+    fn timestamp(date: &str) -> i64 {
+        chrono::DateTime::parse_from_rfc3339(date)
+            .unwrap()
+            .with_timezone(&Utc)
+            .timestamp()
+    }
+
+    #[test]
+    fn test_range_key() {
+        assert_eq!(range_key(None, Some(10.0)), "*-10");
+        assert_eq!(range_key(Some(10.0), Some(20.5)), "10-20.5");
+        assert_eq!(range_key(Some(-1.0), None), "-1-*");
+    }
+
+    #[test]
+    fn test_date_buckets() {
+        let ts = timestamp("2020-08-19T13:45:12Z");
+        let bucket_key = |interval| date_key(date_bucket_start(ts, interval));
+        assert_eq!(
+            bucket_key(DateInterval::Second),
+            "2020-08-19T13:45:12+00:00"
+        );
+        assert_eq!(
+            bucket_key(DateInterval::Minute),
+            "2020-08-19T13:45:00+00:00"
+        );
+        assert_eq!(bucket_key(DateInterval::Hour), "2020-08-19T13:00:00+00:00");
+        assert_eq!(bucket_key(DateInterval::Day), "2020-08-19T00:00:00+00:00");
+        assert_eq!(bucket_key(DateInterval::Week), "2020-08-17T00:00:00+00:00");
+        assert_eq!(bucket_key(DateInterval::Month), "2020-08-01T00:00:00+00:00");
+        assert_eq!(
+            bucket_key(DateInterval::Quarter),
+            "2020-07-01T00:00:00+00:00"
+        );
+        assert_eq!(bucket_key(DateInterval::Year), "2020-01-01T00:00:00+00:00");
+        assert_eq!(
+            date_key(date_bucket_start(-1, DateInterval::Week)),
+            "1969-12-29T00:00:00+00:00"
+        );
+        let next_key =
+            |date: &str, interval| date_key(next_date_bucket_start(timestamp(date), interval));
+        assert_eq!(
+            next_key("2020-12-01T00:00:00Z", DateInterval::Month),
+            "2021-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            next_key("2020-10-01T00:00:00Z", DateInterval::Quarter),
+            "2021-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            next_key("2020-08-17T00:00:00Z", DateInterval::Week),
+            "2020-08-24T00:00:00+00:00"
+        );
+        assert_eq!(
+            Utc.timestamp_opt(next_date_bucket_start(0, DateInterval::Year), 0)
+                .unwrap()
+                .to_rfc3339(),
+            "1971-01-01T00:00:00+00:00"
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use crate::aggregation::agg_req::{Aggregation, Aggregations};
+use crate::aggregation::agg_result::{
+    AggregationResult, AggregationResults, BucketEntry, BucketResult, Key, MetricResult,
+    PercentileValue, PercentilesResult, StatsResult,
+};
+use crate::aggregation::bucket::{date_key, histogram_key, next_date_bucket_start, range_key};
+use crate::collector::{DDSketch, Stats};
+use crate::TantivyError;
+use serde::{Deserialize, Serialize};
+use std::collections::btree_map::Entry as BTreeEntry;
+use std::collections::hash_map::Entry as HashEntry;
+use std::collections::{BTreeMap, HashMap};
+
+/// Mergeable results of aggregations, before they are turned into
+/// their final form.
+///
+/// These results are what the segments collect. They can be serialized,
+/// merged with the results of other segments or other indexes with
+/// [`merge_fruits`](#method.merge_fruits), and finally converted into
+/// [`AggregationResults`](./type.AggregationResults.html) with
+/// [`into_final_result`](#method.into_final_result).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntermediateAggregationResults(BTreeMap<String, IntermediateAggregationResult>);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum IntermediateAggregationResult {
+    /// Buckets of a range aggregation, in the order of the ranges.
+    Range(Vec<IntermediateBucket>),
+    /// Buckets of a histogram, keyed by bucket index, or of a date histogram,
+    /// keyed by the timestamp of their start.
+    Histogram(BTreeMap<i64, IntermediateBucket>),
+    /// Buckets of a terms aggregation.
+    Terms(HashMap<String, IntermediateBucket>),
+    /// Statistics, for the `min`, `max`, `avg`, `sum` and `stats` aggregations.
+    Stats(Stats<f64>),
+    /// Sketch of the values, for the `percentiles` aggregation.
+    Percentiles(DDSketch),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct IntermediateBucket {
+    pub doc_count: u64,
+    pub sub_aggregations: IntermediateAggregationResults,
+}
+
+/// Maximum number of buckets of a histogram or a date histogram.
+pub(crate) const MAX_BUCKET_COUNT: usize = 65_536;
+
+fn stats_result(stats: &Stats<f64>) -> StatsResult {
+    StatsResult {
+        count: stats.count(),
+        min: stats.min(),
+        max: stats.max(),
+        sum: stats.sum(),
+        avg: stats.average(),
+    }
+}
+
+impl IntermediateBucket {
+    fn merge(&mut self, other: IntermediateBucket) -> crate::Result<()> {
+        self.doc_count += other.doc_count;
+        self.sub_aggregations.merge_fruits(other.sub_aggregations)
+    }
+
+    fn into_final(self, key: Key, sub_aggregations: &Aggregations) -> crate::Result<BucketEntry> {
+        Ok(BucketEntry {
+            key,
+            from: None,
+            to: None,
+            doc_count: self.doc_count,
+            sub_aggregations: self.sub_aggregations.into_final_result(sub_aggregations)?,
+        })
+    }
+}
+
+impl IntermediateAggregationResult {
+    /// Result of an aggregation that did not collect any document.
+    fn empty(aggregation: &Aggregation) -> IntermediateAggregationResult {
+        match aggregation {
+            Aggregation::Range(range) => IntermediateAggregationResult::Range(vec![
+                    IntermediateBucket::default();
+                    range.ranges.len()
+                ]),
+            Aggregation::Histogram(_) | Aggregation::DateHistogram(_) => {
+                IntermediateAggregationResult::Histogram(BTreeMap::new())
+            }
+            Aggregation::Terms(_) => IntermediateAggregationResult::Terms(HashMap::new()),
+            Aggregation::Min(_)
+            | Aggregation::Max(_)
+            | Aggregation::Avg(_)
+            | Aggregation::Sum(_)
+            | Aggregation::Stats(_) => IntermediateAggregationResult::Stats(Stats::default()),
+            Aggregation::Percentiles(_) => {
+                IntermediateAggregationResult::Percentiles(DDSketch::default())
+            }
+        }
+    }
+
+    fn merge(&mut self, other: IntermediateAggregationResult) -> crate::Result<()> {
+        match (self, other) {
+            (
+                IntermediateAggregationResult::Range(buckets),
+                IntermediateAggregationResult::Range(other_buckets),
+            ) if buckets.len() == other_buckets.len() => {
+                for (bucket, other_bucket) in buckets.iter_mut().zip(other_buckets) {
+                    bucket.merge(other_bucket)?;
+                }
+            }
+            (
+                IntermediateAggregationResult::Histogram(buckets),
+                IntermediateAggregationResult::Histogram(other_buckets),
+            ) => {
+                for (key, other_bucket) in other_buckets {
+                    match buckets.entry(key) {
+                        BTreeEntry::Vacant(entry) => {
+                            entry.insert(other_bucket);
+                        }
+                        BTreeEntry::Occupied(mut entry) => entry.get_mut().merge(other_bucket)?,
+                    }
+                }
+            }
+            (
+                IntermediateAggregationResult::Terms(buckets),
+                IntermediateAggregationResult::Terms(other_buckets),
+            ) => {
+                for (key, other_bucket) in other_buckets {
+                    match buckets.entry(key) {
+                        HashEntry::Vacant(entry) => {
+                            entry.insert(other_bucket);
+                        }
+                        HashEntry::Occupied(mut entry) => entry.get_mut().merge(other_bucket)?,
+                    }
+                }
+            }
+            (
+                IntermediateAggregationResult::Stats(stats),
+                IntermediateAggregationResult::Stats(other_stats),
+            ) => {
+                stats.merge(&other_stats);
+            }
+            (
+                IntermediateAggregationResult::Percentiles(sketch),
+                IntermediateAggregationResult::Percentiles(other_sketch),
+            ) => {
+                sketch.merge(&other_sketch);
+            }
+            _ => {
+                return Err(TantivyError::InvalidArgument(
+                    "Cannot merge the results of different aggregations".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn into_final(self, aggregation: &Aggregation) -> crate::Result<AggregationResult> {
+        let result = match (self, aggregation) {
+            (IntermediateAggregationResult::Range(buckets), Aggregation::Range(range))
+                if buckets.len() == range.ranges.len() =>
+            {
+                let buckets = buckets
+                    .into_iter()
+                    .zip(range.ranges.iter())
+                    .map(|(bucket, bound)| {
+                        let key = Key::Str(range_key(bound.from, bound.to));
+                        let mut bucket_entry = bucket.into_final(key, &range.sub_aggregations)?;
+                        bucket_entry.from = bound.from;
+                        bucket_entry.to = bound.to;
+                        Ok(bucket_entry)
+                    })
+                    .collect::<crate::Result<_>>()?;
+                AggregationResult::Buckets(BucketResult { buckets })
+            }
+            (
+                IntermediateAggregationResult::Histogram(buckets),
+                Aggregation::Histogram(histogram),
+            ) => {
+                let buckets = fill_gaps(buckets, histogram.min_doc_count, |bucket| bucket + 1)?
+                    .into_iter()
+                    .map(|(bucket, intermediate_bucket)| {
+                        let key =
+                            Key::F64(histogram_key(bucket, histogram.interval, histogram.offset));
+                        intermediate_bucket.into_final(key, &histogram.sub_aggregations)
+                    })
+                    .collect::<crate::Result<_>>()?;
+                AggregationResult::Buckets(BucketResult { buckets })
+            }
+            (
+                IntermediateAggregationResult::Histogram(buckets),
+                Aggregation::DateHistogram(date_histogram),
+            ) => {
+                let interval = date_histogram.interval;
+                let buckets = fill_gaps(buckets, date_histogram.min_doc_count, |bucket_start| {
+                    next_date_bucket_start(bucket_start, interval)
+                })?
+                .into_iter()
+                .map(|(bucket_start, intermediate_bucket)| {
+                    let key = Key::Str(date_key(bucket_start));
+                    intermediate_bucket.into_final(key, &date_histogram.sub_aggregations)
+                })
+                .collect::<crate::Result<_>>()?;
+                AggregationResult::Buckets(BucketResult { buckets })
+            }
+            (IntermediateAggregationResult::Terms(buckets), Aggregation::Terms(terms)) => {
+                let mut buckets: Vec<(String, IntermediateBucket)> = buckets
+                    .into_iter()
+                    .filter(|(_, bucket)| bucket.doc_count >= terms.min_doc_count)
+                    .collect();
+                buckets.sort_by(|(left_key, left), (right_key, right)| {
+                    right
+                        .doc_count
+                        .cmp(&left.doc_count)
+                        .then_with(|| left_key.cmp(right_key))
+                });
+                buckets.truncate(terms.size);
+                let buckets = buckets
+                    .into_iter()
+                    .map(|(key, bucket)| bucket.into_final(Key::Str(key), &terms.sub_aggregations))
+                    .collect::<crate::Result<_>>()?;
+                AggregationResult::Buckets(BucketResult { buckets })
+            }
+            (IntermediateAggregationResult::Stats(stats), Aggregation::Stats(_)) => {
+                AggregationResult::Metric(MetricResult::Stats(stats_result(&stats)))
+            }
+            (IntermediateAggregationResult::Stats(stats), metric_aggregation) => {
+                let stats = stats_result(&stats);
+                let value = match metric_aggregation {
+                    Aggregation::Min(_) => stats.min,
+                    Aggregation::Max(_) => stats.max,
+                    Aggregation::Avg(_) => stats.avg,
+                    Aggregation::Sum(_) => Some(stats.sum),
+                    _ => return Err(incompatible_result(aggregation)),
+                };
+                AggregationResult::Metric(MetricResult::Value { value })
+            }
+            (
+                IntermediateAggregationResult::Percentiles(sketch),
+                Aggregation::Percentiles(percentiles),
+            ) => {
+                let values = percentiles
+                    .percents
+                    .iter()
+                    .map(|&percent| PercentileValue {
+                        percent,
+                        value: sketch.percentile(percent),
+                    })
+                    .collect();
+                AggregationResult::Metric(MetricResult::Percentiles(PercentilesResult { values }))
+            }
+            _ => return Err(incompatible_result(aggregation)),
+        };
+        Ok(result)
+    }
+}
+
+fn incompatible_result(aggregation: &Aggregation) -> TantivyError {
+    TantivyError::InvalidArgument(format!(
+        "The intermediate result does not match the aggregation {:?}",
+        aggregation
+    ))
+}
+
+fn too_many_buckets() -> TantivyError {
+    TantivyError::InvalidArgument(format!(
+        "The aggregation would return more than {} buckets.",
+        MAX_BUCKET_COUNT
+    ))
+}
+
+/// Removes the buckets with fewer than `min_doc_count` documents.
+///
+/// If `min_doc_count` is 0, the missing buckets between the first and the last buckets
+/// are added, `next` returning the key of the bucket following a given bucket.
+///
+/// Returns an `InvalidArgument` error if there would be more than `MAX_BUCKET_COUNT` buckets.
+fn fill_gaps(
+    mut buckets: BTreeMap<i64, IntermediateBucket>,
+    min_doc_count: u64,
+    next: impl Fn(i64) -> i64,
+) -> crate::Result<BTreeMap<i64, IntermediateBucket>> {
+    if min_doc_count > 0 {
+        buckets.retain(|_, bucket| bucket.doc_count >= min_doc_count);
+    } else if let (Some(&first), Some(&last)) = (buckets.keys().next(), buckets.keys().next_back())
+    {
+        let mut bucket = first;
+        let mut num_buckets = 0;
+        while bucket < last {
+            num_buckets += 1;
+            if num_buckets > MAX_BUCKET_COUNT {
+                return Err(too_many_buckets());
+            }
+            buckets.entry(bucket).or_default();
+            bucket = next(bucket);
+        }
+    }
+    if buckets.len() > MAX_BUCKET_COUNT {
+        return Err(too_many_buckets());
+    }
+    Ok(buckets)
+}
+
+impl IntermediateAggregationResults {
+    pub(crate) fn push(&mut self, name: String, result: IntermediateAggregationResult) {
+        self.0.insert(name, result);
+    }
+
+    /// Merges the results of another segment, or of another index.
+    ///
+    /// Returns an `InvalidArgument` error if the results were not computed for
+    /// the same aggregations.
+    pub fn merge_fruits(&mut self, other: IntermediateAggregationResults) -> crate::Result<()> {
+        for (name, other_result) in other.0 {
+            match self.0.entry(name) {
+                BTreeEntry::Vacant(entry) => {
+                    entry.insert(other_result);
+                }
+                BTreeEntry::Occupied(mut entry) => entry.get_mut().merge(other_result)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the results into their final form, given the aggregations they were computed
+    /// for.
+    ///
+    /// Returns an `InvalidArgument` error if the results do not match the aggregations.
+    pub fn into_final_result(
+        mut self,
+        aggregations: &Aggregations,
+    ) -> crate::Result<AggregationResults> {
+        aggregations
+            .iter()
+            .map(|(name, aggregation)| {
+                aggregation.validate()?;
+                let result = self
+                    .0
+                    .remove(name)
+                    .unwrap_or_else(|| IntermediateAggregationResult::empty(aggregation));
+                Ok((name.clone(), result.into_final(aggregation)?))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,615 @@
+/*!
+
+# Aggregations
+
+Aggregations compute analytics over the documents matching a query,
+from the values of their fast fields.
+
+There are two families of aggregations:
+- bucket aggregations put the documents into buckets, and count them:
+  [ranges](./struct.RangeAggregation.html), [histograms](./struct.HistogramAggregation.html),
+  [date histograms](./struct.DateHistogramAggregation.html) and [terms](./struct.TermsAggregation.html).
+  Each bucket can in turn compute sub-aggregations over its documents.
+- metric aggregations compute a value over the documents:
+  `min`, `max`, `avg`, `sum`, `stats` and [`percentiles`](./struct.PercentilesAggregation.html).
+
+Aggregations are described by named [`Aggregations`](./type.Aggregations.html),
+and computed by an [`AggregationCollector`](./struct.AggregationCollector.html).
+Both the aggregations and their results can be serialized with serde.
+
+Documents without any value for the field are ignored by the aggregations on this field.
+Note that single-valued fast fields do not record the absence of a value: documents without
+a value are stored with the value `0`. If the field is also indexed, they are told apart from
+the documents actually having the value `0`. If the field is only a fast field,
+documents without a value are aggregated as if their value was `0`.
+
+```rust
+use tantivy::aggregation::{AggregationCollector, AggregationResult, Aggregations, MetricResult};
+use tantivy::query::AllQuery;
+use tantivy::schema::{Schema, FAST, TEXT};
+use tantivy::{doc, Index};
+
+# fn main() -> tantivy::Result<()> {
+let mut schema_builder = Schema::builder();
+let title = schema_builder.add_text_field("title", TEXT);
+let price = schema_builder.add_f64_field("price", FAST);
+let index = Index::create_in_ram(schema_builder.build());
+let mut index_writer = index.writer(3_000_000)?;
+index_writer.add_document(doc!(title => "Red Bike", price => 300.0));
+index_writer.add_document(doc!(title => "Blue Bike", price => 200.0));
+index_writer.add_document(doc!(title => "Blue Car", price => 20_000.0));
+index_writer.commit()?;
+let searcher = index.reader()?.searcher();
+
+let aggregations: Aggregations = serde_json::from_str(
+    r#"{
+        "price_ranges": {
+            "type": "range",
+            "field": "price",
+            "ranges": [{"to": 1000.0}, {"from": 1000.0}],
+            "sub_aggregations": {
+                "avg_price": {"type": "avg", "field": "price"}
+            }
+        }
+    }"#,
+)?;
+let results = searcher.search(&AllQuery, &AggregationCollector::from_aggs(aggregations))?;
+let buckets = match &results["price_ranges"] {
+    AggregationResult::Buckets(bucket_result) => &bucket_result.buckets,
+    _ => unreachable!(),
+};
+assert_eq!(buckets[0].doc_count, 2);
+assert_eq!(
+    buckets[0].sub_aggregations["avg_price"],
+    AggregationResult::Metric(MetricResult::Value { value: Some(250.0) })
+);
+# Ok(())
+# }
+```
+
+# Distributed aggregations
+
+The results of the segments are merged as [`IntermediateAggregationResults`](./struct.IntermediateAggregationResults.html)
+before being turned into their final form.
+When an index is split across several machines, the
+[`DistributedAggregationCollector`](./struct.DistributedAggregationCollector.html) returns these
+intermediate results, which can be serialized, merged, and eventually converted into final results.
+
+*/
+
+mod agg_req;
+mod agg_result;
+mod bucket;
+mod intermediate_agg_result;
+mod segment_agg_result;
+
+pub use self::agg_req::{
+    Aggregation, Aggregations, DateHistogramAggregation, DateInterval, HistogramAggregation,
+    MetricAggregation, PercentilesAggregation, RangeAggregation, RangeBound, TermsAggregation,
+};
+pub use self::agg_result::{
+    AggregationResult, AggregationResults, BucketEntry, BucketResult, Key, MetricResult,
+    PercentileValue, PercentilesResult, StatsResult,
+};
+pub use self::intermediate_agg_result::IntermediateAggregationResults;
+
+use self::segment_agg_result::{AggregationsWithAccessor, SegmentAggregationResults};
+use crate::collector::{Collector, SegmentCollector};
+use crate::{DocId, Score, SegmentLocalId, SegmentReader};
+
+/// Collector computing aggregations over the matching documents.
+///
+/// See the [module documentation](./index.html).
+pub struct AggregationCollector {
+    aggregations: Aggregations,
+}
+
+impl AggregationCollector {
+    /// Creates a collector computing the given aggregations.
+    pub fn from_aggs(aggregations: Aggregations) -> AggregationCollector {
+        AggregationCollector { aggregations }
+    }
+}
+
+/// Collector computing the intermediate results of aggregations.
+///
+/// Intermediate results can be merged with the results of other indexes with
+/// `IntermediateAggregationResults::merge_fruits`, and converted into
+/// their final form with `IntermediateAggregationResults::into_final_result`.
+pub struct DistributedAggregationCollector {
+    aggregations: Aggregations,
+}
+
+impl DistributedAggregationCollector {
+    /// Creates a collector computing the intermediate results of the given aggregations.
+    pub fn from_aggs(aggregations: Aggregations) -> DistributedAggregationCollector {
+        DistributedAggregationCollector { aggregations }
+    }
+}
+
+fn merge_intermediate_results(
+    segment_fruits: Vec<IntermediateAggregationResults>,
+) -> crate::Result<IntermediateAggregationResults> {
+    let mut merged_results = IntermediateAggregationResults::default();
+    for segment_fruit in segment_fruits {
+        merged_results.merge_fruits(segment_fruit)?;
+    }
+    Ok(merged_results)
+}
+
+impl Collector for AggregationCollector {
+    type Fruit = AggregationResults;
+
+    type Child = AggregationSegmentCollector;
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        AggregationSegmentCollector::open(&self.aggregations, segment_reader)
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<IntermediateAggregationResults>,
+    ) -> crate::Result<Self::Fruit> {
+        merge_intermediate_results(segment_fruits)?.into_final_result(&self.aggregations)
+    }
+}
+
+impl Collector for DistributedAggregationCollector {
+    type Fruit = IntermediateAggregationResults;
+
+    type Child = AggregationSegmentCollector;
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        AggregationSegmentCollector::open(&self.aggregations, segment_reader)
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<IntermediateAggregationResults>,
+    ) -> crate::Result<Self::Fruit> {
+        merge_intermediate_results(segment_fruits)
+    }
+}
+
+/// Segment collector associated to `AggregationCollector` and `DistributedAggregationCollector`.
+pub struct AggregationSegmentCollector {
+    aggregations: AggregationsWithAccessor,
+    results: SegmentAggregationResults,
+}
+
+impl AggregationSegmentCollector {
+    fn open(
+        aggregations: &Aggregations,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<AggregationSegmentCollector> {
+        let aggregations = AggregationsWithAccessor::open(aggregations, segment_reader)?;
+        let results = SegmentAggregationResults::new(&aggregations);
+        Ok(AggregationSegmentCollector {
+            aggregations,
+            results,
+        })
+    }
+}
+
+impl SegmentCollector for AggregationSegmentCollector {
+    type Fruit = IntermediateAggregationResults;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        self.results.collect(doc, &mut self.aggregations);
+    }
+
+    fn harvest(mut self) -> Self::Fruit {
+        self.results.into_intermediate(&mut self.aggregations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AggregationCollector, AggregationResult, AggregationResults, Aggregations, BucketEntry,
+        DistributedAggregationCollector, IntermediateAggregationResults, Key, MetricResult,
+    };
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{
+        Cardinality, Facet, IndexRecordOption, IntOptions, Schema, FAST, INDEXED, STRING,
+    };
+    use crate::{DateTime, Document, Index, Term};
+    use chrono::{TimeZone, Utc};
+
+    fn create_index(docs: &[(&str, f64, i64, &str)]) -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let price = schema_builder.add_f64_field("price", FAST);
+        let quantity = schema_builder.add_i64_field("quantity", FAST);
+        let date = schema_builder.add_date_field("date", FAST);
+        let category = schema_builder.add_facet_field("category");
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        for (i, &(color_str, price_val, quantity_val, category_str)) in docs.iter().enumerate() {
+            let date_val: DateTime = Utc
+                .timestamp_opt(1_600_000_000 + 86_400 * 40 * i as i64, 0)
+                .unwrap();
+            index_writer.add_document(doc!(
+                color => color_str,
+                price => price_val,
+                quantity => quantity_val,
+                date => date_val,
+                category => Facet::from(category_str),
+            ));
+            if i % 3 == 2 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.commit()?;
+        Ok(index)
+    }
+
+    fn test_docs() -> Vec<(&'static str, f64, i64, &'static str)> {
+        vec![
+            ("red", 10.0, 1, "/bike/road"),
+            ("blue", 25.0, -3, "/bike/city"),
+            ("red", 12.5, 2, "/car"),
+            ("green", 100.0, 5, "/bike/road"),
+            ("blue", 7.0, 0, "/car"),
+            ("red", 31.0, 4, "/bike/road"),
+            ("deleted", 1000.0, 100, "/car"),
+        ]
+    }
+
+    fn aggregations() -> Aggregations {
+        serde_json::from_str(
+            r#"{
+                "prices": {
+                    "type": "range",
+                    "field": "price",
+                    "ranges": [{"to": 20.0}, {"from": 10.0, "to": 30.0}, {"from": 30.0}],
+                    "sub_aggregations": {
+                        "quantities": {"type": "stats", "field": "quantity"}
+                    }
+                },
+                "price_histogram": {"type": "histogram", "field": "price", "interval": 20.0},
+                "quantity_histogram": {
+                    "type": "histogram",
+                    "field": "quantity",
+                    "interval": 2.0,
+                    "offset": 1.0,
+                    "min_doc_count": 1
+                },
+                "months": {"type": "date_histogram", "field": "date", "interval": "quarter"},
+                "categories": {
+                    "type": "terms",
+                    "field": "category",
+                    "size": 2,
+                    "sub_aggregations": {
+                        "max_price": {"type": "max", "field": "price"},
+                        "median_price": {"type": "percentiles", "field": "price", "percents": [50.0]}
+                    }
+                },
+                "min_price": {"type": "min", "field": "price"},
+                "sum_quantity": {"type": "sum", "field": "quantity"},
+                "avg_quantity": {"type": "avg", "field": "quantity"}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn buckets(result: &AggregationResult) -> &[BucketEntry] {
+        match result {
+            AggregationResult::Buckets(bucket_result) => &bucket_result.buckets,
+            AggregationResult::Metric(_) => panic!("Expected buckets, got {:?}", result),
+        }
+    }
+
+    fn value(result: &AggregationResult) -> Option<f64> {
+        match result {
+            AggregationResult::Metric(MetricResult::Value { value }) => *value,
+            _ => panic!("Expected a value, got {:?}", result),
+        }
+    }
+
+    fn keys_and_counts(result: &AggregationResult) -> Vec<(Key, u64)> {
+        buckets(result)
+            .iter()
+            .map(|bucket| (bucket.key.clone(), bucket.doc_count))
+            .collect()
+    }
+
+    fn search(index: &Index) -> crate::Result<AggregationResults> {
+        let searcher = index.reader()?.searcher();
+        searcher.search(&AllQuery, &AggregationCollector::from_aggs(aggregations()))
+    }
+
+    fn str_key(key: &str) -> Key {
+        Key::Str(key.to_string())
+    }
+
+    #[test]
+    fn test_aggregations() -> crate::Result<()> {
+        let index = create_index(&test_docs())?;
+        let mut index_writer = index.writer_for_tests()?;
+        let color = index.schema().get_field("color").unwrap();
+        index_writer.delete_term(Term::from_field_text(color, "deleted"));
+        index_writer.commit()?;
+        let results = search(&index)?;
+
+        let prices = &results["prices"];
+        assert_eq!(
+            keys_and_counts(prices),
+            vec![
+                (str_key("*-20"), 3),
+                (str_key("10-30"), 3),
+                (str_key("30-*"), 2)
+            ]
+        );
+        assert_eq!(buckets(prices)[1].from, Some(10.0));
+        assert_eq!(buckets(prices)[1].to, Some(30.0));
+        match &buckets(prices)[1].sub_aggregations["quantities"] {
+            AggregationResult::Metric(MetricResult::Stats(stats)) => {
+                assert_eq!(stats.count, 3);
+                assert_eq!(stats.min, Some(-3.0));
+                assert_eq!(stats.max, Some(2.0));
+                assert_eq!(stats.sum, 0.0);
+                assert_eq!(stats.avg, Some(0.0));
+            }
+            other => panic!("Expected stats, got {:?}", other),
+        }
+
+        // Empty buckets between 40 and 100 are returned.
+        assert_eq!(
+            keys_and_counts(&results["price_histogram"]),
+            vec![
+                (Key::F64(0.0), 3),
+                (Key::F64(20.0), 2),
+                (Key::F64(40.0), 0),
+                (Key::F64(60.0), 0),
+                (Key::F64(80.0), 0),
+                (Key::F64(100.0), 1),
+            ]
+        );
+        assert_eq!(
+            keys_and_counts(&results["quantity_histogram"]),
+            vec![
+                (Key::F64(-3.0), 1),
+                (Key::F64(-1.0), 1),
+                (Key::F64(1.0), 2),
+                (Key::F64(3.0), 1),
+                (Key::F64(5.0), 1),
+            ]
+        );
+
+        // Documents are 40 days apart, starting on 2020-09-13.
+        assert_eq!(
+            keys_and_counts(&results["months"]),
+            vec![
+                (str_key("2020-07-01T00:00:00+00:00"), 1),
+                (str_key("2020-10-01T00:00:00+00:00"), 2),
+                (str_key("2021-01-01T00:00:00+00:00"), 2),
+                (str_key("2021-04-01T00:00:00+00:00"), 1),
+            ]
+        );
+
+        let categories = &results["categories"];
+        assert_eq!(
+            keys_and_counts(categories),
+            vec![(str_key("/bike/road"), 3), (str_key("/car"), 2)]
+        );
+        let road_bikes = &buckets(categories)[0].sub_aggregations;
+        assert_eq!(value(&road_bikes["max_price"]), Some(100.0));
+        match &road_bikes["median_price"] {
+            AggregationResult::Metric(MetricResult::Percentiles(percentiles)) => {
+                assert_eq!(percentiles.values.len(), 1);
+                assert_eq!(percentiles.values[0].percent, 50.0);
+                let median = percentiles.values[0].value.unwrap();
+                assert!((median - 31.0).abs() < 0.31);
+            }
+            other => panic!("Expected percentiles, got {:?}", other),
+        }
+
+        assert_eq!(value(&results["min_price"]), Some(7.0));
+        assert_eq!(value(&results["sum_quantity"]), Some(9.0));
+        assert_eq!(value(&results["avg_quantity"]), Some(1.5));
+
+        // Results can be serialized.
+        let json = serde_json::to_string(&results).unwrap();
+        let deserialized: AggregationResults = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, results);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregations_no_match() -> crate::Result<()> {
+        let index = create_index(&test_docs())?;
+        let searcher = index.reader()?.searcher();
+        let color = index.schema().get_field("color").unwrap();
+        let query = TermQuery::new(
+            Term::from_field_text(color, "purple"),
+            IndexRecordOption::Basic,
+        );
+        let results = searcher.search(&query, &AggregationCollector::from_aggs(aggregations()))?;
+        assert_eq!(keys_and_counts(&results["prices"]).len(), 3);
+        assert!(buckets(&results["price_histogram"]).is_empty());
+        assert!(buckets(&results["categories"]).is_empty());
+        assert_eq!(value(&results["min_price"]), None);
+        assert_eq!(value(&results["sum_quantity"]), Some(0.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_distributed_aggregations() -> crate::Result<()> {
+        let docs = test_docs();
+        let single_index = create_index(&docs)?;
+        let left_index = create_index(&docs[..4])?;
+        let right_index = create_index(&docs[4..])?;
+        let collector = DistributedAggregationCollector::from_aggs(aggregations());
+        let mut intermediate_results = IntermediateAggregationResults::default();
+        for index in &[left_index, right_index] {
+            let shard_results = index.reader()?.searcher().search(&AllQuery, &collector)?;
+            // Intermediate results are sent over the wire.
+            let json = serde_json::to_string(&shard_results).unwrap();
+            intermediate_results.merge_fruits(serde_json::from_str(&json).unwrap())?;
+        }
+        let mut results = intermediate_results.into_final_result(&aggregations())?;
+        let mut expected = search(&single_index)?;
+        // Dates differ across the shards.
+        results.remove("months");
+        expected.remove("months");
+        assert_eq!(results, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregations_invalid() -> crate::Result<()> {
+        let index = create_index(&test_docs())?;
+        let searcher = index.reader()?.searcher();
+        for aggregation in &[
+            r#"{"type": "avg", "field": "missing"}"#,
+            r#"{"type": "avg", "field": "color"}"#,
+            r#"{"type": "avg", "field": "category"}"#,
+            r#"{"type": "terms", "field": "price"}"#,
+            r#"{"type": "date_histogram", "field": "price", "interval": "day"}"#,
+            r#"{"type": "histogram", "field": "price", "interval": 0.0}"#,
+            r#"{"type": "histogram", "field": "price", "interval": -5.0}"#,
+            r#"{"type": "percentiles", "field": "price", "percents": [101.0]}"#,
+        ] {
+            let aggregations: Aggregations =
+                serde_json::from_str(&format!(r#"{{"agg": {}}}"#, aggregation)).unwrap();
+            let collector = AggregationCollector::from_aggs(aggregations);
+            assert!(searcher.search(&AllQuery, &collector).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregations_invalid_intermediate_results() {
+        let aggregations: Aggregations = serde_json::from_str(
+            r#"{"agg": {"type": "histogram", "field": "price", "interval": -1.0}}"#,
+        )
+        .unwrap();
+        assert!(IntermediateAggregationResults::default()
+            .into_final_result(&aggregations)
+            .is_err());
+    }
+
+    #[test]
+    fn test_aggregations_too_many_buckets() -> crate::Result<()> {
+        let index = create_index(&test_docs())?;
+        let searcher = index.reader()?.searcher();
+        for aggregation in &[
+            r#"{"type": "histogram", "field": "price", "interval": 0.001}"#,
+            r#"{"type": "date_histogram", "field": "date", "interval": "second"}"#,
+        ] {
+            let aggregations: Aggregations =
+                serde_json::from_str(&format!(r#"{{"agg": {}}}"#, aggregation)).unwrap();
+            let collector = AggregationCollector::from_aggs(aggregations);
+            assert!(searcher.search(&AllQuery, &collector).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregations_missing_values() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let price = schema_builder.add_f64_field("price", FAST | INDEXED);
+        let sizes = schema_builder.add_u64_field(
+            "sizes",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer
+            .add_document(doc!(color => "red", price => 10.0, sizes => 1u64, sizes => 3u64));
+        index_writer.add_document(doc!(color => "blue", price => 30.0, sizes => 3u64));
+        let mut doc_without_values = Document::new();
+        doc_without_values.add_text(color, "green");
+        index_writer.add_document(doc_without_values);
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let aggregations: Aggregations = serde_json::from_str(
+            r#"{
+                "prices": {"type": "range", "field": "price", "ranges": [{"to": 20.0}]},
+                "price_histogram": {"type": "histogram", "field": "price", "interval": 10.0},
+                "avg_price": {"type": "avg", "field": "price"},
+                "min_price": {"type": "min", "field": "price"},
+                "size_histogram": {"type": "histogram", "field": "sizes", "interval": 2.0},
+                "size_stats": {"type": "stats", "field": "sizes"}
+            }"#,
+        )
+        .unwrap();
+        let results = searcher.search(&AllQuery, &AggregationCollector::from_aggs(aggregations))?;
+        assert_eq!(
+            keys_and_counts(&results["prices"]),
+            vec![(str_key("*-20"), 1)]
+        );
+        assert_eq!(
+            keys_and_counts(&results["price_histogram"]),
+            vec![
+                (Key::F64(10.0), 1),
+                (Key::F64(20.0), 0),
+                (Key::F64(30.0), 1)
+            ]
+        );
+        assert_eq!(value(&results["avg_price"]), Some(20.0));
+        assert_eq!(value(&results["min_price"]), Some(10.0));
+        // A document having several values in the same bucket is only counted once.
+        assert_eq!(
+            keys_and_counts(&results["size_histogram"]),
+            vec![(Key::F64(0.0), 1), (Key::F64(2.0), 2)]
+        );
+        match &results["size_stats"] {
+            AggregationResult::Metric(MetricResult::Stats(stats)) => {
+                assert_eq!(stats.count, 3);
+                assert_eq!(stats.sum, 7.0);
+                assert_eq!(stats.min, Some(1.0));
+                assert_eq!(stats.max, Some(3.0));
+            }
+            other => panic!("Expected stats, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregations_fast_field_only_missing_values() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let price = schema_builder.add_f64_field("price", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(color => "red", price => 10.0));
+        index_writer.add_document(doc!(color => "green"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let aggregations: Aggregations = serde_json::from_str(
+            r#"{
+                "price_histogram": {"type": "histogram", "field": "price", "interval": 10.0},
+                "min_price": {"type": "min", "field": "price"}
+            }"#,
+        )
+        .unwrap();
+        let results = searcher.search(&AllQuery, &AggregationCollector::from_aggs(aggregations))?;
+        // The fast field does not record the absence of a value:
+        // the document without a value is aggregated as if its value was 0.
+        assert_eq!(
+            keys_and_counts(&results["price_histogram"]),
+            vec![(Key::F64(0.0), 1), (Key::F64(10.0), 1)]
+        );
+        assert_eq!(value(&results["min_price"]), Some(0.0));
+        Ok(())
+    }
+}
@@ -0,0 +1,463 @@
+use crate::aggregation::agg_req::{Aggregation, Aggregations};
+use crate::aggregation::bucket::{date_bucket_start, histogram_bucket, range_contains};
+use crate::aggregation::intermediate_agg_result::{
+    IntermediateAggregationResult, IntermediateAggregationResults, IntermediateBucket,
+};
+use crate::collector::{DDSketch, Stats};
+use crate::common::BitSet;
+use crate::fastfield::{FacetReader, FastFieldReader, FastValue, MultiValueIntFastFieldReader};
+use crate::query::docs_with_value;
+use crate::schema::{Cardinality, Facet, FieldType};
+use crate::{DocId, SegmentReader, TantivyError};
+use std::collections::HashMap;
+
+/// Type of the values of a numeric fast field.
+#[derive(Clone, Copy, PartialEq)]
+enum NumericType {
+    U64,
+    I64,
+    F64,
+    Date,
+}
+
+/// Fast field reader of a numeric field.
+enum NumericReader {
+    /// Single-valued fast fields do not record whether a document has a value.
+    /// If the field is indexed, `docs_with_value` holds the documents having one.
+    /// Otherwise, all of the documents are considered to have a value.
+    SingleValue {
+        reader: FastFieldReader<u64>,
+        docs_with_value: Option<BitSet>,
+    },
+    MultiValues(MultiValueIntFastFieldReader<u64>),
+}
+
+/// Reads the values of the field an aggregation applies to.
+enum FieldAccessor {
+    Numeric {
+        reader: NumericReader,
+        numeric_type: NumericType,
+        vals: Vec<u64>,
+    },
+    Facet {
+        reader: Box<FacetReader>,
+        ords: Vec<u64>,
+    },
+}
+
+impl FieldAccessor {
+    /// Reads the values of a numeric field for the given document.
+    fn load_values(&mut self, doc: DocId) {
+        match self {
+            FieldAccessor::Numeric { reader, vals, .. } => match reader {
+                NumericReader::SingleValue {
+                    reader,
+                    docs_with_value,
+                } => {
+                    vals.clear();
+                    let has_value = docs_with_value
+                        .as_ref()
+                        .map(|docs_with_value| docs_with_value.contains(doc))
+                        .unwrap_or(true);
+                    if has_value {
+                        vals.push(reader.get(doc));
+                    }
+                }
+                NumericReader::MultiValues(reader) => reader.get_vals(doc, vals),
+            },
+            FieldAccessor::Facet { .. } => {
+                panic!("Facet fields do not have numeric values.");
+            }
+        }
+    }
+
+    /// Returns the values of a numeric field loaded by `load_values`, as `f64`.
+    ///
+    /// Dates are converted into timestamps, in seconds.
+    fn f64_values<'a>(&'a self) -> impl Iterator<Item = f64> + 'a {
+        let (vals, numeric_type) = match self {
+            FieldAccessor::Numeric {
+                vals, numeric_type, ..
+            } => (vals, *numeric_type),
+            FieldAccessor::Facet { .. } => {
+                panic!("Facet fields do not have numeric values.");
+            }
+        };
+        vals.iter().map(move |&val| match numeric_type {
+            NumericType::U64 => val as f64,
+            NumericType::I64 | NumericType::Date => i64::from_u64(val) as f64,
+            NumericType::F64 => f64::from_u64(val),
+        })
+    }
+
+    /// Returns the timestamps of a date field loaded by `load_values`.
+    fn timestamps<'a>(&'a self) -> impl Iterator<Item = i64> + 'a {
+        let vals = match self {
+            FieldAccessor::Numeric { vals, .. } => vals,
+            FieldAccessor::Facet { .. } => {
+                panic!("Facet fields do not have date values.");
+            }
+        };
+        vals.iter().map(|&val| i64::from_u64(val))
+    }
+}
+
+struct AggregationWithAccessor {
+    name: String,
+    aggregation: Aggregation,
+    accessor: FieldAccessor,
+    sub_aggregations: AggregationsWithAccessor,
+}
+
+/// Aggregations, along with the readers of the fields they apply to in a given segment.
+pub(crate) struct AggregationsWithAccessor(Vec<AggregationWithAccessor>);
+
+fn numeric_accessor(
+    aggregation: &Aggregation,
+    segment_reader: &SegmentReader,
+    accept_numbers: bool,
+) -> crate::Result<FieldAccessor> {
+    let field_name = aggregation.field_name();
+    let schema = segment_reader.schema();
+    let field = schema.get_field(field_name).ok_or_else(|| {
+        TantivyError::SchemaError(format!("Field {:?} does not exist.", field_name))
+    })?;
+    let (numeric_type, int_options) = match schema.get_field_entry(field).field_type() {
+        FieldType::U64(options) if accept_numbers => (NumericType::U64, options),
+        FieldType::I64(options) if accept_numbers => (NumericType::I64, options),
+        FieldType::F64(options) if accept_numbers => (NumericType::F64, options),
+        FieldType::Date(options) => (NumericType::Date, options),
+        _ => {
+            let expected = if accept_numbers {
+                "a numeric or date"
+            } else {
+                "a date"
+            };
+            return Err(TantivyError::SchemaError(format!(
+                "Field {:?} is not {} field, and cannot be aggregated by {:?}.",
+                field_name, expected, aggregation
+            )));
+        }
+    };
+    let not_a_fast_field =
+        || TantivyError::SchemaError(format!("Field {:?} is not a fast field.", field_name));
+    let reader = match int_options.get_fastfield_cardinality() {
+        Some(Cardinality::SingleValue) => {
+            let reader = segment_reader
+                .fast_fields()
+                .u64_lenient(field)
+                .ok_or_else(not_a_fast_field)?;
+            let docs_with_value = if int_options.is_indexed() {
+                Some(docs_with_value(segment_reader, field)?)
+            } else {
+                None
+            };
+            NumericReader::SingleValue {
+                reader,
+                docs_with_value,
+            }
+        }
+        Some(Cardinality::MultiValues) => NumericReader::MultiValues(
+            segment_reader
+                .fast_fields()
+                .u64s_lenient(field)
+                .ok_or_else(not_a_fast_field)?,
+        ),
+        None => return Err(not_a_fast_field()),
+    };
+    Ok(FieldAccessor::Numeric {
+        reader,
+        numeric_type,
+        vals: Vec::new(),
+    })
+}
+
+fn facet_accessor(
+    aggregation: &Aggregation,
+    segment_reader: &SegmentReader,
+) -> crate::Result<FieldAccessor> {
+    let field_name = aggregation.field_name();
+    let schema = segment_reader.schema();
+    let field = schema.get_field(field_name).ok_or_else(|| {
+        TantivyError::SchemaError(format!("Field {:?} does not exist.", field_name))
+    })?;
+    let is_fast_facet = match schema.get_field_entry(field).field_type() {
+        FieldType::HierarchicalFacet(ref options) => options.is_fast(),
+        _ => false,
+    };
+    if !is_fast_facet {
+        return Err(TantivyError::SchemaError(format!(
+            "Field {:?} is not a fast facet field, and cannot be aggregated by terms.",
+            field_name
+        )));
+    }
+    Ok(FieldAccessor::Facet {
+        reader: Box::new(segment_reader.facet_reader(field)?),
+        ords: Vec::new(),
+    })
+}
+
+impl AggregationsWithAccessor {
+    /// Opens the readers of the fields of the aggregations.
+    pub fn open(
+        aggregations: &Aggregations,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<AggregationsWithAccessor> {
+        let mut aggregations_with_accessor = Vec::with_capacity(aggregations.len());
+        for (name, aggregation) in aggregations {
+            aggregation.validate()?;
+            let accessor = match aggregation {
+                Aggregation::Terms(_) => facet_accessor(aggregation, segment_reader)?,
+                Aggregation::DateHistogram(_) => {
+                    numeric_accessor(aggregation, segment_reader, false)?
+                }
+                _ => numeric_accessor(aggregation, segment_reader, true)?,
+            };
+            let sub_aggregations = if let Some(sub_aggregations) = aggregation.sub_aggregations() {
+                AggregationsWithAccessor::open(sub_aggregations, segment_reader)?
+            } else {
+                AggregationsWithAccessor(Vec::new())
+            };
+            aggregations_with_accessor.push(AggregationWithAccessor {
+                name: name.clone(),
+                aggregation: aggregation.clone(),
+                accessor,
+                sub_aggregations,
+            });
+        }
+        Ok(AggregationsWithAccessor(aggregations_with_accessor))
+    }
+}
+
+struct SegmentBucket {
+    doc_count: u64,
+    sub_aggregations: SegmentAggregationResults,
+}
+
+impl SegmentBucket {
+    fn new(sub_aggregations: &AggregationsWithAccessor) -> SegmentBucket {
+        SegmentBucket {
+            doc_count: 0,
+            sub_aggregations: SegmentAggregationResults::new(sub_aggregations),
+        }
+    }
+
+    fn collect(&mut self, doc: DocId, sub_aggregations: &mut AggregationsWithAccessor) {
+        self.doc_count += 1;
+        self.sub_aggregations.collect(doc, sub_aggregations);
+    }
+
+    fn into_intermediate(
+        self,
+        sub_aggregations: &mut AggregationsWithAccessor,
+    ) -> IntermediateBucket {
+        IntermediateBucket {
+            doc_count: self.doc_count,
+            sub_aggregations: self.sub_aggregations.into_intermediate(sub_aggregations),
+        }
+    }
+}
+
+/// Collects a document in the histogram buckets of the given keys.
+///
+/// A document having several values in the same bucket is only counted once.
+fn collect_in_buckets(
+    buckets: &mut HashMap<i64, SegmentBucket>,
+    bucket_keys: &mut Vec<i64>,
+    doc: DocId,
+    sub_aggregations: &mut AggregationsWithAccessor,
+) {
+    bucket_keys.sort_unstable();
+    bucket_keys.dedup();
+    for &bucket_key in bucket_keys.iter() {
+        buckets
+            .entry(bucket_key)
+            .or_insert_with(|| SegmentBucket::new(sub_aggregations))
+            .collect(doc, sub_aggregations);
+    }
+}
+
+enum SegmentAggregationResult {
+    Range(Vec<SegmentBucket>),
+    /// Buckets keyed by bucket index, or by bucket start for date histograms.
+    Histogram(HashMap<i64, SegmentBucket>),
+    /// Buckets keyed by facet ordinal.
+    Terms(HashMap<u64, SegmentBucket>),
+    Stats(Stats<f64>),
+    Percentiles(DDSketch),
+}
+
+/// Results of aggregations being collected in a segment.
+///
+/// Each result is associated to the aggregation of the same index
+/// in `AggregationsWithAccessor`.
+pub(crate) struct SegmentAggregationResults(Vec<SegmentAggregationResult>);
+
+impl SegmentAggregationResults {
+    pub fn new(aggregations: &AggregationsWithAccessor) -> SegmentAggregationResults {
+        let results = aggregations
+            .0
+            .iter()
+            .map(
+                |aggregation_with_accessor| match &aggregation_with_accessor.aggregation {
+                    Aggregation::Range(range) => SegmentAggregationResult::Range(
+                        range
+                            .ranges
+                            .iter()
+                            .map(|_| {
+                                SegmentBucket::new(&aggregation_with_accessor.sub_aggregations)
+                            })
+                            .collect(),
+                    ),
+                    Aggregation::Histogram(_) | Aggregation::DateHistogram(_) => {
+                        SegmentAggregationResult::Histogram(HashMap::new())
+                    }
+                    Aggregation::Terms(_) => SegmentAggregationResult::Terms(HashMap::new()),
+                    Aggregation::Min(_)
+                    | Aggregation::Max(_)
+                    | Aggregation::Avg(_)
+                    | Aggregation::Sum(_)
+                    | Aggregation::Stats(_) => SegmentAggregationResult::Stats(Stats::default()),
+                    Aggregation::Percentiles(_) => {
+                        SegmentAggregationResult::Percentiles(DDSketch::default())
+                    }
+                },
+            )
+            .collect();
+        SegmentAggregationResults(results)
+    }
+
+    pub fn collect(&mut self, doc: DocId, aggregations: &mut AggregationsWithAccessor) {
+        let mut bucket_keys: Vec<i64> = Vec::new();
+        for (result, aggregation_with_accessor) in self.0.iter_mut().zip(aggregations.0.iter_mut())
+        {
+            let AggregationWithAccessor {
+                aggregation,
+                accessor,
+                sub_aggregations,
+                ..
+            } = aggregation_with_accessor;
+            if let FieldAccessor::Numeric { .. } = accessor {
+                accessor.load_values(doc);
+            }
+            match (result, &*aggregation) {
+                (SegmentAggregationResult::Range(buckets), Aggregation::Range(range)) => {
+                    for (bucket, bound) in buckets.iter_mut().zip(range.ranges.iter()) {
+                        if accessor
+                            .f64_values()
+                            .any(|val| range_contains(bound.from, bound.to, val))
+                        {
+                            bucket.collect(doc, sub_aggregations);
+                        }
+                    }
+                }
+                (
+                    SegmentAggregationResult::Histogram(buckets),
+                    Aggregation::Histogram(histogram),
+                ) => {
+                    bucket_keys.clear();
+                    bucket_keys.extend(
+                        accessor
+                            .f64_values()
+                            .filter(|val| !val.is_nan())
+                            .map(|val| histogram_bucket(val, histogram.interval, histogram.offset)),
+                    );
+                    collect_in_buckets(buckets, &mut bucket_keys, doc, sub_aggregations);
+                }
+                (
+                    SegmentAggregationResult::Histogram(buckets),
+                    Aggregation::DateHistogram(date_histogram),
+                ) => {
+                    bucket_keys.clear();
+                    bucket_keys.extend(
+                        accessor
+                            .timestamps()
+                            .map(|timestamp| date_bucket_start(timestamp, date_histogram.interval)),
+                    );
+                    collect_in_buckets(buckets, &mut bucket_keys, doc, sub_aggregations);
+                }
+                (SegmentAggregationResult::Terms(buckets), Aggregation::Terms(_)) => {
+                    if let FieldAccessor::Facet { reader, ords } = accessor {
+                        reader.facet_ords(doc, ords);
+                        ords.sort_unstable();
+                        ords.dedup();
+                        for &ord in ords.iter() {
+                            buckets
+                                .entry(ord)
+                                .or_insert_with(|| SegmentBucket::new(sub_aggregations))
+                                .collect(doc, sub_aggregations);
+                        }
+                    }
+                }
+                (SegmentAggregationResult::Stats(stats), _) => {
+                    for val in accessor.f64_values() {
+                        stats.collect(val);
+                    }
+                }
+                (SegmentAggregationResult::Percentiles(sketch), _) => {
+                    for val in accessor.f64_values() {
+                        sketch.add(val);
+                    }
+                }
+                _ => {
+                    unreachable!("Segment results are created from their aggregation.");
+                }
+            }
+        }
+    }
+
+    pub fn into_intermediate(
+        self,
+        aggregations: &mut AggregationsWithAccessor,
+    ) -> IntermediateAggregationResults {
+        let mut intermediate_results = IntermediateAggregationResults::default();
+        for (result, aggregation_with_accessor) in self.0.into_iter().zip(aggregations.0.iter_mut())
+        {
+            let sub_aggregations = &mut aggregation_with_accessor.sub_aggregations;
+            let intermediate_result = match result {
+                SegmentAggregationResult::Range(buckets) => IntermediateAggregationResult::Range(
+                    buckets
+                        .into_iter()
+                        .map(|bucket| bucket.into_intermediate(sub_aggregations))
+                        .collect(),
+                ),
+                SegmentAggregationResult::Histogram(buckets) => {
+                    IntermediateAggregationResult::Histogram(
+                        buckets
+                            .into_iter()
+                            .map(|(key, bucket)| (key, bucket.into_intermediate(sub_aggregations)))
+                            .collect(),
+                    )
+                }
+                SegmentAggregationResult::Terms(buckets) => {
+                    let reader = match &aggregation_with_accessor.accessor {
+                        FieldAccessor::Facet { reader, .. } => reader,
+                        FieldAccessor::Numeric { .. } => {
+                            unreachable!("Terms are only aggregated over facet fields.")
+                        }
+                    };
+                    let facet_dict = reader.facet_dict();
+                    let mut intermediate_buckets = HashMap::with_capacity(buckets.len());
+                    for (ord, bucket) in buckets {
+                        let mut facet_bytes = Vec::new();
+                        facet_dict.ord_to_term(ord, &mut facet_bytes);
+                        let facet = Facet::from_encoded(facet_bytes).unwrap();
+                        intermediate_buckets.insert(
+                            facet.to_string(),
+                            bucket.into_intermediate(sub_aggregations),
+                        );
+                    }
+                    IntermediateAggregationResult::Terms(intermediate_buckets)
+                }
+                SegmentAggregationResult::Stats(stats) => {
+                    IntermediateAggregationResult::Stats(stats)
+                }
+                SegmentAggregationResult::Percentiles(sketch) => {
+                    IntermediateAggregationResult::Percentiles(sketch)
+                }
+            };
+            intermediate_results.push(aggregation_with_accessor.name.clone(), intermediate_result);
+        }
+        intermediate_results
+    }
+}
@@ -0,0 +1,173 @@
+use super::stats_collector::FastFieldValuesReader;
+use super::Collector;
+use crate::collector::{HyperLogLog, SegmentCollector};
+use crate::fastfield::FastValue;
+use crate::schema::Field;
+use crate::{DocId, Score, SegmentLocalId, SegmentReader};
+use std::marker::PhantomData;
+
+/// Collector estimating the number of distinct values of a fast field
+/// over the matching documents.
+///
+/// The field can be a single-valued or a multi-valued `u64`, `i64`, `f64` or date
+/// fast field, of the type `T`.
+///
+/// The fruit is a [`HyperLogLog`](./struct.HyperLogLog.html) sketch.
+/// It is serializable, so that the sketches of several indexes can be merged.
+///
+/// ```rust
+/// use tantivy::collector::CardinalityCollector;
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let page = schema_builder.add_text_field("page", TEXT);
+/// let user_id = schema_builder.add_u64_field("user_id", FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(3_000_000)?;
+/// index_writer.add_document(doc!(page => "home", user_id => 1u64));
+/// index_writer.add_document(doc!(page => "home", user_id => 2u64));
+/// index_writer.add_document(doc!(page => "home", user_id => 1u64));
+/// index_writer.add_document(doc!(page => "about", user_id => 3u64));
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![page]).parse_query("home")?;
+/// let num_users = searcher.search(&query, &CardinalityCollector::<u64>::for_field(user_id))?;
+/// assert_eq!(num_users.estimate(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct CardinalityCollector<T: FastValue> {
+    field: Field,
+    _marker: PhantomData<T>,
+}
+
+impl<T: FastValue> CardinalityCollector<T> {
+    /// Creates a collector estimating the number of distinct values of `field`.
+    pub fn for_field(field: Field) -> CardinalityCollector<T> {
+        CardinalityCollector {
+            field,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: FastValue + 'static> Collector for CardinalityCollector<T> {
+    type Fruit = HyperLogLog;
+
+    type Child = CardinalitySegmentCollector;
+
+    fn for_segment(
+        &self,
+        _: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<CardinalitySegmentCollector> {
+        let reader = FastFieldValuesReader::open::<T>(segment_reader, self.field)?;
+        Ok(CardinalitySegmentCollector {
+            reader,
+            sketch: HyperLogLog::default(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_sketches: Vec<HyperLogLog>) -> crate::Result<HyperLogLog> {
+        let mut sketch = HyperLogLog::default();
+        for segment_sketch in &segment_sketches {
+            sketch.merge(segment_sketch);
+        }
+        Ok(sketch)
+    }
+}
+
+/// Segment collector associated to `CardinalityCollector`.
+pub struct CardinalitySegmentCollector {
+    reader: FastFieldValuesReader,
+    sketch: HyperLogLog,
+}
+
+impl SegmentCollector for CardinalitySegmentCollector {
+    type Fruit = HyperLogLog;
+
+    fn collect(&mut self, doc: DocId, _: Score) {
+        let sketch = &mut self.sketch;
+        // The `u64` representation of fast values is a bijection,
+        // so it can be hashed directly.
+        self.reader.for_each(doc, |val| sketch.add(val));
+    }
+
+    fn harvest(self) -> HyperLogLog {
+        self.sketch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CardinalityCollector;
+    use crate::collector::HyperLogLog;
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{
+        Cardinality, IndexRecordOption, IntOptions, Schema, FAST, INDEXED, STRING,
+    };
+    use crate::{Index, Term};
+
+    #[test]
+    fn test_cardinality_collector() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let user = schema_builder.add_i64_field("user", FAST | INDEXED);
+        let tags = schema_builder.add_u64_field(
+            "tags",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        for i in 0..1_000i64 {
+            let color_str = if i % 2 == 0 { "red" } else { "blue" };
+            index_writer.add_document(doc!(
+                color => color_str,
+                user => i % 100 - 50,
+                tags => (i % 7) as u64,
+                tags => 100u64,
+            ));
+            if i == 500 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.delete_term(Term::from_field_i64(user, 49));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+
+        let sketch = searcher.search(&AllQuery, &CardinalityCollector::<i64>::for_field(user))?;
+        assert_eq!(sketch.estimate(), 99);
+        let red = TermQuery::new(
+            Term::from_field_text(color, "red"),
+            IndexRecordOption::Basic,
+        );
+        let red_users = searcher.search(&red, &CardinalityCollector::<i64>::for_field(user))?;
+        assert_eq!(red_users.estimate(), 50);
+        let red_tags = searcher.search(&red, &CardinalityCollector::<u64>::for_field(tags))?;
+        assert_eq!(red_tags.estimate(), 8);
+
+        // Fruits of distinct searches, or indexes, can be merged.
+        let json = serde_json::to_string(&red_users)?;
+        let mut merged: HyperLogLog = serde_json::from_str(&json)?;
+        let blue = TermQuery::new(
+            Term::from_field_text(color, "blue"),
+            IndexRecordOption::Basic,
+        );
+        merged.merge(&searcher.search(&blue, &CardinalityCollector::<i64>::for_field(user))?);
+        assert_eq!(merged, sketch);
+
+        assert!(searcher
+            .search(&AllQuery, &CardinalityCollector::<u64>::for_field(user))
+            .is_err());
+        Ok(())
+    }
+}
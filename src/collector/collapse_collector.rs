@@ -0,0 +1,432 @@
+use super::top_collector::ComparableDoc;
+use super::Collector;
+use crate::collector::SegmentCollector;
+use crate::fastfield::{FacetReader, FastFieldReader};
+use crate::schema::{Facet, Field, FieldType};
+use crate::DocAddress;
+use crate::{DocId, Score, SegmentLocalId, SegmentReader, TantivyError};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Key of a group of documents, as collapsed by the
+/// [`CollapseCollector`](./struct.CollapseCollector.html).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GroupKey {
+    /// Value of a `u64` fast field.
+    U64(u64),
+    /// Facet of a facet field.
+    Facet(Facet),
+    /// Group of the documents without any facet.
+    Missing,
+}
+
+/// Group of documents sharing the same `GroupKey`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    key: GroupKey,
+    doc_count: u64,
+    top_docs: Vec<(Score, DocAddress)>,
+}
+
+impl Group {
+    /// Returns the key of the group.
+    pub fn key(&self) -> &GroupKey {
+        &self.key
+    }
+
+    /// Returns the number of matching documents in the group.
+    pub fn doc_count(&self) -> u64 {
+        self.doc_count
+    }
+
+    /// Returns the best hits of the group, sorted by decreasing score.
+    ///
+    /// The first hit is the best hit of the group.
+    pub fn top_docs(&self) -> &[(Score, DocAddress)] {
+        &self.top_docs[..]
+    }
+
+    /// Returns the score of the best hit of the group.
+    pub fn score(&self) -> Score {
+        self.top_docs[0].0
+    }
+
+    fn best_hit(&self) -> &(Score, DocAddress) {
+        &self.top_docs[0]
+    }
+}
+
+/// Sorts hits by decreasing score. Ties are resolved by ascending `DocAddress`.
+fn cmp_hits(left: &(Score, DocAddress), right: &(Score, DocAddress)) -> Ordering {
+    right
+        .0
+        .partial_cmp(&left.0)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| left.1.cmp(&right.1))
+}
+
+/// Collector collapsing the matching documents by the value of a fast field,
+/// and returning the top groups.
+///
+/// Groups are ranked by the score of their best hit, so that a result list does
+/// not get flooded with near-duplicates from the same group.
+/// Each group comes with its number of matching documents, and its
+/// `docs_per_group` best hits (by default, only its best hit).
+///
+/// The group key is taken from a single-valued `u64` fast field or from a facet field.
+/// Documents with several facets are grouped by the first of their facets, in
+/// lexicographical order, and documents without any facet are grouped together
+/// under `GroupKey::Missing`.
+///
+/// The number of documents of the groups are exact: the collector keeps track of
+/// all of the groups of the matching documents, which requires memory proportional
+/// to the number of such groups.
+///
+/// ```rust
+/// use tantivy::collector::{CollapseCollector, GroupKey};
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let site = schema_builder.add_u64_field("site", FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(3_000_000)?;
+/// index_writer.add_document(doc!(title => "Diary of a Muskrat", site => 1u64));
+/// index_writer.add_document(doc!(title => "A Muskrat Diary", site => 1u64));
+/// index_writer.add_document(doc!(title => "A Dairy Cow", site => 2u64));
+/// index_writer.add_document(doc!(title => "The Diary of a Young Girl", site => 3u64));
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("diary")?;
+/// let groups = searcher.search(&query, &CollapseCollector::by_field(site, 10))?;
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0].key(), &GroupKey::U64(1));
+/// assert_eq!(groups[0].doc_count(), 2);
+/// assert_eq!(groups[0].top_docs().len(), 1);
+/// assert_eq!(groups[1].key(), &GroupKey::U64(3));
+/// # Ok(())
+/// # }
+/// ```
+pub struct CollapseCollector {
+    field: Field,
+    num_groups: usize,
+    docs_per_group: usize,
+}
+
+impl CollapseCollector {
+    /// Creates a collector collapsing the documents by the value of `field`,
+    /// and returning the top `num_groups` groups.
+    ///
+    /// # Panics
+    /// The method panics if `num_groups` is 0
+    pub fn by_field(field: Field, num_groups: usize) -> CollapseCollector {
+        if num_groups < 1 {
+            panic!("Number of groups must be strictly greater than 0.");
+        }
+        CollapseCollector {
+            field,
+            num_groups,
+            docs_per_group: 1,
+        }
+    }
+
+    /// Sets the number of best hits returned within each group.
+    ///
+    /// # Panics
+    /// The method panics if `docs_per_group` is 0
+    pub fn with_docs_per_group(mut self, docs_per_group: usize) -> CollapseCollector {
+        if docs_per_group < 1 {
+            panic!("Number of documents per group must be strictly greater than 0.");
+        }
+        self.docs_per_group = docs_per_group;
+        self
+    }
+}
+
+impl Collector for CollapseCollector {
+    type Fruit = Vec<Group>;
+
+    type Child = CollapseSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<CollapseSegmentCollector> {
+        let field_entry = segment_reader.schema().get_field_entry(self.field);
+        let reader = match field_entry.field_type() {
+            FieldType::HierarchicalFacet(_) => GroupKeyReader::Facet {
+                reader: Box::new(segment_reader.facet_reader(self.field)?),
+                ords: Vec::new(),
+            },
+            FieldType::U64(_) => segment_reader
+                .fast_fields()
+                .u64(self.field)
+                .map(GroupKeyReader::U64)
+                .ok_or_else(|| {
+                    TantivyError::SchemaError(format!(
+                        "Field {:?} is not a single-valued u64 fast field.",
+                        field_entry.name()
+                    ))
+                })?,
+            _ => {
+                return Err(TantivyError::SchemaError(format!(
+                    "Field {:?} is neither a u64 fast field nor a facet field.",
+                    field_entry.name()
+                )));
+            }
+        };
+        Ok(CollapseSegmentCollector {
+            segment_local_id,
+            reader,
+            docs_per_group: self.docs_per_group,
+            groups: HashMap::new(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(&self, segment_groups: Vec<Vec<Group>>) -> crate::Result<Vec<Group>> {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut group_ords: HashMap<GroupKey, usize> = HashMap::new();
+        for segment_group in segment_groups.into_iter().flatten() {
+            if let Some(&group_ord) = group_ords.get(&segment_group.key) {
+                let group = &mut groups[group_ord];
+                group.doc_count += segment_group.doc_count;
+                group.top_docs.extend(segment_group.top_docs);
+                group.top_docs.sort_by(cmp_hits);
+                group.top_docs.truncate(self.docs_per_group);
+            } else {
+                group_ords.insert(segment_group.key.clone(), groups.len());
+                groups.push(segment_group);
+            }
+        }
+        groups.sort_by(|left, right| cmp_hits(left.best_hit(), right.best_hit()));
+        groups.truncate(self.num_groups);
+        Ok(groups)
+    }
+}
+
+enum GroupKeyReader {
+    U64(FastFieldReader<u64>),
+    Facet {
+        reader: Box<FacetReader>,
+        ords: Vec<u64>,
+    },
+}
+
+struct SegmentGroup {
+    doc_count: u64,
+    top_docs: BinaryHeap<ComparableDoc<Score, DocId>>,
+}
+
+/// Segment collector associated to `CollapseCollector`.
+pub struct CollapseSegmentCollector {
+    segment_local_id: SegmentLocalId,
+    reader: GroupKeyReader,
+    docs_per_group: usize,
+    // Groups by segment-local key: the value of the `u64` fast field,
+    // or the facet ordinal. `None` stands for documents without any facet.
+    groups: HashMap<Option<u64>, SegmentGroup>,
+}
+
+impl SegmentCollector for CollapseSegmentCollector {
+    type Fruit = Vec<Group>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        let key = match &mut self.reader {
+            GroupKeyReader::U64(reader) => Some(reader.get(doc)),
+            GroupKeyReader::Facet { reader, ords } => {
+                reader.facet_ords(doc, ords);
+                ords.iter().min().cloned()
+            }
+        };
+        let group = self.groups.entry(key).or_insert_with(|| SegmentGroup {
+            doc_count: 0,
+            top_docs: BinaryHeap::new(),
+        });
+        group.doc_count += 1;
+        if group.top_docs.len() < self.docs_per_group {
+            group.top_docs.push(ComparableDoc {
+                feature: score,
+                doc,
+            });
+        } else if let Some(mut head) = group.top_docs.peek_mut() {
+            if head.feature < score {
+                *head = ComparableDoc {
+                    feature: score,
+                    doc,
+                };
+            }
+        }
+    }
+
+    fn harvest(self) -> Vec<Group> {
+        let segment_local_id = self.segment_local_id;
+        let mut facet_bytes = Vec::new();
+        let mut groups = Vec::with_capacity(self.groups.len());
+        for (key, segment_group) in self.groups {
+            let key = match (&self.reader, key) {
+                (GroupKeyReader::U64(_), Some(val)) => GroupKey::U64(val),
+                (GroupKeyReader::Facet { reader, .. }, Some(ord)) => {
+                    facet_bytes.clear();
+                    reader.facet_dict().ord_to_term(ord, &mut facet_bytes);
+                    GroupKey::Facet(Facet::from_encoded(facet_bytes.clone()).unwrap())
+                }
+                (_, None) => GroupKey::Missing,
+            };
+            let top_docs = segment_group
+                .top_docs
+                .into_sorted_vec()
+                .into_iter()
+                .map(|comparable_doc| {
+                    (
+                        comparable_doc.feature,
+                        DocAddress(segment_local_id, comparable_doc.doc),
+                    )
+                })
+                .collect();
+            groups.push(Group {
+                key,
+                doc_count: segment_group.doc_count,
+                top_docs,
+            });
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CollapseCollector, GroupKey};
+    use crate::query::{AllQuery, QueryParser};
+    use crate::schema::{Facet, Schema, FAST, STORED, TEXT};
+    use crate::{DocAddress, Index};
+
+    #[test]
+    fn test_collapse_collector_u64() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT | STORED);
+        let family = schema_builder.add_u64_field("family", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        // The more `a` tokens, the higher the score.
+        index_writer.add_document(doc!(text => "a b b b b", family => 1u64));
+        index_writer.add_document(doc!(text => "a a b b b", family => 1u64));
+        index_writer.add_document(doc!(text => "a a a a a", family => 2u64));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "a a a b b", family => 1u64));
+        index_writer.add_document(doc!(text => "a a a a b", family => 3u64));
+        index_writer.add_document(doc!(text => "b b b b b", family => 3u64));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let query = QueryParser::for_index(&index, vec![text]).parse_query("a")?;
+
+        let groups = searcher.search(
+            &query,
+            &CollapseCollector::by_field(family, 2).with_docs_per_group(2),
+        )?;
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].key(), &GroupKey::U64(2));
+        assert_eq!(groups[0].doc_count(), 1);
+        assert_eq!(groups[0].top_docs().len(), 1);
+        assert_eq!(groups[1].key(), &GroupKey::U64(3));
+        assert_eq!(groups[1].doc_count(), 1);
+        assert!(groups[0].score() > groups[1].score());
+
+        let groups = searcher.search(
+            &query,
+            &CollapseCollector::by_field(family, 10).with_docs_per_group(2),
+        )?;
+        let keys: Vec<&GroupKey> = groups.iter().map(|group| group.key()).collect();
+        assert_eq!(
+            keys,
+            vec![&GroupKey::U64(2), &GroupKey::U64(3), &GroupKey::U64(1)]
+        );
+        // The hits of family 1 are spread over both segments.
+        assert_eq!(groups[2].doc_count(), 3);
+        let top_docs = groups[2].top_docs();
+        assert_eq!(top_docs.len(), 2);
+        assert!(top_docs[0].0 > top_docs[1].0);
+        let top_docs_texts: Vec<String> = top_docs
+            .iter()
+            .map(|&(_, doc_address)| {
+                searcher
+                    .doc(doc_address)
+                    .unwrap()
+                    .get_first(text)
+                    .unwrap()
+                    .text()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(top_docs_texts, vec!["a a a b b", "a a b b b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_collapse_collector_facet() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let site = schema_builder.add_facet_field("site");
+        let num = schema_builder.add_u64_field("num", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(site => Facet::from("/b"), num => 0u64));
+        index_writer.add_document(doc!(site => Facet::from("/a"), num => 1u64));
+        index_writer.add_document(doc!(num => 2u64));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(
+            site => Facet::from("/c"),
+            site => Facet::from("/a"),
+            num => 3u64
+        ));
+        index_writer.add_document(doc!(num => 4u64));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let groups = searcher.search(&AllQuery, &CollapseCollector::by_field(site, 10))?;
+        let mut summary: Vec<(GroupKey, u64)> = groups
+            .iter()
+            .map(|group| (group.key().clone(), group.doc_count()))
+            .collect();
+        summary.sort_by_key(|(_, doc_count)| *doc_count);
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0], (GroupKey::Facet(Facet::from("/b")), 1));
+        // The document with the facets `/a` and `/c` is grouped under `/a`.
+        assert!(summary[1..].contains(&(GroupKey::Facet(Facet::from("/a")), 2)));
+        assert!(summary[1..].contains(&(GroupKey::Missing, 2)));
+        // All of the documents have the same score: ties are resolved by `DocAddress`.
+        let best_hits: Vec<DocAddress> = groups.iter().map(|group| group.top_docs()[0].1).collect();
+        let mut sorted_best_hits = best_hits.clone();
+        sorted_best_hits.sort();
+        assert_eq!(best_hits, sorted_best_hits);
+        Ok(())
+    }
+
+    #[test]
+    fn test_collapse_collector_invalid_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let num = schema_builder.add_i64_field("num", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "a", num => 1i64));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert!(searcher
+            .search(&AllQuery, &CollapseCollector::by_field(text, 10))
+            .is_err());
+        assert!(searcher
+            .search(&AllQuery, &CollapseCollector::by_field(num, 10))
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use crate::collector::SortValue;
+use crate::common::{f64_to_u64, u64_to_f64, BinarySerializable, VInt};
+use crate::fastfield::FastValue;
+use crate::{DateTime, DocAddress, Score, TantivyError};
+use byteorder::{ByteOrder, LittleEndian};
+
+const CURSOR_VERSION: u8 = 1;
+
+const SCORE_CODE: u8 = 0;
+const U64_CODE: u8 = 1;
+const I64_CODE: u8 = 2;
+const F64_CODE: u8 = 3;
+const DATE_CODE: u8 = 4;
+
+fn invalid_token() -> TantivyError {
+    TantivyError::InvalidArgument("Invalid cursor token.".to_string())
+}
+
+impl SortValue {
+    /// Returns the type code and the `u64` representation of the value.
+    pub(crate) fn to_u64(&self) -> (u8, u64) {
+        match *self {
+            SortValue::Score(score) => (SCORE_CODE, f64_to_u64(f64::from(score))),
+            SortValue::U64(val) => (U64_CODE, val),
+            SortValue::I64(val) => (I64_CODE, val.to_u64()),
+            SortValue::F64(val) => (F64_CODE, val.to_u64()),
+            SortValue::Date(ref val) => (DATE_CODE, val.to_u64()),
+        }
+    }
+
+    fn from_u64(code: u8, val: u64) -> Option<SortValue> {
+        let sort_value = match code {
+            SCORE_CODE => SortValue::Score(u64_to_f64(val) as Score),
+            U64_CODE => SortValue::U64(val),
+            I64_CODE => SortValue::I64(i64::from_u64(val)),
+            F64_CODE => SortValue::F64(f64::from_u64(val)),
+            DATE_CODE => SortValue::Date(DateTime::from_u64(val)),
+            _ => return None,
+        };
+        Some(sort_value)
+    }
+}
+
+/// Position of a hit in a list of top documents, used to fetch the next page
+/// of results with `TopDocs::search_after`.
+///
+/// A cursor is made of the sort values and the `DocAddress` of the last hit
+/// of a page. It can be serialized into an opaque token with `to_token`,
+/// and read back with `from_token`.
+///
+/// `DocAddress` depends on the segments of the searcher: a cursor is only
+/// meaningful for searchers working on the same commit as the one
+/// that produced it.
+///
+/// ```rust
+/// use tantivy::collector::{Cursor, TopDocs};
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(3_000_000)?;
+/// for _ in 0..5 {
+///     index_writer.add_document(doc!(title => "The Diary of a Young Girl"));
+/// }
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("diary")?;
+///
+/// let first_page = searcher.search(&query, &TopDocs::with_limit(2))?;
+/// let (score, doc_address) = first_page[1];
+/// let token = Cursor::from_score(score, doc_address).to_token();
+///
+/// // ... later on, with a searcher on the same commit.
+/// let cursor = Cursor::from_token(&token)?;
+/// let second_page = searcher.search(&query, &TopDocs::with_limit(2).search_after(cursor))?;
+/// assert_eq!(second_page.len(), 2);
+/// assert!(second_page[0].1 > doc_address);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cursor {
+    sort_values: Vec<SortValue>,
+    doc_address: DocAddress,
+}
+
+impl Cursor {
+    /// Creates a cursor given the sort values and the address of a hit,
+    /// as returned by `TopDocs::order_by`.
+    pub fn new(sort_values: Vec<SortValue>, doc_address: DocAddress) -> Cursor {
+        Cursor {
+            sort_values,
+            doc_address,
+        }
+    }
+
+    /// Creates a cursor given the score and the address of a hit,
+    /// as returned by `TopDocs`.
+    pub fn from_score(score: Score, doc_address: DocAddress) -> Cursor {
+        Cursor::new(vec![SortValue::Score(score)], doc_address)
+    }
+
+    /// Returns the sort values of the hit.
+    pub fn sort_values(&self) -> &[SortValue] {
+        &self.sort_values
+    }
+
+    /// Returns the address of the hit.
+    pub fn doc_address(&self) -> DocAddress {
+        self.doc_address
+    }
+
+    /// Serializes the cursor into an opaque, url-safe token.
+    pub fn to_token(&self) -> String {
+        let mut bytes = Vec::with_capacity(2 + 9 * self.sort_values.len() + 8);
+        bytes.push(CURSOR_VERSION);
+        VInt(self.sort_values.len() as u64).serialize_into_vec(&mut bytes);
+        let mut buffer = [0u8; 8];
+        for sort_value in &self.sort_values {
+            let (code, val) = sort_value.to_u64();
+            bytes.push(code);
+            LittleEndian::write_u64(&mut buffer, val);
+            bytes.extend_from_slice(&buffer);
+        }
+        let DocAddress(segment_ord, doc) = self.doc_address;
+        LittleEndian::write_u32(&mut buffer[..4], segment_ord);
+        LittleEndian::write_u32(&mut buffer[4..], doc);
+        bytes.extend_from_slice(&buffer);
+        base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD)
+    }
+
+    /// Deserializes a cursor from a token produced by `to_token`.
+    ///
+    /// Returns an `InvalidArgument` error if the token is malformed.
+    pub fn from_token(token: &str) -> crate::Result<Cursor> {
+        let bytes =
+            base64::decode_config(token, base64::URL_SAFE_NO_PAD).map_err(|_| invalid_token())?;
+        if bytes.first() != Some(&CURSOR_VERSION) {
+            return Err(invalid_token());
+        }
+        let mut cursor = &bytes[1..];
+        let num_values = VInt::deserialize(&mut cursor)
+            .map_err(|_| invalid_token())?
+            .val() as usize;
+        if cursor.len() / 9 < num_values || cursor.len() != 9 * num_values + 8 {
+            return Err(invalid_token());
+        }
+        let mut sort_values = Vec::with_capacity(num_values);
+        for chunk in cursor[..9 * num_values].chunks(9) {
+            let val = LittleEndian::read_u64(&chunk[1..]);
+            sort_values.push(SortValue::from_u64(chunk[0], val).ok_or_else(invalid_token)?);
+        }
+        let doc_bytes = &cursor[9 * num_values..];
+        let doc_address = DocAddress(
+            LittleEndian::read_u32(&doc_bytes[..4]),
+            LittleEndian::read_u32(&doc_bytes[4..]),
+        );
+        Ok(Cursor::new(sort_values, doc_address))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cursor;
+    use crate::collector::SortValue;
+    use crate::fastfield::FastValue;
+    use crate::{DateTime, DocAddress};
+
+    #[test]
+    fn test_cursor_token() {
+        let cursor = Cursor::new(
+            vec![
+                SortValue::Score(1.5),
+                SortValue::U64(3),
+                SortValue::I64(-7),
+                SortValue::F64(-0.25),
+                SortValue::Date(DateTime::from_u64(1_600_000_000i64.to_u64())),
+            ],
+            DocAddress(2, 17),
+        );
+        let token = cursor.to_token();
+        assert_eq!(Cursor::from_token(&token).unwrap(), cursor);
+        assert!(Cursor::from_token("").is_err());
+        assert!(Cursor::from_token("not a token!").is_err());
+        assert!(Cursor::from_token(&token[..token.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn test_cursor_token_many_sort_values() {
+        let sort_values: Vec<SortValue> = (0..300u64).map(SortValue::U64).collect();
+        let cursor = Cursor::new(sort_values, DocAddress(0, 1));
+        let token = cursor.to_token();
+        assert_eq!(Cursor::from_token(&token).unwrap(), cursor);
+    }
+}
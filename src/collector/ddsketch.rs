@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Relative accuracy of the quantiles.
+const RELATIVE_ACCURACY: f64 = 0.01;
+
+/// Values whose magnitude is below this threshold are counted as zeros.
+const MIN_INDEXABLE_VALUE: f64 = 1e-9;
+
+/// Mergeable sketch of a distribution of values, computing quantiles with a bounded
+/// relative error.
+///
+/// Values are counted in logarithmic buckets, such that the quantiles returned
+/// are within 1% of an actual value of the distribution.
+/// (See "DDSketch: A Fast and Fully-Mergeable Quantile Sketch with Relative-Error Guarantees")
+///
+/// It is the fruit of the [`PercentilesCollector`](./struct.PercentilesCollector.html).
+/// Sketches are serializable, and can be merged across indexes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DDSketch {
+    positive: BTreeMap<i32, u64>,
+    negative: BTreeMap<i32, u64>,
+    zero_count: u64,
+    count: u64,
+    // Only meaningful if `count > 0`.
+    min: f64,
+    max: f64,
+}
+
+fn gamma() -> f64 {
+    (1.0 + RELATIVE_ACCURACY) / (1.0 - RELATIVE_ACCURACY)
+}
+
+fn bucket_index(magnitude: f64) -> i32 {
+    (magnitude.ln() / gamma().ln()).ceil() as i32
+}
+
+fn bucket_value(index: i32) -> f64 {
+    let gamma = gamma();
+    2.0 * gamma.powi(index) / (gamma + 1.0)
+}
+
+impl DDSketch {
+    /// Adds a value to the sketch. NaN values are ignored.
+    pub fn add(&mut self, val: f64) {
+        if val.is_nan() {
+            return;
+        }
+        if self.count == 0 {
+            self.min = val;
+            self.max = val;
+        } else {
+            self.min = self.min.min(val);
+            self.max = self.max.max(val);
+        }
+        self.count += 1;
+        if val > MIN_INDEXABLE_VALUE {
+            *self.positive.entry(bucket_index(val)).or_insert(0) += 1;
+        } else if val < -MIN_INDEXABLE_VALUE {
+            *self.negative.entry(bucket_index(-val)).or_insert(0) += 1;
+        } else {
+            self.zero_count += 1;
+        }
+    }
+
+    /// Returns the number of values added to the sketch.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Merges another sketch into this one.
+    pub fn merge(&mut self, other: &DDSketch) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            self.min = other.min;
+            self.max = other.max;
+        } else {
+            self.min = self.min.min(other.min);
+            self.max = self.max.max(other.max);
+        }
+        for (&index, &count) in &other.positive {
+            *self.positive.entry(index).or_insert(0) += count;
+        }
+        for (&index, &count) in &other.negative {
+            *self.negative.entry(index).or_insert(0) += count;
+        }
+        self.zero_count += other.zero_count;
+        self.count += other.count;
+    }
+
+    /// Returns the approximate percentile `percent`, with `percent` between 0 and 100,
+    /// or `None` if the sketch is empty.
+    pub fn percentile(&self, percent: f64) -> Option<f64> {
+        self.quantile(percent / 100.0)
+    }
+
+    /// Returns the approximate quantile `q`, with `q` between 0 and 1,
+    /// or `None` if the sketch is empty.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q * (self.count - 1) as f64).floor() as u64;
+        // The extreme values are known exactly.
+        if rank == 0 {
+            return Some(self.min);
+        }
+        if rank == self.count - 1 {
+            return Some(self.max);
+        }
+        let mut cumulated = 0u64;
+        let mut value = self.max;
+        let negatives = self
+            .negative
+            .iter()
+            .rev()
+            .map(|(&index, &count)| (-bucket_value(index), count));
+        let zeros = std::iter::once((0.0, self.zero_count));
+        let positives = self
+            .positive
+            .iter()
+            .map(|(&index, &count)| (bucket_value(index), count));
+        for (bucket_value, count) in negatives.chain(zeros).chain(positives) {
+            cumulated += count;
+            if cumulated > rank {
+                value = bucket_value;
+                break;
+            }
+        }
+        Some(value.max(self.min).min(self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DDSketch;
+
+    fn assert_relative_error(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= 0.01 * expected.abs() + 1e-9,
+            "{} vs {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_ddsketch_quantiles() {
+        let mut left = DDSketch::default();
+        let mut right = DDSketch::default();
+        assert_eq!(left.quantile(0.5), None);
+        for i in -500..=1000 {
+            if i % 2 == 0 {
+                left.add(i as f64);
+            } else {
+                right.add(i as f64);
+            }
+        }
+        left.merge(&right);
+        assert_eq!(left.count, 1501);
+        assert_eq!(left.quantile(0.0), Some(-500.0));
+        assert_eq!(left.quantile(1.0), Some(1000.0));
+        assert_relative_error(left.quantile(0.5).unwrap(), 250.0);
+        assert_relative_error(left.quantile(0.1).unwrap(), -350.0);
+        assert_relative_error(left.quantile(0.99).unwrap(), 985.0);
+    }
+}
@@ -10,7 +10,6 @@ use crate::SegmentReader;
 use std::cmp::Ordering;
 use std::collections::btree_map;
 use std::collections::BTreeMap;
-use std::collections::BTreeSet;
 use std::collections::BinaryHeap;
 use std::collections::Bound;
 use std::iter::Peekable;
@@ -70,8 +69,12 @@ fn facet_depth(facet_bytes: &[u8]) -> usize {
 /// the breakdown of counts for the direct children of `/category`
 /// (e.g. `/category/fiction`, `/category/biography`, `/category/personal_development`).
 ///
+/// The counts of a whole sub-tree, down to a given depth, can also be requested
+/// with `.add_facet_with_depth(...)`, and facets matching too few documents can be
+/// filtered out with `.set_min_count(...)`.
+///
 /// Once collection is finished, you can harvest its results in the form
-/// of a `FacetCounts` object, and extract your face                t counts from it.
+/// of a `FacetCounts` object, and extract your facet counts from it.
 ///
 /// This implementation assumes you are working with a number of facets that
 /// is much hundreds of time lower than your number of documents.
@@ -172,18 +175,24 @@ fn facet_depth(facet_bytes: &[u8]) -> usize {
 /// ```
 pub struct FacetCollector {
     field: Field,
-    facets: BTreeSet<Facet>,
+    // facet -> depth of the sub-tree to collect
+    facets: BTreeMap<Facet, usize>,
+    min_count: u64,
 }
 
 pub struct FacetSegmentCollector {
     reader: FacetReader,
     facet_ords_buf: Vec<u64>,
-    // facet_ord -> collapse facet_id
+    // facet_ord -> collapse facet_id (0 if the facet is not collected)
     collapse_mapping: Vec<usize>,
     // collapse facet_id -> count
     counts: Vec<u64>,
     // collapse facet_id -> facet_ord
     collapse_facet_ords: Vec<u64>,
+    // collapse facet_id -> collapse facet_id of its parent (0 for the children of a root)
+    collapse_parents: Vec<usize>,
+    // collapse facet_id -> last doc counted
+    last_docs: Vec<DocId>,
 }
 
 enum SkipResult {
@@ -191,13 +200,13 @@ enum SkipResult {
     NotFound,
 }
 
-fn skip<'a, I: Iterator<Item = &'a Facet>>(
+fn skip<'a, I: Iterator<Item = (&'a Facet, &'a usize)>>(
     target: &[u8],
     collapse_it: &mut Peekable<I>,
 ) -> SkipResult {
     loop {
         match collapse_it.peek() {
-            Some(facet_bytes) => match facet_bytes.encoded_str().as_bytes().cmp(target) {
+            Some((facet_bytes, _)) => match facet_bytes.encoded_str().as_bytes().cmp(target) {
                 Ordering::Less => {}
                 Ordering::Greater => {
                     return SkipResult::NotFound;
@@ -223,10 +232,16 @@ impl FacetCollector {
     pub fn for_field(field: Field) -> FacetCollector {
         FacetCollector {
             field,
-            facets: BTreeSet::default(),
+            facets: BTreeMap::default(),
+            min_count: 0,
         }
     }
 
+    /// Returns the field of the facets collected.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
     /// Adds a facet that we want to record counts
     ///
     /// Adding facet `Facet::from("/country")` for instance,
@@ -240,8 +255,28 @@ impl FacetCollector {
     where
         Facet: From<T>,
     {
+        self.add_facet_with_depth(facet_from, 1);
+    }
+
+    /// Adds a facet that we want to record the counts of its sub-tree,
+    /// down to `depth` levels below the facet.
+    ///
+    /// With a depth of 1, this is equivalent to `add_facet`. Adding
+    /// `Facet::root()` with a depth of `usize::MAX` records the counts
+    /// of all of the facets.
+    ///
+    /// The counts of the facets deeper than `depth` are accounted for
+    /// in their ancestor at the depth `depth`.
+    ///
+    /// # Panics
+    /// The method panics if `depth` is 0, or under the same conditions as `add_facet`.
+    pub fn add_facet_with_depth<T>(&mut self, facet_from: T, depth: usize)
+    where
+        Facet: From<T>,
+    {
+        assert!(depth > 0, "The depth of a facet must be strictly positive.");
         let facet = Facet::from(facet_from);
-        for old_facet in &self.facets {
+        for old_facet in self.facets.keys() {
             assert!(
                 !old_facet.is_prefix_of(&facet),
                 "Tried to add a facet which is a descendant of an already added facet."
@@ -251,8 +286,26 @@ impl FacetCollector {
                 "Tried to add a facet which is an ancestor of an already added facet."
             );
         }
-        self.facets.insert(facet);
+        self.facets.insert(facet, depth);
     }
+
+    /// Only reports the facets matching at least `min_count` documents.
+    ///
+    /// The threshold is applied once the counts of all of the segments are merged.
+    pub fn set_min_count(&mut self, min_count: u64) {
+        self.min_count = min_count;
+    }
+}
+
+/// Returns true iff `facet_bytes` is a strict descendant of `root_bytes`,
+/// both being encoded facets.
+pub(crate) fn is_strict_descendant(root_bytes: &[u8], facet_bytes: &[u8]) -> bool {
+    if root_bytes.is_empty() {
+        return !facet_bytes.is_empty();
+    }
+    facet_bytes.len() > root_bytes.len()
+        && facet_bytes.starts_with(root_bytes)
+        && facet_bytes[root_bytes.len()] == 0u8
 }
 
 impl Collector for FacetCollector {
@@ -268,58 +321,55 @@ impl Collector for FacetCollector {
         let facet_reader = reader.facet_reader(self.field)?;
 
         let mut collapse_mapping = Vec::new();
-        let mut counts = Vec::new();
-        let mut collapse_facet_ords = Vec::new();
+        let mut collapse_facet_ords = vec![0];
+        let mut collapse_parents = vec![0];
 
         let mut collapse_facet_it = self.facets.iter().peekable();
-        collapse_facet_ords.push(0);
-        {
-            let mut facet_streamer = facet_reader.facet_dict().range().into_stream();
-            if facet_streamer.advance() {
-                'outer: loop {
-                    // at the begining of this loop, facet_streamer
-                    // is positionned on a term that has not been processed yet.
-                    let skip_result = skip(facet_streamer.key(), &mut collapse_facet_it);
-                    match skip_result {
-                        SkipResult::Found => {
-                            // we reach a facet we decided to collapse.
-                            let collapse_depth = facet_depth(facet_streamer.key());
-                            let mut collapsed_id = 0;
-                            collapse_mapping.push(0);
-                            while facet_streamer.advance() {
-                                let depth = facet_depth(facet_streamer.key());
-                                if depth <= collapse_depth {
-                                    continue 'outer;
-                                }
-                                if depth == collapse_depth + 1 {
-                                    collapsed_id = collapse_facet_ords.len();
-                                    collapse_facet_ords.push(facet_streamer.term_ord());
-                                    collapse_mapping.push(collapsed_id);
-                                } else {
-                                    collapse_mapping.push(collapsed_id);
-                                }
-                            }
-                            break;
-                        }
-                        SkipResult::NotFound => {
-                            collapse_mapping.push(0);
-                            if !facet_streamer.advance() {
-                                break;
-                            }
-                        }
+        // Root being collected, as (encoded root, depth of the root, depth of its sub-tree).
+        let mut current_root: Option<(&[u8], usize, usize)> = None;
+        // Collapse facet ids of the ancestors of the current facet, starting from the root.
+        let mut path: Vec<usize> = Vec::new();
+        let mut facet_streamer = facet_reader.facet_dict().range().into_stream();
+        while facet_streamer.advance() {
+            // Facets are sorted so that the ancestors of a facet, which are all indexed,
+            // always come before it, and its descendants come right after it.
+            let facet_bytes = facet_streamer.key();
+            if let Some((root_bytes, root_depth, max_depth)) = current_root {
+                if is_strict_descendant(root_bytes, facet_bytes) {
+                    let depth = facet_depth(facet_bytes) - root_depth;
+                    if depth <= max_depth {
+                        path.truncate(depth - 1);
+                        let collapsed_id = collapse_facet_ords.len();
+                        collapse_facet_ords.push(facet_streamer.term_ord());
+                        collapse_parents.push(path.last().cloned().unwrap_or(0));
+                        path.push(collapsed_id);
+                        collapse_mapping.push(collapsed_id);
+                    } else {
+                        collapse_mapping.push(path.last().cloned().unwrap_or(0));
                     }
+                    continue;
+                }
+                current_root = None;
+            }
+            if let SkipResult::Found = skip(facet_bytes, &mut collapse_facet_it) {
+                if let Some((root, &max_depth)) = collapse_facet_it.peek() {
+                    let root_bytes = root.encoded_str().as_bytes();
+                    current_root = Some((root_bytes, facet_depth(root_bytes), max_depth));
+                    path.clear();
                 }
             }
+            collapse_mapping.push(0);
         }
 
-        counts.resize(collapse_facet_ords.len(), 0);
-
+        let num_collapsed_facets = collapse_facet_ords.len();
         Ok(FacetSegmentCollector {
             reader: facet_reader,
             facet_ords_buf: Vec::with_capacity(255),
             collapse_mapping,
-            counts,
+            counts: vec![0; num_collapsed_facets],
             collapse_facet_ords,
+            collapse_parents,
+            last_docs: vec![DocId::MAX; num_collapsed_facets],
         })
     }
 
@@ -334,6 +384,13 @@ impl Collector for FacetCollector {
                 *(facet_counts.entry(facet).or_insert(0)) += count;
             }
         }
+        if self.min_count > 1 {
+            let min_count = self.min_count;
+            facet_counts = facet_counts
+                .into_iter()
+                .filter(|&(_, count)| count >= min_count)
+                .collect();
+        }
         Ok(FacetCounts { facet_counts })
     }
 }
@@ -343,15 +400,14 @@ impl SegmentCollector for FacetSegmentCollector {
 
     fn collect(&mut self, doc: DocId, _: Score) {
         self.reader.facet_ords(doc, &mut self.facet_ords_buf);
-        let mut previous_collapsed_ord: usize = usize::MAX;
         for &facet_ord in &self.facet_ords_buf {
-            let collapsed_ord = self.collapse_mapping[facet_ord as usize];
-            self.counts[collapsed_ord] += if collapsed_ord == previous_collapsed_ord {
-                0
-            } else {
-                1
-            };
-            previous_collapsed_ord = collapsed_ord;
+            // Counts the document once for the collapsed facet and each of its ancestors.
+            let mut collapsed_id = self.collapse_mapping[facet_ord as usize];
+            while collapsed_id != 0 && self.last_docs[collapsed_id] != doc {
+                self.last_docs[collapsed_id] = doc;
+                self.counts[collapsed_id] += 1;
+                collapsed_id = self.collapse_parents[collapsed_id];
+            }
         }
     }
 
@@ -362,7 +418,7 @@ impl SegmentCollector for FacetSegmentCollector {
     fn harvest(self) -> FacetCounts {
         let mut facet_counts = BTreeMap::new();
         let facet_dict = self.reader.facet_dict();
-        for (collapsed_facet_ord, count) in self.counts.iter().cloned().enumerate() {
+        for (collapsed_facet_ord, count) in self.counts.iter().cloned().enumerate().skip(1) {
             if count == 0 {
                 continue;
             }
@@ -395,6 +451,8 @@ impl<'a> Iterator for FacetChildIterator<'a> {
 }
 
 impl FacetCounts {
+    /// Returns an iterator over the collected facets descending from `facet_from`,
+    /// along with their counts, in lexicographical order.
     pub fn get<T>(&self, facet_from: T) -> FacetChildIterator<'_>
     where
         Facet: From<T>,
@@ -414,6 +472,8 @@ impl FacetCounts {
         FacetChildIterator { underlying }
     }
 
+    /// Returns the `k` collected facets descending from `facet` with the highest counts,
+    /// sorted by decreasing count.
     pub fn top_k<T>(&self, facet: T, k: usize) -> Vec<(&Facet, u64)>
     where
         Facet: From<T>,
@@ -512,6 +572,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_facet_collector_depth_and_min_count() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let facet_field = schema_builder.add_facet_field("facet");
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(
+            facet_field => Facet::from("/category/fiction/fantasy"),
+            facet_field => Facet::from("/category/fiction/horror"),
+            facet_field => Facet::from("/lang/en"),
+        ));
+        index_writer.add_document(doc!(
+            facet_field => Facet::from("/category/fiction/sci-fi/space-opera"),
+            facet_field => Facet::from("/lang/en"),
+        ));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(
+            facet_field => Facet::from("/category/biography"),
+            facet_field => Facet::from("/lang/fr"),
+        ));
+        index_writer.add_document(doc!(facet_field => Facet::from("/category")));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let facet_counts = |facet_collector: &FacetCollector, facet: &str| {
+            let counts = searcher.search(&AllQuery, facet_collector).unwrap();
+            counts
+                .get(facet)
+                .map(|(facet, count)| (facet.to_string(), count))
+                .collect::<Vec<_>>()
+        };
+        let to_owned = |counts: &[(&str, u64)]| {
+            counts
+                .iter()
+                .map(|&(facet, count)| (facet.to_string(), count))
+                .collect::<Vec<_>>()
+        };
+
+        let mut facet_collector = FacetCollector::for_field(facet_field);
+        facet_collector.add_facet_with_depth("/category", 2);
+        assert_eq!(
+            facet_counts(&facet_collector, "/category"),
+            to_owned(&[
+                ("/category/biography", 1),
+                ("/category/fiction", 2),
+                ("/category/fiction/fantasy", 1),
+                ("/category/fiction/horror", 1),
+                ("/category/fiction/sci-fi", 1),
+            ])
+        );
+
+        let mut facet_collector = FacetCollector::for_field(facet_field);
+        facet_collector.add_facet_with_depth(Facet::root(), usize::MAX);
+        facet_collector.set_min_count(2);
+        assert_eq!(
+            facet_counts(&facet_collector, "/"),
+            to_owned(&[
+                ("/category", 4),
+                ("/category/fiction", 2),
+                ("/lang", 3),
+                ("/lang/en", 2),
+            ])
+        );
+        Ok(())
+    }
+
     #[test]
     #[should_panic(expected = "Tried to add a facet which is a descendant of \
                                an already added facet.")]
@@ -657,7 +782,7 @@ mod bench {
 
     use crate::collector::FacetCollector;
     use crate::query::AllQuery;
-    use crate::schema::{Facet, Schema};
+    use crate::schema::{Facet, Schema, INDEXED};
     use crate::Index;
     use rand::seq::SliceRandom;
     use rand::thread_rng;
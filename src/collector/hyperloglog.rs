@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+/// Number of bits of the hash used to pick a register.
+const PRECISION: u32 = 14;
+
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+/// Maximum rank that can be recorded in a register.
+const MAX_RANK: u8 = (64 - PRECISION + 1) as u8;
+
+/// Maximum number of hashes kept in the sparse representation.
+/// Past this threshold, the sparse representation would use more memory than the registers.
+const SPARSE_MAX_LEN: usize = NUM_REGISTERS / 8;
+
+/// Hashes a value into a well distributed `u64`, using the `splitmix64` finalizer.
+///
+/// The hash is stable, so that sketches built on different machines can be merged.
+fn hash(val: u64) -> u64 {
+    let mut z = val.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Representation {
+    /// Exact set of the hashes seen so far.
+    Sparse(BTreeSet<u64>),
+    /// Registers, holding the maximum rank observed for each register.
+    Dense(Vec<u8>),
+}
+
+/// Mergeable sketch estimating the number of distinct values, with the
+/// HyperLogLog algorithm.
+///
+/// The sketch uses 64 bits hashes and 2^14 registers, for a standard error of about 0.8%.
+/// Like in HyperLogLog++, small cardinalities are counted exactly, by keeping the hashes
+/// of the values in a sparse representation until it would outgrow the registers,
+/// and linear counting is used instead of the raw estimate up to `2.5 * 2^14`.
+/// (See "HyperLogLog in Practice: Algorithmic Engineering of a State of The Art
+/// Cardinality Estimation Algorithm")
+/// The empirical bias correction of HyperLogLog++ is not applied: the estimate
+/// may be slightly biased just above `2.5 * 2^14` distinct values.
+///
+/// It is the fruit of the [`CardinalityCollector`](./struct.CardinalityCollector.html).
+/// Sketches are serializable, and can be merged across indexes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "HyperLogLogDef")]
+pub struct HyperLogLog {
+    representation: Representation,
+}
+
+/// Serialized representation of a `HyperLogLog`,
+/// validated when converted into a `HyperLogLog`.
+#[derive(Deserialize)]
+struct HyperLogLogDef {
+    representation: Representation,
+}
+
+impl TryFrom<HyperLogLogDef> for HyperLogLog {
+    type Error = String;
+
+    fn try_from(hyperloglog_def: HyperLogLogDef) -> Result<HyperLogLog, String> {
+        if let Representation::Dense(registers) = &hyperloglog_def.representation {
+            if registers.len() != NUM_REGISTERS {
+                return Err(format!(
+                    "Expected {} registers, got {}",
+                    NUM_REGISTERS,
+                    registers.len()
+                ));
+            }
+            if let Some(&rank) = registers.iter().find(|&&rank| rank > MAX_RANK) {
+                return Err(format!(
+                    "Register rank {} exceeds the maximum rank {}",
+                    rank, MAX_RANK
+                ));
+            }
+        }
+        Ok(HyperLogLog {
+            representation: hyperloglog_def.representation,
+        })
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> HyperLogLog {
+        HyperLogLog {
+            representation: Representation::Sparse(BTreeSet::new()),
+        }
+    }
+}
+
+fn add_hash_to_registers(registers: &mut [u8], hash: u64) {
+    let register = (hash >> (64 - PRECISION)) as usize;
+    let rank = ((hash << PRECISION).leading_zeros().min(64 - PRECISION) + 1) as u8;
+    if registers[register] < rank {
+        registers[register] = rank;
+    }
+}
+
+impl HyperLogLog {
+    /// Adds a value to the sketch.
+    pub fn add(&mut self, val: u64) {
+        self.add_hash(hash(val));
+    }
+
+    fn add_hash(&mut self, hash: u64) {
+        match &mut self.representation {
+            Representation::Sparse(hashes) => {
+                hashes.insert(hash);
+                if hashes.len() > SPARSE_MAX_LEN {
+                    self.densify();
+                }
+            }
+            Representation::Dense(registers) => add_hash_to_registers(registers, hash),
+        }
+    }
+
+    fn densify(&mut self) {
+        if let Representation::Sparse(hashes) = &self.representation {
+            let mut registers = vec![0u8; NUM_REGISTERS];
+            for &hash in hashes {
+                add_hash_to_registers(&mut registers, hash);
+            }
+            self.representation = Representation::Dense(registers);
+        }
+    }
+
+    /// Merges another sketch into this one.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        match &other.representation {
+            Representation::Sparse(hashes) => {
+                for &hash in hashes {
+                    self.add_hash(hash);
+                }
+            }
+            Representation::Dense(other_registers) => {
+                self.densify();
+                if let Representation::Dense(registers) = &mut self.representation {
+                    for (register, &other_register) in registers.iter_mut().zip(other_registers) {
+                        if *register < other_register {
+                            *register = other_register;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the estimated number of distinct values added to the sketch.
+    pub fn estimate(&self) -> u64 {
+        let registers = match &self.representation {
+            Representation::Sparse(hashes) => return hashes.len() as u64,
+            Representation::Dense(registers) => registers,
+        };
+        let num_registers = NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / num_registers);
+        let inverse_sum: f64 = registers
+            .iter()
+            .map(|&register| 2f64.powi(-i32::from(register)))
+            .sum();
+        let raw_estimate = alpha * num_registers * num_registers / inverse_sum;
+        let num_zeros = registers.iter().filter(|&&register| register == 0).count();
+        // The raw estimate is biased for small cardinalities, where linear counting
+        // is more accurate.
+        if raw_estimate <= 2.5 * num_registers && num_zeros > 0 {
+            let linear_count = num_registers * (num_registers / num_zeros as f64).ln();
+            return linear_count.round() as u64;
+        }
+        raw_estimate.round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HyperLogLog;
+
+    fn assert_estimate(hll: &HyperLogLog, expected: u64) {
+        let error = (hll.estimate() as f64 - expected as f64).abs() / expected as f64;
+        assert!(error < 0.03, "{} vs {}", hll.estimate(), expected);
+    }
+
+    #[test]
+    fn test_hyperloglog_estimate() {
+        let mut hll = HyperLogLog::default();
+        assert_eq!(hll.estimate(), 0);
+        for val in 0..1_000u64 {
+            hll.add(val);
+            hll.add(val);
+        }
+        assert_eq!(hll.estimate(), 1_000);
+        for &num_vals in &[5_000u64, 20_000, 300_000] {
+            let mut hll = HyperLogLog::default();
+            for val in 0..num_vals {
+                hll.add(val * 7);
+            }
+            assert_estimate(&hll, num_vals);
+        }
+    }
+
+    #[test]
+    fn test_hyperloglog_merge() {
+        let mut left = HyperLogLog::default();
+        let mut right = HyperLogLog::default();
+        let mut all = HyperLogLog::default();
+        for val in 0..50_000u64 {
+            if val % 10 == 0 {
+                left.add(val);
+            } else {
+                right.add(val);
+            }
+            all.add(val);
+        }
+        let mut merged = left.clone();
+        merged.merge(&right);
+        assert_eq!(merged, all);
+        right.merge(&left);
+        assert_eq!(right, all);
+        assert_estimate(&all, 50_000);
+        let mut small = HyperLogLog::default();
+        small.add(3);
+        small.merge(&HyperLogLog::default());
+        assert_eq!(small.estimate(), 1);
+    }
+
+    #[test]
+    fn test_hyperloglog_serialization() {
+        let mut hll = HyperLogLog::default();
+        for val in 0..10u64 {
+            hll.add(val);
+        }
+        let json = serde_json::to_string(&hll).unwrap();
+        let deserialized: HyperLogLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, hll);
+        for val in 10..10_000u64 {
+            hll.add(val);
+        }
+        let json = serde_json::to_string(&hll).unwrap();
+        let deserialized: HyperLogLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, hll);
+    }
+
+    #[test]
+    fn test_hyperloglog_deserialization_is_validated() {
+        let short_registers = format!(r#"{{"representation":{{"dense":{:?}}}}}"#, vec![1u8; 16]);
+        let err = serde_json::from_str::<HyperLogLog>(&short_registers).unwrap_err();
+        assert!(err.to_string().contains("Expected 16384 registers, got 16"));
+        let invalid_rank = format!(
+            r#"{{"representation":{{"dense":{:?}}}}}"#,
+            vec![200u8; 1 << 14]
+        );
+        assert!(serde_json::from_str::<HyperLogLog>(&invalid_rank).is_err());
+        let valid = format!(
+            r#"{{"representation":{{"dense":{:?}}}}}"#,
+            vec![1u8; 1 << 14]
+        );
+        let hll: HyperLogLog = serde_json::from_str(&valid).unwrap();
+        let mut merged = HyperLogLog::default();
+        merged.merge(&hll);
+        assert_eq!(merged, hll);
+    }
+}
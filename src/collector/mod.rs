@@ -9,6 +9,10 @@ Your fruit could for instance be :
 - [the count of matching documents](./struct.Count.html)
 - [the top 10 documents, by relevancy or by a fast field](./struct.TopDocs.html)
 - [facet counts](./struct.FacetCollector.html)
+- [statistics about the values of a fast field](./struct.StatsCollector.html)
+- [the approximate number of distinct values of a fast field](./struct.CardinalityCollector.html)
+- [approximate percentiles of the values of a fast field](./struct.PercentilesCollector.html)
+- [the top groups of documents, collapsed by a fast field](./struct.CollapseCollector.html)
 
 At one point in your code, you will trigger the actual search operation by calling
 [the `search(...)` method of your `Searcher` object](../struct.Searcher.html#method.search).
@@ -98,6 +102,9 @@ pub use self::multi_collector::MultiCollector;
 
 mod top_collector;
 
+mod cursor;
+pub use self::cursor::Cursor;
+
 mod top_score_collector;
 pub use self::top_score_collector::TopDocs;
 
@@ -107,8 +114,32 @@ pub use self::custom_score_top_collector::{CustomScorer, CustomSegmentScorer};
 mod tweak_score_top_collector;
 pub use self::tweak_score_top_collector::{ScoreSegmentTweaker, ScoreTweaker};
 
+mod sort_key_top_collector;
+pub use self::sort_key_top_collector::{Order, SortKey, SortValue};
+
 mod facet_collector;
-pub use self::facet_collector::FacetCollector;
+pub use self::facet_collector::{FacetCollector, FacetCounts};
+
+mod multi_facet_collector;
+pub use self::multi_facet_collector::{MultiFacetCollector, MultiFacetCounts};
+
+mod stats_collector;
+pub use self::stats_collector::{Stats, StatsCollector};
+
+mod hyperloglog;
+pub use self::hyperloglog::HyperLogLog;
+
+mod cardinality_collector;
+pub use self::cardinality_collector::CardinalityCollector;
+
+mod ddsketch;
+pub use self::ddsketch::DDSketch;
+
+mod percentiles_collector;
+pub use self::percentiles_collector::PercentilesCollector;
+
+mod collapse_collector;
+pub use self::collapse_collector::{CollapseCollector, Group, GroupKey};
 use crate::query::Weight;
 
 mod docset_collector;
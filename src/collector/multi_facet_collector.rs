@@ -0,0 +1,366 @@
+use super::facet_collector::{is_strict_descendant, FacetSegmentCollector};
+use super::Collector;
+use crate::collector::{FacetCollector, FacetCounts, SegmentCollector};
+use crate::fastfield::FacetReader;
+use crate::schema::{Facet, Field};
+use crate::{DocId, Score, SegmentLocalId, SegmentReader};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Collector computing the facet counts of several facet fields in a single pass,
+/// with support for "post-filter" semantics.
+///
+/// Facet counts are computed by the `FacetCollector`s added with
+/// `add_facet_collector`, one per field.
+///
+/// Filters, as added with `add_filter`, restrict the documents counted to
+/// the documents having one of the filtered facets (or one of their descendants)
+/// for each of the filtered fields. The filters of a field are not applied to the
+/// counts of this field, so that a multi-select faceting UI can display the counts
+/// of the other values of a field, for which some values were selected.
+///
+/// ```rust
+/// use tantivy::collector::{FacetCollector, MultiFacetCollector};
+/// use tantivy::query::AllQuery;
+/// use tantivy::schema::{Facet, Schema, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let color = schema_builder.add_facet_field("color");
+/// let size = schema_builder.add_facet_field("size");
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(3_000_000)?;
+/// index_writer.add_document(doc!(title => "shirt", color => Facet::from("/red"), size => Facet::from("/S")));
+/// index_writer.add_document(doc!(title => "shirt", color => Facet::from("/red"), size => Facet::from("/M")));
+/// index_writer.add_document(doc!(title => "shirt", color => Facet::from("/blue"), size => Facet::from("/M")));
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let mut color_collector = FacetCollector::for_field(color);
+/// color_collector.add_facet("/");
+/// let mut size_collector = FacetCollector::for_field(size);
+/// size_collector.add_facet("/");
+/// let mut multi_facet_collector = MultiFacetCollector::new();
+/// multi_facet_collector.add_facet_collector(color_collector);
+/// multi_facet_collector.add_facet_collector(size_collector);
+/// // The user selected the color red.
+/// multi_facet_collector.add_filter(color, "/red");
+///
+/// let facet_counts = searcher.search(&AllQuery, &multi_facet_collector)?;
+/// // The counts of the colors are not filtered...
+/// let colors: Vec<(&Facet, u64)> = facet_counts.get(color).unwrap().get("/").collect();
+/// assert_eq!(colors, vec![(&Facet::from("/blue"), 1), (&Facet::from("/red"), 2)]);
+/// // ... but the counts of the sizes are.
+/// let sizes: Vec<(&Facet, u64)> = facet_counts.get(size).unwrap().get("/").collect();
+/// assert_eq!(sizes, vec![(&Facet::from("/M"), 1), (&Facet::from("/S"), 1)]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct MultiFacetCollector {
+    facet_collectors: Vec<FacetCollector>,
+    filters: BTreeMap<Field, BTreeSet<Facet>>,
+}
+
+impl MultiFacetCollector {
+    /// Creates a new `MultiFacetCollector`, without any facet collector or filter.
+    pub fn new() -> MultiFacetCollector {
+        MultiFacetCollector::default()
+    }
+
+    /// Adds a `FacetCollector`, computing the facet counts of its field.
+    ///
+    /// # Panics
+    /// The method panics if a `FacetCollector` was already added for the same field.
+    pub fn add_facet_collector(&mut self, facet_collector: FacetCollector) {
+        assert!(
+            self.facet_collectors
+                .iter()
+                .all(|added_collector| added_collector.field() != facet_collector.field()),
+            "Tried to add two facet collectors for the same field."
+        );
+        self.facet_collectors.push(facet_collector);
+    }
+
+    /// Adds a filter on the facets of `field`.
+    ///
+    /// Documents are counted if, for each filtered field, they have one of the
+    /// filtered facets or one of their descendants. The filters of a field are
+    /// not applied to its own counts.
+    pub fn add_filter<T>(&mut self, field: Field, facet_from: T)
+    where
+        Facet: From<T>,
+    {
+        self.filters
+            .entry(field)
+            .or_default()
+            .insert(Facet::from(facet_from));
+    }
+}
+
+/// Facet counts of several fields, as computed by a
+/// [`MultiFacetCollector`](./struct.MultiFacetCollector.html).
+pub struct MultiFacetCounts {
+    facet_counts: Vec<(Field, FacetCounts)>,
+}
+
+impl MultiFacetCounts {
+    /// Returns the facet counts of `field`, or `None` if no `FacetCollector`
+    /// was added for `field`.
+    pub fn get(&self, field: Field) -> Option<&FacetCounts> {
+        self.facet_counts
+            .iter()
+            .find(|(counts_field, _)| *counts_field == field)
+            .map(|(_, facet_counts)| facet_counts)
+    }
+}
+
+impl Collector for MultiFacetCollector {
+    type Fruit = MultiFacetCounts;
+
+    type Child = MultiFacetSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentLocalId,
+        reader: &SegmentReader,
+    ) -> crate::Result<MultiFacetSegmentCollector> {
+        let facet_collectors = self
+            .facet_collectors
+            .iter()
+            .map(|facet_collector| {
+                let segment_collector = facet_collector.for_segment(segment_local_id, reader)?;
+                Ok((facet_collector.field(), segment_collector))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+        let filters = self
+            .filters
+            .iter()
+            .map(|(&field, facets)| FacetFilter::open(reader, field, facets))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(MultiFacetSegmentCollector {
+            facet_collectors,
+            filters,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<Vec<FacetCounts>>,
+    ) -> crate::Result<MultiFacetCounts> {
+        let mut segment_fruits_per_field: Vec<Vec<FacetCounts>> = self
+            .facet_collectors
+            .iter()
+            .map(|_| Vec::with_capacity(segment_fruits.len()))
+            .collect();
+        for segment_fruit in segment_fruits {
+            for (fruits, facet_counts) in segment_fruits_per_field.iter_mut().zip(segment_fruit) {
+                fruits.push(facet_counts);
+            }
+        }
+        let facet_counts = self
+            .facet_collectors
+            .iter()
+            .zip(segment_fruits_per_field)
+            .map(|(facet_collector, fruits)| {
+                Ok((
+                    facet_collector.field(),
+                    facet_collector.merge_fruits(fruits)?,
+                ))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(MultiFacetCounts { facet_counts })
+    }
+}
+
+/// Filter on the facets of a field, for a given segment.
+struct FacetFilter {
+    field: Field,
+    reader: FacetReader,
+    facet_ords_buf: Vec<u64>,
+    // facet_ord -> true iff the facet is filtered, or is a descendant of a filtered facet.
+    accepted: Vec<bool>,
+}
+
+impl FacetFilter {
+    fn open(
+        reader: &SegmentReader,
+        field: Field,
+        facets: &BTreeSet<Facet>,
+    ) -> crate::Result<FacetFilter> {
+        let facet_reader = reader.facet_reader(field)?;
+        let mut accepted = Vec::with_capacity(facet_reader.num_facets());
+        let mut facet_streamer = facet_reader.facet_dict().range().into_stream();
+        while facet_streamer.advance() {
+            let facet_bytes = facet_streamer.key();
+            accepted.push(facets.iter().any(|facet| {
+                let filter_bytes = facet.encoded_str().as_bytes();
+                filter_bytes == facet_bytes || is_strict_descendant(filter_bytes, facet_bytes)
+            }));
+        }
+        Ok(FacetFilter {
+            field,
+            reader: facet_reader,
+            facet_ords_buf: Vec::new(),
+            accepted,
+        })
+    }
+
+    fn accepts(&mut self, doc: DocId) -> bool {
+        self.reader.facet_ords(doc, &mut self.facet_ords_buf);
+        let accepted = &self.accepted;
+        self.facet_ords_buf
+            .iter()
+            .any(|&facet_ord| accepted[facet_ord as usize])
+    }
+}
+
+/// Segment collector associated to `MultiFacetCollector`.
+pub struct MultiFacetSegmentCollector {
+    facet_collectors: Vec<(Field, FacetSegmentCollector)>,
+    filters: Vec<FacetFilter>,
+}
+
+impl SegmentCollector for MultiFacetSegmentCollector {
+    type Fruit = Vec<FacetCounts>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        // A document rejected by the filters of a single field is still
+        // counted for this field.
+        let mut rejecting_field: Option<Field> = None;
+        for filter in &mut self.filters {
+            if !filter.accepts(doc) {
+                if rejecting_field.is_some() {
+                    return;
+                }
+                rejecting_field = Some(filter.field);
+            }
+        }
+        for (field, facet_collector) in &mut self.facet_collectors {
+            if rejecting_field.is_none() || rejecting_field == Some(*field) {
+                facet_collector.collect(doc, score);
+            }
+        }
+    }
+
+    fn harvest(self) -> Vec<FacetCounts> {
+        self.facet_collectors
+            .into_iter()
+            .map(|(_, facet_collector)| facet_collector.harvest())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiFacetCollector;
+    use crate::collector::FacetCollector;
+    use crate::query::AllQuery;
+    use crate::schema::{Facet, Schema};
+    use crate::Index;
+
+    #[test]
+    fn test_multi_facet_collector() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_facet_field("color");
+        let size = schema_builder.add_facet_field("size");
+        let brand = schema_builder.add_facet_field("brand");
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        let colors = ["/red/dark", "/red/light", "/blue", "/green"];
+        let sizes = ["/S", "/M", "/L"];
+        for i in 0..24 {
+            index_writer.add_document(doc!(
+                color => Facet::from(colors[i % 4]),
+                size => Facet::from(sizes[i % 3]),
+                brand => Facet::from(if i < 12 { "/acme" } else { "/other" }),
+            ));
+            if i == 10 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let mut color_collector = FacetCollector::for_field(color);
+        color_collector.add_facet("/");
+        let mut size_collector = FacetCollector::for_field(size);
+        size_collector.add_facet("/");
+        let mut multi_facet_collector = MultiFacetCollector::new();
+        multi_facet_collector.add_facet_collector(color_collector);
+        multi_facet_collector.add_facet_collector(size_collector);
+
+        let counts_for = |facet_counts: &super::MultiFacetCounts, field| {
+            facet_counts
+                .get(field)
+                .unwrap()
+                .get("/")
+                .map(|(facet, count)| (facet.to_string(), count))
+                .collect::<Vec<_>>()
+        };
+        let facet_counts = searcher.search(&AllQuery, &multi_facet_collector)?;
+        assert_eq!(
+            counts_for(&facet_counts, color),
+            vec![
+                ("/blue".to_string(), 6),
+                ("/green".to_string(), 6),
+                ("/red".to_string(), 12)
+            ]
+        );
+        assert!(facet_counts.get(brand).is_none());
+
+        // Filters on a single field do not apply to the counts of this field.
+        multi_facet_collector.add_filter(color, "/red");
+        multi_facet_collector.add_filter(color, "/green");
+        let facet_counts = searcher.search(&AllQuery, &multi_facet_collector)?;
+        assert_eq!(counts_for(&facet_counts, color).len(), 3);
+        // Red and green documents are the documents with `i % 4` in `[0, 1, 3]`.
+        assert_eq!(
+            counts_for(&facet_counts, size),
+            vec![
+                ("/L".to_string(), 6),
+                ("/M".to_string(), 6),
+                ("/S".to_string(), 6)
+            ]
+        );
+
+        // Filters on other fields apply.
+        multi_facet_collector.add_filter(size, "/S");
+        multi_facet_collector.add_filter(brand, "/acme");
+        let facet_counts = searcher.search(&AllQuery, &multi_facet_collector)?;
+        // i in [0, 3, 6, 9]: /red/dark, /green, /blue, /red/light.
+        assert_eq!(
+            counts_for(&facet_counts, color),
+            vec![
+                ("/blue".to_string(), 1),
+                ("/green".to_string(), 1),
+                ("/red".to_string(), 2)
+            ]
+        );
+        // i in [0, 1, 3, 4, 5, 7, 8, 9, 11].
+        assert_eq!(
+            counts_for(&facet_counts, size),
+            vec![
+                ("/L".to_string(), 3),
+                ("/M".to_string(), 3),
+                ("/S".to_string(), 3)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to add two facet collectors for the same field.")]
+    fn test_multi_facet_collector_same_field() {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_facet_field("color");
+        let mut multi_facet_collector = MultiFacetCollector::new();
+        multi_facet_collector.add_facet_collector(FacetCollector::for_field(color));
+        multi_facet_collector.add_facet_collector(FacetCollector::for_field(color));
+    }
+}
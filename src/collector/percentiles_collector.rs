@@ -0,0 +1,194 @@
+use super::stats_collector::{fast_value_to_f64, FastFieldValuesReader};
+use super::Collector;
+use crate::collector::{DDSketch, SegmentCollector};
+use crate::fastfield::FastValue;
+use crate::schema::Field;
+use crate::{DocId, Score, SegmentLocalId, SegmentReader};
+use std::marker::PhantomData;
+
+/// Collector computing approximate percentiles of the values of a fast field
+/// over the matching documents.
+///
+/// The field can be a single-valued or a multi-valued `u64`, `i64`, `f64` or date
+/// fast field, of the type `T`. Dates are accounted for as timestamps, in seconds.
+///
+/// The fruit is a [`DDSketch`](./struct.DDSketch.html), returning any percentile
+/// within 1% of an actual value. It is serializable, so that the sketches of
+/// several indexes can be merged.
+///
+/// ```rust
+/// use tantivy::collector::PercentilesCollector;
+/// use tantivy::query::AllQuery;
+/// use tantivy::schema::{Schema, FAST};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let latency = schema_builder.add_u64_field("latency", FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(3_000_000)?;
+/// for latency_ms in 1..=100u64 {
+///     index_writer.add_document(doc!(latency => latency_ms));
+/// }
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let sketch = searcher.search(&AllQuery, &PercentilesCollector::<u64>::for_field(latency))?;
+/// assert_eq!(sketch.count(), 100);
+/// let p99 = sketch.percentile(99.0).unwrap();
+/// assert!((p99 - 99.0).abs() <= 0.99);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PercentilesCollector<T: FastValue> {
+    field: Field,
+    _marker: PhantomData<T>,
+}
+
+impl<T: FastValue> PercentilesCollector<T> {
+    /// Creates a collector sketching the distribution of the values of `field`.
+    pub fn for_field(field: Field) -> PercentilesCollector<T> {
+        PercentilesCollector {
+            field,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: FastValue + 'static> Collector for PercentilesCollector<T> {
+    type Fruit = DDSketch;
+
+    type Child = PercentilesSegmentCollector<T>;
+
+    fn for_segment(
+        &self,
+        _: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<PercentilesSegmentCollector<T>> {
+        let reader = FastFieldValuesReader::open::<T>(segment_reader, self.field)?;
+        Ok(PercentilesSegmentCollector {
+            reader,
+            sketch: DDSketch::default(),
+            _marker: PhantomData,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_sketches: Vec<DDSketch>) -> crate::Result<DDSketch> {
+        let mut sketch = DDSketch::default();
+        for segment_sketch in &segment_sketches {
+            sketch.merge(segment_sketch);
+        }
+        Ok(sketch)
+    }
+}
+
+/// Segment collector associated to `PercentilesCollector`.
+pub struct PercentilesSegmentCollector<T> {
+    reader: FastFieldValuesReader,
+    sketch: DDSketch,
+    _marker: PhantomData<T>,
+}
+
+impl<T: FastValue + 'static> SegmentCollector for PercentilesSegmentCollector<T> {
+    type Fruit = DDSketch;
+
+    fn collect(&mut self, doc: DocId, _: Score) {
+        let sketch = &mut self.sketch;
+        self.reader
+            .for_each(doc, |val| sketch.add(fast_value_to_f64(T::from_u64(val))));
+    }
+
+    fn harvest(self) -> DDSketch {
+        self.sketch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PercentilesCollector;
+    use crate::collector::DDSketch;
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{Cardinality, IndexRecordOption, IntOptions, Schema, FAST, STRING};
+    use crate::{Index, Term};
+
+    fn assert_relative_error(actual: Option<f64>, expected: f64) {
+        let actual = actual.unwrap();
+        assert!(
+            (actual - expected).abs() <= 0.01 * expected.abs(),
+            "{} vs {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_percentiles_collector() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let latency = schema_builder.add_f64_field("latency", FAST);
+        let sizes = schema_builder.add_i64_field(
+            "sizes",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        for i in 1..=1_000i64 {
+            let color_str = if i <= 900 { "green" } else { "red" };
+            index_writer.add_document(doc!(
+                color => color_str,
+                latency => i as f64 / 10.0,
+                sizes => -i,
+                sizes => i,
+            ));
+            if i == 300 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+
+        let sketch =
+            searcher.search(&AllQuery, &PercentilesCollector::<f64>::for_field(latency))?;
+        assert_eq!(sketch.count(), 1_000);
+        assert_eq!(sketch.percentile(0.0), Some(0.1));
+        assert_relative_error(sketch.percentile(50.0), 50.0);
+        assert_relative_error(sketch.percentile(99.0), 99.0);
+        assert_eq!(sketch.percentile(100.0), Some(100.0));
+
+        let sizes_sketch =
+            searcher.search(&AllQuery, &PercentilesCollector::<i64>::for_field(sizes))?;
+        assert_eq!(sizes_sketch.count(), 2_000);
+        assert_eq!(sizes_sketch.percentile(0.0), Some(-1_000.0));
+        assert_relative_error(sizes_sketch.percentile(75.0), 500.0);
+
+        // Fruits of distinct searches, or indexes, can be merged.
+        let green = TermQuery::new(
+            Term::from_field_text(color, "green"),
+            IndexRecordOption::Basic,
+        );
+        let green_sketch =
+            searcher.search(&green, &PercentilesCollector::<f64>::for_field(latency))?;
+        assert_eq!(green_sketch.count(), 900);
+        let red = TermQuery::new(
+            Term::from_field_text(color, "red"),
+            IndexRecordOption::Basic,
+        );
+        let json = serde_json::to_string(
+            &searcher.search(&red, &PercentilesCollector::<f64>::for_field(latency))?,
+        )?;
+        let mut merged: DDSketch = serde_json::from_str(&json)?;
+        merged.merge(&green_sketch);
+        assert_eq!(merged.count(), sketch.count());
+        assert_eq!(merged.percentile(90.0), sketch.percentile(90.0));
+
+        assert!(searcher
+            .search(&AllQuery, &PercentilesCollector::<u64>::for_field(latency))
+            .is_err());
+        Ok(())
+    }
+}
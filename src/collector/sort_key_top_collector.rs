@@ -0,0 +1,450 @@
+use crate::collector::top_collector::{TopCollector, TopSegmentCollector};
+use crate::collector::{Collector, SegmentCollector};
+use crate::common::{f64_to_u64, u64_to_f64};
+use crate::fastfield::{FastFieldReader, FastValue};
+use crate::schema::{Cardinality, Field, FieldType, Schema};
+use crate::{DateTime, DocAddress, DocId, Score, SegmentLocalId, SegmentReader, TantivyError};
+use std::cmp::Ordering;
+
+/// Direction of a sort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    /// Ascending order: the lowest values come first.
+    Asc,
+    /// Descending order: the highest values come first.
+    Desc,
+}
+
+/// Key used to sort documents with [`TopDocs::order_by`](./struct.TopDocs.html#method.order_by).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKey {
+    /// Sorts the documents by their score.
+    Score(Order),
+    /// Sorts the documents by the value of a single-valued fast field.
+    ///
+    /// The field can be a `u64`, `i64`, `f64` or date fast field.
+    FastField(Field, Order),
+}
+
+impl SortKey {
+    /// Returns the direction of the sort.
+    pub fn order(&self) -> Order {
+        match *self {
+            SortKey::Score(order) | SortKey::FastField(_, order) => order,
+        }
+    }
+}
+
+/// Value of a [`SortKey`](./enum.SortKey.html) for a given document.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SortValue {
+    /// Score of the document, for `SortKey::Score`.
+    Score(Score),
+    /// Value of a `u64` fast field.
+    U64(u64),
+    /// Value of a `i64` fast field.
+    I64(i64),
+    /// Value of a `f64` fast field.
+    F64(f64),
+    /// Value of a date fast field.
+    Date(DateTime),
+}
+
+/// Encodes a `u64` representation so that the best documents have the highest values.
+fn encode(val: u64, order: Order) -> u64 {
+    match order {
+        Order::Desc => val,
+        Order::Asc => !val,
+    }
+}
+
+/// Sort values of a document, along with their encoded representation.
+///
+/// Comparisons only rely on the encoded representation, which is such that
+/// the best documents are the greatest.
+#[derive(Clone, Debug)]
+pub struct SortFeature {
+    encoded: Vec<u64>,
+    values: Vec<SortValue>,
+}
+
+impl PartialEq for SortFeature {
+    fn eq(&self, other: &Self) -> bool {
+        self.encoded == other.encoded
+    }
+}
+
+impl PartialOrd for SortFeature {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.encoded.cmp(&other.encoded))
+    }
+}
+
+/// Type of the values of a sort key, used to decode their `u64` representation.
+#[derive(Clone, Copy)]
+enum SortValueType {
+    Score,
+    U64,
+    I64,
+    F64,
+    Date,
+}
+
+impl SortValueType {
+    fn for_key(schema: &Schema, sort_key: &SortKey) -> crate::Result<SortValueType> {
+        let field = match *sort_key {
+            SortKey::Score(_) => return Ok(SortValueType::Score),
+            SortKey::FastField(field, _) => field,
+        };
+        let field_entry = schema.get_field_entry(field);
+        let (value_type, int_options) = match field_entry.field_type() {
+            FieldType::U64(options) => (SortValueType::U64, options),
+            FieldType::I64(options) => (SortValueType::I64, options),
+            FieldType::F64(options) => (SortValueType::F64, options),
+            FieldType::Date(options) => (SortValueType::Date, options),
+            _ => {
+                return Err(TantivyError::SchemaError(format!(
+                    "Field {:?} is not a numeric or date field, and cannot be used as a sort key.",
+                    field_entry.name()
+                )));
+            }
+        };
+        if int_options.get_fastfield_cardinality() != Some(Cardinality::SingleValue) {
+            return Err(TantivyError::SchemaError(format!(
+                "Field {:?} is not a single-valued fast field, and cannot be used as a sort key.",
+                field_entry.name()
+            )));
+        }
+        Ok(value_type)
+    }
+
+    fn accepts(self, sort_value: &SortValue) -> bool {
+        matches!(
+            (self, sort_value),
+            (SortValueType::Score, SortValue::Score(_))
+                | (SortValueType::U64, SortValue::U64(_))
+                | (SortValueType::I64, SortValue::I64(_))
+                | (SortValueType::F64, SortValue::F64(_))
+                | (SortValueType::Date, SortValue::Date(_))
+        )
+    }
+
+    fn decode(self, val: u64) -> SortValue {
+        match self {
+            SortValueType::Score => SortValue::Score(u64_to_f64(val) as Score),
+            SortValueType::U64 => SortValue::U64(val),
+            SortValueType::I64 => SortValue::I64(i64::from_u64(val)),
+            SortValueType::F64 => SortValue::F64(f64::from_u64(val)),
+            SortValueType::Date => SortValue::Date(DateTime::from_u64(val)),
+        }
+    }
+}
+
+pub(crate) struct SortKeyTopCollector {
+    sort_keys: Vec<SortKey>,
+    collector: TopCollector<SortFeature>,
+}
+
+impl SortKeyTopCollector {
+    pub(crate) fn new(
+        sort_keys: Vec<SortKey>,
+        collector: TopCollector<SortFeature>,
+    ) -> SortKeyTopCollector {
+        assert!(!sort_keys.is_empty(), "At least one sort key is required.");
+        SortKeyTopCollector {
+            sort_keys,
+            collector,
+        }
+    }
+
+    /// Returns the encoded sort values and the address of the cursor, if any.
+    fn encoded_after(
+        &self,
+        value_types: &[SortValueType],
+    ) -> crate::Result<Option<(Vec<u64>, DocAddress)>> {
+        let cursor = if let Some(cursor) = self.collector.after.as_ref() {
+            cursor
+        } else {
+            return Ok(None);
+        };
+        let sort_values = cursor.sort_values();
+        let is_valid = sort_values.len() == value_types.len()
+            && sort_values
+                .iter()
+                .zip(value_types.iter())
+                .all(|(sort_value, value_type)| value_type.accepts(sort_value));
+        if !is_valid {
+            return Err(TantivyError::InvalidArgument(format!(
+                "The values of the cursor {:?} do not match the sort keys {:?}",
+                sort_values, self.sort_keys
+            )));
+        }
+        let encoded = sort_values
+            .iter()
+            .zip(self.sort_keys.iter())
+            .map(|(sort_value, sort_key)| encode(sort_value.to_u64().1, sort_key.order()))
+            .collect();
+        Ok(Some((encoded, cursor.doc_address())))
+    }
+}
+
+impl Collector for SortKeyTopCollector {
+    type Fruit = Vec<(Vec<SortValue>, DocAddress)>;
+
+    type Child = SortKeyTopSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        let mut value_types = Vec::with_capacity(self.sort_keys.len());
+        let mut ff_readers = Vec::with_capacity(self.sort_keys.len());
+        for sort_key in &self.sort_keys {
+            value_types.push(SortValueType::for_key(segment_reader.schema(), sort_key)?);
+            let ff_reader = match *sort_key {
+                SortKey::Score(_) => None,
+                SortKey::FastField(field, _) => {
+                    let ff_reader =
+                        segment_reader
+                            .fast_fields()
+                            .u64_lenient(field)
+                            .ok_or_else(|| {
+                                TantivyError::SchemaError(format!(
+                                    "Field requested ({:?}) is not a fast field.",
+                                    field
+                                ))
+                            })?;
+                    Some(ff_reader)
+                }
+            };
+            ff_readers.push(ff_reader);
+        }
+        let after = self.encoded_after(&value_types)?;
+        let segment_collector = self.collector.for_segment_after(segment_local_id, after);
+        Ok(SortKeyTopSegmentCollector {
+            orders: self.sort_keys.iter().map(SortKey::order).collect(),
+            value_types,
+            ff_readers,
+            segment_collector,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        self.sort_keys
+            .iter()
+            .any(|sort_key| matches!(sort_key, SortKey::Score(_)))
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<Vec<(SortFeature, DocAddress)>>,
+    ) -> crate::Result<Self::Fruit> {
+        let top_docs = self.collector.merge_fruits(segment_fruits)?;
+        Ok(top_docs
+            .into_iter()
+            .map(|(sort_feature, doc_address)| (sort_feature.values, doc_address))
+            .collect())
+    }
+}
+
+pub struct SortKeyTopSegmentCollector {
+    orders: Vec<Order>,
+    value_types: Vec<SortValueType>,
+    ff_readers: Vec<Option<FastFieldReader<u64>>>,
+    segment_collector: TopSegmentCollector<Vec<u64>>,
+}
+
+impl SegmentCollector for SortKeyTopSegmentCollector {
+    type Fruit = Vec<(SortFeature, DocAddress)>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        let encoded = self
+            .orders
+            .iter()
+            .zip(self.ff_readers.iter())
+            .map(|(&order, ff_reader_opt)| {
+                let val = if let Some(ff_reader) = ff_reader_opt {
+                    ff_reader.get(doc)
+                } else {
+                    f64_to_u64(f64::from(score))
+                };
+                encode(val, order)
+            })
+            .collect();
+        self.segment_collector.collect(doc, encoded);
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        let orders = self.orders;
+        let value_types = self.value_types;
+        self.segment_collector
+            .harvest()
+            .into_iter()
+            .map(|(encoded, doc_address)| {
+                let values = encoded
+                    .iter()
+                    .zip(orders.iter().zip(value_types.iter()))
+                    .map(|(&val, (&order, value_type))| value_type.decode(encode(val, order)))
+                    .collect();
+                (SortFeature { encoded, values }, doc_address)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Order, SortKey, SortValue};
+    use crate::collector::{Cursor, TopDocs};
+    use crate::query::{AllQuery, QueryParser};
+    use crate::schema::{Cardinality, IntOptions, Schema, FAST, TEXT};
+    use crate::{DocAddress, Index};
+
+    #[test]
+    fn test_order_by_sort_keys() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let price = schema_builder.add_u64_field("price", FAST);
+        let rating = schema_builder.add_f64_field("rating", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "blue car", price => 20u64, rating => 4.0));
+        index_writer.add_document(doc!(title => "red car", price => 10u64, rating => 3.5));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(title => "car car", price => 20u64, rating => 4.5));
+        index_writer.add_document(doc!(title => "red bike", price => 10u64, rating => 3.5));
+        index_writer.add_document(doc!(title => "blue car", price => 20u64, rating => 4.0));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        // Segment ordinals do not necessarily follow the commit order.
+        let (first, second) = if searcher.segment_reader(0).num_docs() == 2 {
+            (0, 1)
+        } else {
+            (1, 0)
+        };
+        let sorted = |mut doc_addresses: Vec<DocAddress>| {
+            doc_addresses.sort();
+            doc_addresses
+        };
+
+        // price asc, then rating desc, with ties broken by doc address.
+        let collector = TopDocs::with_limit(4).order_by(vec![
+            SortKey::FastField(price, Order::Asc),
+            SortKey::FastField(rating, Order::Desc),
+        ]);
+        let top_docs = searcher.search(&AllQuery, &collector)?;
+        let values: Vec<Vec<SortValue>> =
+            top_docs.iter().map(|(values, _)| values.clone()).collect();
+        assert_eq!(
+            values,
+            vec![
+                vec![SortValue::U64(10), SortValue::F64(3.5)],
+                vec![SortValue::U64(10), SortValue::F64(3.5)],
+                vec![SortValue::U64(20), SortValue::F64(4.5)],
+                vec![SortValue::U64(20), SortValue::F64(4.0)],
+            ]
+        );
+        let doc_addresses: Vec<DocAddress> = top_docs.iter().map(|(_, doc)| *doc).collect();
+        let mut expected = sorted(vec![DocAddress(first, 1), DocAddress(second, 1)]);
+        expected.push(DocAddress(second, 0));
+        expected.push(sorted(vec![DocAddress(first, 0), DocAddress(second, 2)])[0]);
+        assert_eq!(doc_addresses, expected);
+
+        // price desc, then score desc.
+        let query = QueryParser::for_index(&index, vec![title]).parse_query("car")?;
+        let collector = TopDocs::with_limit(3).and_offset(1).order_by(vec![
+            SortKey::FastField(price, Order::Desc),
+            SortKey::Score(Order::Desc),
+        ]);
+        let top_docs = searcher.search(&query, &collector)?;
+        let doc_addresses: Vec<DocAddress> = top_docs.iter().map(|(_, doc)| *doc).collect();
+        let mut expected = sorted(vec![DocAddress(first, 0), DocAddress(second, 2)]);
+        expected.push(DocAddress(first, 1));
+        assert_eq!(doc_addresses, expected);
+        assert!(matches!(
+            top_docs[2].0[..],
+            [SortValue::U64(10), SortValue::Score(_)]
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_by_search_after() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let price = schema_builder.add_i64_field("price", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        for i in 0..20i64 {
+            let title_text = if i % 3 == 0 { "car car" } else { "car" };
+            index_writer.add_document(doc!(title => title_text, price => (i % 4) - 2));
+            if i % 7 == 6 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query = QueryParser::for_index(&index, vec![title]).parse_query("car")?;
+        let sort_keys = vec![
+            SortKey::FastField(price, Order::Asc),
+            SortKey::Score(Order::Desc),
+        ];
+        let all_hits = searcher.search(
+            &query,
+            &TopDocs::with_limit(100).order_by(sort_keys.clone()),
+        )?;
+        assert_eq!(all_hits.len(), 20);
+        let mut hits = Vec::new();
+        let mut cursor: Option<Cursor> = None;
+        loop {
+            let mut top_docs = TopDocs::with_limit(3);
+            if let Some(cursor) = cursor.take() {
+                let token = cursor.to_token();
+                top_docs = top_docs.search_after(Cursor::from_token(&token)?);
+            }
+            let page = searcher.search(&query, &top_docs.order_by(sort_keys.clone()))?;
+            if let Some((sort_values, doc_address)) = page.last() {
+                cursor = Some(Cursor::new(sort_values.clone(), *doc_address));
+            } else {
+                break;
+            }
+            hits.extend(page);
+        }
+        assert_eq!(hits, all_hits);
+
+        // The cursor must match the sort keys.
+        for sort_values in &[
+            vec![SortValue::I64(0)],
+            vec![SortValue::U64(0), SortValue::Score(1.0)],
+        ] {
+            let cursor = Cursor::new(sort_values.clone(), DocAddress(0, 0));
+            let collector = TopDocs::with_limit(3)
+                .search_after(cursor)
+                .order_by(sort_keys.clone());
+            assert!(searcher.search(&query, &collector).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_by_invalid_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let prices = schema_builder.add_u64_field(
+            "prices",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "car", prices => 3u64));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        for &field in &[title, prices] {
+            let collector =
+                TopDocs::with_limit(1).order_by(vec![SortKey::FastField(field, Order::Asc)]);
+            assert!(searcher.search(&AllQuery, &collector).is_err());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,509 @@
+use super::Collector;
+use crate::collector::SegmentCollector;
+use crate::common::BitSet;
+use crate::fastfield::{FastFieldReader, FastValue, MultiValueIntFastFieldReader};
+use crate::query::docs_with_value;
+use crate::schema::{Cardinality, Field, FieldType, Type};
+use crate::{DocId, Score, SegmentLocalId, SegmentReader, TantivyError};
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// Converts a fast value into a `f64`. Dates are converted into timestamps, in seconds.
+pub(crate) fn fast_value_to_f64<T: FastValue>(val: T) -> f64 {
+    let val_u64 = val.to_u64();
+    match T::to_type() {
+        Type::U64 => val_u64 as f64,
+        Type::I64 | Type::Date => i64::from_u64(val_u64) as f64,
+        Type::F64 => f64::from_u64(val_u64),
+        _ => unreachable!("Fast values are numbers or dates."),
+    }
+}
+
+/// Statistics about the values of a fast field, as computed by a
+/// [`StatsCollector`](./struct.StatsCollector.html).
+///
+/// For multi-valued fields, all of the values of the documents are accounted for.
+/// `NaN` values are ignored.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stats<T> {
+    count: u64,
+    min: Option<T>,
+    max: Option<T>,
+    sum: f64,
+    mean: f64,
+    // Sum of the squared differences to the mean.
+    m2: f64,
+}
+
+impl<T> Default for Stats<T> {
+    fn default() -> Stats<T> {
+        Stats {
+            count: 0,
+            min: None,
+            max: None,
+            sum: 0.0,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+}
+
+impl<T: FastValue> Stats<T> {
+    pub(crate) fn collect(&mut self, val: T) {
+        let val_f64 = fast_value_to_f64(val);
+        if val_f64.is_nan() {
+            return;
+        }
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => {
+                if val < min {
+                    self.min = Some(val);
+                }
+                if val > max {
+                    self.max = Some(val);
+                }
+            }
+            _ => {
+                self.min = Some(val);
+                self.max = Some(val);
+            }
+        }
+        self.count += 1;
+        self.sum += val_f64;
+        let delta = val_f64 - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (val_f64 - self.mean);
+    }
+
+    /// Merges the statistics of another set of values.
+    ///
+    /// Count, minimum and maximum are merged exactly. The mean and the variance
+    /// are combined as if they had been computed over all of the values at once.
+    pub fn merge(&mut self, other: &Stats<T>) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        if let (Some(other_min), Some(min)) = (other.min, self.min) {
+            if other_min < min {
+                self.min = other.min;
+            }
+        }
+        if let (Some(other_max), Some(max)) = (other.max, self.max) {
+            if other_max > max {
+                self.max = other.max;
+            }
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.m2 +=
+            other.m2 + delta * delta * (self.count as f64 * other.count as f64) / count as f64;
+        self.sum += other.sum;
+        self.count = count;
+    }
+
+    /// Number of values.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Minimum value, or `None` if there are no values.
+    pub fn min(&self) -> Option<T> {
+        self.min
+    }
+
+    /// Maximum value, or `None` if there are no values.
+    pub fn max(&self) -> Option<T> {
+        self.max
+    }
+
+    /// Sum of the values. Dates are summed as timestamps, in seconds.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Average value, or `None` if there are no values.
+    /// The average of dates is expressed as a timestamp, in seconds.
+    pub fn average(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.mean)
+        }
+    }
+
+    /// Population variance of the values, or `None` if there are no values.
+    pub fn variance(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.m2 / self.count as f64)
+        }
+    }
+
+    /// Population standard deviation of the values, or `None` if there are no values.
+    pub fn std_deviation(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+}
+
+/// Collector computing the count, minimum, maximum, sum, average and
+/// standard deviation of the values of a fast field over the matching documents.
+///
+/// The field can be a single-valued or a multi-valued `u64`, `i64`, `f64` or date
+/// fast field, of the type `T`.
+///
+/// Documents without any value for the field are ignored.
+/// Single-valued fast fields do not record the absence of a value though:
+/// unless the field is also indexed, documents without a value are counted
+/// as if their value was `0`.
+///
+/// ```rust
+/// use tantivy::collector::{StatsCollector, TopDocs};
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let price = schema_builder.add_f64_field("price", FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(3_000_000)?;
+/// index_writer.add_document(doc!(title => "Red Bike", price => 300.0));
+/// index_writer.add_document(doc!(title => "Blue Bike", price => 200.0));
+/// index_writer.add_document(doc!(title => "Blue Car", price => 20_000.0));
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("bike")?;
+/// let (top_docs, stats) = searcher.search(
+///     &query,
+///     &(TopDocs::with_limit(10), StatsCollector::<f64>::for_field(price)),
+/// )?;
+/// assert_eq!(top_docs.len(), 2);
+/// assert_eq!(stats.count(), 2);
+/// assert_eq!(stats.min(), Some(200.0));
+/// assert_eq!(stats.average(), Some(250.0));
+/// assert_eq!(stats.std_deviation(), Some(50.0));
+/// # Ok(())
+/// # }
+/// ```
+pub struct StatsCollector<T: FastValue> {
+    field: Field,
+    _marker: PhantomData<T>,
+}
+
+impl<T: FastValue> StatsCollector<T> {
+    /// Creates a collector computing the statistics of the values of `field`.
+    pub fn for_field(field: Field) -> StatsCollector<T> {
+        StatsCollector {
+            field,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: FastValue + 'static> Collector for StatsCollector<T> {
+    type Fruit = Stats<T>;
+
+    type Child = StatsSegmentCollector<T>;
+
+    fn for_segment(
+        &self,
+        _: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<StatsSegmentCollector<T>> {
+        let reader = FastFieldValuesReader::open::<T>(segment_reader, self.field)?;
+        Ok(StatsSegmentCollector {
+            reader,
+            stats: Stats::default(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_stats: Vec<Stats<T>>) -> crate::Result<Stats<T>> {
+        let mut stats = Stats::default();
+        for segment_stats in &segment_stats {
+            stats.merge(segment_stats);
+        }
+        Ok(stats)
+    }
+}
+
+/// Reader of the values of a single-valued or multi-valued numeric fast field,
+/// shared by the collectors over fast field values.
+pub(crate) enum FastFieldValuesReader {
+    /// Single-valued fast fields do not record whether a document has a value.
+    /// If the field is indexed, `docs_with_value` holds the documents having one.
+    /// Otherwise, all of the documents are considered to have a value.
+    Single {
+        reader: FastFieldReader<u64>,
+        docs_with_value: Option<BitSet>,
+    },
+    Multi(MultiValueIntFastFieldReader<u64>, Vec<u64>),
+}
+
+impl FastFieldValuesReader {
+    /// Opens the values of `field`, which must be a `u64`, `i64`, `f64` or date fast field
+    /// of the type `T`.
+    pub fn open<T: FastValue>(
+        segment_reader: &SegmentReader,
+        field: Field,
+    ) -> crate::Result<FastFieldValuesReader> {
+        let field_entry = segment_reader.schema().get_field_entry(field);
+        let fast_fields = segment_reader.fast_fields();
+        let field_type = field_entry.field_type();
+        // Facet ordinals are stored as `u64` fast fields, but are not values.
+        let cardinality = if let FieldType::HierarchicalFacet(_) = field_type {
+            None
+        } else {
+            T::fast_field_cardinality(field_type)
+        };
+        let docs_with_value =
+            if cardinality == Some(Cardinality::SingleValue) && field_entry.is_indexed() {
+                Some(docs_with_value(segment_reader, field)?)
+            } else {
+                None
+            };
+        let reader = match cardinality {
+            Some(Cardinality::SingleValue) => {
+                fast_fields
+                    .u64_lenient(field)
+                    .map(|reader| FastFieldValuesReader::Single {
+                        reader,
+                        docs_with_value,
+                    })
+            }
+            Some(Cardinality::MultiValues) => fast_fields
+                .u64s_lenient(field)
+                .map(|reader| FastFieldValuesReader::Multi(reader, Vec::new())),
+            None => None,
+        };
+        reader.ok_or_else(|| {
+            TantivyError::SchemaError(format!(
+                "Field {:?} is not a {:?} fast field.",
+                field_entry.name(),
+                T::to_type()
+            ))
+        })
+    }
+
+    /// Calls `f` on each of the values of `doc`, as `u64`.
+    pub fn for_each<F: FnMut(u64)>(&mut self, doc: DocId, mut f: F) {
+        match self {
+            FastFieldValuesReader::Single {
+                reader,
+                docs_with_value,
+            } => {
+                let has_value = docs_with_value
+                    .as_ref()
+                    .map(|docs| docs.contains(doc))
+                    .unwrap_or(true);
+                if has_value {
+                    f(reader.get(doc));
+                }
+            }
+            FastFieldValuesReader::Multi(reader, vals) => {
+                reader.get_vals(doc, vals);
+                for &val in vals.iter() {
+                    f(val);
+                }
+            }
+        }
+    }
+}
+
+/// Segment collector associated to `StatsCollector`.
+pub struct StatsSegmentCollector<T> {
+    reader: FastFieldValuesReader,
+    stats: Stats<T>,
+}
+
+impl<T: FastValue + 'static> SegmentCollector for StatsSegmentCollector<T> {
+    type Fruit = Stats<T>;
+
+    fn collect(&mut self, doc: DocId, _: Score) {
+        let stats = &mut self.stats;
+        self.reader
+            .for_each(doc, |val| stats.collect(T::from_u64(val)));
+    }
+
+    fn harvest(self) -> Stats<T> {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Stats, StatsCollector};
+    use crate::aggregation::{AggregationCollector, AggregationResult, Aggregations, MetricResult};
+    use crate::collector::Count;
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{
+        Cardinality, IndexRecordOption, IntOptions, Schema, FAST, INDEXED, STRING,
+    };
+    use crate::{DateTime, Index, Term};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_stats_merge() {
+        let mut left = Stats::default();
+        let mut right = Stats::default();
+        let mut all = Stats::default();
+        for i in 0..100u64 {
+            let val = i * i % 17;
+            if i % 3 == 0 {
+                left.collect(val);
+            } else {
+                right.collect(val);
+            }
+            all.collect(val);
+        }
+        left.merge(&right);
+        assert_eq!(left.count(), all.count());
+        assert_eq!(left.min(), all.min());
+        assert_eq!(left.max(), all.max());
+        assert_eq!(left.sum(), all.sum());
+        crate::assert_nearly_equals!(left.average().unwrap(), all.average().unwrap());
+        crate::assert_nearly_equals!(left.variance().unwrap(), all.variance().unwrap());
+        let mut empty = Stats::<u64>::default();
+        empty.merge(&Stats::default());
+        assert_eq!(empty.average(), None);
+        empty.merge(&all);
+        assert_eq!(empty, all);
+    }
+
+    #[test]
+    fn test_stats_collector() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let price = schema_builder.add_i64_field("price", FAST);
+        let weight = schema_builder.add_f64_field("weight", FAST);
+        let date = schema_builder.add_date_field("date", FAST);
+        let sizes = schema_builder.add_u64_field(
+            "sizes",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let category = schema_builder.add_facet_field("category");
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        let date_val = |timestamp: i64| -> DateTime { Utc.timestamp_opt(timestamp, 0).unwrap() };
+        index_writer.add_document(doc!(
+            color => "red",
+            price => -2i64,
+            weight => 1.5,
+            date => date_val(100),
+            sizes => 3u64,
+            sizes => 5u64,
+        ));
+        index_writer.add_document(doc!(
+            color => "blue",
+            price => 6i64,
+            weight => f64::NAN,
+            date => date_val(300),
+        ));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(
+            color => "red",
+            price => 4i64,
+            weight => 2.5,
+            date => date_val(200),
+            sizes => 1u64,
+        ));
+        index_writer.add_document(doc!(
+            color => "green",
+            price => 1000i64,
+            weight => 1000.0,
+            date => date_val(0),
+            sizes => 1000u64,
+        ));
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(color, "green"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let price_stats = searcher.search(&AllQuery, &StatsCollector::<i64>::for_field(price))?;
+        assert_eq!(price_stats.count(), 3);
+        assert_eq!(price_stats.min(), Some(-2));
+        assert_eq!(price_stats.max(), Some(6));
+        assert_eq!(price_stats.sum(), 8.0);
+        crate::assert_nearly_equals!(price_stats.average().unwrap(), 8.0 / 3.0);
+        crate::assert_nearly_equals!(price_stats.variance().unwrap(), 104.0 / 9.0);
+
+        // NaN values are ignored.
+        let weight_stats = searcher.search(&AllQuery, &StatsCollector::<f64>::for_field(weight))?;
+        assert_eq!(weight_stats.count(), 2);
+        assert_eq!(weight_stats.average(), Some(2.0));
+        assert_eq!(weight_stats.std_deviation(), Some(0.5));
+
+        let date_stats =
+            searcher.search(&AllQuery, &StatsCollector::<DateTime>::for_field(date))?;
+        assert_eq!(date_stats.min(), Some(date_val(100)));
+        assert_eq!(date_stats.max(), Some(date_val(300)));
+        assert_eq!(date_stats.average(), Some(200.0));
+
+        let red = TermQuery::new(
+            Term::from_field_text(color, "red"),
+            IndexRecordOption::Basic,
+        );
+        let (count, size_stats) =
+            searcher.search(&red, &(Count, StatsCollector::<u64>::for_field(sizes)))?;
+        assert_eq!(count, 2);
+        assert_eq!(size_stats.count(), 3);
+        assert_eq!(size_stats.min(), Some(1));
+        assert_eq!(size_stats.max(), Some(5));
+        assert_eq!(size_stats.average(), Some(3.0));
+
+        // The type of the collector must match the type of the field.
+        assert!(searcher
+            .search(&AllQuery, &StatsCollector::<u64>::for_field(price))
+            .is_err());
+        assert!(searcher
+            .search(&AllQuery, &StatsCollector::<u64>::for_field(color))
+            .is_err());
+        assert!(searcher
+            .search(&AllQuery, &StatsCollector::<u64>::for_field(category))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_collector_missing_values() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let price = schema_builder.add_i64_field("price", FAST | INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(color => "red", price => 10i64));
+        index_writer.add_document(doc!(color => "blue", price => 30i64));
+        index_writer.add_document(doc!(color => "green"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let price_stats = searcher.search(&AllQuery, &StatsCollector::<i64>::for_field(price))?;
+        assert_eq!(price_stats.count(), 2);
+        assert_eq!(price_stats.min(), Some(10));
+        assert_eq!(price_stats.average(), Some(20.0));
+        // The aggregations ignore the same documents.
+        let aggregations: Aggregations =
+            serde_json::from_str(r#"{"price_stats": {"type": "stats", "field": "price"}}"#)
+                .unwrap();
+        let results = searcher.search(&AllQuery, &AggregationCollector::from_aggs(aggregations))?;
+        match &results["price_stats"] {
+            AggregationResult::Metric(MetricResult::Stats(stats)) => {
+                assert_eq!(stats.count, price_stats.count());
+                assert_eq!(stats.min, Some(10.0));
+                assert_eq!(stats.avg, price_stats.average());
+            }
+            other => panic!("Expected stats, got {:?}", other),
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
+use crate::collector::Cursor;
 use crate::DocAddress;
 use crate::DocId;
 use crate::SegmentLocalId;
 use crate::SegmentReader;
+use crate::TantivyError;
 use serde::export::PhantomData;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
@@ -55,9 +57,29 @@ impl<T: PartialOrd, D: PartialOrd> PartialEq for ComparableDoc<T, D> {
 
 impl<T: PartialOrd, D: PartialOrd> Eq for ComparableDoc<T, D> {}
 
+/// Returns true iff a document comes strictly after the document of a cursor,
+/// in the order of a top collector.
+///
+/// Documents are sorted by decreasing feature, and ties are resolved by ascending
+/// `DocAddress`.
+#[inline(always)]
+pub(crate) fn is_after<T: PartialOrd>(
+    feature: &T,
+    doc_address: DocAddress,
+    after_feature: &T,
+    after_doc_address: DocAddress,
+) -> bool {
+    match feature.partial_cmp(after_feature) {
+        Some(Ordering::Less) => true,
+        Some(Ordering::Greater) => false,
+        Some(Ordering::Equal) | None => doc_address > after_doc_address,
+    }
+}
+
 pub(crate) struct TopCollector<T> {
     pub limit: usize,
     pub offset: usize,
+    pub after: Option<Cursor>,
     _marker: PhantomData<T>,
 }
 
@@ -76,6 +98,7 @@ where
         Self {
             limit,
             offset: 0,
+            after: None,
             _marker: PhantomData,
         }
     }
@@ -89,6 +112,12 @@ where
         self
     }
 
+    /// Only collect the documents coming strictly after the document of the `cursor`.
+    pub fn search_after(mut self, cursor: Cursor) -> TopCollector<T> {
+        self.after = Some(cursor);
+        self
+    }
+
     pub fn merge_fruits(
         &self,
         children: Vec<Vec<(T, DocAddress)>>,
@@ -116,15 +145,33 @@ where
             .collect())
     }
 
+    /// Creates the segment collector of a top collector that does not support
+    /// `search_after`.
     pub(crate) fn for_segment<F: PartialOrd>(
         &self,
         segment_id: SegmentLocalId,
         _: &SegmentReader,
     ) -> crate::Result<TopSegmentCollector<F>> {
-        Ok(TopSegmentCollector::new(
-            segment_id,
-            self.limit + self.offset,
-        ))
+        if self.after.is_some() {
+            return Err(TantivyError::InvalidArgument(
+                "search_after is only supported when ranking by score, or with \
+                 `TopDocs::order_by`"
+                    .to_string(),
+            ));
+        }
+        Ok(self.for_segment_after(segment_id, None))
+    }
+
+    /// Creates a segment collector only collecting the documents coming
+    /// strictly after `after`.
+    pub(crate) fn for_segment_after<F: PartialOrd>(
+        &self,
+        segment_id: SegmentLocalId,
+        after: Option<(F, DocAddress)>,
+    ) -> TopSegmentCollector<F> {
+        let mut segment_collector = TopSegmentCollector::new(segment_id, self.limit + self.offset);
+        segment_collector.after = after;
+        segment_collector
     }
 
     /// Create a new TopCollector with the same limit and offset.
@@ -136,6 +183,7 @@ where
         TopCollector {
             limit: self.limit,
             offset: self.offset,
+            after: self.after,
             _marker: PhantomData,
         }
     }
@@ -151,6 +199,7 @@ pub(crate) struct TopSegmentCollector<T> {
     limit: usize,
     heap: BinaryHeap<ComparableDoc<T, DocId>>,
     segment_id: u32,
+    after: Option<(T, DocAddress)>,
 }
 
 impl<T: PartialOrd> TopSegmentCollector<T> {
@@ -159,6 +208,7 @@ impl<T: PartialOrd> TopSegmentCollector<T> {
             limit,
             heap: BinaryHeap::with_capacity(limit),
             segment_id,
+            after: None,
         }
     }
 }
@@ -191,6 +241,12 @@ impl<T: PartialOrd + Clone> TopSegmentCollector<T> {
     /// will compare the lowest scoring item with the given one and keep whichever is greater.
     #[inline(always)]
     pub fn collect(&mut self, doc: DocId, feature: T) {
+        if let Some((after_feature, after_doc_address)) = self.after.as_ref() {
+            let doc_address = DocAddress(self.segment_id, doc);
+            if !is_after(&feature, doc_address, after_feature, *after_doc_address) {
+                return;
+            }
+        }
         if self.at_capacity() {
             // It's ok to unwrap as long as a limit of 0 is forbidden.
             if let Some(limit_feature) = self.heap.peek().map(|head| head.feature.clone()) {
@@ -273,6 +329,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_top_segment_collector_after() {
+        let collector = TopCollector::<f32>::with_limit(2);
+        let mut top_collector = collector.for_segment_after(1, Some((0.8, DocAddress(1, 3))));
+        top_collector.collect(1, 0.9);
+        top_collector.collect(2, 0.8);
+        top_collector.collect(3, 0.8);
+        top_collector.collect(4, 0.8);
+        top_collector.collect(5, 0.3);
+        top_collector.collect(6, 0.5);
+        assert_eq!(
+            top_collector.harvest(),
+            vec![(0.8, DocAddress(1, 4)), (0.5, DocAddress(1, 6))]
+        );
+    }
+
     #[test]
     fn test_top_collector_with_limit_and_offset() {
         let collector = TopCollector::with_limit(2).and_offset(1);
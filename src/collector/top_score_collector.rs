@@ -1,8 +1,11 @@
 use super::Collector;
+use crate::collector::sort_key_top_collector::SortKeyTopCollector;
+use crate::collector::top_collector::is_after;
 use crate::collector::top_collector::{ComparableDoc, TopCollector};
 use crate::collector::tweak_score_top_collector::TweakedScoreTopCollector;
 use crate::collector::{
-    CustomScorer, CustomSegmentScorer, ScoreSegmentTweaker, ScoreTweaker, SegmentCollector,
+    Cursor, CustomScorer, CustomSegmentScorer, ScoreSegmentTweaker, ScoreTweaker, SegmentCollector,
+    SortKey, SortValue,
 };
 use crate::fastfield::FastFieldReader;
 use crate::query::Weight;
@@ -209,6 +212,73 @@ impl TopDocs {
         TopDocs(self.0.and_offset(offset))
     }
 
+    /// Only collect the hits coming strictly after the hit identified by `cursor`.
+    ///
+    /// This makes it possible to page through results without the cost of a deep `offset`:
+    /// the segment collectors only need to keep `limit` documents, whatever the page.
+    ///
+    /// When ranking by score, the cursor is built from the last hit of the previous page
+    /// with `Cursor::from_score`. With [`order_by`](#method.order_by), it is built from the
+    /// sort values of the last hit with `Cursor::new`.
+    /// Other orderings (`order_by_fast_field`, `tweak_score`, `custom_score`) do not
+    /// support cursors, and return an error at the moment of search.
+    ///
+    /// A cursor is only meaningful for searchers working on the same commit as the one
+    /// used to compute the previous page. (See [`Cursor`](./struct.Cursor.html).)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tantivy::collector::{Cursor, TopDocs};
+    /// use tantivy::query::QueryParser;
+    /// use tantivy::schema::{Schema, TEXT};
+    /// use tantivy::{doc, DocAddress, Index};
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT);
+    /// let index = Index::create_in_ram(schema_builder.build());
+    /// let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+    /// index_writer.add_document(doc!(title => "The Name of the Wind"));
+    /// index_writer.add_document(doc!(title => "The Diary of Muadib"));
+    /// index_writer.add_document(doc!(title => "A Dairy Cow"));
+    /// index_writer.add_document(doc!(title => "The Diary of a Young Girl"));
+    /// index_writer.add_document(doc!(title => "The Diary of Lena Mukhina"));
+    /// index_writer.commit()?;
+    ///
+    /// let searcher = index.reader()?.searcher();
+    /// let query = QueryParser::for_index(&index, vec![title]).parse_query("diary")?;
+    ///
+    /// let first_page = searcher.search(&query, &TopDocs::with_limit(2))?;
+    /// let (score, doc_address) = first_page[1];
+    /// let cursor = Cursor::from_score(score, doc_address);
+    /// let second_page = searcher.search(&query, &TopDocs::with_limit(2).search_after(cursor))?;
+    ///
+    /// assert_eq!(second_page.len(), 1);
+    /// assert_eq!(second_page[0].1, DocAddress(0, 3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn search_after(self, cursor: Cursor) -> TopDocs {
+        TopDocs(self.0.search_after(cursor))
+    }
+
+    /// Returns the score and the address of the cursor, if any.
+    fn score_after(&self) -> crate::Result<Option<(Score, DocAddress)>> {
+        let cursor = if let Some(cursor) = self.0.after.as_ref() {
+            cursor
+        } else {
+            return Ok(None);
+        };
+        match *cursor.sort_values() {
+            [SortValue::Score(score)] => Ok(Some((score, cursor.doc_address()))),
+            _ => Err(TantivyError::InvalidArgument(format!(
+                "A cursor on the score was expected, got {:?}",
+                cursor.sort_values()
+            ))),
+        }
+    }
+
     /// Set top-K to rank documents by a given fast field.
     ///
     /// If the field is not a fast or does not exist, this method returns successfully (it is not aware of any schema).
@@ -592,6 +662,62 @@ impl TopDocs {
     {
         CustomScoreTopCollector::new(custom_score, self.0.into_tscore())
     }
+
+    /// Ranks the documents using a list of sort keys.
+    ///
+    /// Documents are compared on the first key, then on the second key
+    /// in case of a tie, and so on. Each key has its own direction.
+    /// The remaining ties are resolved by ascending `DocAddress`.
+    ///
+    /// Keys are either the score of the document, or the value of a single-valued
+    /// `u64`, `i64`, `f64` or date fast field. If a field cannot be used as a sort key,
+    /// an explicit error will be returned at the moment of collection.
+    ///
+    /// The values of the sort keys are returned alongside each hit.
+    ///
+    /// ```rust
+    /// use tantivy::collector::{Order, SortKey, SortValue, TopDocs};
+    /// use tantivy::query::QueryParser;
+    /// use tantivy::schema::{Schema, FAST, TEXT};
+    /// use tantivy::{doc, DocAddress, Index};
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT);
+    /// let price = schema_builder.add_u64_field("price", FAST);
+    /// let index = Index::create_in_ram(schema_builder.build());
+    /// let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+    /// index_writer.add_document(doc!(title => "Red Bike", price => 300u64));
+    /// index_writer.add_document(doc!(title => "Blue Bike", price => 200u64));
+    /// index_writer.add_document(doc!(title => "Blue Bike Bike", price => 200u64));
+    /// index_writer.commit()?;
+    ///
+    /// let searcher = index.reader()?.searcher();
+    /// let query = QueryParser::for_index(&index, vec![title]).parse_query("bike")?;
+    /// // Cheapest first, then most relevant first.
+    /// let top_docs = searcher.search(
+    ///     &query,
+    ///     &TopDocs::with_limit(2).order_by(vec![
+    ///         SortKey::FastField(price, Order::Asc),
+    ///         SortKey::Score(Order::Desc),
+    ///     ]),
+    /// )?;
+    /// assert_eq!(top_docs[0].1, DocAddress(0, 2));
+    /// assert_eq!(top_docs[1].1, DocAddress(0, 1));
+    /// assert_eq!(top_docs[0].0[0], SortValue::U64(200));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `sort_keys` is empty.
+    pub fn order_by(
+        self,
+        sort_keys: Vec<SortKey>,
+    ) -> impl Collector<Fruit = Vec<(Vec<SortValue>, DocAddress)>> {
+        SortKeyTopCollector::new(sort_keys, self.0.into_tscore())
+    }
 }
 
 impl Collector for TopDocs {
@@ -602,9 +728,10 @@ impl Collector for TopDocs {
     fn for_segment(
         &self,
         segment_local_id: SegmentLocalId,
-        reader: &SegmentReader,
+        _reader: &SegmentReader,
     ) -> crate::Result<Self::Child> {
-        let collector = self.0.for_segment(segment_local_id, reader)?;
+        let after = self.score_after()?;
+        let collector = self.0.for_segment_after(segment_local_id, after);
         Ok(TopScoreSegmentCollector(collector))
     }
 
@@ -625,13 +752,26 @@ impl Collector for TopDocs {
         segment_ord: u32,
         reader: &SegmentReader,
     ) -> crate::Result<<Self::Child as SegmentCollector>::Fruit> {
+        let after = self.score_after()?;
+        let is_collectable = |doc: DocId, score: Score| {
+            if let Some((after_score, after_doc_address)) = after {
+                is_after(
+                    &score,
+                    DocAddress(segment_ord, doc),
+                    &after_score,
+                    after_doc_address,
+                )
+            } else {
+                true
+            }
+        };
         let heap_len = self.0.limit + self.0.offset;
         let mut heap: BinaryHeap<ComparableDoc<Score, DocId>> = BinaryHeap::with_capacity(heap_len);
 
         if let Some(delete_bitset) = reader.delete_bitset() {
             let mut threshold = Score::MIN;
             weight.for_each_pruning(threshold, reader, &mut |doc, score| {
-                if delete_bitset.is_deleted(doc) {
+                if delete_bitset.is_deleted(doc) || !is_collectable(doc, score) {
                     return threshold;
                 }
                 let heap_item = ComparableDoc {
@@ -650,7 +790,11 @@ impl Collector for TopDocs {
                 threshold
             })?;
         } else {
-            weight.for_each_pruning(Score::MIN, reader, &mut |doc, score| {
+            let mut threshold = Score::MIN;
+            weight.for_each_pruning(threshold, reader, &mut |doc, score| {
+                if !is_collectable(doc, score) {
+                    return threshold;
+                }
                 let heap_item = ComparableDoc {
                     feature: score,
                     doc,
@@ -659,13 +803,13 @@ impl Collector for TopDocs {
                     heap.push(heap_item);
                     // TODO the threshold is suboptimal for heap.len == heap_len
                     if heap.len() == heap_len {
-                        return heap.peek().map(|el| el.feature).unwrap_or(Score::MIN);
-                    } else {
-                        return Score::MIN;
+                        threshold = heap.peek().map(|el| el.feature).unwrap_or(Score::MIN);
                     }
+                    return threshold;
                 }
                 *heap.peek_mut().unwrap() = heap_item;
-                heap.peek().map(|el| el.feature).unwrap_or(Score::MIN)
+                threshold = heap.peek().map(|el| el.feature).unwrap_or(Score::MIN);
+                threshold
             })?;
         }
 
@@ -696,12 +840,13 @@ impl SegmentCollector for TopScoreSegmentCollector {
 #[cfg(test)]
 mod tests {
     use super::TopDocs;
-    use crate::collector::Collector;
+    use crate::collector::{Collector, Count, Cursor, SortValue};
     use crate::query::{AllQuery, Query, QueryParser};
     use crate::schema::{Field, Schema, FAST, STORED, TEXT};
     use crate::Index;
     use crate::IndexWriter;
     use crate::Score;
+    use crate::Term;
     use crate::{DocAddress, DocId, SegmentReader};
 
     fn make_index() -> Index {
@@ -1063,6 +1208,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_top_docs_search_after() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        let texts = ["a", "a b", "a a", "b", "a b c", "a a b"];
+        for i in 0..30 {
+            index_writer.add_document(doc!(text => texts[i % texts.len()]));
+            if i % 10 == 9 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.add_document(doc!(text => "a a a c"));
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(text, "c"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text]);
+        for query_str in &["a", "a b", "a c"] {
+            let query = query_parser.parse_query(query_str)?;
+            let all_hits = searcher.search(&query, &TopDocs::with_limit(100))?;
+            assert!(all_hits.len() > 10);
+            // Goes through both `collect_segment` and the segment collectors.
+            for &use_segment_collector in &[false, true] {
+                let mut hits = Vec::new();
+                let mut collector = TopDocs::with_limit(4);
+                loop {
+                    let page = if use_segment_collector {
+                        searcher.search(&query, &(collector, Count))?.0
+                    } else {
+                        searcher.search(&query, &collector)?
+                    };
+                    if let Some(&(score, doc_address)) = page.last() {
+                        collector = TopDocs::with_limit(4)
+                            .search_after(Cursor::from_score(score, doc_address));
+                    } else {
+                        break;
+                    }
+                    hits.extend(page);
+                }
+                assert_eq!(hits, all_hits);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_docs_search_after_invalid_cursor() {
+        let index = make_index();
+        let searcher = index.reader().unwrap().searcher();
+        let cursor = Cursor::new(vec![SortValue::U64(1)], DocAddress(0, 0));
+        let collector = TopDocs::with_limit(2).search_after(cursor);
+        assert!(searcher.search(&AllQuery, &collector).is_err());
+        let collector = TopDocs::with_limit(2)
+            .search_after(Cursor::from_score(1.0, DocAddress(0, 0)))
+            .custom_score(move |_segment_reader: &SegmentReader| move |doc: DocId| doc);
+        assert!(searcher.search(&AllQuery, &collector).is_err());
+    }
+
     fn index(
         query: &str,
         query_field: Field,
@@ -113,6 +113,7 @@ impl SegmentMeta {
             SegmentComponent::POSITIONSSKIP => ".posidx".to_string(),
             SegmentComponent::TERMS => ".term".to_string(),
             SegmentComponent::STORE => ".store".to_string(),
+            SegmentComponent::TERMVECTORS => ".termvec".to_string(),
             SegmentComponent::FASTFIELDS => ".fast".to_string(),
             SegmentComponent::FIELDNORMS => ".fieldnorm".to_string(),
             SegmentComponent::DELETE => format!(".{}.del", self.delete_opstamp().unwrap_or(0)),
@@ -157,6 +157,7 @@ impl InvertedIndexReader {
         Ok(SegmentPostings::from_block_postings(
             block_postings,
             position_stream,
+            self.record_option.has_offsets(),
         ))
     }
 
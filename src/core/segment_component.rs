@@ -24,6 +24,9 @@ pub enum SegmentComponent {
     /// Accessing a document from the store is relatively slow, as it
     /// requires to decompress the entire block it belongs to.
     STORE,
+    /// Terms of the fields of each document, along with their positions.
+    /// Only the fields for which term vectors are enabled are recorded.
+    TERMVECTORS,
     /// Bitset describing which document of the segment is deleted.
     DELETE,
 }
@@ -31,7 +34,7 @@ pub enum SegmentComponent {
 impl SegmentComponent {
     /// Iterates through the components.
     pub fn iterator() -> slice::Iter<'static, SegmentComponent> {
-        static SEGMENT_COMPONENTS: [SegmentComponent; 8] = [
+        static SEGMENT_COMPONENTS: [SegmentComponent; 9] = [
             SegmentComponent::POSTINGS,
             SegmentComponent::POSITIONS,
             SegmentComponent::POSITIONSSKIP,
@@ -39,6 +42,7 @@ impl SegmentComponent {
             SegmentComponent::FIELDNORMS,
             SegmentComponent::TERMS,
             SegmentComponent::STORE,
+            SegmentComponent::TERMVECTORS,
             SegmentComponent::DELETE,
         ];
         SEGMENT_COMPONENTS.iter()
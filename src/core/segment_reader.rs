@@ -3,6 +3,7 @@ use crate::core::InvertedIndexReader;
 use crate::core::Segment;
 use crate::core::SegmentComponent;
 use crate::core::SegmentId;
+use crate::directory::error::OpenReadError;
 use crate::directory::FileSlice;
 use crate::fastfield::DeleteBitSet;
 use crate::fastfield::FacetReader;
@@ -14,6 +15,7 @@ use crate::schema::{Field, IndexRecordOption};
 use crate::space_usage::SegmentSpaceUsage;
 use crate::store::StoreReader;
 use crate::termdict::TermDictionary;
+use crate::termvector::{TermVector, TermVectorsReader};
 use crate::DocId;
 use crate::{common::CompositeFile, error::DataCorruption};
 use fail::fail_point;
@@ -29,6 +31,7 @@ use std::{collections::HashMap, io};
 /// - store
 /// - fast field readers
 /// - field norm reader
+/// - term vectors
 ///
 /// The segment reader has a very low memory footprint,
 /// as close to all of the memory data is mmapped.
@@ -51,6 +54,7 @@ pub struct SegmentReader {
     fieldnorm_readers: FieldNormReaders,
 
     store_file: FileSlice,
+    term_vectors_reader: TermVectorsReader,
     delete_bitset_opt: Option<DeleteBitSet>,
     schema: Schema,
 }
@@ -108,9 +112,13 @@ impl SegmentReader {
     /// Accessor to the `FacetReader` associated to a given `Field`.
     pub fn facet_reader(&self, field: Field) -> crate::Result<FacetReader> {
         let field_entry = self.schema.get_field_entry(field);
-        if field_entry.field_type() != &FieldType::HierarchicalFacet {
+        let is_fast_facet = match field_entry.field_type() {
+            FieldType::HierarchicalFacet(ref options) => options.is_fast(),
+            _ => false,
+        };
+        if !is_fast_facet {
             return Err(crate::TantivyError::InvalidArgument(format!(
-                "Field {:?} is not a facet field.",
+                "Field {:?} is not a fast facet field.",
                 field_entry.name()
             )));
         }
@@ -135,7 +143,15 @@ impl SegmentReader {
     ///
     /// They are simply stored as a fast field, serialized in
     /// the `.fieldnorm` file of the segment.
+    ///
+    /// If the field norms of an indexed field were omitted
+    /// (See `TextFieldIndexing::set_fieldnorms`), a reader returning a constant
+    /// fieldnorm of `1` for all documents is returned.
     pub fn get_fieldnorms_reader(&self, field: Field) -> crate::Result<FieldNormReader> {
+        let field_entry = self.schema.get_field_entry(field);
+        if field_entry.is_indexed() && !field_entry.has_fieldnorms() {
+            return Ok(FieldNormReader::constant(self.max_doc(), 1));
+        }
         self.fieldnorm_readers.get_field(field)?.ok_or_else(|| {
             let field_name = self.schema.get_field_name(field);
             let err_msg = format!(
@@ -151,6 +167,27 @@ impl SegmentReader {
         StoreReader::open(self.store_file.clone())
     }
 
+    /// Returns the term vector of a field of a document: the terms of the field
+    /// in this document, with their positions.
+    ///
+    /// Returns a `SchemaError` if the term vectors of the field are not recorded
+    /// (See `TextFieldIndexing::set_term_vectors`).
+    /// The term vector is empty if the document does not contain the field.
+    pub fn term_vector(&self, doc: DocId, field: Field) -> crate::Result<TermVector> {
+        let field_entry = self.schema.get_field_entry(field);
+        if !field_entry.has_term_vectors() {
+            return Err(crate::TantivyError::SchemaError(format!(
+                "Field {:?} does not record term vectors.",
+                field_entry.name()
+            )));
+        }
+        self.term_vectors_reader.term_vector(doc, field)
+    }
+
+    pub(crate) fn term_vectors_reader(&self) -> &TermVectorsReader {
+        &self.term_vectors_reader
+    }
+
     /// Open a new segment for reading.
     pub fn open(segment: &Segment) -> crate::Result<SegmentReader> {
         let termdict_file = segment.open_read(SegmentComponent::TERMS)?;
@@ -179,6 +216,13 @@ impl SegmentReader {
             }
         };
 
+        // Segments without any field recording term vectors do not have a term vectors file.
+        let term_vectors_reader = match segment.open_read(SegmentComponent::TERMVECTORS) {
+            Ok(term_vectors_file) => TermVectorsReader::open(term_vectors_file)?,
+            Err(OpenReadError::FileDoesNotExist(_)) => TermVectorsReader::empty(),
+            Err(err) => return Err(err.into()),
+        };
+
         let schema = segment.schema();
 
         let fast_fields_data = segment.open_read(SegmentComponent::FASTFIELDS)?;
@@ -207,6 +251,7 @@ impl SegmentReader {
             fieldnorm_readers,
             segment_id: segment.id(),
             store_file,
+            term_vectors_reader,
             delete_bitset_opt,
             positions_composite,
             positions_idx_composite,
@@ -325,6 +370,7 @@ impl SegmentReader {
             self.fast_fields_readers.space_usage(),
             self.fieldnorm_readers.space_usage(),
             self.get_store_reader()?.space_usage(),
+            self.term_vectors_reader.space_usage(),
             self.delete_bitset_opt
                 .as_ref()
                 .map(DeleteBitSet::space_usage)
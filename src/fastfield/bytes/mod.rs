@@ -1,14 +1,21 @@
+mod multivalued_reader;
+mod multivalued_writer;
 mod reader;
 mod writer;
 
+pub use self::multivalued_reader::MultiValueBytesFastFieldReader;
+pub use self::multivalued_writer::MultiValueBytesFastFieldWriter;
 pub use self::reader::BytesFastFieldReader;
 pub use self::writer::BytesFastFieldWriter;
 
 #[cfg(test)]
 mod tests {
-    use crate::schema::{BytesOptions, IndexRecordOption, Schema, Value};
+    use super::MultiValueBytesFastFieldReader;
+    use crate::collector::{Collector, SegmentCollector};
+    use crate::query::AllQuery;
+    use crate::schema::{BytesOptions, Cardinality, Field, IndexRecordOption, Schema, Value};
     use crate::{query::TermQuery, schema::FAST, schema::INDEXED, schema::STORED};
-    use crate::{DocAddress, DocSet, Index, Searcher, Term};
+    use crate::{DocAddress, DocId, DocSet, Index, Score, Searcher, SegmentReader, Term};
     use std::ops::Deref;
 
     #[test]
@@ -116,4 +123,87 @@ mod tests {
         assert_eq!(fast_field_reader.get_bytes(0u32), b"tantivy");
         Ok(())
     }
+
+    #[test]
+    fn test_multivalued_bytes() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let bytes_field = schema_builder.add_bytes_field(
+            "bytesfield",
+            BytesOptions::default().set_fast_with_cardinality(Cardinality::MultiValues),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(bytes_field=>vec![0u8, 1], bytes_field=>vec![2u8]));
+        index_writer.add_document(doc!());
+        index_writer.add_document(doc!(bytes_field=>vec![], bytes_field=>vec![255u8; 300]));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let fast_fields = searcher.segment_reader(0).fast_fields();
+        assert!(fast_fields.bytes(bytes_field).is_none());
+        let bytes_reader = fast_fields.multivalue_bytes(bytes_field).unwrap();
+        assert_eq!(bytes_reader.total_num_vals(), 4);
+        assert_eq!(bytes_reader.total_num_bytes(), 303);
+        let vals: Vec<&[u8]> = bytes_reader.get_vals(0).collect();
+        assert_eq!(vals, vec![&[0u8, 1][..], &[2u8][..]]);
+        assert_eq!(bytes_reader.num_vals(1), 0);
+        assert_eq!(bytes_reader.get_vals(1).count(), 0);
+        let vals: Vec<&[u8]> = bytes_reader.get_vals(2).collect();
+        assert_eq!(vals, vec![&[][..], &[255u8; 300][..]]);
+
+        // Collectors can go through all of the byte arrays of the matching documents.
+        let num_bytes = searcher.search(&AllQuery, &NumBytesCollector { field: bytes_field })?;
+        assert_eq!(num_bytes, 303);
+        Ok(())
+    }
+
+    struct NumBytesCollector {
+        field: Field,
+    }
+
+    struct NumBytesSegmentCollector {
+        num_bytes: usize,
+        reader: MultiValueBytesFastFieldReader,
+    }
+
+    impl Collector for NumBytesCollector {
+        type Fruit = usize;
+        type Child = NumBytesSegmentCollector;
+
+        fn for_segment(
+            &self,
+            _segment_local_id: u32,
+            segment_reader: &SegmentReader,
+        ) -> crate::Result<NumBytesSegmentCollector> {
+            let reader = segment_reader
+                .fast_fields()
+                .multivalue_bytes(self.field)
+                .unwrap();
+            Ok(NumBytesSegmentCollector {
+                num_bytes: 0,
+                reader,
+            })
+        }
+
+        fn requires_scoring(&self) -> bool {
+            false
+        }
+
+        fn merge_fruits(&self, children: Vec<usize>) -> crate::Result<usize> {
+            Ok(children.into_iter().sum())
+        }
+    }
+
+    impl SegmentCollector for NumBytesSegmentCollector {
+        type Fruit = usize;
+
+        fn collect(&mut self, doc: DocId, _score: Score) {
+            for bytes in self.reader.get_vals(doc) {
+                self.num_bytes += bytes.len();
+            }
+        }
+
+        fn harvest(self) -> usize {
+            self.num_bytes
+        }
+    }
 }
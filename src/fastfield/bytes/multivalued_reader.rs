@@ -0,0 +1,58 @@
+use crate::fastfield::{BytesFastFieldReader, FastFieldReader};
+use crate::DocId;
+
+/// Reader for multi-valued byte array fast fields
+///
+/// The reader is implemented as a `u64` fast field and a bytes fast field.
+///
+/// The `vals_reader` will access the concatenated list of all byte arrays for all documents.
+///
+/// The `idx_reader` associates, for each document, the index of its first byte array.
+#[derive(Clone)]
+pub struct MultiValueBytesFastFieldReader {
+    idx_reader: FastFieldReader<u64>,
+    vals_reader: BytesFastFieldReader,
+}
+
+impl MultiValueBytesFastFieldReader {
+    pub(crate) fn open(
+        idx_reader: FastFieldReader<u64>,
+        vals_reader: BytesFastFieldReader,
+    ) -> MultiValueBytesFastFieldReader {
+        MultiValueBytesFastFieldReader {
+            idx_reader,
+            vals_reader,
+        }
+    }
+
+    /// Returns `(start, stop)`, such that the byte arrays associated
+    /// to the given document are `start..stop`.
+    fn range(&self, doc: DocId) -> (u64, u64) {
+        let start = self.idx_reader.get(doc);
+        let stop = self.idx_reader.get(doc + 1);
+        (start, stop)
+    }
+
+    /// Returns an iterator over the byte arrays associated to the given `doc`,
+    /// in the order in which they were added.
+    pub fn get_vals(&self, doc: DocId) -> impl Iterator<Item = &[u8]> {
+        let (start, stop) = self.range(doc);
+        (start..stop).map(move |val_ord| self.vals_reader.get_bytes(val_ord as DocId))
+    }
+
+    /// Returns the number of byte arrays associated with the document `DocId`.
+    pub fn num_vals(&self, doc: DocId) -> usize {
+        let (start, stop) = self.range(doc);
+        (stop - start) as usize
+    }
+
+    /// Returns the overall number of byte arrays in this field.
+    pub fn total_num_vals(&self) -> u64 {
+        self.idx_reader.max_value()
+    }
+
+    /// Returns the overall number of bytes in this field.
+    pub fn total_num_bytes(&self) -> usize {
+        self.vals_reader.total_num_bytes()
+    }
+}
@@ -0,0 +1,106 @@
+use std::io;
+
+use crate::fastfield::serializer::FastFieldSerializer;
+use crate::schema::{Document, Field, Value};
+use crate::DocId;
+
+/// Writer for multi-valued byte array fast fields
+/// (as in, any number of byte arrays per document).
+///
+/// This `MultiValueBytesFastFieldWriter` is only useful for advanced user.
+/// The normal way to get your associated bytes in your index
+/// is to
+/// - declare your field with fast set to `Cardinality::MultiValues`
+///   in your schema
+/// - add your document simply by calling `.add_document(...)`.
+///
+/// The `MultiValueBytesFastFieldWriter` can be acquired from the
+/// fast field writer by calling
+/// [`.get_multivalue_bytes_writer(...)`](./struct.FastFieldsWriter.html#method.get_multivalue_bytes_writer).
+///
+/// Once acquired, writing is done by calling `.add_document_vals(&[&[u8]])`
+/// once per document, even if there are no bytes associated to it.
+pub struct MultiValueBytesFastFieldWriter {
+    field: Field,
+    vals: Vec<u8>,
+    val_index: Vec<u64>,
+    doc_index: Vec<u64>,
+}
+
+impl MultiValueBytesFastFieldWriter {
+    /// Creates a new `MultiValueBytesFastFieldWriter`
+    pub fn new(field: Field) -> Self {
+        MultiValueBytesFastFieldWriter {
+            field,
+            vals: Vec::new(),
+            val_index: Vec::new(),
+            doc_index: Vec::new(),
+        }
+    }
+
+    /// Access the field associated to the `MultiValueBytesFastFieldWriter`
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Finalize the current document.
+    pub(crate) fn next_doc(&mut self) {
+        self.doc_index.push(self.val_index.len() as u64);
+    }
+
+    /// Pushes a new byte array to the current document.
+    fn add_val(&mut self, val: &[u8]) {
+        self.val_index.push(self.vals.len() as u64);
+        self.vals.extend_from_slice(val);
+    }
+
+    /// Shift to the next document and add all of the
+    /// matching field values present in the document.
+    pub fn add_document(&mut self, doc: &Document) {
+        self.next_doc();
+        for field_value in doc.get_all(self.field) {
+            if let Value::Bytes(ref bytes) = field_value {
+                self.add_val(bytes);
+            }
+        }
+    }
+
+    /// Register all of the byte arrays associated to a document.
+    ///
+    /// The method returns the `DocId` of the document that was
+    /// just written.
+    pub fn add_document_vals(&mut self, vals: &[&[u8]]) -> DocId {
+        let doc = self.doc_index.len() as DocId;
+        self.next_doc();
+        for val in vals {
+            self.add_val(val);
+        }
+        doc
+    }
+
+    /// Serializes the fast field values by pushing them to the `FastFieldSerializer`.
+    pub fn serialize(&self, serializer: &mut FastFieldSerializer) -> io::Result<()> {
+        let num_vals = self.val_index.len() as u64;
+        // writing the index of the first value of each document
+        let mut doc_index_serializer =
+            serializer.new_u64_fast_field_with_idx(self.field, 0, num_vals, 0)?;
+        for &val_ord in &self.doc_index {
+            doc_index_serializer.add_val(val_ord)?;
+        }
+        doc_index_serializer.add_val(num_vals)?;
+        doc_index_serializer.close_field()?;
+        // writing the offset of each value
+        let mut val_index_serializer =
+            serializer.new_u64_fast_field_with_idx(self.field, 0, self.vals.len() as u64, 1)?;
+        for &offset in &self.val_index {
+            val_index_serializer.add_val(offset)?;
+        }
+        val_index_serializer.add_val(self.vals.len() as u64)?;
+        val_index_serializer.close_field()?;
+        // writing the values themselves
+        serializer
+            .new_bytes_fast_field_with_idx(self.field, 2)?
+            .write_all(&self.vals)?;
+        Ok(())
+    }
+}
@@ -80,11 +80,11 @@ impl FacetReader {
 
 #[cfg(test)]
 mod tests {
-    use crate::Index;
-    use crate::{
-        schema::{Facet, SchemaBuilder},
-        Document,
-    };
+    use crate::collector::FacetCollector;
+    use crate::query::AllQuery;
+    use crate::schema::{Facet, SchemaBuilder, Value, FAST, INDEXED, STORED};
+    use crate::{DocAddress, Document, Index};
+    use futures::executor::block_on;
 
     #[test]
     fn test_facet_not_populated_for_all_docs() -> crate::Result<()> {
@@ -130,4 +130,75 @@ mod tests {
         assert!(facet_ords.is_empty());
         Ok(())
     }
+
+    #[test]
+    fn test_facet_stored_and_not_stored() -> crate::Result<()> {
+        let mut schema_builder = SchemaBuilder::default();
+        let indexed_facet =
+            schema_builder.add_facet_field_with_options("indexed_facet", INDEXED | FAST);
+        let stored_facet =
+            schema_builder.add_facet_field_with_options("stored_facet", INDEXED | STORED);
+        let only_stored_facet =
+            schema_builder.add_facet_field_with_options("only_stored_facet", STORED);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(
+            indexed_facet => Facet::from_text("/a/b"),
+            stored_facet => Facet::from_text("/c/d"),
+            only_stored_facet => Facet::from_text("/e/f"),
+        ));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let doc = searcher.doc(DocAddress(0u32, 0u32))?;
+        assert!(doc.get_first(indexed_facet).is_none());
+        assert_eq!(
+            doc.get_first(stored_facet),
+            Some(&Value::Facet(Facet::from_text("/c/d")))
+        );
+        assert_eq!(
+            doc.get_first(only_stored_facet),
+            Some(&Value::Facet(Facet::from_text("/e/f")))
+        );
+        let segment_reader = searcher.segment_reader(0u32);
+        let mut facet_ords = Vec::new();
+        segment_reader
+            .facet_reader(indexed_facet)?
+            .facet_ords(0u32, &mut facet_ords);
+        assert_eq!(&facet_ords, &[2u64]);
+        assert!(segment_reader.facet_reader(stored_facet).is_err());
+        assert!(segment_reader.facet_reader(only_stored_facet).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_facet_fast_only() -> crate::Result<()> {
+        let mut schema_builder = SchemaBuilder::default();
+        let facet_field = schema_builder.add_facet_field_with_options("facet", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(facet_field => Facet::from_text("/a/b")));
+        index_writer.add_document(doc!(facet_field => Facet::from_text("/a/c")));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(facet_field => Facet::from_text("/a/b")));
+        index_writer.commit()?;
+        let count_facets = |index: &Index| -> crate::Result<Vec<(String, u64)>> {
+            let searcher = index.reader()?.searcher();
+            let mut facet_collector = FacetCollector::for_field(facet_field);
+            facet_collector.add_facet("/a");
+            let facet_counts = searcher.search(&AllQuery, &facet_collector)?;
+            Ok(facet_counts
+                .get("/a")
+                .map(|(facet, count)| (facet.to_string(), count))
+                .collect())
+        };
+        let expected = vec![("/a/b".to_string(), 2), ("/a/c".to_string(), 1)];
+        assert_eq!(count_facets(&index)?, expected);
+        let segment_ids = index.searchable_segment_ids()?;
+        block_on(index_writer.merge(&segment_ids))?;
+        index_writer.wait_merging_threads()?;
+        assert_eq!(count_facets(&index)?, expected);
+        Ok(())
+    }
 }
@@ -24,6 +24,7 @@ Read access performance is comparable to that of an array lookup.
 */
 
 pub use self::bytes::{BytesFastFieldReader, BytesFastFieldWriter};
+pub use self::bytes::{MultiValueBytesFastFieldReader, MultiValueBytesFastFieldWriter};
 pub use self::delete::write_delete_bitset;
 pub use self::delete::DeleteBitSet;
 pub use self::error::{FastFieldNotAvailableError, Result};
@@ -32,6 +33,7 @@ pub use self::multivalued::{MultiValueIntFastFieldReader, MultiValueIntFastField
 pub use self::reader::FastFieldReader;
 pub use self::readers::FastFieldReaders;
 pub use self::serializer::FastFieldSerializer;
+pub(crate) use self::writer::fast_field_default_value;
 pub use self::writer::{FastFieldsWriter, IntFastFieldWriter};
 use crate::common;
 use crate::schema::Cardinality;
@@ -96,7 +98,9 @@ impl FastValue for u64 {
     fn fast_field_cardinality(field_type: &FieldType) -> Option<Cardinality> {
         match *field_type {
             FieldType::U64(ref integer_options) => integer_options.get_fastfield_cardinality(),
-            FieldType::HierarchicalFacet => Some(Cardinality::MultiValues),
+            FieldType::HierarchicalFacet(ref facet_options) if facet_options.is_fast() => {
+                Some(Cardinality::MultiValues)
+            }
             _ => None,
         }
     }
@@ -59,6 +59,24 @@ impl<Item: FastValue> MultiValueIntFastFieldReader<Item> {
     pub fn total_num_vals(&self) -> u64 {
         self.idx_reader.max_value()
     }
+
+    /// Returns the minimum value for this fast field.
+    ///
+    /// The min value does not take in account of possible
+    /// deleted document, and should be considered as a lower bound
+    /// of the actual minimum value.
+    pub fn min_value(&self) -> Item {
+        self.vals_reader.min_value()
+    }
+
+    /// Returns the maximum value for this fast field.
+    ///
+    /// The max value does not take in account of possible
+    /// deleted document, and should be considered as an upper bound
+    /// of the actual maximum value.
+    pub fn max_value(&self) -> Item {
+        self.vals_reader.max_value()
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 use crate::common::CompositeFile;
 use crate::fastfield::BytesFastFieldReader;
+use crate::fastfield::MultiValueBytesFastFieldReader;
 use crate::fastfield::MultiValueIntFastFieldReader;
 use crate::fastfield::{FastFieldNotAvailableError, FastFieldReader};
 use crate::schema::{Cardinality, Field, FieldType, Schema};
@@ -20,6 +21,7 @@ pub struct FastFieldReaders {
     fast_field_f64s: HashMap<Field, MultiValueIntFastFieldReader<f64>>,
     fast_field_dates: HashMap<Field, MultiValueIntFastFieldReader<crate::DateTime>>,
     fast_bytes: HashMap<Field, BytesFastFieldReader>,
+    fast_bytes_multi: HashMap<Field, MultiValueBytesFastFieldReader>,
     fast_fields_composite: CompositeFile,
 }
 
@@ -44,7 +46,9 @@ fn type_and_cardinality(field_type: &FieldType) -> Option<(FastType, Cardinality
         FieldType::Date(options) => options
             .get_fastfield_cardinality()
             .map(|cardinality| (FastType::Date, cardinality)),
-        FieldType::HierarchicalFacet => Some((FastType::U64, Cardinality::MultiValues)),
+        FieldType::HierarchicalFacet(options) if options.is_fast() => {
+            Some((FastType::U64, Cardinality::MultiValues))
+        }
         _ => None,
     }
 }
@@ -64,25 +68,42 @@ impl FastFieldReaders {
             fast_field_f64s: Default::default(),
             fast_field_dates: Default::default(),
             fast_bytes: Default::default(),
+            fast_bytes_multi: Default::default(),
             fast_fields_composite: fast_fields_composite.clone(),
         };
         for (field, field_entry) in schema.fields() {
             let field_type = field_entry.field_type();
             if let FieldType::Bytes(bytes_option) = field_type {
-                if !bytes_option.is_fast() {
-                    continue;
+                let cardinality = match bytes_option.get_fastfield_cardinality() {
+                    Some(cardinality) => cardinality,
+                    None => continue,
+                };
+                let open_idx = |idx: usize| {
+                    fast_fields_composite
+                        .open_read_with_idx(field, idx)
+                        .ok_or_else(|| FastFieldNotAvailableError::new(field_entry))
+                };
+                match cardinality {
+                    Cardinality::SingleValue => {
+                        let idx_reader = FastFieldReader::open(open_idx(0)?)?;
+                        let bytes_fast_field_reader =
+                            BytesFastFieldReader::open(idx_reader, open_idx(1)?)?;
+                        fast_field_readers
+                            .fast_bytes
+                            .insert(field, bytes_fast_field_reader);
+                    }
+                    Cardinality::MultiValues => {
+                        let idx_reader = FastFieldReader::open(open_idx(0)?)?;
+                        let vals_idx_reader = FastFieldReader::open(open_idx(1)?)?;
+                        let vals_reader =
+                            BytesFastFieldReader::open(vals_idx_reader, open_idx(2)?)?;
+                        let multivalued_bytes_fast_field =
+                            MultiValueBytesFastFieldReader::open(idx_reader, vals_reader);
+                        fast_field_readers
+                            .fast_bytes_multi
+                            .insert(field, multivalued_bytes_fast_field);
+                    }
                 }
-                let fast_field_idx_file = fast_fields_composite
-                    .open_read_with_idx(field, 0)
-                    .ok_or_else(|| FastFieldNotAvailableError::new(field_entry))?;
-                let idx_reader = FastFieldReader::open(fast_field_idx_file)?;
-                let data = fast_fields_composite
-                    .open_read_with_idx(field, 1)
-                    .ok_or_else(|| FastFieldNotAvailableError::new(field_entry))?;
-                let bytes_fast_field_reader = BytesFastFieldReader::open(idx_reader, data)?;
-                fast_field_readers
-                    .fast_bytes
-                    .insert(field, bytes_fast_field_reader);
             } else if let Some((fast_type, cardinality)) = type_and_cardinality(field_type) {
                 match cardinality {
                     Cardinality::SingleValue => {
@@ -230,8 +251,8 @@ impl FastFieldReaders {
     }
 
     /// If the field is a u64s-fast field return the associated reader.
-    /// If the field is a i64s-fast field, return the associated u64s reader. Values are
-    /// mapped from i64 to u64 using a (well the, it is unique) monotonic mapping.
+    /// If the field is a i64s, f64s or dates fast field, return the associated u64s reader. Values are
+    /// mapped to u64 using a (well the, it is unique) monotonic mapping.
     ///
     /// This method is useful when merging segment reader.
     pub(crate) fn u64s_lenient(&self, field: Field) -> Option<MultiValueIntFastFieldReader<u64>> {
@@ -244,6 +265,9 @@ impl FastFieldReaders {
         if let Some(f64s_ff_reader) = self.f64s(field) {
             return Some(f64s_ff_reader.into_u64s_reader());
         }
+        if let Some(dates_ff_reader) = self.dates(field) {
+            return Some(dates_ff_reader.into_u64s_reader());
+        }
         None
     }
 
@@ -274,4 +298,11 @@ impl FastFieldReaders {
     pub fn bytes(&self, field: Field) -> Option<BytesFastFieldReader> {
         self.fast_bytes.get(&field).cloned()
     }
+
+    /// Returns the multi-valued `bytes` fast field reader associated to `field`.
+    ///
+    /// If `field` is not a multi-valued bytes fast field, returns `None`.
+    pub fn multivalue_bytes(&self, field: Field) -> Option<MultiValueBytesFastFieldReader> {
+        self.fast_bytes_multi.get(&field).cloned()
+    }
 }
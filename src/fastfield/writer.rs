@@ -2,7 +2,7 @@ use super::multivalued::MultiValueIntFastFieldWriter;
 use crate::common;
 use crate::common::BinarySerializable;
 use crate::common::VInt;
-use crate::fastfield::{BytesFastFieldWriter, FastFieldSerializer};
+use crate::fastfield::{BytesFastFieldWriter, FastFieldSerializer, MultiValueBytesFastFieldWriter};
 use crate::postings::UnorderedTermId;
 use crate::schema::{Cardinality, Document, Field, FieldEntry, FieldType, Schema};
 use crate::termdict::TermOrdinal;
@@ -15,9 +15,12 @@ pub struct FastFieldsWriter {
     single_value_writers: Vec<IntFastFieldWriter>,
     multi_values_writers: Vec<MultiValueIntFastFieldWriter>,
     bytes_value_writers: Vec<BytesFastFieldWriter>,
+    multi_bytes_value_writers: Vec<MultiValueBytesFastFieldWriter>,
 }
 
-fn fast_field_default_value(field_entry: &FieldEntry) -> u64 {
+/// Returns the value recorded in a single-valued fast field
+/// for the documents without any value.
+pub(crate) fn fast_field_default_value(field_entry: &FieldEntry) -> u64 {
     match *field_entry.field_type() {
         FieldType::I64(_) | FieldType::Date(_) => common::i64_to_u64(0i64),
         FieldType::F64(_) => common::f64_to_u64(0.0f64),
@@ -31,6 +34,7 @@ impl FastFieldsWriter {
         let mut single_value_writers = Vec::new();
         let mut multi_values_writers = Vec::new();
         let mut bytes_value_writers = Vec::new();
+        let mut multi_bytes_value_writers = Vec::new();
 
         for (field, field_entry) in schema.fields() {
             match field_entry.field_type() {
@@ -52,16 +56,21 @@ impl FastFieldsWriter {
                         None => {}
                     }
                 }
-                FieldType::HierarchicalFacet => {
+                FieldType::HierarchicalFacet(facet_options) if facet_options.is_fast() => {
                     let fast_field_writer = MultiValueIntFastFieldWriter::new(field, true);
                     multi_values_writers.push(fast_field_writer);
                 }
-                FieldType::Bytes(bytes_option) => {
-                    if bytes_option.is_fast() {
+                FieldType::Bytes(bytes_option) => match bytes_option.get_fastfield_cardinality() {
+                    Some(Cardinality::SingleValue) => {
                         let fast_field_writer = BytesFastFieldWriter::new(field);
                         bytes_value_writers.push(fast_field_writer);
                     }
-                }
+                    Some(Cardinality::MultiValues) => {
+                        let fast_field_writer = MultiValueBytesFastFieldWriter::new(field);
+                        multi_bytes_value_writers.push(fast_field_writer);
+                    }
+                    None => {}
+                },
                 _ => {}
             }
         }
@@ -69,6 +78,7 @@ impl FastFieldsWriter {
             single_value_writers,
             multi_values_writers,
             bytes_value_writers,
+            multi_bytes_value_writers,
         }
     }
 
@@ -105,6 +115,19 @@ impl FastFieldsWriter {
             .find(|field_writer| field_writer.field() == field)
     }
 
+    /// Returns the multi-valued bytes fast field writer for the given field.
+    ///
+    /// Returns None if the field does not exist, or is not
+    /// configured as a multi-valued bytes fastfield in the schema.
+    pub fn get_multivalue_bytes_writer(
+        &mut self,
+        field: Field,
+    ) -> Option<&mut MultiValueBytesFastFieldWriter> {
+        self.multi_bytes_value_writers
+            .iter_mut()
+            .find(|field_writer| field_writer.field() == field)
+    }
+
     /// Indexes all of the fastfields of a new document.
     pub fn add_document(&mut self, doc: &Document) {
         for field_writer in &mut self.single_value_writers {
@@ -116,6 +139,9 @@ impl FastFieldsWriter {
         for field_writer in &mut self.bytes_value_writers {
             field_writer.add_document(doc);
         }
+        for field_writer in &mut self.multi_bytes_value_writers {
+            field_writer.add_document(doc);
+        }
     }
 
     /// Serializes all of the `FastFieldWriter`s by pushing them in
@@ -136,6 +162,9 @@ impl FastFieldsWriter {
         for field_writer in &self.bytes_value_writers {
             field_writer.serialize(serializer)?;
         }
+        for field_writer in &self.multi_bytes_value_writers {
+            field_writer.serialize(serializer)?;
+        }
         Ok(())
     }
 }
@@ -61,16 +61,24 @@ impl FieldNormReaders {
 /// precompute computationally expensive functions of the fieldnorm
 /// in a very short array.
 #[derive(Clone)]
-pub struct FieldNormReader {
-    data: OwnedBytes,
+pub struct FieldNormReader(FieldNormReaderImpl);
+
+#[derive(Clone)]
+enum FieldNormReaderImpl {
+    Data(OwnedBytes),
+    Constant { num_docs: u32, fieldnorm_id: u8 },
 }
 
 impl FieldNormReader {
     /// Creates a `FieldNormReader` with a constant fieldnorm.
+    ///
+    /// The reader does not allocate any per-document data.
     pub fn constant(num_docs: u32, fieldnorm: u32) -> FieldNormReader {
         let fieldnorm_id = fieldnorm_to_id(fieldnorm);
-        let field_norms_data = OwnedBytes::new(vec![fieldnorm_id; num_docs as usize]);
-        FieldNormReader::new(field_norms_data)
+        FieldNormReader(FieldNormReaderImpl::Constant {
+            num_docs,
+            fieldnorm_id,
+        })
     }
 
     /// Opens a field norm reader given its file.
@@ -80,12 +88,15 @@ impl FieldNormReader {
     }
 
     fn new(data: OwnedBytes) -> Self {
-        FieldNormReader { data }
+        FieldNormReader(FieldNormReaderImpl::Data(data))
     }
 
     /// Returns the number of documents in this segment.
     pub fn num_docs(&self) -> u32 {
-        self.data.len() as u32
+        match &self.0 {
+            FieldNormReaderImpl::Data(data) => data.len() as u32,
+            FieldNormReaderImpl::Constant { num_docs, .. } => *num_docs,
+        }
     }
 
     /// Returns the `fieldnorm` associated to a doc id.
@@ -105,7 +116,10 @@ impl FieldNormReader {
     /// Returns the `fieldnorm_id` associated to a document.
     #[inline(always)]
     pub fn fieldnorm_id(&self, doc_id: DocId) -> u8 {
-        self.data.as_slice()[doc_id as usize]
+        match &self.0 {
+            FieldNormReaderImpl::Data(data) => data.as_slice()[doc_id as usize],
+            FieldNormReaderImpl::Constant { fieldnorm_id, .. } => *fieldnorm_id,
+        }
     }
 
     /// Converts a `fieldnorm_id` into a fieldnorm.
@@ -129,9 +143,7 @@ impl FieldNormReader {
             .map(FieldNormReader::fieldnorm_to_id)
             .collect::<Vec<u8>>();
         let field_norms_data = OwnedBytes::new(field_norms_id);
-        FieldNormReader {
-            data: field_norms_data,
-        }
+        FieldNormReader::new(field_norms_data)
     }
 }
 
@@ -150,4 +162,16 @@ mod tests {
         assert_eq!(fieldnorm_reader.fieldnorm(3), 4);
         assert_eq!(fieldnorm_reader.fieldnorm(4), 983_064);
     }
+
+    #[test]
+    fn test_constant_fieldnorm_reader() {
+        let fieldnorm_reader = FieldNormReader::constant(3, 10);
+        assert_eq!(fieldnorm_reader.num_docs(), 3);
+        assert_eq!(fieldnorm_reader.fieldnorm(0), 10);
+        assert_eq!(fieldnorm_reader.fieldnorm(2), 10);
+        assert_eq!(
+            fieldnorm_reader.fieldnorm_id(1),
+            FieldNormReader::fieldnorm_to_id(10)
+        );
+    }
 }
@@ -7,6 +7,7 @@ use crate::fastfield::BytesFastFieldReader;
 use crate::fastfield::DeleteBitSet;
 use crate::fastfield::FastFieldReader;
 use crate::fastfield::FastFieldSerializer;
+use crate::fastfield::MultiValueBytesFastFieldReader;
 use crate::fastfield::MultiValueIntFastFieldReader;
 use crate::fieldnorm::FieldNormsSerializer;
 use crate::fieldnorm::FieldNormsWriter;
@@ -20,6 +21,7 @@ use crate::schema::{Field, Schema};
 use crate::store::StoreWriter;
 use crate::termdict::TermMerger;
 use crate::termdict::TermOrdinal;
+use crate::termvector::TermVectorsSerializer;
 use crate::{DocId, InvertedIndexReader, SegmentComponent};
 use std::cmp;
 use std::collections::HashMap;
@@ -29,7 +31,13 @@ fn compute_total_num_tokens(readers: &[SegmentReader], field: Field) -> crate::R
     let mut total_tokens = 0u64;
     let mut count: [usize; 256] = [0; 256];
     for reader in readers {
-        if reader.has_deletes() {
+        let has_fieldnorms = reader.schema().get_field_entry(field).has_fieldnorms();
+        if reader.has_deletes() && !has_fieldnorms {
+            // without fieldnorms, we remove the deleted documents proportionally.
+            let total_num_tokens = reader.inverted_index(field)?.total_num_tokens();
+            total_tokens += total_num_tokens * u64::from(reader.num_docs())
+                / u64::from(reader.max_doc().max(1));
+        } else if reader.has_deletes() {
             // if there are deletes, then we use an approximation
             // using the fieldnorm
             let fieldnorms_reader = reader.get_fieldnorms_reader(field)?;
@@ -195,7 +203,7 @@ impl IndexMerger {
         for (field, field_entry) in self.schema.fields() {
             let field_type = field_entry.field_type();
             match field_type {
-                FieldType::HierarchicalFacet => {
+                FieldType::HierarchicalFacet(ref options) if options.is_fast() => {
                     let term_ordinal_mapping = term_ord_mappings
                         .remove(&field)
                         .expect("Logic Error in Tantivy (Please report). HierarchicalFact field should have required a\
@@ -218,16 +226,23 @@ impl IndexMerger {
                     }
                     None => {}
                 },
+                FieldType::HierarchicalFacet(_) => {
+                    // Facets which are not fast do not have any fast field.
+                }
                 FieldType::Str(_) => {
                     // We don't handle str fast field for the moment
                     // They can be implemented using what is done
                     // for facets in the future.
                 }
-                FieldType::Bytes(byte_options) => {
-                    if byte_options.is_fast() {
+                FieldType::Bytes(byte_options) => match byte_options.get_fastfield_cardinality() {
+                    Some(Cardinality::SingleValue) => {
                         self.write_bytes_fast_field(field, fast_field_serializer)?;
                     }
-                }
+                    Some(Cardinality::MultiValues) => {
+                        self.write_multi_bytes_fast_field(field, fast_field_serializer)?;
+                    }
+                    None => {}
+                },
             }
         }
         Ok(())
@@ -493,6 +508,83 @@ impl IndexMerger {
         Ok(())
     }
 
+    fn write_multi_bytes_fast_field(
+        &self,
+        field: Field,
+        fast_field_serializer: &mut FastFieldSerializer,
+    ) -> crate::Result<()> {
+        let mut total_num_vals = 0u64;
+        let mut total_num_bytes = 0u64;
+        let mut bytes_readers: Vec<MultiValueBytesFastFieldReader> = Vec::new();
+
+        for reader in &self.readers {
+            let bytes_reader = reader
+                .fast_fields()
+                .multivalue_bytes(field)
+                .ok_or_else(|| {
+                    crate::TantivyError::InvalidArgument(format!(
+                        "Multivalued bytes fast field {:?} not found in segment.",
+                        field
+                    ))
+                })?;
+            if reader.has_deletes() {
+                for doc in reader.doc_ids_alive() {
+                    for val in bytes_reader.get_vals(doc) {
+                        total_num_vals += 1;
+                        total_num_bytes += val.len() as u64;
+                    }
+                }
+            } else {
+                total_num_vals += bytes_reader.total_num_vals();
+                total_num_bytes += bytes_reader.total_num_bytes() as u64;
+            }
+            bytes_readers.push(bytes_reader);
+        }
+
+        {
+            // The index of the first value of each document.
+            let mut serialize_idx =
+                fast_field_serializer.new_u64_fast_field_with_idx(field, 0, total_num_vals, 0)?;
+            let mut idx = 0;
+            for (segment_reader, bytes_reader) in self.readers.iter().zip(&bytes_readers) {
+                for doc in segment_reader.doc_ids_alive() {
+                    serialize_idx.add_val(idx)?;
+                    idx += bytes_reader.num_vals(doc) as u64;
+                }
+            }
+            serialize_idx.add_val(idx)?;
+            serialize_idx.close_field()?;
+        }
+
+        {
+            // The offset of each value.
+            let mut serialize_vals_idx =
+                fast_field_serializer.new_u64_fast_field_with_idx(field, 0, total_num_bytes, 1)?;
+            let mut offset = 0;
+            for (segment_reader, bytes_reader) in self.readers.iter().zip(&bytes_readers) {
+                for doc in segment_reader.doc_ids_alive() {
+                    for val in bytes_reader.get_vals(doc) {
+                        serialize_vals_idx.add_val(offset)?;
+                        offset += val.len() as u64;
+                    }
+                }
+            }
+            serialize_vals_idx.add_val(offset)?;
+            serialize_vals_idx.close_field()?;
+        }
+
+        let mut serialize_vals = fast_field_serializer.new_bytes_fast_field_with_idx(field, 2)?;
+        for (segment_reader, bytes_reader) in self.readers.iter().zip(&bytes_readers) {
+            for doc in segment_reader.doc_ids_alive() {
+                for val in bytes_reader.get_vals(doc) {
+                    serialize_vals.write_all(val)?;
+                }
+            }
+        }
+        serialize_vals.flush()?;
+        Ok(())
+    }
+
     fn write_postings_for_field(
         &self,
         indexed_field: Field,
@@ -501,6 +593,7 @@ impl IndexMerger {
         fieldnorm_reader: Option<FieldNormReader>,
     ) -> crate::Result<Option<TermOrdinalMapping>> {
         let mut positions_buffer: Vec<u32> = Vec::with_capacity(1_000);
+        let mut offsets_buffer: Vec<(u32, u32)> = Vec::new();
         let mut delta_computer = DeltaComputer::new();
 
         let mut field_term_streams = Vec::new();
@@ -518,7 +611,7 @@ impl IndexMerger {
             max_term_ords.push(terms.num_terms() as u64);
         }
 
-        let mut term_ord_mapping_opt = if *field_type == FieldType::HierarchicalFacet {
+        let mut term_ord_mapping_opt = if let FieldType::HierarchicalFacet(_) = field_type {
             Some(TermOrdinalMapping::new(max_term_ords))
         } else {
             None
@@ -632,7 +725,21 @@ impl IndexMerger {
                         segment_postings.positions(&mut positions_buffer);
 
                         let delta_positions = delta_computer.compute_delta(&positions_buffer);
-                        field_serializer.write_doc(remapped_doc_id, term_freq, delta_positions)?;
+                        if segment_postings_option.has_offsets() {
+                            segment_postings.offsets(&mut offsets_buffer);
+                            field_serializer.write_doc_with_offsets(
+                                remapped_doc_id,
+                                term_freq,
+                                delta_positions,
+                                &offsets_buffer,
+                            )?;
+                        } else {
+                            field_serializer.write_doc(
+                                remapped_doc_id,
+                                term_freq,
+                                delta_positions,
+                            )?;
+                        }
                     }
 
                     doc = segment_postings.advance();
@@ -654,7 +761,7 @@ impl IndexMerger {
         let mut term_ordinal_mappings = HashMap::new();
         for (field, field_entry) in self.schema.fields() {
             let fieldnorm_reader = fieldnorm_readers.get_field(field)?;
-            if field_entry.is_indexed() {
+            if field_entry.field_type().get_index_record_option().is_some() {
                 if let Some(term_ordinal_mapping) = self.write_postings_for_field(
                     field,
                     field_entry.field_type(),
@@ -682,6 +789,20 @@ impl IndexMerger {
         }
         Ok(())
     }
+
+    fn write_term_vectors(
+        &self,
+        term_vectors_serializer: &mut TermVectorsSerializer,
+    ) -> crate::Result<()> {
+        for reader in &self.readers {
+            let term_vectors_reader = reader.term_vectors_reader();
+            for doc_id in reader.doc_ids_alive() {
+                let doc_data = term_vectors_reader.doc_data(doc_id)?;
+                term_vectors_serializer.store_doc(doc_data.as_slice())?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl SerializableSegment for IndexMerger {
@@ -697,6 +818,9 @@ impl SerializableSegment for IndexMerger {
             self.write_postings(serializer.get_postings_serializer(), fieldnorm_readers)?;
         self.write_fast_fields(serializer.get_fast_field_serializer(), term_ord_mappings)?;
         self.write_storable_fields(serializer.get_store_writer())?;
+        if let Some(term_vectors_serializer) = serializer.get_term_vectors_serializer() {
+            self.write_term_vectors(term_vectors_serializer)?;
+        }
         serializer.close()?;
         Ok(self.max_doc)
     }
@@ -711,6 +835,7 @@ mod tests {
     use crate::core::Index;
     use crate::query::AllQuery;
     use crate::query::BooleanQuery;
+    use crate::query::PhraseQuery;
     use crate::query::Scorer;
     use crate::query::TermQuery;
     use crate::schema::Document;
@@ -724,7 +849,7 @@ mod tests {
     use crate::DocAddress;
     use crate::IndexWriter;
     use crate::Searcher;
-    use crate::{schema, DocSet, SegmentId};
+    use crate::{schema, DocSet, Postings, SegmentId, TERMINATED};
     use byteorder::{BigEndian, ReadBytesExt};
     use futures::executor::block_on;
     use schema::FAST;
@@ -1584,4 +1709,116 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_merge_preserves_offsets() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_options = schema::TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("default")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositionsAndOffsets),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "hello happy tax payer"));
+        index_writer.add_document(doc!(text => "happy happy"));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "a happy tax"));
+        index_writer.add_document(doc!(text => "unhappy tax"));
+        index_writer.delete_term(Term::from_field_text(text, "unhappy"));
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        block_on(index_writer.merge(&segment_ids))?;
+        index_writer.wait_merging_threads()?;
+
+        let reader = index.reader()?;
+        let searcher = reader.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        let inverted_index = searcher.segment_reader(0u32).inverted_index(text)?;
+        let mut postings = inverted_index
+            .read_postings(
+                &Term::from_field_text(text, "happy"),
+                IndexRecordOption::WithFreqsAndPositionsAndOffsets,
+            )?
+            .unwrap();
+        let mut positions = Vec::new();
+        let mut offsets = Vec::new();
+        let mut docs = Vec::new();
+        while postings.doc() != TERMINATED {
+            postings.positions(&mut positions);
+            postings.offsets(&mut offsets);
+            docs.push((postings.doc(), positions.clone(), offsets.clone()));
+            postings.advance();
+        }
+        assert_eq!(
+            docs,
+            vec![
+                (0, vec![1], vec![(6, 11)]),
+                (1, vec![0, 1], vec![(0, 5), (6, 11)]),
+                (2, vec![1], vec![(2, 7)]),
+            ]
+        );
+        let phrase_query = PhraseQuery::new(vec![
+            Term::from_field_text(text, "happy"),
+            Term::from_field_text(text, "tax"),
+        ]);
+        assert_eq!(searcher.search(&phrase_query, &Count)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_multivalued_bytes_fast_field() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let bytes_field = schema_builder.add_bytes_field(
+            "bytes",
+            schema::BytesOptions::default().set_fast_with_cardinality(Cardinality::MultiValues),
+        );
+        let id_field = schema_builder.add_u64_field("id", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(
+            id_field => 1u64,
+            bytes_field => vec![1u8],
+            bytes_field => vec![1u8, 1]
+        ));
+        index_writer.add_document(doc!(id_field=>2u64, bytes_field=>vec![2u8]));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(id_field=>3u64));
+        index_writer.add_document(doc!(
+            id_field => 4u64,
+            bytes_field => vec![4u8; 3],
+            bytes_field => vec![],
+            bytes_field => vec![4u8]
+        ));
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_u64(id_field, 2));
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        block_on(index_writer.merge(&segment_ids))?;
+        index_writer.wait_merging_threads()?;
+
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        let segment_reader = searcher.segment_reader(0u32);
+        assert_eq!(segment_reader.max_doc(), 3);
+        let bytes_reader = segment_reader
+            .fast_fields()
+            .multivalue_bytes(bytes_field)
+            .unwrap();
+        let vals: Vec<Vec<&[u8]>> = (0..3)
+            .map(|doc| bytes_reader.get_vals(doc).collect())
+            .collect();
+        assert_eq!(
+            vals,
+            vec![
+                vec![&[1u8][..], &[1u8, 1][..]],
+                vec![],
+                vec![&[4u8; 3][..], &[][..], &[4u8][..]],
+            ]
+        );
+        assert_eq!(bytes_reader.total_num_vals(), 5);
+        assert_eq!(bytes_reader.total_num_bytes(), 7);
+        Ok(())
+    }
 }
@@ -4,6 +4,7 @@ use crate::fastfield::FastFieldSerializer;
 use crate::fieldnorm::FieldNormsSerializer;
 use crate::postings::InvertedIndexSerializer;
 use crate::store::StoreWriter;
+use crate::termvector::TermVectorsSerializer;
 
 /// Segment serializer is in charge of laying out on disk
 /// the data accumulated and sorted by the `SegmentWriter`.
@@ -13,6 +14,7 @@ pub struct SegmentSerializer {
     fast_field_serializer: FastFieldSerializer,
     fieldnorms_serializer: Option<FieldNormsSerializer>,
     postings_serializer: InvertedIndexSerializer,
+    term_vectors_serializer: Option<TermVectorsSerializer>,
 }
 
 impl SegmentSerializer {
@@ -27,12 +29,24 @@ impl SegmentSerializer {
         let fieldnorms_serializer = FieldNormsSerializer::from_write(fieldnorms_write)?;
 
         let postings_serializer = InvertedIndexSerializer::open(&mut segment)?;
+
+        let has_term_vectors = segment
+            .schema()
+            .fields()
+            .any(|(_, field_entry)| field_entry.has_term_vectors());
+        let term_vectors_serializer = if has_term_vectors {
+            let term_vectors_write = segment.open_write(SegmentComponent::TERMVECTORS)?;
+            Some(TermVectorsSerializer::from_write(term_vectors_write))
+        } else {
+            None
+        };
         Ok(SegmentSerializer {
             segment,
             store_writer: StoreWriter::new(store_write),
             fast_field_serializer,
             fieldnorms_serializer: Some(fieldnorms_serializer),
             postings_serializer,
+            term_vectors_serializer,
         })
     }
 
@@ -62,6 +76,13 @@ impl SegmentSerializer {
         &mut self.store_writer
     }
 
+    /// Accessor to the `TermVectorsSerializer`.
+    ///
+    /// Returns `None` if no field of the schema records term vectors.
+    pub fn get_term_vectors_serializer(&mut self) -> Option<&mut TermVectorsSerializer> {
+        self.term_vectors_serializer.as_mut()
+    }
+
     /// Finalize the segment serialization.
     pub fn close(mut self) -> crate::Result<()> {
         if let Some(fieldnorms_serializer) = self.extract_fieldnorms_serializer() {
@@ -70,6 +91,9 @@ impl SegmentSerializer {
         self.fast_field_serializer.close()?;
         self.postings_serializer.close()?;
         self.store_writer.close()?;
+        if let Some(term_vectors_serializer) = self.term_vectors_serializer {
+            term_vectors_serializer.close()?;
+        }
         Ok(())
     }
 }
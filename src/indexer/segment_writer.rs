@@ -11,7 +11,8 @@ use crate::schema::Schema;
 use crate::schema::Term;
 use crate::schema::Value;
 use crate::schema::{Field, FieldEntry};
-use crate::tokenizer::{BoxTokenStream, PreTokenizedStream};
+use crate::termvector::TermVectorsWriter;
+use crate::tokenizer::{BoxTokenStream, PreTokenizedStream, PreTokenizedString, TokenStream};
 use crate::tokenizer::{FacetTokenizer, TextAnalyzer};
 use crate::tokenizer::{TokenStreamChain, Tokenizer};
 use crate::Opstamp;
@@ -44,6 +45,7 @@ pub struct SegmentWriter {
     segment_serializer: SegmentSerializer,
     fast_field_writers: FastFieldsWriter,
     fieldnorms_writer: FieldNormsWriter,
+    term_vectors_writer: TermVectorsWriter,
     doc_opstamps: Vec<Opstamp>,
     tokenizers: Vec<Option<TextAnalyzer>>,
     term_buffer: Term,
@@ -86,6 +88,7 @@ impl SegmentWriter {
             max_doc: 0,
             multifield_postings,
             fieldnorms_writer: FieldNormsWriter::for_schema(schema),
+            term_vectors_writer: TermVectorsWriter::for_schema(schema),
             segment_serializer,
             fast_field_writers: FastFieldsWriter::from_schema(schema),
             doc_opstamps: Vec::with_capacity(1_000),
@@ -136,13 +139,13 @@ impl SegmentWriter {
                     field_entry.name()
                 ))
             };
-            if !field_entry.is_indexed() {
+            if field_entry.field_type().get_index_record_option().is_none() {
                 continue;
             }
             let (term_buffer, multifield_postings) =
                 (&mut self.term_buffer, &mut self.multifield_postings);
             match *field_entry.field_type() {
-                FieldType::HierarchicalFacet => {
+                FieldType::HierarchicalFacet(ref facet_options) => {
                     term_buffer.set_field(field);
                     let facets =
                         field_values
@@ -163,6 +166,9 @@ impl SegmentWriter {
                                     multifield_postings.subscribe(doc_id, &term_buffer);
                                 unordered_term_id_opt = Some(unordered_term_id);
                             });
+                        if !facet_options.is_fast() {
+                            continue;
+                        }
                         if let Some(unordered_term_id) = unordered_term_id_opt {
                             self.fast_field_writers
                                 .get_multivalue_writer(field)
@@ -201,6 +207,22 @@ impl SegmentWriter {
 
                     let num_tokens = if token_streams.is_empty() {
                         0
+                    } else if self.term_vectors_writer.has_term_vectors(field) {
+                        // The tokens are recorded in the term vectors, and then indexed.
+                        let mut tokens = Vec::new();
+                        TokenStreamChain::new(offsets, token_streams)
+                            .process(&mut |token| tokens.push(token.clone()));
+                        self.term_vectors_writer.record(field, &tokens);
+                        let mut token_stream = PreTokenizedStream::from(PreTokenizedString {
+                            text: String::new(),
+                            tokens,
+                        });
+                        multifield_postings.index_text(
+                            doc_id,
+                            field,
+                            &mut token_stream,
+                            term_buffer,
+                        )
                     } else {
                         let mut token_stream = TokenStreamChain::new(offsets, token_streams);
                         multifield_postings.index_text(
@@ -280,6 +302,11 @@ impl SegmentWriter {
                 }
             }
         }
+        if let Some(term_vectors_serializer) = self.segment_serializer.get_term_vectors_serializer()
+        {
+            self.term_vectors_writer
+                .serialize_doc(term_vectors_serializer)?;
+        }
         doc.filter_fields(|field| schema.get_field_entry(field).is_stored());
         doc.prepare_for_store();
         let doc_writer = self.segment_serializer.get_store_writer();
@@ -137,6 +137,7 @@ mod indexer;
 pub mod error;
 pub mod tokenizer;
 
+pub mod aggregation;
 pub mod collector;
 pub mod directory;
 pub mod fastfield;
@@ -148,6 +149,7 @@ pub mod schema;
 pub mod space_usage;
 pub mod store;
 pub mod termdict;
+pub mod termvector;
 
 mod reader;
 
@@ -23,17 +23,44 @@
 /// The long skip structure makes it possible to skip rapidly to the a checkpoint close to this
 /// value, and then skip normally.
 ///
+/// If the offsets of the tokens are indexed, they are interleaved with the positions: each
+/// occurrence of a term is then encoded as its position delta, followed by the delta
+/// between its start offset and the start offset of the previous occurrence
+/// within the document, and the length of the token in bytes.
+///
 mod reader;
 mod serializer;
 
 pub use self::reader::PositionReader;
 pub use self::serializer::PositionSerializer;
+use crate::schema::IndexRecordOption;
 use bitpacking::{BitPacker, BitPacker4x};
 
 const COMPRESSION_BLOCK_SIZE: usize = BitPacker4x::BLOCK_LEN;
 const LONG_SKIP_IN_BLOCKS: usize = 1_024;
 const LONG_SKIP_INTERVAL: u64 = (LONG_SKIP_IN_BLOCKS * COMPRESSION_BLOCK_SIZE) as u64;
 
+/// Returns the number of integers encoded in the positions stream
+/// for each occurrence of a term.
+pub(crate) fn position_stride(record_option: IndexRecordOption) -> u32 {
+    if record_option.has_offsets() {
+        3
+    } else {
+        1
+    }
+}
+
+/// Decodes the offsets interleaved with the position deltas of the occurrences
+/// of a term within a document.
+pub(crate) fn decode_offsets(position_data: &[u32], output: &mut Vec<(u32, u32)>) {
+    output.clear();
+    let mut offset_from = 0u32;
+    for occurrence in position_data.chunks(3) {
+        offset_from = offset_from.wrapping_add(occurrence[1]);
+        output.push((offset_from, offset_from.wrapping_add(occurrence[2])));
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
 
@@ -11,6 +11,7 @@ pub struct PositionSerializer<W: io::Write> {
     write_skiplist: W,
     block: Vec<u32>,
     buffer: Vec<u8>,
+    offsets_buffer: Vec<u32>,
     num_ints: u64,
     long_skips: Vec<u64>,
 }
@@ -23,6 +24,7 @@ impl<W: io::Write> PositionSerializer<W> {
             write_skiplist,
             block: Vec::with_capacity(128),
             buffer: vec![0u8; 128 * 4],
+            offsets_buffer: Vec::new(),
             num_ints: 0u64,
             long_skips: Vec::new(),
         }
@@ -50,6 +52,31 @@ impl<W: io::Write> PositionSerializer<W> {
         Ok(())
     }
 
+    /// Writes the position deltas of the occurrences of a term within a document,
+    /// interleaved with their `(offset_from, offset_to)` byte offsets.
+    pub fn write_positions_and_offsets(
+        &mut self,
+        position_deltas: &[u32],
+        offsets: &[(u32, u32)],
+    ) -> io::Result<()> {
+        debug_assert_eq!(position_deltas.len(), offsets.len());
+        let mut buffer = std::mem::take(&mut self.offsets_buffer);
+        buffer.clear();
+        let mut prev_offset_from = 0u32;
+        for (&position_delta, &(offset_from, offset_to)) in position_deltas.iter().zip(offsets) {
+            // Offsets are expected to increase with positions, but some token filters
+            // may break this assumption. Such offsets are clamped to the previous ones.
+            let offset_from = offset_from.max(prev_offset_from);
+            buffer.push(position_delta);
+            buffer.push(offset_from - prev_offset_from);
+            buffer.push(offset_to.saturating_sub(offset_from));
+            prev_offset_from = offset_from;
+        }
+        let result = self.write_all(&buffer);
+        self.offsets_buffer = buffer;
+        result
+    }
+
     fn flush_block(&mut self) -> io::Result<()> {
         let num_bits = self.bit_packer.num_bits(&self.block[..]);
         self.write_skiplist.write_all(&[num_bits])?;
@@ -150,6 +150,68 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_offsets() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("default")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositionsAndOffsets),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "abc def abc"));
+        for _ in 0..300 {
+            index_writer.add_document(doc!(text => "xyz abc"));
+        }
+        index_writer.add_document(doc!(text => "abc", text => "ghi abc"));
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let inverted_index = searcher.segment_reader(0u32).inverted_index(text)?;
+        let term = Term::from_field_text(text, "abc");
+        let mut positions = Vec::new();
+        let mut offsets = Vec::new();
+        let mut postings = inverted_index
+            .read_postings(&term, IndexRecordOption::WithFreqsAndPositionsAndOffsets)?
+            .unwrap();
+        assert_eq!(postings.doc(), 0);
+        postings.positions(&mut positions);
+        assert_eq!(&positions[..], &[0, 2]);
+        postings.offsets(&mut offsets);
+        assert_eq!(&offsets[..], &[(0, 3), (8, 11)]);
+        assert_eq!(postings.advance(), 1);
+        postings.offsets(&mut offsets);
+        assert_eq!(&offsets[..], &[(4, 7)]);
+        postings.positions(&mut positions);
+        assert_eq!(&positions[..], &[1]);
+        assert_eq!(postings.seek(301), 301);
+        postings.positions(&mut positions);
+        assert_eq!(&positions[..], &[0, 3]);
+        // Offsets are cumulative over the values of a multivalued field.
+        postings.offsets(&mut offsets);
+        assert_eq!(&offsets[..], &[(0, 3), (7, 10)]);
+
+        // Positions can still be read when offsets are not requested.
+        let mut postings = inverted_index
+            .read_postings(&term, IndexRecordOption::WithFreqsAndPositions)?
+            .unwrap();
+        assert_eq!(postings.seek(200), 200);
+        postings.positions(&mut positions);
+        assert_eq!(&positions[..], &[1]);
+        postings.offsets(&mut offsets);
+        assert_eq!(&offsets[..], &[(4, 7)]);
+
+        // Without positions, no offsets are available.
+        let mut postings = inverted_index
+            .read_postings(&term, IndexRecordOption::WithFreqs)?
+            .unwrap();
+        postings.offsets(&mut offsets);
+        assert!(offsets.is_empty());
+        Ok(())
+    }
     #[test]
     pub fn test_drop_token_that_are_too_long() -> crate::Result<()> {
         let ok_token_text: String = iter::repeat('A').take(MAX_TOKEN_LEN).collect();
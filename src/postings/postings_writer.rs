@@ -3,6 +3,7 @@ use super::stacker::{Addr, MemoryArena, TermHashMap};
 use crate::fieldnorm::FieldNormReaders;
 use crate::postings::recorder::{
     BufferLender, NothingRecorder, Recorder, TFAndPositionRecorder, TermFrequencyRecorder,
+    TfPositionAndOffsetRecorder,
 };
 use crate::postings::UnorderedTermId;
 use crate::postings::{FieldSerializer, InvertedIndexSerializer};
@@ -32,6 +33,9 @@ fn posting_from_field_entry(field_entry: &FieldEntry) -> Box<dyn PostingsWriter>
                 IndexRecordOption::WithFreqsAndPositions => {
                     SpecializedPostingsWriter::<TFAndPositionRecorder>::new_boxed()
                 }
+                IndexRecordOption::WithFreqsAndPositionsAndOffsets => {
+                    SpecializedPostingsWriter::<TfPositionAndOffsetRecorder>::new_boxed()
+                }
             })
             .unwrap_or_else(|| SpecializedPostingsWriter::<NothingRecorder>::new_boxed()),
         FieldType::U64(_)
@@ -39,7 +43,9 @@ fn posting_from_field_entry(field_entry: &FieldEntry) -> Box<dyn PostingsWriter>
         | FieldType::F64(_)
         | FieldType::Date(_)
         | FieldType::Bytes(_)
-        | FieldType::HierarchicalFacet => SpecializedPostingsWriter::<NothingRecorder>::new_boxed(),
+        | FieldType::HierarchicalFacet(_) => {
+            SpecializedPostingsWriter::<NothingRecorder>::new_boxed()
+        }
     }
 }
 
@@ -142,7 +148,7 @@ impl MultiFieldPostingsWriter {
             let field_entry = self.schema.get_field_entry(field);
 
             match *field_entry.field_type() {
-                FieldType::Str(_) | FieldType::HierarchicalFacet => {
+                FieldType::Str(_) | FieldType::HierarchicalFacet(_) => {
                     // populating the (unordered term ord) -> (ordered term ord) mapping
                     // for the field.
                     let unordered_term_ids = term_offsets[start..stop]
@@ -201,6 +207,20 @@ pub trait PostingsWriter {
         heap: &mut MemoryArena,
     ) -> UnorderedTermId;
 
+    /// Record that a document contains a term at a given position,
+    /// and at the given `(offset_from, offset_to)` byte offsets of the text.
+    ///
+    /// Offsets are only retained if the field indexes them.
+    fn subscribe_with_offsets(
+        &mut self,
+        term_index: &mut TermHashMap,
+        doc: DocId,
+        pos: u32,
+        offsets: (u32, u32),
+        term: &Term,
+        heap: &mut MemoryArena,
+    ) -> UnorderedTermId;
+
     /// Serializes the postings on disk.
     /// The actual serialization format is handled by the `PostingsSerializer`.
     fn serialize(
@@ -226,10 +246,11 @@ pub trait PostingsWriter {
             // We skip all tokens with a len greater than u16.
             if token.text.len() <= MAX_TOKEN_LEN {
                 term_buffer.set_text(token.text.as_str());
-                self.subscribe(
+                self.subscribe_with_offsets(
                     term_index,
                     doc_id,
                     token.position as u32,
+                    (token.offset_from as u32, token.offset_to as u32),
                     &term_buffer,
                     heap,
                 );
@@ -268,36 +289,63 @@ impl<Rec: Recorder + 'static> SpecializedPostingsWriter<Rec> {
     pub fn new_boxed() -> Box<dyn PostingsWriter> {
         Box::new(SpecializedPostingsWriter::<Rec>::new())
     }
-}
 
-impl<Rec: Recorder + 'static> PostingsWriter for SpecializedPostingsWriter<Rec> {
-    fn subscribe(
+    fn record(
         &mut self,
         term_index: &mut TermHashMap,
         doc: DocId,
         position: u32,
+        offsets_opt: Option<(u32, u32)>,
         term: &Term,
         heap: &mut MemoryArena,
     ) -> UnorderedTermId {
         debug_assert!(term.as_slice().len() >= 4);
         self.total_num_tokens += 1;
         term_index.mutate_or_create(term, |opt_recorder: Option<Rec>| {
-            if let Some(mut recorder) = opt_recorder {
+            let mut recorder = if let Some(mut recorder) = opt_recorder {
                 let current_doc = recorder.current_doc();
                 if current_doc != doc {
                     recorder.close_doc(heap);
                     recorder.new_doc(doc, heap);
                 }
-                recorder.record_position(position, heap);
                 recorder
             } else {
                 let mut recorder = Rec::new();
                 recorder.new_doc(doc, heap);
-                recorder.record_position(position, heap);
                 recorder
+            };
+            recorder.record_position(position, heap);
+            if let Some((offset_from, offset_to)) = offsets_opt {
+                recorder.record_offsets(offset_from, offset_to, heap);
             }
+            recorder
         }) as UnorderedTermId
     }
+}
+
+impl<Rec: Recorder + 'static> PostingsWriter for SpecializedPostingsWriter<Rec> {
+    fn subscribe(
+        &mut self,
+        term_index: &mut TermHashMap,
+        doc: DocId,
+        position: u32,
+        term: &Term,
+        heap: &mut MemoryArena,
+    ) -> UnorderedTermId {
+        self.record(term_index, doc, position, None, term, heap)
+    }
+
+    fn subscribe_with_offsets(
+        &mut self,
+        term_index: &mut TermHashMap,
+        doc: DocId,
+        position: u32,
+        offsets: (u32, u32),
+        term: &Term,
+        heap: &mut MemoryArena,
+    ) -> UnorderedTermId {
+        self.record(term_index, doc, position, Some(offsets), term, heap)
+    }
 
     fn serialize(
         &self,
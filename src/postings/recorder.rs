@@ -10,6 +10,7 @@ const POSITION_END: u32 = 0;
 pub(crate) struct BufferLender {
     buffer_u8: Vec<u8>,
     buffer_u32: Vec<u32>,
+    buffer_offsets: Vec<(u32, u32)>,
 }
 
 impl BufferLender {
@@ -22,6 +23,16 @@ impl BufferLender {
         self.buffer_u32.clear();
         (&mut self.buffer_u8, &mut self.buffer_u32)
     }
+    pub fn lend_all_with_offsets(&mut self) -> (&mut Vec<u8>, &mut Vec<u32>, &mut Vec<(u32, u32)>) {
+        self.buffer_u8.clear();
+        self.buffer_u32.clear();
+        self.buffer_offsets.clear();
+        (
+            &mut self.buffer_u8,
+            &mut self.buffer_u32,
+            &mut self.buffer_offsets,
+        )
+    }
 }
 
 pub struct VInt32Reader<'a> {
@@ -55,6 +66,7 @@ impl<'a> Iterator for VInt32Reader<'a> {
 ///   * the document id
 ///   * the term frequency
 ///   * the term positions
+///   * the offsets of the tokens
 pub(crate) trait Recorder: Copy + 'static {
     ///
     fn new() -> Self;
@@ -66,6 +78,9 @@ pub(crate) trait Recorder: Copy + 'static {
     /// Record the position of a term. For each document,
     /// this method will be called `term_freq` times.
     fn record_position(&mut self, position: u32, heap: &mut MemoryArena);
+    /// Record the byte offsets of the token at the last recorded position.
+    /// Recorders that do not record offsets simply ignore them.
+    fn record_offsets(&mut self, _offset_from: u32, _offset_to: u32, _heap: &mut MemoryArena) {}
     /// Close the document. It will help record the term frequency.
     fn close_doc(&mut self, heap: &mut MemoryArena);
     /// Pushes the postings information to the serializer.
@@ -258,6 +273,90 @@ impl Recorder for TFAndPositionRecorder {
     }
 }
 
+/// Recorder encoding term frequencies, positions, and the byte offsets of the tokens.
+#[derive(Clone, Copy)]
+pub struct TfPositionAndOffsetRecorder {
+    stack: ExpUnrolledLinkedList,
+    current_doc: DocId,
+    term_doc_freq: u32,
+}
+
+impl Recorder for TfPositionAndOffsetRecorder {
+    fn new() -> Self {
+        TfPositionAndOffsetRecorder {
+            stack: ExpUnrolledLinkedList::new(),
+            current_doc: u32::MAX,
+            term_doc_freq: 0u32,
+        }
+    }
+
+    fn current_doc(&self) -> DocId {
+        self.current_doc
+    }
+
+    fn new_doc(&mut self, doc: DocId, heap: &mut MemoryArena) {
+        self.current_doc = doc;
+        self.term_doc_freq += 1u32;
+        let _ = write_u32_vint(doc, &mut self.stack.writer(heap));
+    }
+
+    fn record_position(&mut self, position: u32, heap: &mut MemoryArena) {
+        let _ = write_u32_vint(position + 1u32, &mut self.stack.writer(heap));
+    }
+
+    fn record_offsets(&mut self, offset_from: u32, offset_to: u32, heap: &mut MemoryArena) {
+        let mut writer = self.stack.writer(heap);
+        let _ = write_u32_vint(offset_from, &mut writer);
+        let _ = write_u32_vint(offset_to.saturating_sub(offset_from), &mut writer);
+    }
+
+    fn close_doc(&mut self, heap: &mut MemoryArena) {
+        let _ = write_u32_vint(POSITION_END, &mut self.stack.writer(heap));
+    }
+
+    fn serialize(
+        &self,
+        buffer_lender: &mut BufferLender,
+        serializer: &mut FieldSerializer<'_>,
+        heap: &MemoryArena,
+    ) -> io::Result<()> {
+        let (buffer_u8, buffer_positions, buffer_offsets) = buffer_lender.lend_all_with_offsets();
+        self.stack.read_to_end(heap, buffer_u8);
+        let mut u32_it = VInt32Reader::new(&buffer_u8[..]);
+        while let Some(doc) = u32_it.next() {
+            let mut prev_position_plus_one = 1u32;
+            buffer_positions.clear();
+            buffer_offsets.clear();
+            loop {
+                match u32_it.next() {
+                    Some(POSITION_END) | None => {
+                        break;
+                    }
+                    Some(position_plus_one) => {
+                        let delta_position = position_plus_one - prev_position_plus_one;
+                        buffer_positions.push(delta_position);
+                        prev_position_plus_one = position_plus_one;
+                        let offset_from = u32_it.next().unwrap_or(0u32);
+                        let num_bytes = u32_it.next().unwrap_or(0u32);
+                        buffer_offsets.push((offset_from, offset_from + num_bytes));
+                    }
+                }
+            }
+            serializer.write_doc_with_offsets(
+                doc,
+                buffer_positions.len() as u32,
+                buffer_positions,
+                buffer_offsets,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn term_doc_freq(&self) -> Option<u32> {
+        Some(self.term_doc_freq)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
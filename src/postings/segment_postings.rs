@@ -2,7 +2,7 @@ use crate::common::HasLen;
 use crate::directory::FileSlice;
 use crate::docset::DocSet;
 use crate::fastfield::DeleteBitSet;
-use crate::positions::PositionReader;
+use crate::positions::{decode_offsets, PositionReader};
 use crate::postings::compression::COMPRESSION_BLOCK_SIZE;
 use crate::postings::serializer::PostingsSerializer;
 use crate::postings::BlockSearcher;
@@ -21,6 +21,8 @@ pub struct SegmentPostings {
     pub(crate) block_cursor: BlockSegmentPostings,
     cur: usize,
     position_reader: Option<PositionReader>,
+    has_offsets: bool,
+    position_data: Vec<u32>,
     block_searcher: BlockSearcher,
 }
 
@@ -31,6 +33,8 @@ impl SegmentPostings {
             block_cursor: BlockSegmentPostings::empty(),
             cur: 0,
             position_reader: None,
+            has_offsets: false,
+            position_data: Vec::new(),
             block_searcher: BlockSearcher::default(),
         }
     }
@@ -88,7 +92,7 @@ impl SegmentPostings {
             IndexRecordOption::Basic,
         )
         .unwrap();
-        SegmentPostings::from_block_postings(block_segment_postings, None)
+        SegmentPostings::from_block_postings(block_segment_postings, None, false)
     }
 
     /// Helper functions to create `SegmentPostings` for tests.
@@ -133,7 +137,7 @@ impl SegmentPostings {
             IndexRecordOption::WithFreqs,
         )
         .unwrap();
-        SegmentPostings::from_block_postings(block_segment_postings, None)
+        SegmentPostings::from_block_postings(block_segment_postings, None, false)
     }
 
     /// Reads a Segment postings from an &[u8]
@@ -142,17 +146,57 @@ impl SegmentPostings {
     /// * `data` - data array. The complete data is not necessarily used.
     /// * `freq_handler` - the freq handler is in charge of decoding
     ///   frequencies and/or positions
+    /// * `has_offsets` - whether offsets are interleaved with the positions
     pub(crate) fn from_block_postings(
         segment_block_postings: BlockSegmentPostings,
         position_reader: Option<PositionReader>,
+        has_offsets: bool,
     ) -> SegmentPostings {
         SegmentPostings {
             block_cursor: segment_block_postings,
             cur: 0, // cursor within the block
             position_reader,
+            has_offsets,
+            position_data: Vec::new(),
             block_searcher: BlockSearcher::default(),
         }
     }
+
+    /// Reads the data associated to the occurrences of the term in the current
+    /// document from the positions stream, into `position_data`.
+    ///
+    /// Returns false if positions are not available.
+    fn read_position_data(&mut self) -> bool {
+        let stride = if self.has_offsets { 3 } else { 1 };
+        let term_freq = self.term_freq() as usize;
+        if let Some(position_reader) = self.position_reader.as_mut() {
+            let read_offset = self.block_cursor.position_offset()
+                + (self.block_cursor.freqs()[..self.cur]
+                    .iter()
+                    .cloned()
+                    .sum::<u32>() as u64)
+                    * stride;
+            self.position_data.resize(term_freq * stride as usize, 0u32);
+            position_reader.read(read_offset, &mut self.position_data[..]);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the `(offset_from, offset_to)` byte offsets of the occurrences
+    /// of the term in the current document, in the order of their positions.
+    ///
+    /// The output vector will be resized to the `term_freq`, or cleared if the field
+    /// was not indexed with `IndexRecordOption::WithFreqsAndPositionsAndOffsets`, or
+    /// if positions were not requested when opening the postings.
+    pub fn offsets(&mut self, output: &mut Vec<(u32, u32)>) {
+        if self.has_offsets && self.read_position_data() {
+            decode_offsets(&self.position_data, output);
+        } else {
+            output.clear();
+        }
+    }
 }
 
 impl DocSet for SegmentPostings {
@@ -240,19 +284,31 @@ impl Postings for SegmentPostings {
     }
 
     fn positions_with_offset(&mut self, offset: u32, output: &mut Vec<u32>) {
-        let term_freq = self.term_freq() as usize;
-        if let Some(position_reader) = self.position_reader.as_mut() {
-            let read_offset = self.block_cursor.position_offset()
-                + (self.block_cursor.freqs()[..self.cur]
-                    .iter()
-                    .cloned()
-                    .sum::<u32>() as u64);
-            output.resize(term_freq, 0u32);
-            position_reader.read(read_offset, &mut output[..]);
+        if !self.has_offsets {
+            let term_freq = self.term_freq() as usize;
+            if let Some(position_reader) = self.position_reader.as_mut() {
+                let read_offset = self.block_cursor.position_offset()
+                    + (self.block_cursor.freqs()[..self.cur]
+                        .iter()
+                        .cloned()
+                        .sum::<u32>() as u64);
+                output.resize(term_freq, 0u32);
+                position_reader.read(read_offset, &mut output[..]);
+                let mut cum = offset;
+                for output_mut in output.iter_mut() {
+                    cum += *output_mut;
+                    *output_mut = cum;
+                }
+            } else {
+                output.clear();
+            }
+        } else if self.read_position_data() {
+            // Offsets are interleaved with the position deltas.
+            output.clear();
             let mut cum = offset;
-            for output_mut in output.iter_mut() {
-                cum += *output_mut;
-                *output_mut = cum;
+            for occurrence in self.position_data.chunks(3) {
+                cum += occurrence[0];
+                output.push(cum);
             }
         } else {
             output.clear();
@@ -4,10 +4,10 @@ use crate::common::{CompositeWrite, CountingWriter};
 use crate::core::Segment;
 use crate::directory::WritePtr;
 use crate::fieldnorm::FieldNormReader;
-use crate::positions::PositionSerializer;
+use crate::positions::{position_stride, PositionSerializer};
 use crate::postings::compression::{BlockEncoder, VIntEncoder, COMPRESSION_BLOCK_SIZE};
 use crate::postings::skip::SkipSerializer;
-use crate::query::BM25Weight;
+use crate::query::SimilarityWeight;
 use crate::schema::{Field, FieldEntry, FieldType};
 use crate::schema::{IndexRecordOption, Schema};
 use crate::termdict::{TermDictionaryBuilder, TermOrdinal};
@@ -127,6 +127,7 @@ pub struct FieldSerializer<'a> {
     term_dictionary_builder: TermDictionaryBuilder<&'a mut CountingWriter<WritePtr>>,
     postings_serializer: PostingsSerializer<&'a mut CountingWriter<WritePtr>>,
     positions_serializer_opt: Option<PositionSerializer<&'a mut CountingWriter<WritePtr>>>,
+    has_offsets: bool,
     current_term_info: TermInfo,
     term_open: bool,
     num_terms: TermOrdinal,
@@ -170,6 +171,7 @@ impl<'a> FieldSerializer<'a> {
             term_dictionary_builder,
             postings_serializer,
             positions_serializer_opt,
+            has_offsets: mode.has_offsets(),
             current_term_info: TermInfo::default(),
             term_open: false,
             num_terms: TermOrdinal::default(),
@@ -234,6 +236,29 @@ impl<'a> FieldSerializer<'a> {
         Ok(())
     }
 
+    /// Serialize the information that a document contains the current term,
+    /// its term frequency, the position deltas and the `(offset_from, offset_to)`
+    /// byte offsets of its occurrences.
+    ///
+    /// Offsets are ignored if the field does not index them.
+    pub fn write_doc_with_offsets(
+        &mut self,
+        doc_id: DocId,
+        term_freq: u32,
+        position_deltas: &[u32],
+        offsets: &[(u32, u32)],
+    ) -> io::Result<()> {
+        if !self.has_offsets {
+            return self.write_doc(doc_id, term_freq, position_deltas);
+        }
+        self.current_term_info.doc_freq += 1;
+        self.postings_serializer.write_doc(doc_id, term_freq);
+        if let Some(ref mut positions_serializer) = self.positions_serializer_opt.as_mut() {
+            positions_serializer.write_positions_and_offsets(position_deltas, offsets)?;
+        }
+        Ok(())
+    }
+
     /// Finish the serialization for this term postings.
     ///
     /// If the current block is incomplete, it need to be encoded
@@ -323,7 +348,7 @@ pub struct PostingsSerializer<W: Write> {
     mode: IndexRecordOption,
     fieldnorm_reader: Option<FieldNormReader>,
 
-    bm25_weight: Option<BM25Weight>,
+    bm25_weight: Option<SimilarityWeight>,
 
     num_docs: u32, // Number of docs in the segment
     avg_fieldnorm: Score, // Average number of term in the field for that segment.
@@ -363,7 +388,7 @@ impl<W: Write> PostingsSerializer<W> {
 
     pub fn new_term(&mut self, term_doc_freq: u32) {
         if self.mode.has_freq() && self.num_docs > 0 {
-            let bm25_weight = BM25Weight::for_one_term(
+            let bm25_weight = SimilarityWeight::for_one_term(
                 term_doc_freq as u64,
                 self.num_docs as u64,
                 self.avg_fieldnorm,
@@ -393,8 +418,11 @@ impl<W: Write> PostingsSerializer<W> {
             if self.mode.has_positions() {
                 // We serialize the sum of term freqs within the skip information
                 // in order to navigate through positions.
-                let sum_freq = self.block.term_freqs().iter().cloned().sum();
-                self.skip_write.write_total_term_freq(sum_freq);
+                // If offsets are indexed, each occurrence takes several integers
+                // in the positions stream.
+                let sum_freq: u32 = self.block.term_freqs().iter().cloned().sum();
+                self.skip_write
+                    .write_total_term_freq(sum_freq * position_stride(self.mode));
             }
             let mut blockwand_params = (0u8, 0u32);
             if let Some(bm25_weight) = self.bm25_weight.as_ref() {
@@ -1,7 +1,7 @@
 use crate::common::{read_u32_vint_no_advance, serialize_vint_u32, BinarySerializable};
 use crate::directory::OwnedBytes;
 use crate::postings::compression::{compressed_block_size, COMPRESSION_BLOCK_SIZE};
-use crate::query::BM25Weight;
+use crate::query::SimilarityWeight;
 use crate::schema::IndexRecordOption;
 use crate::{DocId, Score, TERMINATED};
 
@@ -134,7 +134,13 @@ impl SkipReader {
     //
     // The block max score is available for all full bitpacked block,
     // but no available for the last VInt encoded incomplete block.
-    pub fn block_max_score(&self, bm25_weight: &BM25Weight) -> Option<Score> {
+    //
+    // It is computed at indexing time for the default BM25 similarity,
+    // and is not available for any other similarity.
+    pub fn block_max_score(&self, bm25_weight: &SimilarityWeight) -> Option<Score> {
+        if !bm25_weight.has_block_wand_support() {
+            return None;
+        }
         match self.block_info {
             BlockInfo::BitPacked {
                 block_wand_fieldnorm_id,
@@ -193,7 +199,8 @@ impl SkipReader {
                     block_wand_term_freq,
                 };
             }
-            IndexRecordOption::WithFreqsAndPositions => {
+            IndexRecordOption::WithFreqsAndPositions
+            | IndexRecordOption::WithFreqsAndPositionsAndOffsets => {
                 let bytes = self.owned_read.as_slice();
                 let tf_num_bits = bytes[5];
                 let tf_sum = {
@@ -10,10 +10,11 @@ use crate::{DocId, Score};
 use std::sync::Arc;
 use tantivy_fst::Automaton;
 
-/// A weight struct for Fuzzy Term and Regex Queries
+/// A weight struct for Fuzzy Term, Regex and Wildcard Queries
 pub struct AutomatonWeight<A> {
     field: Field,
     automaton: Arc<A>,
+    max_expansions: Option<usize>,
 }
 
 impl<A> AutomatonWeight<A>
@@ -25,9 +26,19 @@ where
         AutomatonWeight {
             field,
             automaton: automaton.into(),
+            max_expansions: None,
         }
     }
 
+    /// Limits the number of terms the automaton may match within a segment.
+    ///
+    /// Creating a scorer over a segment in which more terms match
+    /// returns an `InvalidArgument` error.
+    pub fn with_max_expansions(mut self, max_expansions: usize) -> AutomatonWeight<A> {
+        self.max_expansions = Some(max_expansions);
+        self
+    }
+
     fn automaton_stream<'a>(&'a self, term_dict: &'a TermDictionary) -> TermStreamer<'a, &'a A> {
         let automaton: &A = &*self.automaton;
         let term_stream_builder = term_dict.search(automaton);
@@ -45,7 +56,17 @@ where
         let inverted_index = reader.inverted_index(self.field)?;
         let term_dict = inverted_index.terms();
         let mut term_stream = self.automaton_stream(term_dict);
+        let mut num_terms = 0;
         while term_stream.advance() {
+            num_terms += 1;
+            if let Some(max_expansions) = self.max_expansions {
+                if num_terms > max_expansions {
+                    return Err(TantivyError::InvalidArgument(format!(
+                        "The query matches more than {} terms",
+                        max_expansions
+                    )));
+                }
+            }
             let term_info = term_stream.value();
             let mut block_segment_postings = inverted_index
                 .read_block_postings_from_terminfo(term_info, IndexRecordOption::Basic)?;
@@ -166,4 +187,17 @@ mod tests {
         assert_eq!(scorer.doc(), 0u32);
         assert_eq!(scorer.score(), 1.32);
     }
+
+    #[test]
+    fn test_automaton_weight_max_expansions() {
+        let index = create_index();
+        let field = index.schema().get_field("title").unwrap();
+        let reader = index.reader().unwrap();
+        let searcher = reader.searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let automaton_weight = AutomatonWeight::new(field, PrefixedByA).with_max_expansions(2);
+        assert!(automaton_weight.scorer(segment_reader, 1.0).is_ok());
+        let automaton_weight = AutomatonWeight::new(field, PrefixedByA).with_max_expansions(1);
+        assert!(automaton_weight.scorer(segment_reader, 1.0).is_err());
+    }
 }
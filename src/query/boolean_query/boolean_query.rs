@@ -164,6 +164,12 @@ impl Query for BooleanQuery {
             subquery.query_terms(term_set);
         }
     }
+
+    fn query_phrases(&self, phrases: &mut Vec<Vec<(usize, Term)>>) {
+        for (_occur, subquery) in &self.subqueries {
+            subquery.query_phrases(phrases);
+        }
+    }
 }
 
 impl BooleanQuery {
@@ -202,6 +208,11 @@ impl BooleanQuery {
     pub fn clauses(&self) -> &[(Occur, Box<dyn Query>)] {
         &self.subqueries[..]
     }
+
+    /// Consumes the query, returning its clauses.
+    pub fn into_clauses(self) -> Vec<(Occur, Box<dyn Query>)> {
+        self.subqueries
+    }
 }
 
 #[cfg(test)]
@@ -20,6 +20,24 @@ impl BoostQuery {
     pub fn new(query: Box<dyn Query>, boost: Score) -> BoostQuery {
         BoostQuery { query, boost }
     }
+
+    /// Returns the underlying query.
+    pub fn query(&self) -> &dyn Query {
+        self.query.as_ref()
+    }
+
+    /// Returns the boost factor.
+    pub fn boost(&self) -> Score {
+        self.boost
+    }
+
+    pub(crate) fn map_query<F>(self, f: F) -> Option<BoostQuery>
+    where
+        F: FnOnce(Box<dyn Query>) -> Option<Box<dyn Query>>,
+    {
+        let boost = self.boost;
+        f(self.query).map(|query| BoostQuery { query, boost })
+    }
 }
 
 impl Clone for BoostQuery {
@@ -51,6 +69,10 @@ impl Query for BoostQuery {
     fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
         self.query.query_terms(term_set)
     }
+
+    fn query_phrases(&self, phrases: &mut Vec<Vec<(usize, Term)>>) {
+        self.query.query_phrases(phrases)
+    }
 }
 
 pub(crate) struct BoostWeight {
@@ -0,0 +1,164 @@
+use crate::query::explanation::does_not_match;
+use crate::query::{ConstScorer, Explanation, Query, Scorer, Weight};
+use crate::{DocId, Score, Searcher, SegmentReader, Term};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// `ConstScoreQuery` is a wrapper over a query to turn it into a filter.
+///
+/// The document set matched by the `ConstScoreQuery` is strictly the same as the underlying
+/// query, but all of the documents get the same constant `score`.
+///
+/// Since the score of the underlying query is never computed, scoring
+/// is disabled when creating its `Weight`.
+pub struct ConstScoreQuery {
+    query: Box<dyn Query>,
+    score: Score,
+}
+
+impl ConstScoreQuery {
+    /// Builds a const score query.
+    pub fn new(query: Box<dyn Query>, score: Score) -> ConstScoreQuery {
+        ConstScoreQuery { query, score }
+    }
+
+    /// Returns the underlying query.
+    pub fn query(&self) -> &dyn Query {
+        self.query.as_ref()
+    }
+
+    /// Returns the score given to all of the matching documents.
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    pub(crate) fn map_query<F>(self, f: F) -> Option<ConstScoreQuery>
+    where
+        F: FnOnce(Box<dyn Query>) -> Option<Box<dyn Query>>,
+    {
+        let score = self.score;
+        f(self.query).map(|query| ConstScoreQuery { query, score })
+    }
+}
+
+impl Clone for ConstScoreQuery {
+    fn clone(&self) -> Self {
+        ConstScoreQuery {
+            query: self.query.box_clone(),
+            score: self.score,
+        }
+    }
+}
+
+impl fmt::Debug for ConstScoreQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ConstScore(query={:?}, score={})",
+            self.query, self.score
+        )
+    }
+}
+
+impl Query for ConstScoreQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        let weight = self.query.weight(searcher, false)?;
+        if scoring_enabled {
+            Ok(Box::new(ConstScoreWeight::new(weight, self.score)))
+        } else {
+            Ok(weight)
+        }
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.query.query_terms(term_set)
+    }
+
+    fn query_phrases(&self, phrases: &mut Vec<Vec<(usize, Term)>>) {
+        self.query.query_phrases(phrases)
+    }
+}
+
+pub(crate) struct ConstScoreWeight {
+    weight: Box<dyn Weight>,
+    score: Score,
+}
+
+impl ConstScoreWeight {
+    pub fn new(weight: Box<dyn Weight>, score: Score) -> Self {
+        ConstScoreWeight { weight, score }
+    }
+}
+
+impl Weight for ConstScoreWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        let scorer = self.weight.scorer(reader, 1.0)?;
+        Ok(Box::new(ConstScorer::new(scorer, self.score * boost)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.weight.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new("ConstScore", self.score))
+    }
+
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        self.weight.count(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstScoreQuery;
+    use crate::collector::TopDocs;
+    use crate::query::{BooleanQuery, BoostQuery, Occur, Query, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, TEXT};
+    use crate::{DocAddress, Index, Term};
+
+    #[test]
+    fn test_const_score_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "a b"));
+        index_writer.add_document(doc!(text => "a a a a c"));
+        index_writer.add_document(doc!(text => "c"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let term_query = |text_str: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(text, text_str),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        let query = ConstScoreQuery::new(term_query("a"), 0.5);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(10))?;
+        assert_eq!(top_docs.len(), 2);
+        assert!(top_docs.iter().all(|(score, _)| *score == 0.5));
+        assert_eq!(query.count(&searcher)?, 2);
+
+        // Boosting a const score query multiplies the constant score.
+        let boosted_query = BoostQuery::new(Box::new(query.clone()), 2.0);
+        let top_docs = searcher.search(&boosted_query, &TopDocs::with_limit(10))?;
+        assert!(top_docs.iter().all(|(score, _)| *score == 1.0));
+
+        // Nested in a boolean query, the const score acts as a filter with a fixed
+        // contribution to the score.
+        let boolean_query = BooleanQuery::from(vec![
+            (Occur::Must, Box::new(query.clone()) as Box<dyn Query>),
+            (Occur::Should, term_query("c")),
+        ]);
+        let top_docs = searcher.search(&boolean_query, &TopDocs::with_limit(10))?;
+        assert_eq!(top_docs.len(), 2);
+        assert_eq!(top_docs[0].1, DocAddress(0, 1));
+        assert_eq!(top_docs[1], (0.5, DocAddress(0, 0)));
+
+        let explanation = query.explain(&searcher, DocAddress(0, 1))?;
+        assert_eq!(explanation.value(), 0.5);
+        assert!(query.explain(&searcher, DocAddress(0, 2)).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+use crate::common::BitSet;
+use crate::core::SegmentReader;
+use crate::query::explanation::does_not_match;
+use crate::query::{BitSetDocSet, ConstScorer, Explanation, Query, Scorer, Weight};
+use crate::schema::{Cardinality, Field, FieldType, IndexRecordOption, TextFieldIndexing};
+use crate::{DocId, Score, Searcher, TantivyError};
+
+/// `ExistsQuery` matches all of the documents that have at least one value
+/// for a given field.
+///
+/// Matched documents all get a constant `Score` of one.
+///
+/// # Implementation
+///
+/// When possible, the query relies on a per-document structure recorded at indexing time:
+/// - for indexed text fields with field norms, documents with a non-zero field norm have a value.
+/// - for multivalued fast fields (including facets), documents with at least one value
+///   in the fast field have a value.
+///
+/// Otherwise, if the field is indexed, the query iterates over all of the terms of the field
+/// and appends all of their postings into a `BitSet`.
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::query::ExistsQuery;
+/// use tantivy::schema::{Schema, INDEXED, TEXT};
+/// use tantivy::{doc, Index};
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let year = schema_builder.add_u64_field("year", INDEXED);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+/// {
+///     let mut index_writer = index.writer(3_000_000)?;
+///     index_writer.add_document(doc!(title => "The Name of the Wind", year => 2007u64));
+///     index_writer.add_document(doc!(title => "The Diary of Muadib"));
+///     index_writer.commit()?;
+/// }
+/// let reader = index.reader()?;
+/// let searcher = reader.searcher();
+/// assert_eq!(searcher.search(&ExistsQuery::new(title), &Count)?, 2);
+/// assert_eq!(searcher.search(&ExistsQuery::new(year), &Count)?, 1);
+/// Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExistsQuery {
+    field: Field,
+}
+
+impl ExistsQuery {
+    /// Creates a new `ExistsQuery` for the given field.
+    pub fn new(field: Field) -> ExistsQuery {
+        ExistsQuery { field }
+    }
+
+    /// Field to search over
+    pub fn field(&self) -> Field {
+        self.field
+    }
+}
+
+impl Query for ExistsQuery {
+    fn weight(
+        &self,
+        searcher: &Searcher,
+        _scoring_enabled: bool,
+    ) -> crate::Result<Box<dyn Weight>> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        if !is_exists_supported(field_entry.field_type()) {
+            return Err(TantivyError::SchemaError(format!(
+                "Field {:?} is neither indexed nor a multivalued fast field",
+                field_entry.name()
+            )));
+        }
+        Ok(Box::new(ExistsWeight { field: self.field }))
+    }
+}
+
+/// Per-document structure recorded at indexing time that can tell whether
+/// a document has a value for a field.
+enum PresenceSource {
+    FieldNorms,
+    MultiValuedFastField,
+}
+
+/// Returns true iff an `ExistsQuery` can be run on a field of the given type.
+pub(crate) fn is_exists_supported(field_type: &FieldType) -> bool {
+    field_type.is_indexed() || presence_source(field_type).is_some()
+}
+
+fn presence_source(field_type: &FieldType) -> Option<PresenceSource> {
+    match field_type {
+        FieldType::Str(options)
+            if options
+                .get_indexing_options()
+                .map(TextFieldIndexing::fieldnorms)
+                .unwrap_or(false) =>
+        {
+            Some(PresenceSource::FieldNorms)
+        }
+        FieldType::U64(options)
+        | FieldType::I64(options)
+        | FieldType::F64(options)
+        | FieldType::Date(options)
+            if options.get_fastfield_cardinality() == Some(Cardinality::MultiValues) =>
+        {
+            Some(PresenceSource::MultiValuedFastField)
+        }
+        FieldType::HierarchicalFacet(options) if options.is_fast() => {
+            Some(PresenceSource::MultiValuedFastField)
+        }
+        _ => None,
+    }
+}
+
+struct ExistsWeight {
+    field: Field,
+}
+
+fn union_postings(
+    reader: &SegmentReader,
+    field: Field,
+    doc_bitset: &mut BitSet,
+) -> crate::Result<()> {
+    let inverted_index = reader.inverted_index(field)?;
+    let mut term_stream = inverted_index.terms().stream();
+    while term_stream.advance() {
+        let term_info = term_stream.value();
+        let mut block_segment_postings = inverted_index
+            .read_block_postings_from_terminfo(term_info, IndexRecordOption::Basic)?;
+        loop {
+            let docs = block_segment_postings.docs();
+            if docs.is_empty() {
+                break;
+            }
+            for &doc in docs {
+                doc_bitset.insert(doc);
+            }
+            block_segment_postings.advance();
+        }
+    }
+    Ok(())
+}
+
+/// Returns the set of the documents of the segment having at least one value for the field.
+///
+/// The field must support the `ExistsQuery`. (See `is_exists_supported`)
+pub(crate) fn docs_with_value(reader: &SegmentReader, field: Field) -> crate::Result<BitSet> {
+    let max_doc = reader.max_doc();
+    let mut doc_bitset = BitSet::with_max_value(max_doc);
+    let field_type = reader.schema().get_field_entry(field).field_type();
+    match presence_source(field_type) {
+        Some(PresenceSource::FieldNorms) => {
+            let fieldnorm_reader = reader.get_fieldnorms_reader(field)?;
+            for doc in 0..max_doc {
+                if fieldnorm_reader.fieldnorm_id(doc) != 0 {
+                    doc_bitset.insert(doc);
+                }
+            }
+        }
+        Some(PresenceSource::MultiValuedFastField) => {
+            let ff_reader = reader.fast_fields().u64s_lenient(field).ok_or_else(|| {
+                TantivyError::SchemaError(format!(
+                    "Field {:?} is not a multivalued fast field",
+                    reader.schema().get_field_name(field)
+                ))
+            })?;
+            for doc in 0..max_doc {
+                if ff_reader.num_vals(doc) > 0 {
+                    doc_bitset.insert(doc);
+                }
+            }
+        }
+        None => {
+            union_postings(reader, field, &mut doc_bitset)?;
+        }
+    }
+    Ok(doc_bitset)
+}
+
+impl Weight for ExistsWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        let doc_bitset = BitSetDocSet::from(docs_with_value(reader, self.field)?);
+        Ok(Box::new(ConstScorer::new(doc_bitset, boost)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new("ExistsQuery", 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExistsQuery;
+    use crate::collector::Count;
+    use crate::query::{Query, QueryParser};
+    use crate::schema::{
+        Cardinality, Facet, IntOptions, Schema, TextFieldIndexing, TextOptions, FAST, INDEXED,
+        STORED, STRING, TEXT,
+    };
+    use crate::{Document, Index, Term};
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_exists_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let string = schema_builder.add_text_field("string", STRING);
+        let num = schema_builder.add_i64_field("num", INDEXED);
+        let nums = schema_builder.add_u64_field(
+            "nums",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let facet = schema_builder.add_facet_field("facet");
+        let stored = schema_builder.add_text_field("stored", STORED);
+        let fast = schema_builder.add_u64_field("fast", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "hello", num => -1i64, nums => 1u64));
+        index_writer.add_document(doc!(string => "hello", facet => Facet::from("/a/b")));
+        index_writer.add_document(doc!(text => "", nums => 2u64, nums => 3u64));
+        index_writer.add_document(Document::new());
+        index_writer.commit()?;
+        index_writer.add_document(doc!(string => "world", num => 3i64));
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_i64(num, 3));
+        index_writer.add_document(doc!(text => "happy", num => 4i64));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let count = |field| searcher.search(&ExistsQuery::new(field), &Count).unwrap();
+        assert_eq!(count(text), 2);
+        assert_eq!(count(string), 1);
+        assert_eq!(count(num), 2);
+        assert_eq!(count(nums), 2);
+        assert_eq!(count(facet), 1);
+        assert!(ExistsQuery::new(stored).weight(&searcher, false).is_err());
+        assert!(ExistsQuery::new(fast).weight(&searcher, false).is_err());
+
+        let query_parser = QueryParser::for_index(&index, vec![text]);
+        let query = query_parser.parse_query("num:* AND -text:*")?;
+        assert_eq!(query.count(&searcher)?, 0);
+        let query = query_parser.parse_query("nums:* OR string:*")?;
+        assert_eq!(query.count(&searcher)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_exists_query_without_fieldnorms() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field(
+            "text",
+            TextOptions::default()
+                .set_indexing_options(TextFieldIndexing::default().set_fieldnorms(false)),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "hello"));
+        index_writer.add_document(doc!(text => ""));
+        index_writer.add_document(Document::new());
+        index_writer.add_document(doc!(text => "happy tax payer"));
+        index_writer.commit()?;
+        let reader = index.reader()?;
+        assert_eq!(
+            reader.searcher().search(&ExistsQuery::new(text), &Count)?,
+            2
+        );
+
+        // Merging segments with deletes does not rely on the fieldnorms either.
+        index_writer.add_document(doc!(text => "world"));
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(text, "hello"));
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        block_on(index_writer.merge(&segment_ids))?;
+        index_writer.wait_merging_threads()?;
+        reader.reload()?;
+        let searcher = reader.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        assert_eq!(searcher.search(&ExistsQuery::new(text), &Count)?, 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+use crate::common::BitSet;
+use crate::core::SegmentReader;
+use crate::fastfield::{fast_field_default_value, FastFieldNotAvailableError};
+use crate::query::explanation::does_not_match;
+use crate::query::{AllWeight, BitSetDocSet, ConstScorer, EmptyScorer, Explanation};
+use crate::query::{Scorer, Weight};
+use crate::schema::{Field, IndexRecordOption, Term};
+use crate::{DocId, DocSet, Score, TERMINATED};
+use std::collections::Bound;
+use std::ops::RangeInclusive;
+
+/// Decodes a bound expressed as the value bytes of a term
+/// into the `u64` representation used by fast fields.
+///
+/// Numerical terms are encoded as the big endian representation
+/// of the same monotonic `u64` mapping as the one used in fast fields.
+fn bytes_to_u64_bound(bound: &Bound<Vec<u8>>) -> Option<Bound<u64>> {
+    let to_u64 = |bytes: &Vec<u8>| -> Option<u64> {
+        let mut buffer = [0u8; 8];
+        if bytes.len() != buffer.len() {
+            return None;
+        }
+        buffer.copy_from_slice(&bytes[..]);
+        Some(u64::from_be_bytes(buffer))
+    };
+    Some(match bound {
+        Bound::Included(bytes) => Bound::Included(to_u64(bytes)?),
+        Bound::Excluded(bytes) => Bound::Excluded(to_u64(bytes)?),
+        Bound::Unbounded => Bound::Unbounded,
+    })
+}
+
+/// Converts a pair of bounds into an inclusive range.
+///
+/// Returns `None` if the range is empty.
+fn to_inclusive_range(
+    left_bound: &Bound<u64>,
+    right_bound: &Bound<u64>,
+) -> Option<RangeInclusive<u64>> {
+    let start = match *left_bound {
+        Bound::Included(val) => val,
+        Bound::Excluded(val) => val.checked_add(1)?,
+        Bound::Unbounded => 0u64,
+    };
+    let end = match *right_bound {
+        Bound::Included(val) => val,
+        Bound::Excluded(val) => val.checked_sub(1)?,
+        Bound::Unbounded => u64::MAX,
+    };
+    if start > end {
+        return None;
+    }
+    Some(start..=end)
+}
+
+/// `Weight` associated to a `RangeQuery` on a numerical fast field.
+///
+/// Rather than going through the term dictionary, this weight
+/// scans the fast field column of each segment.
+/// Segments for which the fast field `min_value`/`max_value`
+/// do not intersect the range are skipped entirely.
+pub(crate) struct FastFieldRangeWeight {
+    field: Field,
+    value_range: Option<RangeInclusive<u64>>,
+}
+
+impl FastFieldRangeWeight {
+    /// Creates a new `FastFieldRangeWeight` given the value bytes of the
+    /// bounding terms.
+    pub fn new(
+        field: Field,
+        left_bound: &Bound<Vec<u8>>,
+        right_bound: &Bound<Vec<u8>>,
+    ) -> FastFieldRangeWeight {
+        let value_range = bytes_to_u64_bound(left_bound)
+            .zip(bytes_to_u64_bound(right_bound))
+            .and_then(|(left, right)| to_inclusive_range(&left, &right));
+        FastFieldRangeWeight { field, value_range }
+    }
+
+    /// Returns the documents actually having the default value of a single-valued fast field,
+    /// read from the posting list of this value.
+    ///
+    /// Returns `None` if the field is not indexed, in which case the documents without a value
+    /// cannot be told apart from the documents having the default value.
+    fn docs_with_default_value(
+        &self,
+        reader: &SegmentReader,
+        default_value: u64,
+    ) -> crate::Result<Option<BitSet>> {
+        if !reader.schema().get_field_entry(self.field).is_indexed() {
+            return Ok(None);
+        }
+        let mut doc_bitset = BitSet::with_max_value(reader.max_doc());
+        let term = Term::from_field_u64(self.field, default_value);
+        if let Some(mut postings) = reader
+            .inverted_index(self.field)?
+            .read_postings(&term, IndexRecordOption::Basic)?
+        {
+            let mut doc = postings.doc();
+            while doc != TERMINATED {
+                doc_bitset.insert(doc);
+                doc = postings.advance();
+            }
+        }
+        Ok(Some(doc_bitset))
+    }
+
+    fn fast_field_not_available(&self, reader: &SegmentReader) -> crate::TantivyError {
+        let field_entry = reader.schema().get_field_entry(self.field);
+        From::from(FastFieldNotAvailableError::new(field_entry))
+    }
+}
+
+impl Weight for FastFieldRangeWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        let value_range = if let Some(value_range) = self.value_range.as_ref() {
+            value_range
+        } else {
+            return Ok(Box::new(EmptyScorer));
+        };
+        let max_doc = reader.max_doc();
+        let fast_fields = reader.fast_fields();
+        let mut doc_bitset = BitSet::with_max_value(max_doc);
+        if let Some(ff_reader) = fast_fields.u64_lenient(self.field) {
+            let (min_value, max_value) = (ff_reader.min_value(), ff_reader.max_value());
+            if max_value < *value_range.start() || min_value > *value_range.end() {
+                return Ok(Box::new(EmptyScorer));
+            }
+            // Documents without a value are recorded with the default value.
+            let default_value =
+                fast_field_default_value(reader.schema().get_field_entry(self.field));
+            let docs_with_default_value = if value_range.contains(&default_value) {
+                self.docs_with_default_value(reader, default_value)?
+            } else {
+                None
+            };
+            if docs_with_default_value.is_none()
+                && value_range.contains(&min_value)
+                && value_range.contains(&max_value)
+            {
+                return AllWeight.scorer(reader, boost);
+            }
+            for doc in 0..max_doc {
+                let val = ff_reader.get(doc);
+                if !value_range.contains(&val) {
+                    continue;
+                }
+                let has_value = val != default_value
+                    || docs_with_default_value
+                        .as_ref()
+                        .map(|docs| docs.contains(doc))
+                        .unwrap_or(true);
+                if has_value {
+                    doc_bitset.insert(doc);
+                }
+            }
+        } else if let Some(ff_reader) = fast_fields.u64s_lenient(self.field) {
+            if ff_reader.total_num_vals() == 0
+                || ff_reader.max_value() < *value_range.start()
+                || ff_reader.min_value() > *value_range.end()
+            {
+                return Ok(Box::new(EmptyScorer));
+            }
+            let mut vals = Vec::new();
+            for doc in 0..max_doc {
+                ff_reader.get_vals(doc, &mut vals);
+                if vals.iter().any(|val| value_range.contains(val)) {
+                    doc_bitset.insert(doc);
+                }
+            }
+        } else {
+            return Err(self.fast_field_not_available(reader));
+        }
+        let doc_bitset = BitSetDocSet::from(doc_bitset);
+        Ok(Box::new(ConstScorer::new(doc_bitset, boost)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new("RangeQuery", 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_inclusive_range;
+    use std::collections::Bound;
+
+    #[test]
+    fn test_to_inclusive_range() {
+        assert_eq!(
+            to_inclusive_range(&Bound::Included(3), &Bound::Excluded(5)),
+            Some(3..=4)
+        );
+        assert_eq!(
+            to_inclusive_range(&Bound::Excluded(3), &Bound::Included(5)),
+            Some(4..=5)
+        );
+        assert_eq!(
+            to_inclusive_range(&Bound::Unbounded, &Bound::Unbounded),
+            Some(0..=u64::MAX)
+        );
+        assert_eq!(
+            to_inclusive_range(&Bound::Included(3), &Bound::Excluded(3)),
+            None
+        );
+        assert_eq!(
+            to_inclusive_range(&Bound::Excluded(u64::MAX), &Bound::Unbounded),
+            None
+        );
+        assert_eq!(
+            to_inclusive_range(&Bound::Unbounded, &Bound::Excluded(0)),
+            None
+        );
+    }
+}
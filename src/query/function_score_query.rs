@@ -0,0 +1,746 @@
+use crate::fastfield::{DeleteBitSet, FastFieldReader};
+use crate::query::explanation::does_not_match;
+use crate::query::{Explanation, Query, Scorer, Weight};
+use crate::schema::{Cardinality, Field, FieldType, Type};
+use crate::{DocId, DocSet, Score, Searcher, SegmentReader, TantivyError, Term};
+use murmurhash32::murmurhash2;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Modifier applied to the fast field value by a `ScoreFunction::FieldValueFactor`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldValueModifier {
+    /// The value is used as is.
+    None,
+    /// `log10(1 + value)`
+    Log1p,
+    /// `ln(1 + value)`
+    Ln1p,
+    /// `sqrt(value)`
+    Sqrt,
+    /// `value * value`
+    Square,
+    /// `1 / value`
+    Reciprocal,
+}
+
+impl FieldValueModifier {
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            FieldValueModifier::None => value,
+            FieldValueModifier::Log1p => value.ln_1p() / std::f64::consts::LN_10,
+            FieldValueModifier::Ln1p => value.ln_1p(),
+            FieldValueModifier::Sqrt => value.sqrt(),
+            FieldValueModifier::Square => value * value,
+            FieldValueModifier::Reciprocal => 1.0 / value,
+        }
+    }
+}
+
+/// Shape of the curve of a `ScoreFunction::Decay`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecayFunction {
+    /// Exponential decay.
+    Exp,
+    /// Gaussian decay.
+    Gauss,
+    /// Linear decay. The score reaches 0 for distances greater than
+    /// `scale / (1 - decay)`.
+    Linear,
+}
+
+/// Defines how the score of the underlying query and the value of the
+/// `ScoreFunction` are combined.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CombineMode {
+    /// `score * function_value`
+    Multiply,
+    /// `function_value`. The score of the underlying query is ignored.
+    Replace,
+    /// `score + function_value`
+    Sum,
+    /// `(score + function_value) / 2`
+    Avg,
+    /// `max(score, function_value)`
+    Max,
+    /// `min(score, function_value)`
+    Min,
+}
+
+// `#[derive(Default)]` on enums requires a more recent version of rustc.
+#[allow(clippy::derivable_impls)]
+impl Default for CombineMode {
+    fn default() -> CombineMode {
+        CombineMode::Multiply
+    }
+}
+
+impl CombineMode {
+    fn combine(self, score: Score, function_value: Score) -> Score {
+        match self {
+            CombineMode::Multiply => score * function_value,
+            CombineMode::Replace => function_value,
+            CombineMode::Sum => score + function_value,
+            CombineMode::Avg => (score + function_value) / 2.0,
+            CombineMode::Max => score.max(function_value),
+            CombineMode::Min => score.min(function_value),
+        }
+    }
+}
+
+/// A function computing a per-document value, used by the `FunctionScoreQuery`.
+///
+/// Fast field based functions require a single-valued `u64`, `i64`, `f64` or
+/// `date` fast field. Dates are expressed as a timestamp in seconds.
+///
+/// If the function is not defined for the value of a document (e.g. the `Reciprocal`
+/// of `0`, or the `Ln1p` of a value lower than `-1`), its value is clamped to `0`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScoreFunction {
+    /// `modifier(factor * value)`, where `value` is the value of the fast field.
+    FieldValueFactor {
+        /// Fast field to read the value from.
+        field: Field,
+        /// Factor the value is multiplied by.
+        factor: f64,
+        /// Modifier applied to the resulting value.
+        modifier: FieldValueModifier,
+    },
+    /// A value decaying with the distance between the value of the fast field and `origin`.
+    ///
+    /// The function is equal to 1 for distances lower than `offset`, and equal
+    /// to `decay` for distances of `offset + scale`.
+    Decay {
+        /// Fast field to read the value from.
+        field: Field,
+        /// Shape of the decay.
+        function: DecayFunction,
+        /// Value for which the function is maximal.
+        origin: f64,
+        /// Distance from `origin + offset` at which the function is equal to `decay`.
+        scale: f64,
+        /// Distance from `origin` under which the function is equal to 1.
+        offset: f64,
+        /// Value of the function at a distance `offset + scale` from `origin`.
+        decay: f64,
+    },
+    /// A pseudo random value in `[0, 1)`.
+    ///
+    /// The value only depends on the seed, the segment and the document, so
+    /// that a given seed gives the same order as long as the segments are not merged.
+    RandomScore {
+        /// Seed of the random function.
+        seed: u64,
+    },
+}
+
+impl ScoreFunction {
+    /// Creates a `FieldValueFactor` function.
+    pub fn field_value_factor(
+        field: Field,
+        factor: f64,
+        modifier: FieldValueModifier,
+    ) -> ScoreFunction {
+        ScoreFunction::FieldValueFactor {
+            field,
+            factor,
+            modifier,
+        }
+    }
+
+    /// Creates a `Decay` function over a numerical fast field.
+    ///
+    /// Returns an error if `origin` or `offset` are not finite, if `scale` is not
+    /// strictly positive or if `decay` is not within `(0, 1)`.
+    pub fn decay(
+        field: Field,
+        function: DecayFunction,
+        origin: f64,
+        scale: f64,
+        offset: f64,
+        decay: f64,
+    ) -> crate::Result<ScoreFunction> {
+        let score_function = ScoreFunction::Decay {
+            field,
+            function,
+            origin,
+            scale,
+            offset,
+            decay,
+        };
+        score_function.validate()?;
+        Ok(score_function)
+    }
+
+    /// Creates a `Decay` function over a date fast field.
+    ///
+    /// See [`ScoreFunction::decay`](#method.decay) for the validation of the parameters.
+    pub fn date_decay(
+        field: Field,
+        function: DecayFunction,
+        origin: crate::DateTime,
+        scale: chrono::Duration,
+        offset: chrono::Duration,
+        decay: f64,
+    ) -> crate::Result<ScoreFunction> {
+        ScoreFunction::decay(
+            field,
+            function,
+            origin.timestamp() as f64,
+            scale.num_seconds() as f64,
+            offset.num_seconds() as f64,
+            decay,
+        )
+    }
+
+    /// Creates a `RandomScore` function.
+    pub fn random_score(seed: u64) -> ScoreFunction {
+        ScoreFunction::RandomScore { seed }
+    }
+
+    /// Checks that the parameters of the function are valid.
+    fn validate(&self) -> crate::Result<()> {
+        if let ScoreFunction::Decay {
+            origin,
+            scale,
+            offset,
+            decay,
+            ..
+        } = *self
+        {
+            if !origin.is_finite() || !offset.is_finite() {
+                return Err(TantivyError::InvalidArgument(format!(
+                    "The origin ({}) and offset ({}) of a decay function must be finite",
+                    origin, offset
+                )));
+            }
+            if !(scale.is_finite() && scale > 0.0) {
+                return Err(TantivyError::InvalidArgument(format!(
+                    "The scale of a decay function must be strictly positive, got {}",
+                    scale
+                )));
+            }
+            if !(decay > 0.0 && decay < 1.0) {
+                return Err(TantivyError::InvalidArgument(format!(
+                    "The decay of a decay function must be within (0, 1), got {}",
+                    decay
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn field(&self) -> Option<Field> {
+        match *self {
+            ScoreFunction::FieldValueFactor { field, .. } | ScoreFunction::Decay { field, .. } => {
+                Some(field)
+            }
+            ScoreFunction::RandomScore { .. } => None,
+        }
+    }
+
+    /// Computes the value of the function given the value of the fast field.
+    fn eval(&self, value: f64) -> f64 {
+        match *self {
+            ScoreFunction::FieldValueFactor {
+                factor, modifier, ..
+            } => modifier.apply(factor * value),
+            ScoreFunction::Decay {
+                function,
+                origin,
+                scale,
+                offset,
+                decay,
+                ..
+            } => {
+                let distance = ((value - origin).abs() - offset).max(0.0);
+                match function {
+                    DecayFunction::Exp => (decay.ln() / scale * distance).exp(),
+                    DecayFunction::Gauss => {
+                        let variance = -scale * scale / (2.0 * decay.ln());
+                        (-distance * distance / (2.0 * variance)).exp()
+                    }
+                    DecayFunction::Linear => {
+                        let max_distance = scale / (1.0 - decay);
+                        ((max_distance - distance) / max_distance).max(0.0)
+                    }
+                }
+            }
+            ScoreFunction::RandomScore { .. } => value,
+        }
+    }
+}
+
+/// `FunctionScoreQuery` is a wrapper over a query that alters its score
+/// using a `ScoreFunction`.
+///
+/// The document set matched by the `FunctionScoreQuery` is strictly the same as the
+/// underlying query. The score of each document is obtained by combining the score of
+/// the underlying query with the value of the function, as defined by the `CombineMode`.
+///
+/// Unlike `TopDocs::tweak_score`, a `FunctionScoreQuery` can be nested in
+/// other queries such as a `BooleanQuery`.
+///
+/// ```rust
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::{CombineMode, FieldValueModifier, FunctionScoreQuery, QueryParser, ScoreFunction};
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{doc, DocAddress, Index};
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let popularity = schema_builder.add_u64_field("popularity", FAST);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+/// {
+///     let mut index_writer = index.writer(3_000_000)?;
+///     index_writer.add_document(doc!(title => "The Diary of Muadib", popularity => 10u64));
+///     index_writer.add_document(doc!(title => "The Diary of a Young Girl", popularity => 1000u64));
+///     index_writer.commit()?;
+/// }
+/// let reader = index.reader()?;
+/// let searcher = reader.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("diary")?;
+/// let query = FunctionScoreQuery::new(
+///     query,
+///     ScoreFunction::field_value_factor(popularity, 1.0, FieldValueModifier::Log1p),
+/// )
+/// .with_combine_mode(CombineMode::Multiply);
+/// let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+/// assert_eq!(top_docs[0].1, DocAddress(0, 1));
+/// Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+pub struct FunctionScoreQuery {
+    query: Box<dyn Query>,
+    function: ScoreFunction,
+    combine_mode: CombineMode,
+}
+
+impl FunctionScoreQuery {
+    /// Builds a function score query.
+    ///
+    /// By default, the score of the underlying query is multiplied by the
+    /// value of the function.
+    pub fn new(query: Box<dyn Query>, function: ScoreFunction) -> FunctionScoreQuery {
+        FunctionScoreQuery {
+            query,
+            function,
+            combine_mode: CombineMode::default(),
+        }
+    }
+
+    /// Sets how the score of the underlying query and the value of the function
+    /// are combined.
+    pub fn with_combine_mode(mut self, combine_mode: CombineMode) -> FunctionScoreQuery {
+        self.combine_mode = combine_mode;
+        self
+    }
+
+    /// Returns the underlying query.
+    pub fn query(&self) -> &dyn Query {
+        self.query.as_ref()
+    }
+
+    pub(crate) fn map_query<F>(self, f: F) -> Option<FunctionScoreQuery>
+    where
+        F: FnOnce(Box<dyn Query>) -> Option<Box<dyn Query>>,
+    {
+        let FunctionScoreQuery {
+            query,
+            function,
+            combine_mode,
+        } = self;
+        f(query).map(|query| FunctionScoreQuery {
+            query,
+            function,
+            combine_mode,
+        })
+    }
+}
+
+impl Clone for FunctionScoreQuery {
+    fn clone(&self) -> Self {
+        FunctionScoreQuery {
+            query: self.query.box_clone(),
+            function: self.function.clone(),
+            combine_mode: self.combine_mode,
+        }
+    }
+}
+
+impl fmt::Debug for FunctionScoreQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "FunctionScore(query={:?}, function={:?}, combine_mode={:?})",
+            self.query, self.function, self.combine_mode
+        )
+    }
+}
+
+impl Query for FunctionScoreQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        self.function.validate()?;
+        if let Some(field) = self.function.field() {
+            let field_entry = searcher.schema().get_field_entry(field);
+            let cardinality = match field_entry.field_type() {
+                FieldType::U64(options)
+                | FieldType::I64(options)
+                | FieldType::F64(options)
+                | FieldType::Date(options) => options.get_fastfield_cardinality(),
+                _ => None,
+            };
+            if cardinality != Some(Cardinality::SingleValue) {
+                return Err(TantivyError::SchemaError(format!(
+                    "Field {:?} is not a single-valued numerical fast field",
+                    field_entry.name()
+                )));
+            }
+        }
+        let weight = self.query.weight(searcher, scoring_enabled)?;
+        if !scoring_enabled {
+            return Ok(weight);
+        }
+        Ok(Box::new(FunctionScoreWeight {
+            weight,
+            function: self.function.clone(),
+            combine_mode: self.combine_mode,
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.query.query_terms(term_set)
+    }
+
+    fn query_phrases(&self, phrases: &mut Vec<Vec<(usize, Term)>>) {
+        self.query.query_phrases(phrases)
+    }
+}
+
+struct FunctionScoreWeight {
+    weight: Box<dyn Weight>,
+    function: ScoreFunction,
+    combine_mode: CombineMode,
+}
+
+impl FunctionScoreWeight {
+    fn segment_function(&self, reader: &SegmentReader) -> crate::Result<SegmentScoreFunction> {
+        let column = if let Some(field) = self.function.field() {
+            let ff_reader = reader.fast_fields().u64_lenient(field).ok_or_else(|| {
+                let field_name = reader.schema().get_field_name(field);
+                TantivyError::SchemaError(format!(
+                    "Field {:?} is not a fast field in this segment",
+                    field_name
+                ))
+            })?;
+            let value_type = reader
+                .schema()
+                .get_field_entry(field)
+                .field_type()
+                .value_type();
+            Some((ff_reader, value_type))
+        } else {
+            None
+        };
+        Ok(SegmentScoreFunction {
+            function: self.function.clone(),
+            column,
+            segment_salt: murmurhash2(reader.segment_id().uuid_string().as_bytes()),
+        })
+    }
+}
+
+impl Weight for FunctionScoreWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        let scorer = self.weight.scorer(reader, 1.0)?;
+        Ok(Box::new(FunctionScorer {
+            scorer,
+            function: self.segment_function(reader)?,
+            combine_mode: self.combine_mode,
+            boost,
+        }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let mut explanation = Explanation::new(
+            format!("FunctionScore ({:?}) of ...", self.combine_mode),
+            scorer.score(),
+        );
+        explanation.add_detail(self.weight.explain(reader, doc)?);
+        let function_value = self.segment_function(reader)?.eval(doc);
+        explanation.add_detail(Explanation::new(
+            format!("{:?}", self.function),
+            function_value,
+        ));
+        Ok(explanation)
+    }
+
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        self.weight.count(reader)
+    }
+}
+
+/// `ScoreFunction` bound to a specific segment.
+struct SegmentScoreFunction {
+    function: ScoreFunction,
+    column: Option<(FastFieldReader<u64>, Type)>,
+    segment_salt: u32,
+}
+
+impl SegmentScoreFunction {
+    fn value(&self, doc: DocId) -> f64 {
+        if let ScoreFunction::RandomScore { seed } = self.function {
+            let mut bytes = [0u8; 16];
+            bytes[..8].copy_from_slice(&seed.to_le_bytes());
+            bytes[8..12].copy_from_slice(&self.segment_salt.to_le_bytes());
+            bytes[12..].copy_from_slice(&doc.to_le_bytes());
+            return f64::from(murmurhash2(&bytes)) / (f64::from(u32::MAX) + 1.0);
+        }
+        match self.column {
+            Some((ref ff_reader, value_type)) => {
+                let val = ff_reader.get(doc);
+                match value_type {
+                    Type::I64 | Type::Date => crate::u64_to_i64(val) as f64,
+                    Type::F64 => crate::u64_to_f64(val),
+                    _ => val as f64,
+                }
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Computes the value of the function for the given document.
+    ///
+    /// Non-finite values are clamped to `0`.
+    fn eval(&self, doc: DocId) -> Score {
+        let function_value = self.function.eval(self.value(doc));
+        if function_value.is_finite() {
+            function_value as Score
+        } else {
+            0.0
+        }
+    }
+}
+
+struct FunctionScorer {
+    scorer: Box<dyn Scorer>,
+    function: SegmentScoreFunction,
+    combine_mode: CombineMode,
+    boost: Score,
+}
+
+impl DocSet for FunctionScorer {
+    fn advance(&mut self) -> DocId {
+        self.scorer.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.scorer.seek(target)
+    }
+
+    fn fill_buffer(&mut self, buffer: &mut [DocId]) -> usize {
+        self.scorer.fill_buffer(buffer)
+    }
+
+    fn doc(&self) -> DocId {
+        self.scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorer.size_hint()
+    }
+
+    fn count(&mut self, delete_bitset: &DeleteBitSet) -> u32 {
+        self.scorer.count(delete_bitset)
+    }
+
+    fn count_including_deleted(&mut self) -> u32 {
+        self.scorer.count_including_deleted()
+    }
+}
+
+impl Scorer for FunctionScorer {
+    fn score(&mut self) -> Score {
+        let function_value = self.function.eval(self.doc());
+        self.combine_mode
+            .combine(self.scorer.score(), function_value)
+            * self.boost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CombineMode, DecayFunction, FieldValueModifier, FunctionScoreQuery, ScoreFunction,
+    };
+    use crate::assert_nearly_equals;
+    use crate::collector::TopDocs;
+    use crate::query::{AllQuery, BooleanQuery, Occur, Query, TermQuery};
+    use crate::schema::{
+        Cardinality, IndexRecordOption, IntOptions, Schema, FAST, INDEXED, STRING,
+    };
+    use crate::{DocAddress, Index, TantivyError, Term};
+
+    #[test]
+    fn test_score_function_eval() {
+        let field = crate::schema::Field::from_field_id(0);
+        let factor = ScoreFunction::field_value_factor(field, 2.0, FieldValueModifier::Sqrt);
+        assert_nearly_equals!(factor.eval(8.0) as f32, 4.0);
+        for &function in &[
+            DecayFunction::Exp,
+            DecayFunction::Gauss,
+            DecayFunction::Linear,
+        ] {
+            let decay = ScoreFunction::decay(field, function, 100.0, 10.0, 5.0, 0.5).unwrap();
+            assert_nearly_equals!(decay.eval(100.0) as f32, 1.0);
+            assert_nearly_equals!(decay.eval(103.0) as f32, 1.0);
+            assert_nearly_equals!(decay.eval(115.0) as f32, 0.5);
+            assert_nearly_equals!(decay.eval(85.0) as f32, 0.5);
+            assert!(decay.eval(120.0) < 0.5);
+        }
+        let linear =
+            ScoreFunction::decay(field, DecayFunction::Linear, 0.0, 10.0, 0.0, 0.5).unwrap();
+        assert_nearly_equals!(linear.eval(30.0) as f32, 0.0);
+    }
+
+    #[test]
+    fn test_decay_invalid_parameters() {
+        let field = crate::schema::Field::from_field_id(0);
+        let decay = |origin: f64, scale: f64, offset: f64, decay: f64| {
+            ScoreFunction::decay(field, DecayFunction::Exp, origin, scale, offset, decay)
+        };
+        assert!(decay(0.0, 1.0, 0.0, 0.5).is_ok());
+        for &(origin, scale, offset, decay_val) in &[
+            (0.0, 0.0, 0.0, 0.5),
+            (0.0, -1.0, 0.0, 0.5),
+            (0.0, f64::INFINITY, 0.0, 0.5),
+            (0.0, 1.0, 0.0, 0.0),
+            (0.0, 1.0, 0.0, 1.0),
+            (0.0, 1.0, 0.0, f64::NAN),
+            (f64::NAN, 1.0, 0.0, 0.5),
+            (0.0, 1.0, f64::INFINITY, 0.5),
+        ] {
+            assert!(matches!(
+                decay(origin, scale, offset, decay_val),
+                Err(TantivyError::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_function_score_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let category = schema_builder.add_text_field("category", STRING);
+        let popularity = schema_builder.add_u64_field("popularity", FAST);
+        let date = schema_builder.add_date_field("date", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        let origin = chrono::Utc::now();
+        for (i, days) in [3i64, 0, 10, 1].iter().enumerate() {
+            index_writer.add_document(doc!(
+                category => "book",
+                popularity => i as u64,
+                date => origin - chrono::Duration::days(*days),
+            ));
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let doc_ids = |query: &dyn Query| -> Vec<u32> {
+            searcher
+                .search(query, &TopDocs::with_limit(10))
+                .unwrap()
+                .into_iter()
+                .map(|(_, doc_address)| doc_address.doc())
+                .collect()
+        };
+
+        let popular = FunctionScoreQuery::new(
+            Box::new(AllQuery),
+            ScoreFunction::field_value_factor(popularity, 1.0, FieldValueModifier::None),
+        );
+        assert_eq!(doc_ids(&popular), vec![3, 2, 1, 0]);
+        assert_eq!(popular.count(&searcher)?, 4);
+
+        let recent = FunctionScoreQuery::new(
+            Box::new(AllQuery),
+            ScoreFunction::date_decay(
+                date,
+                DecayFunction::Gauss,
+                origin,
+                chrono::Duration::days(2),
+                chrono::Duration::zero(),
+                0.5,
+            )?,
+        )
+        .with_combine_mode(CombineMode::Replace);
+        assert_eq!(doc_ids(&recent), vec![1, 3, 0, 2]);
+        let explanation = recent.explain(&searcher, DocAddress(0, 3))?;
+        assert_nearly_equals!(explanation.value(), 0.840_896_4);
+
+        // Function score queries can be nested in boolean queries.
+        let boolean_query = BooleanQuery::from(vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(category, "book"),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+            (Occur::Should, Box::new(recent)),
+        ]);
+        assert_eq!(doc_ids(&boolean_query), vec![1, 3, 0, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_function_score_query_random() -> crate::Result<()> {
+        let schema = Schema::builder().build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        for _ in 0..100 {
+            index_writer.add_document(doc!());
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let random_query = |seed: u64| {
+            FunctionScoreQuery::new(Box::new(AllQuery), ScoreFunction::random_score(seed))
+                .with_combine_mode(CombineMode::Replace)
+        };
+        let top_docs =
+            |query: &FunctionScoreQuery| searcher.search(query, &TopDocs::with_limit(100)).unwrap();
+        let top_docs_seed_1 = top_docs(&random_query(1));
+        assert_eq!(top_docs_seed_1.len(), 100);
+        assert!(top_docs_seed_1
+            .iter()
+            .all(|(score, _)| *score >= 0.0 && *score < 1.0));
+        assert_eq!(top_docs_seed_1, top_docs(&random_query(1)));
+        assert_ne!(top_docs_seed_1, top_docs(&random_query(2)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_function_score_query_requires_single_valued_fast_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let not_fast = schema_builder.add_u64_field("not_fast", INDEXED);
+        let multivalued = schema_builder.add_u64_field(
+            "multivalued",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let searcher = index.reader()?.searcher();
+        for &field in &[not_fast, multivalued] {
+            let query = FunctionScoreQuery::new(
+                Box::new(AllQuery),
+                ScoreFunction::field_value_factor(field, 1.0, FieldValueModifier::None),
+            );
+            assert!(query.weight(&searcher, true).is_err());
+        }
+        Ok(())
+    }
+}
@@ -35,7 +35,7 @@ impl Automaton for DFAWrapper {
 
 /// A range of Levenshtein distances that we will build DFAs for our terms
 /// The computation is exponential, so best keep it to low single digits
-const VALID_LEVENSHTEIN_DISTANCE_RANGE: Range<u8> = 0..3;
+pub(crate) const VALID_LEVENSHTEIN_DISTANCE_RANGE: Range<u8> = 0..3;
 
 static LEV_BUILDER: Lazy<HashMap<(u8, bool), LevenshteinAutomatonBuilder>> = Lazy::new(|| {
     let mut lev_builder_cache = HashMap::new();
@@ -129,7 +129,7 @@ impl FuzzyTermQuery {
 
     fn specialized_weight(&self) -> crate::Result<AutomatonWeight<DFAWrapper>> {
         // LEV_BUILDER is a HashMap, whose `get` method returns an Option
-        match LEV_BUILDER.get(&(self.distance, false)) {
+        match LEV_BUILDER.get(&(self.distance, self.transposition_cost_one)) {
             // Unwrap the option and build the Ok(AutomatonWeight)
             Some(automaton_builder) => {
                 let automaton = if self.prefix {
@@ -225,5 +225,22 @@ mod test {
             let (score, _) = top_docs[0];
             assert_nearly_equals!(1.0, score);
         }
+
+        // passes only if a transposition costs 1 ('a' and 'p' are swapped)
+        {
+            let term = Term::from_field_text(country_field, "jpaan");
+
+            let fuzzy_query = FuzzyTermQuery::new(term.clone(), 1, true);
+            let top_docs = searcher
+                .search(&fuzzy_query, &TopDocs::with_limit(2))
+                .unwrap();
+            assert_eq!(top_docs.len(), 1, "Expected only 1 document");
+
+            let fuzzy_query = FuzzyTermQuery::new(term, 1, false);
+            let top_docs = searcher
+                .search(&fuzzy_query, &TopDocs::with_limit(2))
+                .unwrap();
+            assert_eq!(top_docs.len(), 0, "Expected no document");
+        }
     }
 }
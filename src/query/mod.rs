@@ -3,31 +3,39 @@
 mod all_query;
 mod automaton_weight;
 mod bitset;
-mod bm25;
 mod boolean_query;
 mod boost_query;
+mod const_score_query;
 mod empty_query;
 mod exclude;
+mod exists_query;
 mod explanation;
+mod fast_field_range_weight;
+mod function_score_query;
 mod fuzzy_query;
 mod intersection;
+mod more_like_this;
 mod phrase_query;
 mod query;
 mod query_parser;
 mod range_query;
 mod regex_query;
 mod reqopt_scorer;
+mod rewriter;
 mod scorer;
+mod similarity;
 mod term_query;
 mod union;
 mod weight;
+mod wildcard_query;
 
 #[cfg(test)]
 mod vec_docset;
 
 pub(crate) mod score_combiner;
-pub(crate) use self::bm25::BM25Weight;
+pub(crate) use self::exists_query::docs_with_value;
 pub use self::intersection::Intersection;
+pub(crate) use self::similarity::SimilarityWeight;
 pub use self::union::Union;
 
 #[cfg(test)]
@@ -38,24 +46,35 @@ pub use self::automaton_weight::AutomatonWeight;
 pub use self::bitset::BitSetDocSet;
 pub use self::boolean_query::BooleanQuery;
 pub use self::boost_query::BoostQuery;
+pub use self::const_score_query::ConstScoreQuery;
 pub use self::empty_query::{EmptyQuery, EmptyScorer, EmptyWeight};
 pub use self::exclude::Exclude;
+pub use self::exists_query::ExistsQuery;
 pub use self::explanation::Explanation;
+pub use self::function_score_query::{
+    CombineMode, DecayFunction, FieldValueModifier, FunctionScoreQuery, ScoreFunction,
+};
 #[cfg(test)]
 pub(crate) use self::fuzzy_query::DFAWrapper;
 pub use self::fuzzy_query::FuzzyTermQuery;
 pub use self::intersection::intersect_scorers;
+pub use self::more_like_this::{MoreLikeThisQuery, MoreLikeThisQueryBuilder};
 pub use self::phrase_query::PhraseQuery;
 pub use self::query::{Query, QueryClone};
+pub use self::query_parser::FuzzyConfig;
+pub use self::query_parser::QueryDsl;
 pub use self::query_parser::QueryParser;
 pub use self::query_parser::QueryParserError;
+pub use self::query_parser::{LogicalAST, LogicalLiteral};
 pub use self::range_query::RangeQuery;
 pub use self::regex_query::RegexQuery;
 pub use self::reqopt_scorer::RequiredOptionalScorer;
+pub use self::rewriter::{rewrite_query, subqueries, visit_query, QueryRewriter, QueryVisitor};
 pub use self::scorer::ConstScorer;
 pub use self::scorer::Scorer;
 pub use self::term_query::TermQuery;
 pub use self::weight::Weight;
+pub use self::wildcard_query::WildcardQuery;
 pub use tantivy_query_grammar::Occur;
 
 #[cfg(test)]
@@ -0,0 +1,276 @@
+use crate::query::similarity::idf;
+use crate::query::{BooleanQuery, BoostQuery, Occur, Query, TermQuery};
+use crate::schema::{Field, FieldType, IndexRecordOption, Term, Value};
+use crate::tokenizer::{BoxTokenStream, FacetTokenizer, PreTokenizedStream, Tokenizer};
+use crate::{DocAddress, Score, Searcher};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// A term with its `tf-idf` score, as computed while
+/// building a `MoreLikeThis` query.
+#[derive(Debug, PartialEq)]
+struct ScoreTerm {
+    term: Term,
+    score: Score,
+}
+
+impl Eq for ScoreTerm {}
+
+impl PartialOrd for ScoreTerm {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// The ordering is reversed so that the `BinaryHeap` behaves as a min-heap
+// and the lowest scoring term is popped first.
+// Ties are broken on the term, to make the selection deterministic.
+impl Ord for ScoreTerm {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .partial_cmp(&self.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.term.cmp(&other.term))
+    }
+}
+
+/// Parameters of a `MoreLikeThisQuery`.
+///
+/// It selects the most "interesting" terms of a document, as measured by
+/// their `tf-idf`, and builds a disjunction of `TermQuery` out of them.
+#[derive(Debug, Clone)]
+pub(crate) struct MoreLikeThis {
+    /// Ignore terms appearing in less documents than this.
+    pub min_doc_frequency: Option<u64>,
+    /// Ignore terms appearing in more documents than this.
+    pub max_doc_frequency: Option<u64>,
+    /// Ignore terms appearing less than this number of times in the source document.
+    pub min_term_frequency: Option<usize>,
+    /// Maximum number of terms in the resulting query.
+    pub max_query_terms: Option<usize>,
+    /// Ignore words shorter than this.
+    pub min_word_length: Option<usize>,
+    /// Ignore words longer than this.
+    pub max_word_length: Option<usize>,
+    /// Boost applied to the best term. The other terms are boosted
+    /// proportionally to their score.
+    pub boost_factor: Option<Score>,
+    /// Words that should never be selected.
+    pub stop_words: Vec<String>,
+}
+
+impl Default for MoreLikeThis {
+    fn default() -> Self {
+        MoreLikeThis {
+            min_doc_frequency: Some(5),
+            max_doc_frequency: None,
+            min_term_frequency: Some(2),
+            max_query_terms: Some(25),
+            min_word_length: None,
+            max_word_length: None,
+            boost_factor: Some(1.0),
+            stop_words: vec![],
+        }
+    }
+}
+
+impl MoreLikeThis {
+    /// Creates a `BooleanQuery` out of the fields of the document stored
+    /// at `doc_address`.
+    pub fn query_with_document(
+        &self,
+        searcher: &Searcher,
+        doc_address: DocAddress,
+    ) -> crate::Result<BooleanQuery> {
+        let score_terms = self.retrieve_terms_from_doc_address(searcher, doc_address)?;
+        Ok(self.create_query(score_terms))
+    }
+
+    /// Creates a `BooleanQuery` out of a list of field values.
+    pub fn query_with_document_fields(
+        &self,
+        searcher: &Searcher,
+        doc_fields: &[(Field, Vec<Value>)],
+    ) -> crate::Result<BooleanQuery> {
+        let score_terms = self.retrieve_terms_from_doc_fields(searcher, doc_fields)?;
+        Ok(self.create_query(score_terms))
+    }
+
+    fn create_query(&self, score_terms: Vec<ScoreTerm>) -> BooleanQuery {
+        let best_score = score_terms
+            .iter()
+            .map(|score_term| score_term.score)
+            .fold(0.0, Score::max);
+        let queries = score_terms
+            .into_iter()
+            .map(|ScoreTerm { term, score }| {
+                let term_query: Box<dyn Query> =
+                    Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs));
+                let query: Box<dyn Query> = match self.boost_factor {
+                    Some(boost_factor) if best_score > 0.0 => Box::new(BoostQuery::new(
+                        term_query,
+                        boost_factor * score / best_score,
+                    )),
+                    _ => term_query,
+                };
+                (Occur::Should, query)
+            })
+            .collect::<Vec<_>>();
+        BooleanQuery::from(queries)
+    }
+
+    fn retrieve_terms_from_doc_address(
+        &self,
+        searcher: &Searcher,
+        doc_address: DocAddress,
+    ) -> crate::Result<Vec<ScoreTerm>> {
+        let doc = searcher.doc(doc_address)?;
+        let doc_fields = doc
+            .get_sorted_field_values()
+            .into_iter()
+            .map(|(field, values)| {
+                let values = values
+                    .into_iter()
+                    .map(|field_value| field_value.value().clone())
+                    .collect::<Vec<_>>();
+                (field, values)
+            })
+            .collect::<Vec<_>>();
+        self.retrieve_terms_from_doc_fields(searcher, &doc_fields)
+    }
+
+    fn retrieve_terms_from_doc_fields(
+        &self,
+        searcher: &Searcher,
+        doc_fields: &[(Field, Vec<Value>)],
+    ) -> crate::Result<Vec<ScoreTerm>> {
+        let mut term_frequencies: HashMap<Term, usize> = HashMap::new();
+        for (field, values) in doc_fields {
+            self.add_term_frequencies(searcher, *field, values, &mut term_frequencies)?;
+        }
+        self.create_score_terms(searcher, term_frequencies)
+    }
+
+    /// Computes the term frequencies of the terms emitted for the given field values.
+    fn add_term_frequencies(
+        &self,
+        searcher: &Searcher,
+        field: Field,
+        values: &[Value],
+        term_frequencies: &mut HashMap<Term, usize>,
+    ) -> crate::Result<()> {
+        let field_entry = searcher.schema().get_field_entry(field);
+        if !field_entry.is_indexed() {
+            return Ok(());
+        }
+        match field_entry.field_type() {
+            FieldType::Str(_) => {
+                let tokenizer = searcher.index().tokenizer_for_field(field)?;
+                for value in values {
+                    let mut token_stream: BoxTokenStream = match value {
+                        Value::Str(text) => tokenizer.token_stream(text),
+                        Value::PreTokStr(tok_str) => {
+                            PreTokenizedStream::from(tok_str.clone()).into()
+                        }
+                        _ => continue,
+                    };
+                    token_stream.process(&mut |token| {
+                        if !self.is_noise_word(&token.text) {
+                            let term = Term::from_field_text(field, &token.text);
+                            *term_frequencies.entry(term).or_insert(0) += 1;
+                        }
+                    });
+                }
+            }
+            FieldType::HierarchicalFacet(_) => {
+                for value in values {
+                    if let Value::Facet(facet) = value {
+                        let mut token_stream = FacetTokenizer.token_stream(facet.encoded_str());
+                        token_stream.process(&mut |token| {
+                            let term = Term::from_field_text(field, &token.text);
+                            *term_frequencies.entry(term).or_insert(0) += 1;
+                        });
+                    }
+                }
+            }
+            FieldType::U64(_) | FieldType::I64(_) | FieldType::F64(_) | FieldType::Date(_) => {
+                for value in values {
+                    let term = match value {
+                        Value::U64(val) => Term::from_field_u64(field, *val),
+                        Value::I64(val) => Term::from_field_i64(field, *val),
+                        Value::F64(val) => Term::from_field_f64(field, *val),
+                        Value::Date(val) => Term::from_field_date(field, val),
+                        _ => continue,
+                    };
+                    *term_frequencies.entry(term).or_insert(0) += 1;
+                }
+            }
+            FieldType::Bytes(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Scores the candidate terms by `tf-idf` and only keeps
+    /// the `max_query_terms` best ones.
+    fn create_score_terms(
+        &self,
+        searcher: &Searcher,
+        term_frequencies: HashMap<Term, usize>,
+    ) -> crate::Result<Vec<ScoreTerm>> {
+        // Document frequencies include deleted documents:
+        // like `BM25Weight`, the idf is computed over all of the documents of the segments.
+        let total_num_docs: u64 = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment_reader| u64::from(segment_reader.max_doc()))
+            .sum();
+        let mut score_terms: BinaryHeap<ScoreTerm> = BinaryHeap::new();
+        for (term, term_freq) in term_frequencies {
+            if let Some(min_term_frequency) = self.min_term_frequency {
+                if term_freq < min_term_frequency {
+                    continue;
+                }
+            }
+            let doc_freq = searcher.doc_freq(&term)?;
+            if let Some(min_doc_frequency) = self.min_doc_frequency {
+                if doc_freq < min_doc_frequency {
+                    continue;
+                }
+            }
+            if let Some(max_doc_frequency) = self.max_doc_frequency {
+                if doc_freq > max_doc_frequency {
+                    continue;
+                }
+            }
+            // A term that does not appear in the index cannot match anything.
+            if doc_freq == 0 {
+                continue;
+            }
+            let score = term_freq as Score * idf(doc_freq, total_num_docs);
+            score_terms.push(ScoreTerm { term, score });
+            if let Some(max_query_terms) = self.max_query_terms {
+                if score_terms.len() > max_query_terms {
+                    score_terms.pop();
+                }
+            }
+        }
+        // The heap ordering is reversed: ascending order yields the best terms first.
+        Ok(score_terms.into_sorted_vec())
+    }
+
+    fn is_noise_word(&self, word: &str) -> bool {
+        let word_length = word.chars().count();
+        if let Some(min_word_length) = self.min_word_length {
+            if word_length < min_word_length {
+                return true;
+            }
+        }
+        if let Some(max_word_length) = self.max_word_length {
+            if word_length > max_word_length {
+                return true;
+            }
+        }
+        self.stop_words.iter().any(|stop_word| stop_word == word)
+    }
+}
@@ -0,0 +1,4 @@
+mod mlt;
+mod query;
+
+pub use self::query::{MoreLikeThisQuery, MoreLikeThisQueryBuilder};
@@ -0,0 +1,323 @@
+use super::mlt::MoreLikeThis;
+use crate::query::{Query, Weight};
+use crate::schema::{Field, Value};
+use crate::{DocAddress, Score, Searcher};
+
+/// A `MoreLikeThisQuery` matches documents that are "similar" to
+/// a given target document.
+///
+/// The target document is either a document of the index, identified by its
+/// `DocAddress`, or a list of raw field values.
+///
+/// The terms of the target document are extracted (text fields go through the
+/// `TextAnalyzer` of the field) and scored by `tf-idf`. The `max_query_terms`
+/// best terms that pass the document frequency, term frequency and word length filters
+/// are then combined into a `BooleanQuery` of boosted `TermQuery`s.
+///
+/// ```rust
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::MoreLikeThisQuery;
+/// use tantivy::schema::{Schema, STORED, TEXT};
+/// use tantivy::{doc, DocAddress, Index};
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT | STORED);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+/// {
+///     let mut index_writer = index.writer(3_000_000)?;
+///     index_writer.add_document(doc!(title => "The Name of the Wind"));
+///     index_writer.add_document(doc!(title => "The Diary of Muadib"));
+///     index_writer.add_document(doc!(title => "A Dairy Cow"));
+///     index_writer.add_document(doc!(title => "The Diary of a Young Girl"));
+///     index_writer.commit()?;
+/// }
+/// let reader = index.reader()?;
+/// let searcher = reader.searcher();
+/// let query = MoreLikeThisQuery::builder()
+///     .with_min_doc_frequency(1)
+///     .with_max_doc_frequency(2)
+///     .with_min_term_frequency(1)
+///     .with_document(DocAddress(0, 1));
+/// let top_docs = searcher.search(&query, &TopDocs::with_limit(3))?;
+/// assert_eq!(top_docs.len(), 2);
+/// Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MoreLikeThisQuery {
+    mlt: MoreLikeThis,
+    target: TargetDocument,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TargetDocument {
+    DocumentAddress(DocAddress),
+    DocumentFields(Vec<(Field, Vec<Value>)>),
+}
+
+impl MoreLikeThisQuery {
+    /// Creates a new builder.
+    pub fn builder() -> MoreLikeThisQueryBuilder {
+        MoreLikeThisQueryBuilder::default()
+    }
+}
+
+impl Query for MoreLikeThisQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        match &self.target {
+            TargetDocument::DocumentAddress(doc_address) => self
+                .mlt
+                .query_with_document(searcher, *doc_address)?
+                .weight(searcher, scoring_enabled),
+            TargetDocument::DocumentFields(doc_fields) => self
+                .mlt
+                .query_with_document_fields(searcher, doc_fields)?
+                .weight(searcher, scoring_enabled),
+        }
+    }
+}
+
+/// The builder for a `MoreLikeThisQuery`.
+///
+/// Unless overridden, terms are only selected if they appear at least twice
+/// in the target document and in at least 5 documents of the index,
+/// and the query is made of at most 25 terms.
+#[derive(Debug, Clone, Default)]
+pub struct MoreLikeThisQueryBuilder {
+    mlt: MoreLikeThis,
+}
+
+impl MoreLikeThisQueryBuilder {
+    /// Sets the minimum number of documents a term must appear in to be selected.
+    pub fn with_min_doc_frequency(mut self, value: u64) -> Self {
+        self.mlt.min_doc_frequency = Some(value);
+        self
+    }
+
+    /// Sets the maximum number of documents a term may appear in to be selected.
+    ///
+    /// This makes it possible to ignore terms that are too common.
+    pub fn with_max_doc_frequency(mut self, value: u64) -> Self {
+        self.mlt.max_doc_frequency = Some(value);
+        self
+    }
+
+    /// Sets the minimum number of times a term must appear in the target
+    /// document to be selected.
+    pub fn with_min_term_frequency(mut self, value: usize) -> Self {
+        self.mlt.min_term_frequency = Some(value);
+        self
+    }
+
+    /// Sets the maximum number of terms the resulting query will be made of.
+    pub fn with_max_query_terms(mut self, value: usize) -> Self {
+        self.mlt.max_query_terms = Some(value);
+        self
+    }
+
+    /// Sets the minimum length (in chars) of a word for it to be selected.
+    pub fn with_min_word_length(mut self, value: usize) -> Self {
+        self.mlt.min_word_length = Some(value);
+        self
+    }
+
+    /// Sets the maximum length (in chars) of a word for it to be selected.
+    pub fn with_max_word_length(mut self, value: usize) -> Self {
+        self.mlt.max_word_length = Some(value);
+        self
+    }
+
+    /// Sets the boost of the best scoring term.
+    ///
+    /// Other terms get a boost proportional to their `tf-idf` score.
+    pub fn with_boost_factor(mut self, value: Score) -> Self {
+        self.mlt.boost_factor = Some(value);
+        self
+    }
+
+    /// Disables boosting: all terms will have the same weight.
+    pub fn without_boost(mut self) -> Self {
+        self.mlt.boost_factor = None;
+        self
+    }
+
+    /// Sets the words that should never be selected.
+    pub fn with_stop_words(mut self, value: Vec<String>) -> Self {
+        self.mlt.stop_words = value;
+        self
+    }
+
+    /// Builds a `MoreLikeThisQuery` matching documents similar to the document stored
+    /// at `doc_address`.
+    ///
+    /// Only the stored fields of the document are taken in account.
+    pub fn with_document(self, doc_address: DocAddress) -> MoreLikeThisQuery {
+        MoreLikeThisQuery {
+            mlt: self.mlt,
+            target: TargetDocument::DocumentAddress(doc_address),
+        }
+    }
+
+    /// Builds a `MoreLikeThisQuery` matching documents similar to the given field values.
+    ///
+    /// The document does not need to be part of the index.
+    pub fn with_document_fields(self, doc_fields: Vec<(Field, Vec<Value>)>) -> MoreLikeThisQuery {
+        MoreLikeThisQuery {
+            mlt: self.mlt,
+            target: TargetDocument::DocumentFields(doc_fields),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MoreLikeThisQuery, TargetDocument};
+    use crate::collector::TopDocs;
+    use crate::schema::{Schema, Value, STORED, TEXT};
+    use crate::{DocAddress, Index, Term};
+
+    fn create_test_index() -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT | STORED);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "aaa", body => "the old man and the sea"));
+        index_writer.add_document(doc!(title => "bbb", body => "an old man sailing on the sea"));
+        index_writer.add_document(doc!(title => "ccc", body=> "send this message to alice"));
+        index_writer.add_document(doc!(title => "ddd", body=> "a lady was riding and old bike"));
+        index_writer.add_document(doc!(title => "eee", body=> "Yes, my lady."));
+        index_writer.commit()?;
+        Ok(index)
+    }
+
+    #[test]
+    fn test_more_like_this_query_builder() {
+        let query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(2)
+            .with_max_doc_frequency(5)
+            .with_min_term_frequency(2)
+            .with_min_word_length(2)
+            .with_max_word_length(4)
+            .with_boost_factor(0.5)
+            .with_stop_words(vec!["all".to_string(), "for".to_string()])
+            .with_document(DocAddress(1, 2));
+        assert_eq!(query.mlt.min_doc_frequency, Some(2));
+        assert_eq!(query.mlt.max_doc_frequency, Some(5));
+        assert_eq!(query.mlt.min_term_frequency, Some(2));
+        assert_eq!(query.mlt.min_word_length, Some(2));
+        assert_eq!(query.mlt.max_word_length, Some(4));
+        assert_eq!(query.mlt.boost_factor, Some(0.5));
+        assert_eq!(query.mlt.stop_words, vec!["all", "for"]);
+        assert_eq!(
+            query.target,
+            TargetDocument::DocumentAddress(DocAddress(1, 2))
+        );
+
+        let query = MoreLikeThisQuery::builder()
+            .without_boost()
+            .with_document(DocAddress(1, 2));
+        assert!(query.mlt.boost_factor.is_none());
+    }
+
+    #[test]
+    fn test_more_like_this_query_with_document() -> crate::Result<()> {
+        let index = create_test_index()?;
+        let searcher = index.reader()?.searcher();
+        let query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(1)
+            .with_max_doc_frequency(10)
+            .with_min_term_frequency(1)
+            .with_min_word_length(2)
+            .with_max_word_length(5)
+            .with_document(DocAddress(0, 0));
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(5))?;
+        let mut doc_ids: Vec<_> = top_docs.iter().map(|(_, doc)| doc.1).collect();
+        doc_ids.sort();
+        assert_eq!(doc_ids, vec![0, 1, 3]);
+
+        // `title` is not stored: only the terms of `body` are used.
+        let query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(1)
+            .with_max_doc_frequency(10)
+            .with_min_term_frequency(1)
+            .with_stop_words(vec![
+                "the".to_string(),
+                "old".to_string(),
+                "man".to_string(),
+            ])
+            .with_document(DocAddress(0, 0));
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(5))?;
+        let mut doc_ids: Vec<_> = top_docs.iter().map(|(_, doc)| doc.1).collect();
+        doc_ids.sort();
+        assert_eq!(doc_ids, vec![0, 1, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_more_like_this_query_with_deleted_documents() -> crate::Result<()> {
+        let index = create_test_index()?;
+        let body = index.schema().get_field("body").unwrap();
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.delete_term(Term::from_field_text(body, "sea"));
+        index_writer.delete_term(Term::from_field_text(body, "lady"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 1);
+        let query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(1)
+            .with_min_term_frequency(1)
+            .with_document_fields(vec![(
+                body,
+                vec![Value::Str("the old man and the sea".to_string())],
+            )]);
+        // The document frequencies of the terms still include the deleted documents.
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(5))?;
+        assert!(top_docs.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_more_like_this_query_with_document_fields() -> crate::Result<()> {
+        let index = create_test_index()?;
+        let body = index.schema().get_field("body").unwrap();
+        let searcher = index.reader()?.searcher();
+        let query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(1)
+            .with_max_doc_frequency(1)
+            .with_min_term_frequency(1)
+            .with_min_word_length(2)
+            .with_document_fields(vec![(
+                body,
+                vec![Value::Str("a message for alice and her lady".to_string())],
+            )]);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(5))?;
+        let doc_ids: Vec<_> = top_docs.iter().map(|(_, doc)| doc.1).collect();
+        assert_eq!(doc_ids, vec![2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_more_like_this_query_max_query_terms() -> crate::Result<()> {
+        let index = create_test_index()?;
+        let body = index.schema().get_field("body").unwrap();
+        let searcher = index.reader()?.searcher();
+        // "lady" appears twice in the source text, and is therefore the best term.
+        let query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(1)
+            .with_min_term_frequency(1)
+            .with_max_query_terms(1)
+            .with_document_fields(vec![(
+                body,
+                vec![Value::Str("lady alice lady".to_string())],
+            )]);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(5))?;
+        let mut doc_ids: Vec<_> = top_docs.iter().map(|(_, doc)| doc.1).collect();
+        doc_ids.sort();
+        assert_eq!(doc_ids, vec![3, 4]);
+        Ok(())
+    }
+}
@@ -1,10 +1,10 @@
 use super::PhraseWeight;
 use crate::core::searcher::Searcher;
-use crate::query::bm25::BM25Weight;
+use crate::query::similarity::{field_similarity, SimilarityWeight};
 use crate::query::Query;
 use crate::query::Weight;
-use crate::schema::IndexRecordOption;
 use crate::schema::{Field, Term};
+use crate::schema::{IndexRecordOption, Similarity};
 use std::collections::BTreeSet;
 
 /// `PhraseQuery` matches a specific sequence of words.
@@ -21,10 +21,15 @@ use std::collections::BTreeSet;
 /// Using a `PhraseQuery` on a field requires positions
 /// to be indexed for this field.
 ///
+/// Documents are scored by the [`Similarity`](../schema/enum.Similarity.html)
+/// defined in the schema for the field, using the number of occurrences of the phrase
+/// as term frequency.
 #[derive(Clone, Debug)]
 pub struct PhraseQuery {
     field: Field,
     phrase_terms: Vec<(usize, Term)>,
+    similarity: Option<Similarity>,
+    length_normalization: bool,
 }
 
 impl PhraseQuery {
@@ -55,9 +60,27 @@ impl PhraseQuery {
         PhraseQuery {
             field,
             phrase_terms: terms,
+            similarity: None,
+            length_normalization: true,
         }
     }
 
+    /// Overrides the similarity defined in the schema for the field of the phrase.
+    pub fn with_similarity(mut self, similarity: Similarity) -> PhraseQuery {
+        self.similarity = Some(similarity);
+        self
+    }
+
+    /// Enables or disables length normalization when scoring the documents.
+    ///
+    /// When disabled, the length of the field (its field norm) is ignored,
+    /// as if the field norms of the field had been omitted in the schema.
+    /// Length normalization is enabled by default.
+    pub fn with_length_normalization(mut self, length_normalization: bool) -> PhraseQuery {
+        self.length_normalization = length_normalization;
+        self
+    }
+
     /// The `Field` this `PhraseQuery` is targeting.
     pub fn field(&self) -> Field {
         self.field
@@ -95,10 +118,14 @@ impl PhraseQuery {
             )));
         }
         let terms = self.phrase_terms();
-        let bm25_weight = BM25Weight::for_terms(searcher, &terms)?;
+        let similarity = self
+            .similarity
+            .unwrap_or_else(|| field_similarity(schema, self.field));
+        let similarity_weight =
+            SimilarityWeight::for_terms(searcher, &terms, similarity, self.length_normalization)?;
         Ok(PhraseWeight::new(
             self.phrase_terms.clone(),
-            bm25_weight,
+            similarity_weight,
             scoring_enabled,
         ))
     }
@@ -118,4 +145,8 @@ impl Query for PhraseQuery {
             term_set.insert(query_term.clone());
         }
     }
+
+    fn query_phrases(&self, phrases: &mut Vec<Vec<(usize, Term)>>) {
+        phrases.push(self.phrase_terms.clone());
+    }
 }
@@ -2,8 +2,8 @@ use super::PhraseScorer;
 use crate::core::SegmentReader;
 use crate::fieldnorm::FieldNormReader;
 use crate::postings::SegmentPostings;
-use crate::query::bm25::BM25Weight;
 use crate::query::explanation::does_not_match;
+use crate::query::similarity::SimilarityWeight;
 use crate::query::Scorer;
 use crate::query::Weight;
 use crate::query::{EmptyScorer, Explanation};
@@ -14,7 +14,7 @@ use crate::{DocId, DocSet};
 
 pub struct PhraseWeight {
     phrase_terms: Vec<(usize, Term)>,
-    similarity_weight: BM25Weight,
+    similarity_weight: SimilarityWeight,
     score_needed: bool,
 }
 
@@ -22,7 +22,7 @@ impl PhraseWeight {
     /// Creates a new phrase weight.
     pub fn new(
         phrase_terms: Vec<(usize, Term)>,
-        similarity_weight: BM25Weight,
+        similarity_weight: SimilarityWeight,
         score_needed: bool,
     ) -> PhraseWeight {
         PhraseWeight {
@@ -69,6 +69,20 @@ pub trait Query: QueryClone + Send + Sync + downcast_rs::Downcast + fmt::Debug {
     /// Extract all of the terms associated to the query and insert them in the
     /// term set given in arguments.
     fn query_terms(&self, _term_set: &mut BTreeSet<Term>) {}
+
+    /// Extract all of the phrases associated to the query, as lists of terms
+    /// with their offsets within the phrase, and append them to `phrases`.
+    ///
+    /// The terms of the query which do not belong to a phrase are returned
+    /// as phrases of a single term. This is used to highlight phrase queries.
+    ///
+    /// By default, every term returned by `query_terms` is considered as a phrase
+    /// of its own.
+    fn query_phrases(&self, phrases: &mut Vec<Vec<(usize, Term)>>) {
+        let mut term_set = BTreeSet::new();
+        self.query_terms(&mut term_set);
+        phrases.extend(term_set.into_iter().map(|term| vec![(0, term)]));
+    }
 }
 
 /// Implements `box_clone`.
@@ -98,6 +112,10 @@ impl Query for Box<dyn Query> {
     fn query_terms(&self, term_set: &mut BTreeSet<Term<Vec<u8>>>) {
         self.as_ref().query_terms(term_set);
     }
+
+    fn query_phrases(&self, phrases: &mut Vec<Vec<(usize, Term)>>) {
+        self.as_ref().query_phrases(phrases);
+    }
 }
 
 impl QueryClone for Box<dyn Query> {
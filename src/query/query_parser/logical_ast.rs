@@ -1,4 +1,8 @@
+use super::query_parser::convert_to_query;
 use crate::query::Occur;
+use crate::query::Query;
+use crate::query::RegexQuery;
+use crate::query::WildcardQuery;
 use crate::schema::Field;
 use crate::schema::Term;
 use crate::schema::Type;
@@ -6,26 +10,68 @@ use crate::Score;
 use std::fmt;
 use std::ops::Bound;
 
+/// Leaf of a `LogicalAST`, resolved against the schema.
 #[derive(Clone)]
 pub enum LogicalLiteral {
+    /// Matches a term. (See `TermQuery`)
     Term(Term),
+    /// Matches a sequence of terms, given with their position. (See `PhraseQuery`)
     Phrase(Vec<(usize, Term)>),
+    /// Matches a range of values. (See `RangeQuery`)
     Range {
+        /// Field of the range
         field: Field,
+        /// Type of the values of the field
         value_type: Type,
+        /// Lower bound of the range
         lower: Bound<Term>,
+        /// Upper bound of the range
         upper: Bound<Term>,
     },
+    /// Matches all documents. (See `AllQuery`)
     All,
+    /// Matches the documents having a value for a field. (See `ExistsQuery`)
+    Exists(Field),
+    /// Matches the terms matching a wildcard pattern. (See `WildcardQuery`)
+    Wildcard(WildcardQuery),
+    /// Matches the terms matching a regular expression. (See `RegexQuery`)
+    Regex {
+        /// Field of the query
+        field: Field,
+        /// Regular expression
+        pattern: String,
+        /// Query built from the regular expression
+        query: RegexQuery,
+    },
+    /// Matches the terms within an edit distance. (See `FuzzyTermQuery`)
+    Fuzzy {
+        /// Term to match
+        term: Term,
+        /// Edit distance
+        distance: u8,
+        /// If true, the term is matched as a prefix
+        prefix: bool,
+        /// If true, a transposition costs one edit
+        transposition_cost_one: bool,
+    },
 }
 
+/// Query resolved against the schema, as produced by the `QueryParser`.
+///
+/// The `LogicalAST` is the last representation of a query before it gets turned into
+/// a tree of `Query` objects, which makes it the right place for custom rewriting passes.
+/// (See `QueryParser::parse_query_to_logical_ast` and `LogicalAST::rewrite`)
 pub enum LogicalAST {
+    /// Combines subqueries, as a `BooleanQuery`.
     Clause(Vec<(Occur, LogicalAST)>),
+    /// Single literal.
     Leaf(Box<LogicalLiteral>),
+    /// Boosted subquery, as a `BoostQuery`.
     Boost(Box<LogicalAST>, Score),
 }
 
 impl LogicalAST {
+    /// Boosts the AST by the given factor. A boost of 1 leaves the AST unchanged.
     pub fn boost(self, boost: Score) -> LogicalAST {
         if (boost - 1.0).abs() < Score::EPSILON {
             self
@@ -33,6 +79,43 @@ impl LogicalAST {
             LogicalAST::Boost(Box::new(self), boost)
         }
     }
+
+    /// Rewrites the AST, children first.
+    ///
+    /// `rewriter` is called on each node of the AST once its children have been rewritten,
+    /// and returns the node replacing it, or `None` to remove it from the AST.
+    /// A clause whose children were all removed is removed as well.
+    ///
+    /// Returns `None` if the whole AST was removed.
+    pub fn rewrite<F>(self, rewriter: &mut F) -> Option<LogicalAST>
+    where
+        F: FnMut(LogicalAST) -> Option<LogicalAST>,
+    {
+        let ast = match self {
+            LogicalAST::Clause(children) => {
+                let children: Vec<(Occur, LogicalAST)> = children
+                    .into_iter()
+                    .filter_map(|(occur, child)| {
+                        child.rewrite(rewriter).map(|child| (occur, child))
+                    })
+                    .collect();
+                if children.is_empty() {
+                    return None;
+                }
+                LogicalAST::Clause(children)
+            }
+            LogicalAST::Boost(child, boost) => {
+                LogicalAST::Boost(Box::new(child.rewrite(rewriter)?), boost)
+            }
+            leaf @ LogicalAST::Leaf(_) => leaf,
+        };
+        rewriter(ast)
+    }
+
+    /// Builds the query described by the AST.
+    pub fn into_query(self) -> Box<dyn Query> {
+        convert_to_query(self)
+    }
 }
 
 fn occur_letter(occur: Occur) -> &'static str {
@@ -82,6 +165,28 @@ impl fmt::Debug for LogicalLiteral {
                 ..
             } => write!(formatter, "({:?} TO {:?})", lower, upper),
             LogicalLiteral::All => write!(formatter, "*"),
+            LogicalLiteral::Exists(field) => write!(formatter, "Exists({:?})", field),
+            LogicalLiteral::Wildcard(ref query) => write!(
+                formatter,
+                "Wildcard({:?}, {:?})",
+                query.field(),
+                query.pattern()
+            ),
+            LogicalLiteral::Regex {
+                field, ref pattern, ..
+            } => write!(formatter, "Regex({:?}, {:?})", field, pattern),
+            LogicalLiteral::Fuzzy {
+                ref term,
+                distance,
+                prefix,
+                ..
+            } => {
+                write!(formatter, "Fuzzy({:?}, distance={}", term, distance)?;
+                if prefix {
+                    write!(formatter, ", prefix")?;
+                }
+                write!(formatter, ")")
+            }
         }
     }
 }
@@ -1,5 +1,9 @@
+mod query_dsl;
 mod query_parser;
 
 pub mod logical_ast;
+pub use self::logical_ast::{LogicalAST, LogicalLiteral};
+pub use self::query_dsl::QueryDsl;
+pub use self::query_parser::FuzzyConfig;
 pub use self::query_parser::QueryParser;
 pub use self::query_parser::QueryParserError;
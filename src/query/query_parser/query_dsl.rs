@@ -0,0 +1,653 @@
+use super::logical_ast::{LogicalAST, LogicalLiteral};
+use super::query_parser::convert_to_query;
+use crate::error::TantivyError;
+use crate::query::fuzzy_query::VALID_LEVENSHTEIN_DISTANCE_RANGE;
+use crate::query::{Occur, Query, RegexQuery, WildcardQuery};
+use crate::schema::{Facet, Field, FieldType, Schema, Term, Value};
+use crate::Score;
+use chrono::TimeZone;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::ops::Bound;
+
+/// Serializable representation of a query.
+///
+/// `QueryDsl` makes it possible to receive structured queries from clients,
+/// or to log queries in order to replay them later.
+/// Fields are designated by their name, and values are expressed as
+/// they are in JSON documents (see `Schema::parse_document`).
+///
+/// Term values are not tokenized: they must match the terms emitted
+/// by the tokenizer of the field.
+///
+/// ```rust
+/// use tantivy::query::QueryDsl;
+/// use tantivy::schema::{Schema, TEXT, INDEXED};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// schema_builder.add_text_field("title", TEXT);
+/// schema_builder.add_u64_field("year", INDEXED);
+/// let schema = schema_builder.build();
+///
+/// let query_dsl: QueryDsl = serde_json::from_str(r#"{
+///     "bool": {
+///         "must": [{"term": {"field": "title", "value": "diary"}}],
+///         "must_not": [{"range": {"field": "year", "lt": 1990}}]
+///     }
+/// }"#)?;
+/// let query = query_dsl.to_query(&schema)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum QueryDsl {
+    /// Matches the documents containing a given term.
+    Term {
+        /// Name of the field
+        field: String,
+        /// Value of the term
+        value: JsonValue,
+    },
+    /// Matches the documents containing a sequence of terms.
+    Phrase {
+        /// Name of the field
+        field: String,
+        /// Terms of the phrase. There must be at least two of them.
+        terms: Vec<String>,
+        /// Positions of the terms within the phrase.
+        /// Defaults to consecutive positions.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        positions: Option<Vec<usize>>,
+    },
+    /// Matches the documents having a value within a range.
+    /// Missing bounds are unbounded.
+    Range {
+        /// Name of the field
+        field: String,
+        /// Excluded lower bound
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gt: Option<JsonValue>,
+        /// Included lower bound
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gte: Option<JsonValue>,
+        /// Excluded upper bound
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lt: Option<JsonValue>,
+        /// Included upper bound
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lte: Option<JsonValue>,
+    },
+    /// Combines subqueries, as a `BooleanQuery`.
+    Bool {
+        /// Subqueries that must match
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        must: Vec<QueryDsl>,
+        /// Subqueries that should match
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        should: Vec<QueryDsl>,
+        /// Subqueries that must not match
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        must_not: Vec<QueryDsl>,
+    },
+    /// Multiplies the score of a query by a given factor.
+    Boost {
+        /// Boosted query
+        query: Box<QueryDsl>,
+        /// Boost factor
+        boost: Score,
+    },
+    /// Matches the documents containing a term within a given edit distance.
+    Fuzzy {
+        /// Name of the field
+        field: String,
+        /// Value of the term
+        value: String,
+        /// Edit distance. Defaults to 2.
+        #[serde(default = "default_fuzzy_distance")]
+        distance: u8,
+        /// If true, the value is matched as a prefix. Defaults to false.
+        #[serde(default)]
+        prefix: bool,
+        /// If true, a transposition costs one edit. Defaults to true.
+        #[serde(default = "default_transposition_cost_one")]
+        transposition_cost_one: bool,
+    },
+    /// Matches the documents containing a term matching a regular expression.
+    Regex {
+        /// Name of the field
+        field: String,
+        /// Regular expression
+        pattern: String,
+    },
+    /// Matches the documents containing a term matching a wildcard pattern.
+    Wildcard {
+        /// Name of the field
+        field: String,
+        /// Wildcard pattern, which may start with a wildcard.
+        pattern: String,
+    },
+    /// Matches the documents having a value for a given field.
+    Exists {
+        /// Name of the field
+        field: String,
+    },
+    /// Matches all documents.
+    All,
+}
+
+fn default_fuzzy_distance() -> u8 {
+    2
+}
+
+fn default_transposition_cost_one() -> bool {
+    true
+}
+
+fn resolve_field(schema: &Schema, field_name: &str) -> crate::Result<Field> {
+    schema.get_field(field_name).ok_or_else(|| {
+        TantivyError::InvalidArgument(format!("Field does not exist: '{}'", field_name))
+    })
+}
+
+fn json_to_term(schema: &Schema, field: Field, json: &JsonValue) -> crate::Result<Term> {
+    let field_entry = schema.get_field_entry(field);
+    let value = field_entry
+        .field_type()
+        .value_from_json(json)
+        .map_err(|err| {
+            TantivyError::InvalidArgument(format!(
+                "Invalid value for field '{}': {:?}",
+                field_entry.name(),
+                err
+            ))
+        })?;
+    match value {
+        Value::Str(text) => Ok(Term::from_field_text(field, &text)),
+        Value::U64(val) => Ok(Term::from_field_u64(field, val)),
+        Value::I64(val) => Ok(Term::from_field_i64(field, val)),
+        Value::F64(val) => Ok(Term::from_field_f64(field, val)),
+        Value::Date(date) => Ok(Term::from_field_date(field, &date)),
+        Value::Facet(facet) => Ok(Term::from_facet(field, &facet)),
+        Value::Bytes(bytes) => Ok(Term::from_field_bytes(field, &bytes)),
+        Value::PreTokStr(_) => Err(TantivyError::InvalidArgument(format!(
+            "Pre-tokenized strings cannot be used as a term for field '{}'",
+            field_entry.name()
+        ))),
+    }
+}
+
+fn term_to_json(schema: &Schema, term: &Term) -> JsonValue {
+    match schema.get_field_entry(term.field()).field_type() {
+        FieldType::Str(_) => JsonValue::from(term.text()),
+        FieldType::U64(_) => JsonValue::from(term.get_u64()),
+        FieldType::I64(_) => JsonValue::from(term.get_i64()),
+        FieldType::F64(_) => JsonValue::from(term.get_f64()),
+        FieldType::Date(_) => chrono::Utc
+            .timestamp_opt(term.get_i64(), 0)
+            .single()
+            .map(|date| JsonValue::from(date.to_rfc3339()))
+            .unwrap_or(JsonValue::Null),
+        FieldType::HierarchicalFacet(_) => {
+            let facet = Facet::from_encoded(term.value_bytes().to_vec())
+                .map(|facet| facet.to_string())
+                .unwrap_or_default();
+            JsonValue::from(facet)
+        }
+        FieldType::Bytes(_) => JsonValue::from(base64::encode(term.value_bytes())),
+    }
+}
+
+fn text_field(schema: &Schema, field_name: &str) -> crate::Result<Field> {
+    let field = resolve_field(schema, field_name)?;
+    match schema.get_field_entry(field).field_type() {
+        FieldType::Str(_) => Ok(field),
+        _ => Err(TantivyError::InvalidArgument(format!(
+            "Field '{}' is not a text field",
+            field_name
+        ))),
+    }
+}
+
+fn bound(
+    schema: &Schema,
+    field: Field,
+    excluded: &Option<JsonValue>,
+    included: &Option<JsonValue>,
+) -> crate::Result<Bound<Term>> {
+    match (excluded, included) {
+        (Some(_), Some(_)) => Err(TantivyError::InvalidArgument(
+            "A range cannot have both an excluded and an included bound on the same side"
+                .to_string(),
+        )),
+        (Some(json), None) => Ok(Bound::Excluded(json_to_term(schema, field, json)?)),
+        (None, Some(json)) => Ok(Bound::Included(json_to_term(schema, field, json)?)),
+        (None, None) => Ok(Bound::Unbounded),
+    }
+}
+
+fn bound_to_json(schema: &Schema, bound: &Bound<Term>) -> (Option<JsonValue>, Option<JsonValue>) {
+    match bound {
+        Bound::Excluded(term) => (Some(term_to_json(schema, term)), None),
+        Bound::Included(term) => (None, Some(term_to_json(schema, term))),
+        Bound::Unbounded => (None, None),
+    }
+}
+
+impl QueryDsl {
+    /// Builds the query described by this `QueryDsl`.
+    ///
+    /// Returns an error if the query references fields that are not in the schema,
+    /// or values that do not match the type of their field.
+    pub fn to_query(&self, schema: &Schema) -> crate::Result<Box<dyn Query>> {
+        let logical_ast = self.to_logical_ast(schema)?;
+        Ok(convert_to_query(logical_ast))
+    }
+
+    /// Converts this `QueryDsl` into a `LogicalAST`, resolving
+    /// its fields and values against the schema.
+    ///
+    /// This is the inverse of `LogicalAST::to_dsl`.
+    ///
+    /// ```rust
+    /// use tantivy::query::{QueryDsl, QueryParser};
+    /// use tantivy::schema::{Schema, TEXT};
+    /// use tantivy::Index;
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT);
+    /// let schema = schema_builder.build();
+    /// let index = Index::create_in_ram(schema.clone());
+    /// let query_parser = QueryParser::for_index(&index, vec![title]);
+    ///
+    /// let logical_ast = query_parser.parse_query_to_logical_ast("+diary -\"old man\"")?;
+    /// let query_dsl: QueryDsl = logical_ast.to_dsl(&schema);
+    /// // The DSL can be serialized, e.g. to be logged, and deserialized later.
+    /// let json = serde_json::to_string(&query_dsl)?;
+    /// let replayed_dsl: QueryDsl = serde_json::from_str(&json)?;
+    /// let replayed_ast = replayed_dsl.to_logical_ast(&schema)?;
+    /// assert_eq!(replayed_ast.to_dsl(&schema), query_dsl);
+    /// assert_eq!(format!("{:?}", replayed_ast), format!("{:?}", logical_ast));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_logical_ast(&self, schema: &Schema) -> crate::Result<LogicalAST> {
+        let literal = match self {
+            QueryDsl::Term { field, value } => {
+                let field = resolve_field(schema, field)?;
+                LogicalLiteral::Term(json_to_term(schema, field, value)?)
+            }
+            QueryDsl::Phrase {
+                field,
+                terms,
+                positions,
+            } => {
+                let field = text_field(schema, field)?;
+                if terms.len() < 2 {
+                    return Err(TantivyError::InvalidArgument(
+                        "A phrase must have at least two terms".to_string(),
+                    ));
+                }
+                let positions: Vec<usize> = match positions {
+                    Some(positions) if positions.len() != terms.len() => {
+                        return Err(TantivyError::InvalidArgument(
+                            "A phrase must have as many positions as terms".to_string(),
+                        ));
+                    }
+                    Some(positions) => positions.clone(),
+                    None => (0..terms.len()).collect(),
+                };
+                let terms = positions
+                    .into_iter()
+                    .zip(terms.iter())
+                    .map(|(position, text)| (position, Term::from_field_text(field, text)))
+                    .collect();
+                LogicalLiteral::Phrase(terms)
+            }
+            QueryDsl::Range {
+                field,
+                gt,
+                gte,
+                lt,
+                lte,
+            } => {
+                let field = resolve_field(schema, field)?;
+                LogicalLiteral::Range {
+                    field,
+                    value_type: schema.get_field_entry(field).field_type().value_type(),
+                    lower: bound(schema, field, gt, gte)?,
+                    upper: bound(schema, field, lt, lte)?,
+                }
+            }
+            QueryDsl::Bool {
+                must,
+                should,
+                must_not,
+            } => {
+                let mut clauses = Vec::with_capacity(must.len() + should.len() + must_not.len());
+                for (occur, queries) in &[
+                    (Occur::Must, must),
+                    (Occur::Should, should),
+                    (Occur::MustNot, must_not),
+                ] {
+                    for query in queries.iter() {
+                        clauses.push((*occur, query.to_logical_ast(schema)?));
+                    }
+                }
+                return Ok(LogicalAST::Clause(clauses));
+            }
+            QueryDsl::Boost { query, boost } => {
+                let logical_ast = query.to_logical_ast(schema)?;
+                return Ok(LogicalAST::Boost(Box::new(logical_ast), *boost));
+            }
+            QueryDsl::Fuzzy {
+                field,
+                value,
+                distance,
+                prefix,
+                transposition_cost_one,
+            } => {
+                let field = text_field(schema, field)?;
+                if !VALID_LEVENSHTEIN_DISTANCE_RANGE.contains(distance) {
+                    return Err(TantivyError::InvalidArgument(format!(
+                        "Unsupported fuzzy edit distance: {}",
+                        distance
+                    )));
+                }
+                LogicalLiteral::Fuzzy {
+                    term: Term::from_field_text(field, value),
+                    distance: *distance,
+                    prefix: *prefix,
+                    transposition_cost_one: *transposition_cost_one,
+                }
+            }
+            QueryDsl::Regex { field, pattern } => {
+                let field = text_field(schema, field)?;
+                LogicalLiteral::Regex {
+                    field,
+                    pattern: pattern.clone(),
+                    query: RegexQuery::from_pattern(pattern, field)?,
+                }
+            }
+            QueryDsl::Wildcard { field, pattern } => {
+                let field = text_field(schema, field)?;
+                LogicalLiteral::Wildcard(WildcardQuery::from_pattern_with_leading_wildcard(
+                    pattern, field,
+                )?)
+            }
+            QueryDsl::Exists { field } => LogicalLiteral::Exists(resolve_field(schema, field)?),
+            QueryDsl::All => LogicalLiteral::All,
+        };
+        Ok(LogicalAST::from(literal))
+    }
+}
+
+impl LogicalAST {
+    /// Converts the logical AST into its serializable representation.
+    ///
+    /// The resulting `QueryDsl` can be converted back with `QueryDsl::to_logical_ast`.
+    pub fn to_dsl(&self, schema: &Schema) -> QueryDsl {
+        match self {
+            LogicalAST::Clause(clauses) => {
+                let mut must = Vec::new();
+                let mut should = Vec::new();
+                let mut must_not = Vec::new();
+                for (occur, ast) in clauses {
+                    let queries = match occur {
+                        Occur::Must => &mut must,
+                        Occur::Should => &mut should,
+                        Occur::MustNot => &mut must_not,
+                    };
+                    queries.push(ast.to_dsl(schema));
+                }
+                QueryDsl::Bool {
+                    must,
+                    should,
+                    must_not,
+                }
+            }
+            LogicalAST::Boost(ast, boost) => QueryDsl::Boost {
+                query: Box::new(ast.to_dsl(schema)),
+                boost: *boost,
+            },
+            LogicalAST::Leaf(literal) => literal.to_dsl(schema),
+        }
+    }
+}
+
+impl LogicalLiteral {
+    fn to_dsl(&self, schema: &Schema) -> QueryDsl {
+        let field_name = |field: Field| schema.get_field_name(field).to_string();
+        match self {
+            LogicalLiteral::Term(term) => QueryDsl::Term {
+                field: field_name(term.field()),
+                value: term_to_json(schema, term),
+            },
+            LogicalLiteral::Phrase(terms) => {
+                let positions: Vec<usize> = terms.iter().map(|(position, _)| *position).collect();
+                let consecutive = positions
+                    .iter()
+                    .enumerate()
+                    .all(|(ord, position)| ord == *position);
+                QueryDsl::Phrase {
+                    field: field_name(terms[0].1.field()),
+                    terms: terms
+                        .iter()
+                        .map(|(_, term)| term.text().to_string())
+                        .collect(),
+                    positions: if consecutive { None } else { Some(positions) },
+                }
+            }
+            LogicalLiteral::Range {
+                field,
+                lower,
+                upper,
+                ..
+            } => {
+                let (gt, gte) = bound_to_json(schema, lower);
+                let (lt, lte) = bound_to_json(schema, upper);
+                QueryDsl::Range {
+                    field: field_name(*field),
+                    gt,
+                    gte,
+                    lt,
+                    lte,
+                }
+            }
+            LogicalLiteral::All => QueryDsl::All,
+            LogicalLiteral::Exists(field) => QueryDsl::Exists {
+                field: field_name(*field),
+            },
+            LogicalLiteral::Wildcard(query) => QueryDsl::Wildcard {
+                field: field_name(query.field()),
+                pattern: query.pattern().to_string(),
+            },
+            LogicalLiteral::Regex { field, pattern, .. } => QueryDsl::Regex {
+                field: field_name(*field),
+                pattern: pattern.clone(),
+            },
+            LogicalLiteral::Fuzzy {
+                term,
+                distance,
+                prefix,
+                transposition_cost_one,
+            } => QueryDsl::Fuzzy {
+                field: field_name(term.field()),
+                value: term.text().to_string(),
+                distance: *distance,
+                prefix: *prefix,
+                transposition_cost_one: *transposition_cost_one,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryDsl;
+    use crate::collector::Count;
+    use crate::query::QueryParser;
+    use crate::schema::{Schema, FAST, INDEXED, STRING, TEXT};
+    use crate::Index;
+    use serde_json::json;
+
+    fn make_index() -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let year = schema_builder.add_u64_field("year", INDEXED | FAST);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "The Name of the Wind", year => 2007u64));
+        index_writer.add_document(doc!(title => "The Diary of Muadib", year => 1995u64));
+        index_writer.add_document(doc!(title => "A Dairy Cow", year => 1987u64, tag => "cow"));
+        index_writer.add_document(doc!(title => "The Diary of a Young Girl", year => 1947u64));
+        index_writer.commit()?;
+        Ok(index)
+    }
+
+    #[test]
+    fn test_query_dsl_deserialize() -> crate::Result<()> {
+        let index = make_index()?;
+        let searcher = index.reader()?.searcher();
+        let count = |json: serde_json::Value| {
+            let query_dsl: QueryDsl = serde_json::from_value(json).unwrap();
+            let query = query_dsl.to_query(&index.schema()).unwrap();
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(
+            count(json!({"term": {"field": "title", "value": "diary"}})),
+            2
+        );
+        assert_eq!(count(json!({"term": {"field": "year", "value": 1995}})), 1);
+        assert_eq!(
+            count(json!({"phrase": {"field": "title", "terms": ["the", "diary"]}})),
+            2
+        );
+        assert_eq!(
+            count(
+                json!({"phrase": {"field": "title", "terms": ["the", "of"], "positions": [0, 2]}})
+            ),
+            3
+        );
+        assert_eq!(
+            count(json!({"range": {"field": "year", "gte": 1987, "lt": 2007}})),
+            2
+        );
+        assert_eq!(count(json!({"range": {"field": "year", "gt": 1987}})), 2);
+        assert_eq!(
+            count(json!({"bool": {
+                "must": [{"term": {"field": "title", "value": "the"}}],
+                "must_not": [{"term": {"field": "title", "value": "wind"}}]
+            }})),
+            2
+        );
+        assert_eq!(
+            count(
+                json!({"boost": {"query": {"term": {"field": "title", "value": "cow"}}, "boost": 2.0}})
+            ),
+            1
+        );
+        assert_eq!(
+            count(json!({"fuzzy": {"field": "title", "value": "dairy", "distance": 1}})),
+            3
+        );
+        assert_eq!(
+            count(json!({"fuzzy": {
+                "field": "title",
+                "value": "dairy",
+                "distance": 1,
+                "transposition_cost_one": false
+            }})),
+            1
+        );
+        assert_eq!(
+            count(json!({"fuzzy": {"field": "title", "value": "dairy"}})),
+            3
+        );
+        assert_eq!(
+            count(json!({"regex": {"field": "title", "pattern": "d[ai]{2}ry"}})),
+            3
+        );
+        assert_eq!(
+            count(json!({"wildcard": {"field": "title", "pattern": "*ind"}})),
+            1
+        );
+        assert_eq!(count(json!({"exists": {"field": "tag"}})), 1);
+        assert_eq!(count(json!("all")), 4);
+        assert_eq!(count(json!({"bool": {}})), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_dsl_errors() -> crate::Result<()> {
+        let index = make_index()?;
+        let schema = index.schema();
+        let to_query = |json: serde_json::Value| {
+            let query_dsl: QueryDsl = serde_json::from_value(json).unwrap();
+            query_dsl.to_query(&schema).map(|_| ())
+        };
+        assert!(to_query(json!({"term": {"field": "unknown", "value": "a"}})).is_err());
+        assert!(to_query(json!({"term": {"field": "year", "value": "a"}})).is_err());
+        assert!(to_query(json!({"phrase": {"field": "title", "terms": ["a"]}})).is_err());
+        assert!(to_query(
+            json!({"phrase": {"field": "title", "terms": ["a", "b"], "positions": [0]}})
+        )
+        .is_err());
+        assert!(to_query(json!({"range": {"field": "year", "gt": 1, "gte": 1}})).is_err());
+        assert!(
+            to_query(json!({"fuzzy": {"field": "title", "value": "a", "distance": 3}})).is_err()
+        );
+        assert!(to_query(json!({"fuzzy": {"field": "year", "value": "a"}})).is_err());
+        assert!(to_query(json!({"regex": {"field": "title", "pattern": "("}})).is_err());
+        assert!(serde_json::from_value::<QueryDsl>(
+            json!({"term": {"field": "title", "value": "a", "boost": 2}})
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_dsl_round_trip() -> crate::Result<()> {
+        let index = make_index()?;
+        let schema = index.schema();
+        let query_parser = QueryParser::for_index(&index, vec![schema.get_field("title").unwrap()]);
+        let query_dsl = query_parser
+            .parse_query_to_dsl("+diary -(year:[1900 TO 1990} OR tag:cow) \"young girl\"^2 wind~1")
+            .unwrap();
+        let json = serde_json::to_value(&query_dsl).unwrap();
+        assert_eq!(
+            json,
+            json!({"bool": {
+                "must": [{"term": {"field": "title", "value": "diary"}}],
+                "should": [
+                    {"boost": {
+                        "query": {"phrase": {"field": "title", "terms": ["young", "girl"]}},
+                        "boost": 2.0
+                    }},
+                    {"fuzzy": {
+                        "field": "title",
+                        "value": "wind",
+                        "distance": 1,
+                        "prefix": false,
+                        "transposition_cost_one": true
+                    }}
+                ],
+                "must_not": [{"bool": {"should": [
+                    {"range": {"field": "year", "gte": 1900, "lt": 1990}},
+                    {"term": {"field": "tag", "value": "cow"}}
+                ]}}]
+            }})
+        );
+        let deserialized: QueryDsl = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, query_dsl);
+        let logical_ast = deserialized.to_logical_ast(&schema)?;
+        assert_eq!(logical_ast.to_dsl(&schema), query_dsl);
+        let searcher = index.reader()?.searcher();
+        let query = deserialized.to_query(&schema)?;
+        assert_eq!(searcher.search(&query, &Count)?, 1);
+        Ok(())
+    }
+}
@@ -1,47 +1,62 @@
 use super::logical_ast::*;
+use super::query_dsl::QueryDsl;
 use crate::core::Index;
+use crate::query::exists_query::is_exists_supported;
+use crate::query::fuzzy_query::VALID_LEVENSHTEIN_DISTANCE_RANGE;
+use crate::query::wildcard_query::has_leading_wildcard;
 use crate::query::BooleanQuery;
 use crate::query::EmptyQuery;
+use crate::query::ExistsQuery;
+use crate::query::FuzzyTermQuery;
 use crate::query::Occur;
 use crate::query::PhraseQuery;
 use crate::query::Query;
 use crate::query::RangeQuery;
 use crate::query::TermQuery;
+use crate::query::WildcardQuery;
 use crate::query::{AllQuery, BoostQuery};
 use crate::schema::{Facet, IndexRecordOption};
 use crate::schema::{Field, Schema};
-use crate::schema::{FieldType, Term};
-use crate::tokenizer::TokenizerManager;
+use crate::schema::{FieldType, Term, Type, FACET_SEP_BYTE};
+use crate::tokenizer::{SynonymMap, TokenizerManager};
 use crate::Score;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::num::{ParseFloatError, ParseIntError};
-use std::ops::Bound;
+use std::ops::{Bound, Range};
 use std::str::FromStr;
-use tantivy_query_grammar::{UserInputAST, UserInputBound, UserInputLeaf};
+use tantivy_query_grammar::{UserInputAST, UserInputBound, UserInputLeaf, UserInputLiteral};
 
 /// Possible error that may happen when parsing a query.
 #[derive(Debug, PartialEq, Eq, Error)]
 pub enum QueryParserError {
-    /// Error in the query syntax
-    #[error("Syntax Error")]
-    SyntaxError,
-    /// `FieldDoesNotExist(field_name: String)`
+    /// Error in the query syntax.
+    ///
+    /// `position` is the byte offset in the query at which the error was detected,
+    /// and `expected` lists the tokens that would have been accepted there.
+    #[error("Syntax Error at position {position}, expected one of {expected:?}")]
+    SyntaxError {
+        /// Byte offset of the error in the query
+        position: usize,
+        /// Tokens expected at `position`
+        expected: Vec<String>,
+    },
+    /// `FieldDoesNotExist(field_name: String, span)`
     /// The query references a field that is not in the schema
     #[error("File does not exists: '{0:?}'")]
-    FieldDoesNotExist(String),
+    FieldDoesNotExist(String, Range<usize>),
     /// The query contains a term for a `u64` or `i64`-field, but the value
     /// is neither.
     #[error("Expected a valid integer: '{0:?}'")]
-    ExpectedInt(ParseIntError),
+    ExpectedInt(ParseIntError, Range<usize>),
     /// The query contains a term for a bytes field, but the value is not valid
     /// base64.
     #[error("Expected base64: '{0:?}'")]
-    ExpectedBase64(base64::DecodeError),
+    ExpectedBase64(base64::DecodeError, Range<usize>),
     /// The query contains a term for a `f64`-field, but the value
     /// is not a f64.
-    #[error("Invalid query: Only excluding terms given")]
-    ExpectedFloat(ParseFloatError),
+    #[error("Expected a valid float: '{0:?}'")]
+    ExpectedFloat(ParseFloatError, Range<usize>),
     /// It is forbidden queries that are only "excluding". (e.g. -title:pop)
     #[error("Invalid query: Only excluding terms given")]
     AllButQueryForbidden,
@@ -52,39 +67,66 @@ pub enum QueryParserError {
     /// The field searched for is not declared
     /// as indexed in the schema.
     #[error("The field '{0:?}' is not declared as indexed")]
-    FieldNotIndexed(String),
+    FieldNotIndexed(String, Range<usize>),
     /// A phrase query was requested for a field that does not
     /// have any positions indexed.
     #[error("The field '{0:?}' does not have positions indexed")]
-    FieldDoesNotHavePositionsIndexed(String),
+    FieldDoesNotHavePositionsIndexed(String, Range<usize>),
     /// The tokenizer for the given field is unknown
     /// The two argument strings are the name of the field, the name of the tokenizer
     #[error("The tokenizer '{0:?}' for the field '{1:?}' is unknown")]
-    UnknownTokenizer(String, String),
+    UnknownTokenizer(String, String, Range<usize>),
     /// The query contains a range query with a phrase as one of the bounds.
     /// Only terms can be used as bounds.
     #[error("A range query cannot have a phrase as one of the bounds")]
-    RangeMustNotHavePhrase,
+    RangeMustNotHavePhrase(Range<usize>),
     /// The format for the date field is not RFC 3339 compliant.
     #[error("The date field has an invalid format")]
-    DateFormatError(chrono::ParseError),
-}
-
-impl From<ParseIntError> for QueryParserError {
-    fn from(err: ParseIntError) -> QueryParserError {
-        QueryParserError::ExpectedInt(err)
-    }
+    DateFormatError(chrono::ParseError, Range<usize>),
+    /// The query contains a wildcard pattern starting with `*` or `?`,
+    /// which is forbidden unless `.set_allow_leading_wildcard(true)` was called.
+    #[error("The wildcard pattern '{0:?}' starts with a wildcard")]
+    LeadingWildcardForbidden(String, Range<usize>),
+    /// A wildcard pattern was given for a field that is not a text field.
+    #[error("The field '{0:?}' does not support wildcard patterns")]
+    WildcardOnNonTextField(String, Range<usize>),
+    /// The wildcard pattern could not be compiled into an automaton.
+    #[error("Invalid wildcard pattern: '{0:?}'")]
+    InvalidWildcard(String, Range<usize>),
+    /// A fuzzy term was given for a field that is not a text field.
+    #[error("The field '{0:?}' does not support fuzzy terms")]
+    FuzzyOnNonTextField(String, Range<usize>),
+    /// The edit distance of a fuzzy term is not supported.
+    #[error("Unsupported fuzzy edit distance: {0}")]
+    InvalidFuzzyDistance(u8, Range<usize>),
 }
 
-impl From<ParseFloatError> for QueryParserError {
-    fn from(err: ParseFloatError) -> QueryParserError {
-        QueryParserError::ExpectedFloat(err)
-    }
-}
-
-impl From<chrono::ParseError> for QueryParserError {
-    fn from(err: chrono::ParseError) -> QueryParserError {
-        QueryParserError::DateFormatError(err)
+impl QueryParserError {
+    /// Returns the byte range of the query clause this error relates to.
+    ///
+    /// Syntax errors span the single byte at which they were detected.
+    /// Errors that do not relate to a specific clause return `None`.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            QueryParserError::SyntaxError { position, .. } => Some(*position..*position + 1),
+            QueryParserError::AllButQueryForbidden | QueryParserError::NoDefaultFieldDeclared => {
+                None
+            }
+            QueryParserError::FieldDoesNotExist(_, span)
+            | QueryParserError::ExpectedInt(_, span)
+            | QueryParserError::ExpectedBase64(_, span)
+            | QueryParserError::ExpectedFloat(_, span)
+            | QueryParserError::FieldNotIndexed(_, span)
+            | QueryParserError::FieldDoesNotHavePositionsIndexed(_, span)
+            | QueryParserError::UnknownTokenizer(_, _, span)
+            | QueryParserError::RangeMustNotHavePhrase(span)
+            | QueryParserError::DateFormatError(_, span)
+            | QueryParserError::LeadingWildcardForbidden(_, span)
+            | QueryParserError::WildcardOnNonTextField(_, span)
+            | QueryParserError::InvalidWildcard(_, span)
+            | QueryParserError::FuzzyOnNonTextField(_, span)
+            | QueryParserError::InvalidFuzzyDistance(_, span) => Some(span.clone()),
+        }
     }
 }
 
@@ -110,6 +152,40 @@ fn trim_ast(logical_ast: LogicalAST) -> Option<LogicalAST> {
     }
 }
 
+/// Fuzzy matching parameters of the terms of a field.
+///
+/// (See [`QueryParser::set_field_fuzzy(...)`](./struct.QueryParser.html#method.set_field_fuzzy))
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FuzzyConfig {
+    /// Maximum Levenshtein distance between the query term and the indexed terms.
+    pub distance: u8,
+    /// If true, the distance is computed against the prefix of the indexed terms.
+    pub prefix: bool,
+    /// If true, swapping two adjacent characters costs 1 instead of 2.
+    pub transposition_cost_one: bool,
+}
+
+impl Default for FuzzyConfig {
+    fn default() -> FuzzyConfig {
+        FuzzyConfig {
+            distance: 2,
+            prefix: false,
+            transposition_cost_one: true,
+        }
+    }
+}
+
+/// A group of sub queries of a clause, as their number, along with the
+/// clauses matching their synonyms.
+type SynonymGroup = (usize, Vec<(Occur, LogicalAST)>);
+
+/// Synonyms applied to the terms of a field.
+#[derive(Clone, Debug)]
+struct SynonymOptions {
+    synonyms: SynonymMap,
+    boost: Score,
+}
+
 /// Tantivy's Query parser
 ///
 /// The language covered by the current parser is extremely simple.
@@ -161,6 +237,27 @@ fn trim_ast(logical_ast: LogicalAST) -> Option<LogicalAST> {
 ///
 /// *  all docs query: A plain `*` will match all documents in the index.
 ///
+/// *  exists query: `field:*` will match all documents having a value for `field`.
+///
+/// * wildcard terms: Unquoted terms containing `*` or `?` become wildcard searches on
+///   text fields. `*` matches any sequence of characters and `?` matches exactly one
+///   character, e.g. `title:win*` or `colo?r`. The pattern is not tokenized.
+///   Patterns starting with a wildcard are rejected unless
+///   [`set_allow_leading_wildcard(true)`](#method.set_allow_leading_wildcard) was called.
+///
+/// * fuzzy terms: Unquoted terms followed by `~N` match all of the terms within an edit
+///   distance of `N` on text fields, e.g. `title:roam~1`. If the distance is omitted (`roam~`),
+///   the fuzzy distance of the field is used (2 by default). Fuzziness can also be enabled for
+///   all of the terms of a field (See [`set_field_fuzzy(...)`](#method.set_field_fuzzy)).
+///   Terms yielding several tokens are searched as a phrase, without fuzziness.
+///
+/// * synonyms: Terms and phrases can be expanded with their synonyms
+///   (See [`set_field_synonyms(...)`](#method.set_field_synonyms)).
+///
+/// * facets: `category:/books/novels` matches the documents having the facet `/books/novels`
+///   or any of its descendants, while `category:/books/novels/*` only matches the documents
+///   having a strict descendant of `/books/novels`.
+///
 /// Parts of the queries can be boosted by appending `^boostfactor`.
 /// For instance, `"SRE"^2.0 OR devops^0.4` will boost documents containing `SRE` instead of
 /// devops. Negative boosts are not allowed.
@@ -173,8 +270,11 @@ pub struct QueryParser {
     schema: Schema,
     default_fields: Vec<Field>,
     conjunction_by_default: bool,
+    allow_leading_wildcard: bool,
     tokenizer_manager: TokenizerManager,
     boost: HashMap<Field, Score>,
+    fuzzy: HashMap<Field, FuzzyConfig>,
+    synonyms: HashMap<Field, SynonymOptions>,
 }
 
 fn all_negative(ast: &LogicalAST) -> bool {
@@ -202,7 +302,10 @@ impl QueryParser {
             default_fields,
             tokenizer_manager,
             conjunction_by_default: false,
+            allow_leading_wildcard: false,
             boost: Default::default(),
+            fuzzy: Default::default(),
+            synonyms: Default::default(),
         }
     }
 
@@ -223,6 +326,14 @@ impl QueryParser {
         self.conjunction_by_default = true;
     }
 
+    /// Allows wildcard patterns starting with `*` or `?`.
+    ///
+    /// Such patterns require to scan the entire term dictionary of the field,
+    /// and are forbidden by default.
+    pub fn set_allow_leading_wildcard(&mut self, allow_leading_wildcard: bool) {
+        self.allow_leading_wildcard = allow_leading_wildcard;
+    }
+
     /// Sets a boost for a specific field.
     ///
     /// The parse query will automatically boost this field.
@@ -234,32 +345,111 @@ impl QueryParser {
         self.boost.insert(field, boost);
     }
 
+    /// Enables fuzzy matching for all of the terms of a text field.
+    ///
+    /// Terms of this field will match all of the terms within the Levenshtein
+    /// distance defined in the `FuzzyConfig`.
+    ///
+    /// This configuration is also used for the explicit fuzzy terms (e.g. `roam~`)
+    /// of the field. An explicit distance (e.g. `roam~1`) takes precedence over
+    /// the configured distance.
+    pub fn set_field_fuzzy(&mut self, field: Field, fuzzy_config: FuzzyConfig) {
+        self.fuzzy.insert(field, fuzzy_config);
+    }
+
+    /// Expands the terms and phrases of a text field with their synonyms.
+    ///
+    /// A term or a phrase having synonyms is replaced by a boolean query
+    /// matching either the original term or phrase, or any of its synonyms.
+    /// Multi-word synonyms are searched as phrases.
+    ///
+    /// Multi-word entries also match sequences of unquoted terms (e.g. `new york`),
+    /// as long as the terms target the same field and have no occur or boost of their own.
+    ///
+    /// The score of the synonym matches is multiplied by `boost`,
+    /// making it possible to rank them below the exact matches.
+    pub fn set_field_synonyms(&mut self, field: Field, synonyms: SynonymMap, boost: Score) {
+        self.synonyms
+            .insert(field, SynonymOptions { synonyms, boost });
+    }
+
     /// Parse a query
     ///
     /// Note that `parse_query` returns an error if the input
     /// is not a valid query.
     ///
-    /// There is currently no lenient mode for the query parser
-    /// which makes it a bad choice for a public/broad user search engine.
-    ///
-    /// Implementing a lenient mode for this query parser is tracked
-    /// in [Issue 5](https://github.com/fulmicoton/tantivy/issues/5)
+    /// For a public/broad user search engine, consider using
+    /// [`parse_query_lenient`](#method.parse_query_lenient) instead.
     pub fn parse_query(&self, query: &str) -> Result<Box<dyn Query>, QueryParserError> {
         let logical_ast = self.parse_query_to_logical_ast(query)?;
         Ok(convert_to_query(logical_ast))
     }
 
-    /// Parse the user query into an AST.
-    fn parse_query_to_logical_ast(&self, query: &str) -> Result<LogicalAST, QueryParserError> {
-        let user_input_ast =
-            tantivy_query_grammar::parse_query(query).map_err(|_| QueryParserError::SyntaxError)?;
+    /// Parse a query, doing its best to recover from errors.
+    ///
+    /// Unlike `parse_query`, this method never fails. Instead, it returns
+    /// the best-effort query along with the list of the errors encountered:
+    /// * the fragments of the query that are not syntactically valid are skipped,
+    ///   and their alphanumeric parts are searched as plain text.
+    ///   (e.g. `"unclosed phrase` is searched as `unclosed phrase`)
+    /// * a clause with an unknown field prefix is searched as plain text on the default fields.
+    ///   (e.g. `foo:bar`, `foo:bar*` and `foo:bar~1` are searched as `"foo bar"`,
+    ///   `foo:[a TO b]` as `"foo a b"` and `foo:*` as `foo`)
+    /// * the clauses yielding any other error (e.g. a non-integer value for an integer field)
+    ///   are dropped.
+    ///
+    /// A query made of excluding terms only is replaced by an `EmptyQuery`.
+    pub fn parse_query_lenient(&self, query: &str) -> (Box<dyn Query>, Vec<QueryParserError>) {
+        let (user_input_ast, syntax_errors) = tantivy_query_grammar::parse_query_lenient(query);
+        let mut errors: Vec<QueryParserError> = syntax_errors
+            .into_iter()
+            .map(|error| QueryParserError::SyntaxError {
+                position: error.position,
+                expected: Vec::new(),
+            })
+            .collect();
+        let logical_ast = self.compute_logical_ast_lenient(user_input_ast, &mut errors);
+        match trim_ast(logical_ast) {
+            Some(ref logical_ast) if all_negative(logical_ast) => {
+                errors.push(QueryParserError::AllButQueryForbidden);
+                (Box::new(EmptyQuery), errors)
+            }
+            Some(logical_ast) => (convert_to_query(logical_ast), errors),
+            None => (Box::new(EmptyQuery), errors),
+        }
+    }
+
+    /// Parse a query into its serializable representation.
+    ///
+    /// The query is resolved against the schema exactly as in `parse_query`,
+    /// so that the resulting `QueryDsl` builds an equivalent query.
+    pub fn parse_query_to_dsl(&self, query: &str) -> Result<QueryDsl, QueryParserError> {
+        let logical_ast = self.parse_query_to_logical_ast(query)?;
+        Ok(logical_ast.to_dsl(&self.schema))
+    }
+
+    /// Parse the user query into a `LogicalAST`.
+    ///
+    /// Custom rewriting passes can then be applied to the AST
+    /// before building the query with `LogicalAST::into_query`.
+    pub fn parse_query_to_logical_ast(&self, query: &str) -> Result<LogicalAST, QueryParserError> {
+        let user_input_ast = tantivy_query_grammar::parse_query(query).map_err(|error| {
+            QueryParserError::SyntaxError {
+                position: error.position,
+                expected: error.expected,
+            }
+        })?;
         self.compute_logical_ast(user_input_ast)
     }
 
-    fn resolve_field_name(&self, field_name: &str) -> Result<Field, QueryParserError> {
-        self.schema
-            .get_field(field_name)
-            .ok_or_else(|| QueryParserError::FieldDoesNotExist(String::from(field_name)))
+    fn resolve_field_name(
+        &self,
+        field_name: &str,
+        span: &Range<usize>,
+    ) -> Result<Field, QueryParserError> {
+        self.schema.get_field(field_name).ok_or_else(|| {
+            QueryParserError::FieldDoesNotExist(String::from(field_name), span.clone())
+        })
     }
 
     fn compute_logical_ast(
@@ -282,21 +472,24 @@ impl QueryParser {
         &self,
         field: Field,
         phrase: &str,
+        span: &Range<usize>,
     ) -> Result<Vec<(usize, Term)>, QueryParserError> {
         let field_entry = self.schema.get_field_entry(field);
         let field_type = field_entry.field_type();
         if !field_type.is_indexed() {
             let field_name = field_entry.name().to_string();
-            return Err(QueryParserError::FieldNotIndexed(field_name));
+            return Err(QueryParserError::FieldNotIndexed(field_name, span.clone()));
         }
         match *field_type {
             FieldType::I64(_) => {
-                let val: i64 = i64::from_str(phrase)?;
+                let val: i64 = i64::from_str(phrase)
+                    .map_err(|err| QueryParserError::ExpectedInt(err, span.clone()))?;
                 let term = Term::from_field_i64(field, val);
                 Ok(vec![(0, term)])
             }
             FieldType::F64(_) => {
-                let val: f64 = f64::from_str(phrase)?;
+                let val: f64 = f64::from_str(phrase)
+                    .map_err(|err| QueryParserError::ExpectedFloat(err, span.clone()))?;
                 let term = Term::from_field_f64(field, val);
                 Ok(vec![(0, term)])
             }
@@ -305,10 +498,11 @@ impl QueryParser {
                     0,
                     Term::from_field_date(field, &x.with_timezone(&chrono::Utc)),
                 )]),
-                Err(e) => Err(QueryParserError::DateFormatError(e)),
+                Err(e) => Err(QueryParserError::DateFormatError(e, span.clone())),
             },
             FieldType::U64(_) => {
-                let val: u64 = u64::from_str(phrase)?;
+                let val: u64 = u64::from_str(phrase)
+                    .map_err(|err| QueryParserError::ExpectedInt(err, span.clone()))?;
                 let term = Term::from_field_u64(field, val);
                 Ok(vec![(0, term)])
             }
@@ -321,6 +515,7 @@ impl QueryParser {
                                 QueryParserError::UnknownTokenizer(
                                     field_entry.name().to_string(),
                                     option.tokenizer().to_string(),
+                                    span.clone(),
                                 )
                             })?;
                     let mut terms: Vec<(usize, Term)> = Vec::new();
@@ -343,45 +538,281 @@ impl QueryParser {
                                 let fieldname = self.schema.get_field_name(field).to_string();
                                 Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
                                     fieldname,
+                                    span.clone(),
                                 ))
                             }
                         } else {
                             let fieldname = self.schema.get_field_name(field).to_string();
-                            Err(QueryParserError::FieldNotIndexed(fieldname))
+                            Err(QueryParserError::FieldNotIndexed(fieldname, span.clone()))
                         }
                     }
                 } else {
                     // This should have been seen earlier really.
                     Err(QueryParserError::FieldNotIndexed(
                         field_entry.name().to_string(),
+                        span.clone(),
                     ))
                 }
             }
-            FieldType::HierarchicalFacet => {
+            FieldType::HierarchicalFacet(_) => {
                 let facet = Facet::from_text(phrase);
                 Ok(vec![(0, Term::from_field_text(field, facet.encoded_str()))])
             }
             FieldType::Bytes(_) => {
-                let bytes = base64::decode(phrase).map_err(QueryParserError::ExpectedBase64)?;
+                let bytes = base64::decode(phrase)
+                    .map_err(|err| QueryParserError::ExpectedBase64(err, span.clone()))?;
                 let term = Term::from_field_bytes(field, &bytes);
                 Ok(vec![(0, term)])
             }
         }
     }
 
+    /// Computes the range of the descendants of a facet, given a pattern of the form `/a/b/*`.
+    fn compute_facet_descendants(
+        &self,
+        field: Field,
+        pattern: &str,
+        span: &Range<usize>,
+    ) -> Result<LogicalLiteral, QueryParserError> {
+        let field_entry = self.schema.get_field_entry(field);
+        if !field_entry.is_indexed() {
+            return Err(QueryParserError::FieldNotIndexed(
+                field_entry.name().to_string(),
+                span.clone(),
+            ));
+        }
+        let facet_str = match pattern.strip_suffix("/*") {
+            Some(facet_str) if !facet_str.contains(&['*', '?'][..]) => facet_str,
+            _ => {
+                return Err(QueryParserError::WildcardOnNonTextField(
+                    field_entry.name().to_string(),
+                    span.clone(),
+                ));
+            }
+        };
+        // The descendants of a facet are encoded as the facet followed by a separator,
+        // which sorts right before `\u{1}`.
+        let facet = if facet_str.is_empty() {
+            Facet::root()
+        } else {
+            Facet::from_text(facet_str)
+        };
+        let mut lower = facet.encoded_str().to_string();
+        let mut upper = lower.clone();
+        if !facet.is_root() {
+            lower.push(char::from(FACET_SEP_BYTE));
+            upper.push('\u{1}');
+        }
+        Ok(LogicalLiteral::Range {
+            field,
+            value_type: Type::HierarchicalFacet,
+            lower: Bound::Excluded(Term::from_field_text(field, &lower)),
+            upper: if facet.is_root() {
+                Bound::Unbounded
+            } else {
+                Bound::Excluded(Term::from_field_text(field, &upper))
+            },
+        })
+    }
+
     fn compute_logical_ast_for_leaf(
         &self,
         field: Field,
         phrase: &str,
+        span: &Range<usize>,
+    ) -> Result<Option<LogicalLiteral>, QueryParserError> {
+        let fuzzy_options = match self.schema.get_field_entry(field).field_type() {
+            FieldType::Str(_) => self.fuzzy.get(&field).cloned(),
+            _ => None,
+        };
+        self.compute_logical_ast_for_fuzzy_leaf(field, phrase, fuzzy_options, span)
+    }
+
+    fn compute_logical_ast_for_fuzzy_leaf(
+        &self,
+        field: Field,
+        phrase: &str,
+        fuzzy_options: Option<FuzzyConfig>,
+        span: &Range<usize>,
     ) -> Result<Option<LogicalLiteral>, QueryParserError> {
-        let terms = self.compute_terms_for_string(field, phrase)?;
+        let terms = self.compute_terms_for_string(field, phrase, span)?;
         match &terms[..] {
             [] => Ok(None),
-            [(_, term)] => Ok(Some(LogicalLiteral::Term(term.clone()))),
+            [(_, term)] => match fuzzy_options {
+                Some(fuzzy_options) => {
+                    if !VALID_LEVENSHTEIN_DISTANCE_RANGE.contains(&fuzzy_options.distance) {
+                        return Err(QueryParserError::InvalidFuzzyDistance(
+                            fuzzy_options.distance,
+                            span.clone(),
+                        ));
+                    }
+                    Ok(Some(LogicalLiteral::Fuzzy {
+                        term: term.clone(),
+                        distance: fuzzy_options.distance,
+                        prefix: fuzzy_options.prefix,
+                        transposition_cost_one: fuzzy_options.transposition_cost_one,
+                    }))
+                }
+                None => Ok(Some(LogicalLiteral::Term(term.clone()))),
+            },
             _ => Ok(Some(LogicalLiteral::Phrase(terms.clone()))),
         }
     }
 
+    /// Expands a term or a phrase into a disjunction with its synonyms.
+    fn expand_synonyms(
+        &self,
+        field: Field,
+        literal: LogicalLiteral,
+        span: &Range<usize>,
+    ) -> Result<LogicalAST, QueryParserError> {
+        let synonym_options = match self.synonyms.get(&field) {
+            Some(synonym_options) => synonym_options,
+            None => return Ok(LogicalAST::from(literal)),
+        };
+        let words: Vec<&str> = match (self.schema.get_field_entry(field).field_type(), &literal) {
+            (FieldType::Str(_), LogicalLiteral::Term(term)) => vec![term.text()],
+            (FieldType::Str(_), LogicalLiteral::Phrase(terms)) => {
+                terms.iter().map(|(_, term)| term.text()).collect()
+            }
+            _ => return Ok(LogicalAST::from(literal)),
+        };
+        let synonyms = synonym_options.synonyms.synonyms(&words.join(" "));
+        if synonyms.is_empty() {
+            return Ok(LogicalAST::from(literal));
+        }
+        let mut clauses = vec![(Occur::Should, LogicalAST::from(literal))];
+        for synonym in synonyms {
+            let synonym_ast = self.synonym_ast(field, synonym, span)?;
+            clauses.push((Occur::Should, synonym_ast.boost(synonym_options.boost)));
+        }
+        Ok(LogicalAST::Clause(clauses))
+    }
+
+    /// Builds the term or phrase matching a synonym.
+    fn synonym_ast(
+        &self,
+        field: Field,
+        synonym: &str,
+        span: &Range<usize>,
+    ) -> Result<LogicalAST, QueryParserError> {
+        let mut terms: Vec<(usize, Term)> = synonym
+            .split(' ')
+            .enumerate()
+            .map(|(position, word)| (position, Term::from_field_text(field, word)))
+            .collect();
+        if terms.len() == 1 {
+            return Ok(LogicalAST::from(LogicalLiteral::Term(
+                terms.pop().unwrap().1,
+            )));
+        }
+        let has_positions = self
+            .schema
+            .get_field_entry(field)
+            .field_type()
+            .get_index_record_option()
+            .map(IndexRecordOption::has_positions)
+            .unwrap_or(false);
+        if !has_positions {
+            return Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
+                self.schema.get_field_name(field).to_string(),
+                span.clone(),
+            ));
+        }
+        Ok(LogicalAST::from(LogicalLiteral::Phrase(terms)))
+    }
+
+    /// Splits the sub queries of a clause into groups, such that the sequences of
+    /// unquoted terms forming a multi-word synonym entry (e.g. `new york`) are grouped together.
+    ///
+    /// Each group is returned as its number of sub queries, along with the clauses
+    /// matching its synonyms. (empty for the sub queries that are not grouped)
+    fn group_multi_word_synonyms(
+        &self,
+        sub_queries: &[(Option<Occur>, UserInputAST)],
+    ) -> Result<Vec<SynonymGroup>, QueryParserError> {
+        let mut groups = Vec::new();
+        let mut start = 0;
+        while start < sub_queries.len() {
+            let group = self
+                .multi_word_synonyms(&sub_queries[start..])?
+                .unwrap_or_else(|| (1, Vec::new()));
+            start += group.0;
+            groups.push(group);
+        }
+        Ok(groups)
+    }
+
+    /// Looks for a sequence of unquoted terms, at the beginning of `sub_queries`,
+    /// forming a multi-word synonym entry.
+    ///
+    /// The longest sequence is returned as its number of terms, along with the
+    /// clauses matching its synonyms.
+    fn multi_word_synonyms(
+        &self,
+        sub_queries: &[(Option<Occur>, UserInputAST)],
+    ) -> Result<Option<SynonymGroup>, QueryParserError> {
+        if self.synonyms.is_empty() {
+            return Ok(None);
+        }
+        let mut literals: Vec<(&UserInputLiteral, &Range<usize>)> = Vec::new();
+        for (occur_opt, sub_ast) in sub_queries {
+            let (literal, span) = match (occur_opt, sub_ast) {
+                (None, UserInputAST::Leaf(leaf, span)) => match **leaf {
+                    UserInputLeaf::Literal(ref literal) => (literal, span),
+                    _ => break,
+                },
+                _ => break,
+            };
+            let is_same_field = literals
+                .first()
+                .map(|(first_literal, _)| first_literal.field_name == literal.field_name)
+                .unwrap_or(true);
+            if !is_same_field || literal.phrase.contains(char::is_whitespace) {
+                break;
+            }
+            literals.push((literal, span));
+        }
+        let num_literals = literals.len();
+        if num_literals < 2 {
+            return Ok(None);
+        }
+        let fields: Vec<Field> = match literals[0].0.field_name {
+            Some(ref field_name) => self.schema.get_field(field_name).into_iter().collect(),
+            None => self.default_fields.clone(),
+        };
+        for num_words in (2..=num_literals).rev() {
+            let span = literals[0].1.start..literals[num_words - 1].1.end;
+            let mut clauses = Vec::new();
+            for &field in &fields {
+                let synonym_options = match self.synonyms.get(&field) {
+                    Some(synonym_options) => synonym_options,
+                    None => continue,
+                };
+                if !matches!(
+                    self.schema.get_field_entry(field).field_type(),
+                    FieldType::Str(_)
+                ) {
+                    continue;
+                }
+                let mut words: Vec<String> = Vec::new();
+                for (literal, span) in &literals[..num_words] {
+                    for (_, term) in self.compute_terms_for_string(field, &literal.phrase, span)? {
+                        words.push(term.text().to_string());
+                    }
+                }
+                for synonym in synonym_options.synonyms.synonyms(&words.join(" ")) {
+                    let synonym_ast = self.synonym_ast(field, synonym, &span)?;
+                    clauses.push((Occur::Should, synonym_ast.boost(synonym_options.boost)));
+                }
+            }
+            if !clauses.is_empty() {
+                return Ok(Some((num_words, clauses)));
+            }
+        }
+        Ok(None)
+    }
+
     fn default_occur(&self) -> Occur {
         if self.conjunction_by_default {
             Occur::Must
@@ -394,13 +825,14 @@ impl QueryParser {
         &self,
         field: Field,
         bound: &UserInputBound,
+        span: &Range<usize>,
     ) -> Result<Bound<Term>, QueryParserError> {
         if bound.term_str() == "*" {
             return Ok(Bound::Unbounded);
         }
-        let terms = self.compute_terms_for_string(field, bound.term_str())?;
+        let terms = self.compute_terms_for_string(field, bound.term_str(), span)?;
         if terms.len() != 1 {
-            return Err(QueryParserError::RangeMustNotHavePhrase);
+            return Err(QueryParserError::RangeMustNotHavePhrase(span.clone()));
         }
         let (_, term) = terms.into_iter().next().unwrap();
         match *bound {
@@ -413,6 +845,7 @@ impl QueryParser {
     fn resolved_fields(
         &self,
         given_field: &Option<String>,
+        span: &Range<usize>,
     ) -> Result<Cow<'_, [Field]>, QueryParserError> {
         match *given_field {
             None => {
@@ -422,7 +855,7 @@ impl QueryParser {
                     Ok(Cow::from(&self.default_fields[..]))
                 }
             }
-            Some(ref field) => Ok(Cow::from(vec![self.resolve_field_name(&*field)?])),
+            Some(ref field) => Ok(Cow::from(vec![self.resolve_field_name(field, span)?])),
         }
     }
 
@@ -433,11 +866,21 @@ impl QueryParser {
         match user_input_ast {
             UserInputAST::Clause(sub_queries) => {
                 let default_occur = self.default_occur();
+                let groups = self.group_multi_word_synonyms(&sub_queries)?;
+                let mut sub_queries = sub_queries.into_iter();
                 let mut logical_sub_queries: Vec<(Occur, LogicalAST)> = Vec::new();
-                for (occur_opt, sub_ast) in sub_queries {
-                    let sub_ast = self.compute_logical_ast_with_occur(sub_ast)?;
-                    let occur = occur_opt.unwrap_or(default_occur);
-                    logical_sub_queries.push((occur, sub_ast));
+                for (num_sub_queries, synonym_clauses) in groups {
+                    let mut group_sub_queries: Vec<(Occur, LogicalAST)> = Vec::new();
+                    for (occur_opt, sub_ast) in sub_queries.by_ref().take(num_sub_queries) {
+                        let sub_ast = self.compute_logical_ast_with_occur(sub_ast)?;
+                        let occur = occur_opt.unwrap_or(default_occur);
+                        group_sub_queries.push((occur, sub_ast));
+                    }
+                    logical_sub_queries.extend(with_synonyms(
+                        group_sub_queries,
+                        synonym_clauses,
+                        default_occur,
+                    ));
                 }
                 Ok(LogicalAST::Clause(logical_sub_queries))
             }
@@ -445,10 +888,105 @@ impl QueryParser {
                 let ast = self.compute_logical_ast_with_occur(*ast)?;
                 Ok(ast.boost(boost as Score))
             }
-            UserInputAST::Leaf(leaf) => self.compute_logical_ast_from_leaf(*leaf),
+            UserInputAST::Leaf(leaf, span) => self.compute_logical_ast_from_leaf(*leaf, &span),
         }
     }
 
+    /// Same as `compute_logical_ast_with_occur`, except that the leaves yielding
+    /// an error are dropped, and the error is appended to `errors`.
+    fn compute_logical_ast_lenient(
+        &self,
+        user_input_ast: UserInputAST,
+        errors: &mut Vec<QueryParserError>,
+    ) -> LogicalAST {
+        match user_input_ast {
+            UserInputAST::Clause(sub_queries) => {
+                let default_occur = self.default_occur();
+                let groups = self
+                    .group_multi_word_synonyms(&sub_queries)
+                    .unwrap_or_else(|error| {
+                        errors.push(error);
+                        (0..sub_queries.len()).map(|_| (1, Vec::new())).collect()
+                    });
+                let mut sub_queries = sub_queries.into_iter();
+                let mut logical_sub_queries: Vec<(Occur, LogicalAST)> = Vec::new();
+                for (num_sub_queries, synonym_clauses) in groups {
+                    let group_sub_queries = sub_queries
+                        .by_ref()
+                        .take(num_sub_queries)
+                        .map(|(occur_opt, sub_ast)| {
+                            let sub_ast = self.compute_logical_ast_lenient(sub_ast, errors);
+                            (occur_opt.unwrap_or(default_occur), sub_ast)
+                        })
+                        .collect();
+                    logical_sub_queries.extend(with_synonyms(
+                        group_sub_queries,
+                        synonym_clauses,
+                        default_occur,
+                    ));
+                }
+                LogicalAST::Clause(logical_sub_queries)
+            }
+            UserInputAST::Boost(ast, boost) => self
+                .compute_logical_ast_lenient(*ast, errors)
+                .boost(boost as Score),
+            UserInputAST::Leaf(leaf, span) => {
+                let leaf = match self.unknown_field_as_text(&leaf) {
+                    Some((field_name, phrase)) => {
+                        errors.push(QueryParserError::FieldDoesNotExist(
+                            field_name,
+                            span.clone(),
+                        ));
+                        UserInputLeaf::Literal(UserInputLiteral {
+                            field_name: None,
+                            phrase,
+                        })
+                    }
+                    None => *leaf,
+                };
+                self.compute_logical_ast_from_leaf(leaf, &span)
+                    .unwrap_or_else(|error| {
+                        errors.push(error);
+                        LogicalAST::Clause(Vec::new())
+                    })
+            }
+        }
+    }
+
+    /// If the leaf targets a field that does not exist, returns the name of this field
+    /// along with the text of the leaf, as it should be searched on the default fields.
+    fn unknown_field_as_text(&self, leaf: &UserInputLeaf) -> Option<(String, String)> {
+        let (field_name, text) = match *leaf {
+            UserInputLeaf::Literal(UserInputLiteral {
+                field_name: Some(ref field_name),
+                ref phrase,
+            }) => (field_name, format!("{}:{}", field_name, phrase)),
+            UserInputLeaf::Wildcard {
+                field: Some(ref field_name),
+                ref pattern,
+            } => (field_name, format!("{}:{}", field_name, pattern)),
+            UserInputLeaf::Fuzzy {
+                field: Some(ref field_name),
+                ref term,
+                ..
+            } => (field_name, format!("{}:{}", field_name, term)),
+            UserInputLeaf::Range {
+                field: Some(ref field_name),
+                ref lower,
+                ref upper,
+            } => (
+                field_name,
+                format!("{}:{} {}", field_name, lower.term_str(), upper.term_str()),
+            ),
+            UserInputLeaf::Exists { ref field } => (field, field.clone()),
+            _ => return None,
+        };
+        if self.schema.get_field(field_name).is_some() {
+            return None;
+        }
+        Some((field_name.clone(), text))
+    }
+
     fn field_boost(&self, field: Field) -> Score {
         self.boost.get(&field).cloned().unwrap_or(1.0)
     }
@@ -456,12 +994,13 @@ impl QueryParser {
     fn compute_logical_ast_from_leaf(
         &self,
         leaf: UserInputLeaf,
+        span: &Range<usize>,
     ) -> Result<LogicalAST, QueryParserError> {
         match leaf {
             UserInputLeaf::Literal(literal) => {
                 let term_phrases: Vec<(Field, String)> = match literal.field_name {
                     Some(ref field_name) => {
-                        let field = self.resolve_field_name(field_name)?;
+                        let field = self.resolve_field_name(field_name, span)?;
                         vec![(field, literal.phrase.clone())]
                     }
                     None => {
@@ -477,10 +1016,10 @@ impl QueryParser {
                 };
                 let mut asts: Vec<LogicalAST> = Vec::new();
                 for (field, phrase) in term_phrases {
-                    if let Some(ast) = self.compute_logical_ast_for_leaf(field, &phrase)? {
+                    if let Some(ast) = self.compute_logical_ast_for_leaf(field, &phrase, span)? {
                         // Apply some field specific boost defined at the query parser level.
                         let boost = self.field_boost(field);
-                        asts.push(LogicalAST::Leaf(Box::new(ast)).boost(boost));
+                        asts.push(self.expand_synonyms(field, ast, span)?.boost(boost));
                     }
                 }
                 let result_ast: LogicalAST = if asts.len() == 1 {
@@ -491,12 +1030,119 @@ impl QueryParser {
                 Ok(result_ast)
             }
             UserInputLeaf::All => Ok(LogicalAST::Leaf(Box::new(LogicalLiteral::All))),
+            UserInputLeaf::Exists { field } => {
+                let field = self.resolve_field_name(&field, span)?;
+                let field_entry = self.schema.get_field_entry(field);
+                if !is_exists_supported(field_entry.field_type()) {
+                    return Err(QueryParserError::FieldNotIndexed(
+                        field_entry.name().to_string(),
+                        span.clone(),
+                    ));
+                }
+                let boost = self.field_boost(field);
+                Ok(LogicalAST::Leaf(Box::new(LogicalLiteral::Exists(field))).boost(boost))
+            }
+            UserInputLeaf::Wildcard {
+                field: field_name,
+                pattern,
+            } => {
+                if !self.allow_leading_wildcard && has_leading_wildcard(&pattern) {
+                    return Err(QueryParserError::LeadingWildcardForbidden(
+                        pattern,
+                        span.clone(),
+                    ));
+                }
+                let fields = self.resolved_fields(&field_name, span)?;
+                let mut asts: Vec<LogicalAST> = Vec::new();
+                for &field in fields.iter() {
+                    let field_entry = self.schema.get_field_entry(field);
+                    match field_entry.field_type() {
+                        FieldType::Str(ref str_options)
+                            if str_options.get_indexing_options().is_some() => {}
+                        FieldType::HierarchicalFacet(_) if field_name.is_some() => {
+                            let literal = self.compute_facet_descendants(field, &pattern, span)?;
+                            let boost = self.field_boost(field);
+                            asts.push(LogicalAST::Leaf(Box::new(literal)).boost(boost));
+                            continue;
+                        }
+                        // Default fields that cannot hold the pattern are ignored.
+                        _ if field_name.is_none() => continue,
+                        FieldType::Str(_) => {
+                            return Err(QueryParserError::FieldNotIndexed(
+                                field_entry.name().to_string(),
+                                span.clone(),
+                            ));
+                        }
+                        _ => {
+                            return Err(QueryParserError::WildcardOnNonTextField(
+                                field_entry.name().to_string(),
+                                span.clone(),
+                            ));
+                        }
+                    }
+                    let query = WildcardQuery::from_pattern_with_leading_wildcard(&pattern, field)
+                        .map_err(|_| {
+                            QueryParserError::InvalidWildcard(pattern.clone(), span.clone())
+                        })?;
+                    let boost = self.field_boost(field);
+                    asts.push(
+                        LogicalAST::Leaf(Box::new(LogicalLiteral::Wildcard(query))).boost(boost),
+                    );
+                }
+                let result_ast: LogicalAST = if asts.len() == 1 {
+                    asts.into_iter().next().unwrap()
+                } else {
+                    LogicalAST::Clause(asts.into_iter().map(|ast| (Occur::Should, ast)).collect())
+                };
+                Ok(result_ast)
+            }
+            UserInputLeaf::Fuzzy {
+                field: field_name,
+                term,
+                distance,
+            } => {
+                let fields = self.resolved_fields(&field_name, span)?;
+                let mut asts: Vec<LogicalAST> = Vec::new();
+                for &field in fields.iter() {
+                    let field_entry = self.schema.get_field_entry(field);
+                    match field_entry.field_type() {
+                        FieldType::Str(_) => {}
+                        // Default fields that cannot hold fuzzy terms are ignored.
+                        _ if field_name.is_none() => continue,
+                        _ => {
+                            return Err(QueryParserError::FuzzyOnNonTextField(
+                                field_entry.name().to_string(),
+                                span.clone(),
+                            ));
+                        }
+                    }
+                    let mut fuzzy_options = self.fuzzy.get(&field).cloned().unwrap_or_default();
+                    if let Some(distance) = distance {
+                        fuzzy_options.distance = distance;
+                    }
+                    if let Some(ast) = self.compute_logical_ast_for_fuzzy_leaf(
+                        field,
+                        &term,
+                        Some(fuzzy_options),
+                        span,
+                    )? {
+                        let boost = self.field_boost(field);
+                        asts.push(LogicalAST::Leaf(Box::new(ast)).boost(boost));
+                    }
+                }
+                let result_ast: LogicalAST = if asts.len() == 1 {
+                    asts.into_iter().next().unwrap()
+                } else {
+                    LogicalAST::Clause(asts.into_iter().map(|ast| (Occur::Should, ast)).collect())
+                };
+                Ok(result_ast)
+            }
             UserInputLeaf::Range {
                 field,
                 lower,
                 upper,
             } => {
-                let fields = self.resolved_fields(&field)?;
+                let fields = self.resolved_fields(&field, span)?;
                 let mut clauses = fields
                     .iter()
                     .map(|&field| {
@@ -506,8 +1152,8 @@ impl QueryParser {
                         let logical_ast = LogicalAST::Leaf(Box::new(LogicalLiteral::Range {
                             field,
                             value_type,
-                            lower: self.resolve_bound(field, &lower)?,
-                            upper: self.resolve_bound(field, &upper)?,
+                            lower: self.resolve_bound(field, &lower, span)?,
+                            upper: self.resolve_bound(field, &upper, span)?,
                         }));
                         Ok(logical_ast.boost(boost))
                     })
@@ -528,6 +1174,29 @@ impl QueryParser {
     }
 }
 
+/// Adds the synonyms of a group of sub queries, as computed by
+/// `QueryParser::group_multi_word_synonyms`.
+///
+/// A group having synonyms becomes a single sub query matching either
+/// the original sub queries, or any of the synonyms.
+fn with_synonyms(
+    mut sub_queries: Vec<(Occur, LogicalAST)>,
+    synonym_clauses: Vec<(Occur, LogicalAST)>,
+    default_occur: Occur,
+) -> Vec<(Occur, LogicalAST)> {
+    if synonym_clauses.is_empty() {
+        return sub_queries;
+    }
+    let original_ast = if sub_queries.len() == 1 {
+        sub_queries.pop().unwrap().1
+    } else {
+        LogicalAST::Clause(sub_queries)
+    };
+    let mut clauses = vec![(Occur::Should, original_ast)];
+    clauses.extend(synonym_clauses);
+    vec![(default_occur, LogicalAST::Clause(clauses))]
+}
+
 fn convert_literal_to_query(logical_literal: LogicalLiteral) -> Box<dyn Query> {
     match logical_literal {
         LogicalLiteral::Term(term) => Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs)),
@@ -543,10 +1212,29 @@ fn convert_literal_to_query(logical_literal: LogicalLiteral) -> Box<dyn Query> {
             field, value_type, &lower, &upper,
         )),
         LogicalLiteral::All => Box::new(AllQuery),
+        LogicalLiteral::Exists(field) => Box::new(ExistsQuery::new(field)),
+        LogicalLiteral::Wildcard(query) => Box::new(query),
+        LogicalLiteral::Regex { query, .. } => Box::new(query),
+        LogicalLiteral::Fuzzy {
+            term,
+            distance,
+            prefix,
+            transposition_cost_one,
+        } => {
+            if prefix {
+                Box::new(FuzzyTermQuery::new_prefix(
+                    term,
+                    distance,
+                    transposition_cost_one,
+                ))
+            } else {
+                Box::new(FuzzyTermQuery::new(term, distance, transposition_cost_one))
+            }
+        }
     }
 }
 
-fn convert_to_query(logical_ast: LogicalAST) -> Box<dyn Query> {
+pub(crate) fn convert_to_query(logical_ast: LogicalAST) -> Box<dyn Query> {
     match trim_ast(logical_ast) {
         Some(LogicalAST::Clause(trimmed_clause)) => {
             let occur_subqueries = trimmed_clause
@@ -574,17 +1262,20 @@ fn convert_to_query(logical_ast: LogicalAST) -> Box<dyn Query> {
 #[cfg(test)]
 mod test {
     use super::super::logical_ast::*;
+    use super::FuzzyConfig;
     use super::QueryParser;
     use super::QueryParserError;
+    use crate::collector::TopDocs;
     use crate::query::Query;
-    use crate::schema::Field;
+    use crate::schema::{Facet, Field};
     use crate::schema::{IndexRecordOption, TextFieldIndexing, TextOptions};
     use crate::schema::{Schema, Term, INDEXED, STORED, STRING, TEXT};
     use crate::tokenizer::{
-        LowerCaser, SimpleTokenizer, StopWordFilter, TextAnalyzer, TokenizerManager,
+        LowerCaser, SimpleTokenizer, StopWordFilter, SynonymMap, TextAnalyzer, TokenizerManager,
     };
-    use crate::Index;
+    use crate::{DocAddress, Index};
     use matches::assert_matches;
+    use std::collections::BTreeSet;
 
     fn make_schema() -> Schema {
         let mut schema_builder = Schema::builder();
@@ -658,6 +1349,45 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_parse_query_facet_descendants() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let category = schema_builder.add_facet_field("category");
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "a", category => Facet::from("/books")));
+        index_writer.add_document(doc!(title => "b", category => Facet::from("/books/novels")));
+        index_writer.add_document(doc!(
+            title => "c",
+            category => Facet::from("/books/novels/french")
+        ));
+        index_writer.add_document(doc!(title => "d", category => Facet::from("/booksellers")));
+        index_writer.add_document(doc!(title => "e", category => Facet::from("/music")));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count = |query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            query.count(&searcher).unwrap()
+        };
+        assert_eq!(count("category:/books"), 3);
+        assert_eq!(count("category:/books/*"), 2);
+        assert_eq!(count("category:/books/novels/*"), 1);
+        assert_eq!(count("category:/books/novels/french/*"), 0);
+        assert_eq!(count("category:/*"), 5);
+        assert_eq!(count("a OR category:/music/*"), 1);
+        assert_matches!(
+            query_parser.parse_query("category:/bo*"),
+            Err(QueryParserError::WildcardOnNonTextField(_, _))
+        );
+        assert_matches!(
+            query_parser.parse_query("category:/*/novels/*"),
+            Err(QueryParserError::WildcardOnNonTextField(_, _))
+        );
+        Ok(())
+    }
+
     #[test]
     pub fn test_parse_query_with_boost() {
         let mut query_parser = make_query_parser();
@@ -703,7 +1433,7 @@ mod test {
 
         let is_not_indexed_err = |query: &str| {
             let result: Result<Box<dyn Query>, QueryParserError> = query_parser.parse_query(query);
-            if let Err(QueryParserError::FieldNotIndexed(field_name)) = result {
+            if let Err(QueryParserError::FieldNotIndexed(field_name, _)) = result {
                 Some(field_name.clone())
             } else {
                 None
@@ -724,6 +1454,348 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_parse_query_exists() {
+        test_parse_query_to_logical_ast_helper("title:*", "Exists(Field(0))", false);
+        test_parse_query_to_logical_ast_helper(
+            "title:* -signed:*",
+            "(Exists(Field(0)) -Exists(Field(2)))",
+            false,
+        );
+        let query_parser = make_query_parser();
+        assert_matches!(
+            query_parser.parse_query("notindexed_text:*"),
+            Err(QueryParserError::FieldNotIndexed(_, _))
+        );
+        assert_matches!(
+            query_parser.parse_query("unknown:*"),
+            Err(QueryParserError::FieldDoesNotExist(_, _))
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_wildcard() {
+        test_parse_query_to_logical_ast_helper(
+            "title:ba?ack*",
+            "Wildcard(Field(0), \"ba?ack*\")",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "oba*",
+            "(Wildcard(Field(0), \"oba*\") Wildcard(Field(1), \"oba*\"))",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:\"oba*\"",
+            "Term(field=0,bytes=[111, 98, 97])",
+            false,
+        );
+        let mut query_parser = make_query_parser();
+        assert_matches!(
+            query_parser.parse_query("title:*bama"),
+            Err(QueryParserError::LeadingWildcardForbidden(_, _))
+        );
+        assert_matches!(
+            query_parser.parse_query("?bama"),
+            Err(QueryParserError::LeadingWildcardForbidden(_, _))
+        );
+        assert_matches!(
+            query_parser.parse_query("signed:1*"),
+            Err(QueryParserError::WildcardOnNonTextField(_, _))
+        );
+        assert_matches!(
+            query_parser.parse_query("notindexed_text:a*"),
+            Err(QueryParserError::FieldNotIndexed(_, _))
+        );
+        query_parser.set_allow_leading_wildcard(true);
+        assert!(query_parser.parse_query("title:*bama").is_ok());
+    }
+
+    #[test]
+    pub fn test_parse_query_fuzzy() {
+        test_parse_query_to_logical_ast_helper(
+            "title:roam~1",
+            "Fuzzy(Term(field=0,bytes=[114, 111, 97, 109]), distance=1)",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "Roam~",
+            "(Fuzzy(Term(field=0,bytes=[114, 111, 97, 109]), distance=2) \
+             Fuzzy(Term(field=1,bytes=[114, 111, 97, 109]), distance=2))",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:a-b~1",
+            "\"[(0, Term(field=0,bytes=[97])), (1, Term(field=0,bytes=[98]))]\"",
+            false,
+        );
+        let mut query_parser = make_query_parser();
+        assert_matches!(
+            query_parser.parse_query("title:roam~3"),
+            Err(QueryParserError::InvalidFuzzyDistance(3, _))
+        );
+        assert_matches!(
+            query_parser.parse_query("signed:2~1"),
+            Err(QueryParserError::FuzzyOnNonTextField(_, _))
+        );
+        assert_matches!(
+            query_parser.parse_query("notindexed_text:roam~1"),
+            Err(QueryParserError::FieldNotIndexed(_, _))
+        );
+
+        let title = make_schema().get_field("title").unwrap();
+        query_parser.set_field_fuzzy(
+            title,
+            FuzzyConfig {
+                distance: 1,
+                prefix: true,
+                transposition_cost_one: false,
+            },
+        );
+        let logical_ast = query_parser
+            .parse_query_to_logical_ast("title:roam text:roam title:roam~2")
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", logical_ast),
+            "(Fuzzy(Term(field=0,bytes=[114, 111, 97, 109]), distance=1, prefix) \
+             Term(field=1,bytes=[114, 111, 97, 109]) \
+             Fuzzy(Term(field=0,bytes=[114, 111, 97, 109]), distance=2, prefix))"
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_fuzzy_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let country = schema_builder.add_text_field("country", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(country => "japan"));
+        index_writer.add_document(doc!(country => "korea"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![country]);
+        let count = |query_parser: &QueryParser, query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            query.count(&searcher).unwrap()
+        };
+        assert_eq!(count(&query_parser, "japon"), 0);
+        assert_eq!(count(&query_parser, "japon~1"), 1);
+        assert_eq!(count(&query_parser, "jpaon~1"), 0);
+        assert_eq!(count(&query_parser, "jpaon~"), 1);
+        assert_eq!(count(&query_parser, "jpaon~ OR korea"), 2);
+        query_parser.set_field_fuzzy(
+            country,
+            FuzzyConfig {
+                distance: 1,
+                prefix: false,
+                transposition_cost_one: true,
+            },
+        );
+        assert_eq!(count(&query_parser, "japon"), 1);
+        assert_eq!(count(&query_parser, "jpaan"), 1);
+        assert_eq!(count(&query_parser, "jpaon"), 0);
+        assert_eq!(count(&query_parser, "jpaan~0"), 0);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_parse_query_synonyms() {
+        let mut query_parser = make_query_parser();
+        let title = query_parser.schema.get_field("title").unwrap();
+        let nottokenized = query_parser.schema.get_field("nottokenized").unwrap();
+        let mut synonyms = SynonymMap::new();
+        synonyms.add_synonyms(&["nyc", "new york"]);
+        synonyms.add_synonym("car", "auto");
+        query_parser.set_field_synonyms(title, synonyms.clone(), 0.5);
+        query_parser.set_field_synonyms(nottokenized, synonyms, 1.0);
+        let parse = |query: &str| {
+            format!(
+                "{:?}",
+                query_parser.parse_query_to_logical_ast(query).unwrap()
+            )
+        };
+        assert_eq!(
+            parse("title:car"),
+            "(Term(field=0,bytes=[99, 97, 114]) Term(field=0,bytes=[97, 117, 116, 111])^0.5)"
+        );
+        assert_eq!(
+            parse("title:nyc"),
+            "(Term(field=0,bytes=[110, 121, 99]) \
+             \"[(0, Term(field=0,bytes=[110, 101, 119])), \
+             (1, Term(field=0,bytes=[121, 111, 114, 107]))]\"^0.5)"
+        );
+        assert_eq!(
+            parse("title:\"New York\""),
+            "(\"[(0, Term(field=0,bytes=[110, 101, 119])), \
+             (1, Term(field=0,bytes=[121, 111, 114, 107]))]\" \
+             Term(field=0,bytes=[110, 121, 99])^0.5)"
+        );
+        assert_eq!(
+            parse("title:york"),
+            "Term(field=0,bytes=[121, 111, 114, 107])"
+        );
+        assert_eq!(
+            parse("title:New title:York title:car"),
+            "(((Term(field=0,bytes=[110, 101, 119]) Term(field=0,bytes=[121, 111, 114, 107])) \
+             Term(field=0,bytes=[110, 121, 99])^0.5) \
+             (Term(field=0,bytes=[99, 97, 114]) Term(field=0,bytes=[97, 117, 116, 111])^0.5))"
+        );
+        assert_eq!(
+            parse("title:new +title:york"),
+            "(Term(field=0,bytes=[110, 101, 119]) +Term(field=0,bytes=[121, 111, 114, 107]))"
+        );
+        assert_eq!(parse("text:car"), "Term(field=1,bytes=[99, 97, 114])");
+        assert_eq!(
+            parse("nottokenized:car"),
+            "(Term(field=7,bytes=[99, 97, 114]) Term(field=7,bytes=[97, 117, 116, 111]))"
+        );
+        assert_matches!(
+            query_parser.parse_query("nottokenized:nyc"),
+            Err(QueryParserError::FieldDoesNotHavePositionsIndexed(_, _))
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_synonyms_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let city = schema_builder.add_text_field("city", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(city => "Welcome to New York"));
+        index_writer.add_document(doc!(city => "NYC subway"));
+        index_writer.add_document(doc!(city => "York minster"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![city]);
+        let mut synonyms = SynonymMap::new();
+        synonyms.add_synonyms(&["nyc", "new york"]);
+        query_parser.set_field_synonyms(city, synonyms, 0.5);
+        let top_docs = |query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            searcher.search(&query, &TopDocs::with_limit(10)).unwrap()
+        };
+        let nyc_docs = top_docs("nyc");
+        assert_eq!(nyc_docs.len(), 2);
+        assert_eq!(nyc_docs[0].1, DocAddress(0, 1));
+        assert_eq!(top_docs("\"new york\"").len(), 2);
+        assert_eq!(top_docs("york").len(), 2);
+        assert_eq!(top_docs("new york").len(), 3);
+        query_parser.set_conjunction_by_default();
+        let top_docs = |query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            searcher.search(&query, &TopDocs::with_limit(10)).unwrap()
+        };
+        assert_eq!(top_docs("new york").len(), 2);
+        assert_eq!(top_docs("new york subway").len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_logical_ast_rewrite() {
+        let query_parser = make_query_parser();
+        let title = query_parser.schema.get_field("title").unwrap();
+        let text = query_parser.schema.get_field("text").unwrap();
+        let logical_ast = query_parser
+            .parse_query_to_logical_ast("title:a (title:b -title:c)^2")
+            .unwrap();
+        let removed = Term::from_field_text(title, "c");
+        let mut rewriter = |ast: LogicalAST| match ast {
+            LogicalAST::Leaf(literal) => match *literal {
+                LogicalLiteral::Term(ref term) if term == &removed => None,
+                LogicalLiteral::Term(term) => Some(LogicalAST::from(LogicalLiteral::Term(
+                    Term::from_field_text(text, term.text()),
+                ))),
+                literal => Some(LogicalAST::from(literal)),
+            },
+            ast => Some(ast),
+        };
+        let logical_ast = logical_ast.rewrite(&mut rewriter).unwrap();
+        assert_eq!(
+            format!("{:?}", logical_ast),
+            "(Term(field=1,bytes=[97]) (Term(field=1,bytes=[98]))^2)"
+        );
+        let query = logical_ast.into_query();
+        let mut terms = BTreeSet::new();
+        query.query_terms(&mut terms);
+        assert_eq!(terms.len(), 2);
+        let only_removed = query_parser.parse_query_to_logical_ast("title:c").unwrap();
+        assert!(only_removed.rewrite(&mut rewriter).is_none());
+    }
+
+    #[test]
+    pub fn test_parse_query_lenient() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let count = schema_builder.add_u64_field("count", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "hello world", count => 1u64));
+        index_writer.add_document(doc!(title => "foo bar"));
+        index_writer.add_document(doc!(title => "happy"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let parse = |query: &str| {
+            let (query, errors) = query_parser.parse_query_lenient(query);
+            (query.count(&searcher).unwrap(), errors)
+        };
+        assert_eq!(parse("hello happy"), (2, vec![]));
+        assert_eq!(
+            parse("\"hello happy"),
+            (
+                2,
+                vec![QueryParserError::SyntaxError {
+                    position: 0,
+                    expected: vec![]
+                }]
+            )
+        );
+        assert_eq!(
+            parse("foo:bar"),
+            (
+                1,
+                vec![QueryParserError::FieldDoesNotExist("foo".to_string(), 0..7)]
+            )
+        );
+        for &(query, num_docs) in &[
+            ("foo:bar*", 1),
+            ("foo:bar~1", 1),
+            ("foo:{* TO bar]", 1),
+            ("foo:[bar TO baz]", 0),
+            ("foo:*", 1),
+        ] {
+            assert_eq!(
+                parse(query),
+                (
+                    num_docs,
+                    vec![QueryParserError::FieldDoesNotExist(
+                        "foo".to_string(),
+                        0..query.len()
+                    )]
+                )
+            );
+        }
+        let (num_docs, errors) = parse("count:abc happy count:1");
+        assert_eq!(num_docs, 2);
+        assert_eq!(errors.len(), 1);
+        assert_matches!(errors[0], QueryParserError::ExpectedInt(_, ref span) if *span == (0..9));
+        assert_eq!(
+            parse("-hello"),
+            (0, vec![QueryParserError::AllButQueryForbidden])
+        );
+        assert_eq!(
+            parse(")"),
+            (
+                0,
+                vec![QueryParserError::SyntaxError {
+                    position: 0,
+                    expected: vec![]
+                }]
+            )
+        );
+        Ok(())
+    }
+
     #[test]
     pub fn test_parse_query_untokenized() {
         test_parse_query_to_logical_ast_helper(
@@ -807,7 +1879,7 @@ mod test {
     #[test]
     fn test_parse_bytes_not_indexed() {
         let error = parse_query_to_logical_ast("bytes_not_indexed:aaa", false).unwrap_err();
-        assert!(matches!(error, QueryParserError::FieldNotIndexed(_)));
+        assert!(matches!(error, QueryParserError::FieldNotIndexed(_, _)));
     }
 
     #[test]
@@ -823,7 +1895,7 @@ mod test {
     fn test_parse_bytes_invalid_base64() {
         let base64_err: QueryParserError =
             parse_query_to_logical_ast("bytes:aa", false).unwrap_err();
-        assert!(matches!(base64_err, QueryParserError::ExpectedBase64(_)));
+        assert!(matches!(base64_err, QueryParserError::ExpectedBase64(_, _)));
     }
 
     #[test]
@@ -936,8 +2008,29 @@ mod test {
         let query_parser = make_query_parser();
         assert_matches!(
             query_parser.parse_query("boujou:\"18446744073709551615\""),
-            Err(QueryParserError::FieldDoesNotExist(_))
+            Err(QueryParserError::FieldDoesNotExist(_, _))
+        );
+    }
+
+    #[test]
+    pub fn test_query_parser_error_positions() {
+        let query_parser = make_query_parser();
+        let error = query_parser.parse_query("title:abc boujou:x").unwrap_err();
+        assert_eq!(
+            error,
+            QueryParserError::FieldDoesNotExist("boujou".to_string(), 10..18)
+        );
+        assert_eq!(error.span(), Some(10..18));
+        assert_matches!(
+            query_parser.parse_query("title:a (signed:b^2 OR unsigned:2.5)"),
+            Err(QueryParserError::ExpectedInt(_, ref span)) if *span == (9..17)
+        );
+        assert_matches!(
+            query_parser.parse_query("title:abc \"def"),
+            Err(QueryParserError::SyntaxError { position: 14, ref expected })
+                if expected.contains(&"`\"`".to_string())
         );
+        assert_eq!(QueryParserError::AllButQueryForbidden.span(), None);
     }
 
     #[test]
@@ -945,7 +2038,7 @@ mod test {
         let query_parser = make_query_parser();
         assert_matches!(
             query_parser.parse_query("notindexed_text:\"18446744073709551615\""),
-            Err(QueryParserError::FieldNotIndexed(_))
+            Err(QueryParserError::FieldNotIndexed(_, _))
         );
     }
 
@@ -963,7 +2056,7 @@ mod test {
         let query_parser = QueryParser::new(schema, default_fields, tokenizer_manager);
         assert_matches!(
             query_parser.parse_query("title:\"happy tax payer\""),
-            Err(QueryParserError::UnknownTokenizer(_, _))
+            Err(QueryParserError::UnknownTokenizer(_, _, _))
         );
     }
 
@@ -983,7 +2076,7 @@ mod test {
         let query_parser = QueryParser::for_index(&index, vec![title]);
         assert_eq!(
             query_parser.parse_query("title:\"happy tax\"").unwrap_err(),
-            QueryParserError::FieldDoesNotHavePositionsIndexed("title".to_string())
+            QueryParserError::FieldDoesNotHavePositionsIndexed("title".to_string(), 0..17)
         );
     }
 
@@ -992,17 +2085,17 @@ mod test {
         let query_parser = make_query_parser();
         assert_matches!(
             query_parser.parse_query("unsigned:18a"),
-            Err(QueryParserError::ExpectedInt(_))
+            Err(QueryParserError::ExpectedInt(_, _))
         );
         assert!(query_parser.parse_query("unsigned:\"18\"").is_ok());
         assert_matches!(
             query_parser.parse_query("signed:18b"),
-            Err(QueryParserError::ExpectedInt(_))
+            Err(QueryParserError::ExpectedInt(_, _))
         );
         assert!(query_parser.parse_query("float:\"1.8\"").is_ok());
         assert_matches!(
             query_parser.parse_query("float:1.8a"),
-            Err(QueryParserError::ExpectedFloat(_))
+            Err(QueryParserError::ExpectedFloat(_, _))
         );
     }
 
@@ -1011,7 +2104,7 @@ mod test {
         let query_parser = make_query_parser();
         assert_matches!(
             query_parser.parse_query("date:18a"),
-            Err(QueryParserError::DateFormatError(_))
+            Err(QueryParserError::DateFormatError(_, _))
         );
         assert!(query_parser
             .parse_query("date:\"1985-04-12T23:20:50.52Z\"")
@@ -3,6 +3,7 @@ use crate::core::Searcher;
 use crate::core::SegmentReader;
 use crate::error::TantivyError;
 use crate::query::explanation::does_not_match;
+use crate::query::fast_field_range_weight::FastFieldRangeWeight;
 use crate::query::ConstScorer;
 use crate::query::{BitSetDocSet, Explanation};
 use crate::query::{Query, Scorer, Weight};
@@ -31,8 +32,18 @@ fn map_bound<TFrom, TTo, Transform: Fn(&TFrom) -> TTo>(
 ///
 /// # Implementation
 ///
-/// The current implement will iterate over the terms within the range
-/// and append all of the document cross into a `BitSet`.
+/// If the field is a numerical fast field, the query scans the fast field
+/// column of each segment and skips segments for which the fast field
+/// `min_value`/`max_value` do not intersect the range.
+///
+/// Single-valued fast fields do not record the absence of a value: documents without
+/// a value are stored with the value `0`. If the field is also indexed, these documents
+/// are told apart from the documents actually having the value `0` using the posting list
+/// of this value, and do not match. If the field is only a fast field, documents without
+/// a value behave as if their value was `0`.
+///
+/// Otherwise, the current implement will iterate over the terms
+/// within the range and append all of the document cross into a `BitSet`.
 ///
 /// # Example
 ///
@@ -259,6 +270,15 @@ impl Query for RangeQuery {
             );
             return Err(TantivyError::SchemaError(err_msg));
         }
+        let field_entry = schema.get_field_entry(self.field);
+        let is_numerical = matches!(value_type, Type::U64 | Type::I64 | Type::F64 | Type::Date);
+        if is_numerical && field_entry.is_fast() {
+            return Ok(Box::new(FastFieldRangeWeight::new(
+                self.field,
+                &self.left_bound,
+                &self.right_bound,
+            )));
+        }
         Ok(Box::new(RangeWeight {
             field: self.field,
             left_bound: self.left_bound.clone(),
@@ -333,7 +353,7 @@ mod tests {
     use super::RangeQuery;
     use crate::collector::{Count, TopDocs};
     use crate::query::QueryParser;
-    use crate::schema::{Document, Field, Schema, INDEXED, TEXT};
+    use crate::schema::{Cardinality, Document, Field, IntOptions, Schema, FAST, INDEXED, TEXT};
     use crate::Index;
     use std::collections::Bound;
 
@@ -504,4 +524,166 @@ mod tests {
         assert_eq!(top_docs.len(), 1);
         Ok(())
     }
+
+    #[test]
+    fn test_range_query_fast_field_only() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let year_field = schema_builder.add_u64_field("year", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        for year in 1950u64..2017u64 {
+            let num_docs_within_year = 10 + (year - 1950) * (year - 1950);
+            for _ in 0..num_docs_within_year {
+                index_writer.add_document(doc!(year_field => year));
+            }
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let count = |range_query: RangeQuery| searcher.search(&range_query, &Count).unwrap();
+        assert_eq!(
+            count(RangeQuery::new_u64(year_field, 1960u64..1970u64)),
+            2285
+        );
+        assert_eq!(count(RangeQuery::new_u64(year_field, 1900u64..1950u64)), 0);
+        assert_eq!(
+            count(RangeQuery::new_u64(year_field, 1900u64..3000u64)),
+            98_691
+        );
+        assert_eq!(
+            count(RangeQuery::new_u64_bounds(
+                year_field,
+                Bound::Excluded(2015),
+                Bound::Unbounded
+            )),
+            4366
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_query_fast_field_skips_segments() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let val_field = schema_builder.add_i64_field("val", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        for val in -10i64..0i64 {
+            index_writer.add_document(doc!(val_field => val));
+        }
+        index_writer.commit()?;
+        for val in 0i64..10i64 {
+            index_writer.add_document(doc!(val_field => val));
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let count = |range_query: RangeQuery| searcher.search(&range_query, &Count).unwrap();
+        assert_eq!(count(RangeQuery::new_i64(val_field, -5..5)), 10);
+        assert_eq!(count(RangeQuery::new_i64(val_field, -20..-8)), 2);
+        assert_eq!(count(RangeQuery::new_i64(val_field, 0..100)), 10);
+        assert_eq!(count(RangeQuery::new_i64(val_field, 100..200)), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_query_indexed_fast_field_missing_value() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let val_field = schema_builder.add_i64_field("val", FAST | INDEXED);
+        let title = schema_builder.add_text_field("title", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(val_field => 3i64));
+        index_writer.add_document(doc!(val_field => 20i64));
+        index_writer.add_document(doc!(title => "no value"));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(val_field => 0i64));
+        index_writer.add_document(doc!(title => "no value"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let count = |range_query: RangeQuery| searcher.search(&range_query, &Count).unwrap();
+        assert_eq!(
+            count(RangeQuery::new_i64_bounds(
+                val_field,
+                Bound::Included(-10),
+                Bound::Included(10)
+            )),
+            2
+        );
+        assert_eq!(count(RangeQuery::new_i64(val_field, -100..100)), 3);
+        assert_eq!(count(RangeQuery::new_i64(val_field, 1..100)), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_query_fast_field_only_missing_value() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let val_field = schema_builder.add_i64_field("val", FAST);
+        let title = schema_builder.add_text_field("title", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(val_field => 3i64));
+        index_writer.add_document(doc!(title => "no value"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let count = |range_query: RangeQuery| searcher.search(&range_query, &Count).unwrap();
+        // The fast field does not record the absence of a value:
+        // the document without a value behaves as if its value was 0.
+        assert_eq!(count(RangeQuery::new_i64(val_field, -10..10)), 2);
+        assert_eq!(count(RangeQuery::new_i64(val_field, 1..10)), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_query_multivalued_fast_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let float_field = schema_builder.add_f64_field(
+            "floatfield",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        for i in 1..100 {
+            let mut doc = Document::new();
+            for j in 1..100 {
+                if i % j == 0 {
+                    doc.add_f64(float_field, j as f64);
+                }
+            }
+            index_writer.add_document(doc);
+        }
+        index_writer.add_document(Document::new());
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let count = |range_query: RangeQuery| searcher.search(&range_query, &Count).unwrap();
+        assert_eq!(count(RangeQuery::new_f64(float_field, 10.0..11.0)), 9);
+        assert_eq!(
+            count(RangeQuery::new_f64_bounds(
+                float_field,
+                Bound::Excluded(9.0),
+                Bound::Included(10.0)
+            )),
+            9
+        );
+        assert_eq!(
+            count(RangeQuery::new_f64_bounds(
+                float_field,
+                Bound::Included(9.0),
+                Bound::Unbounded
+            )),
+            91
+        );
+        assert_eq!(
+            count(RangeQuery::new_f64_bounds(
+                float_field,
+                Bound::Unbounded,
+                Bound::Unbounded
+            )),
+            99
+        );
+        Ok(())
+    }
 }
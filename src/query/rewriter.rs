@@ -0,0 +1,269 @@
+use crate::query::{BooleanQuery, BoostQuery, ConstScoreQuery, FunctionScoreQuery, Query};
+
+/// Read-only traversal of a tree of queries.
+///
+/// See [`visit_query`](./fn.visit_query.html).
+pub trait QueryVisitor {
+    /// Called on each query of the tree, parents first.
+    fn visit(&mut self, query: &dyn Query);
+}
+
+impl<F: FnMut(&dyn Query)> QueryVisitor for F {
+    fn visit(&mut self, query: &dyn Query) {
+        self(query)
+    }
+}
+
+/// Rewriting pass over a tree of queries.
+///
+/// See [`rewrite_query`](./fn.rewrite_query.html).
+pub trait QueryRewriter {
+    /// Called on each query of the tree, once its subqueries have been rewritten.
+    ///
+    /// Returns the query replacing `query`, or `None` to remove it from the tree.
+    fn rewrite(&mut self, query: Box<dyn Query>) -> Option<Box<dyn Query>>;
+}
+
+impl<F: FnMut(Box<dyn Query>) -> Option<Box<dyn Query>>> QueryRewriter for F {
+    fn rewrite(&mut self, query: Box<dyn Query>) -> Option<Box<dyn Query>> {
+        self(query)
+    }
+}
+
+/// Returns the direct subqueries of a query.
+///
+/// Only the built-in compound queries (`BooleanQuery`, `BoostQuery`, `ConstScoreQuery`
+/// and `FunctionScoreQuery`) have subqueries. Any other query is considered as a leaf.
+pub fn subqueries(query: &dyn Query) -> Vec<&dyn Query> {
+    if let Some(boolean_query) = query.downcast_ref::<BooleanQuery>() {
+        boolean_query
+            .clauses()
+            .iter()
+            .map(|(_, subquery)| subquery.as_ref())
+            .collect()
+    } else if let Some(boost_query) = query.downcast_ref::<BoostQuery>() {
+        vec![boost_query.query()]
+    } else if let Some(const_score_query) = query.downcast_ref::<ConstScoreQuery>() {
+        vec![const_score_query.query()]
+    } else if let Some(function_score_query) = query.downcast_ref::<FunctionScoreQuery>() {
+        vec![function_score_query.query()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Calls `visitor` on each query of the tree rooted at `query`, parents first.
+///
+/// Queries can be inspected by downcasting them to their concrete type.
+///
+/// ```rust
+/// use tantivy::query::{visit_query, Query, QueryParser, TermQuery};
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::Index;
+///
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let query_parser = QueryParser::for_index(&index, vec![title]);
+/// let query = query_parser.parse_query("+diary (girl OR cow^2)").unwrap();
+///
+/// let mut num_terms = 0;
+/// visit_query(query.as_ref(), &mut |query: &dyn Query| {
+///     if query.downcast_ref::<TermQuery>().is_some() {
+///         num_terms += 1;
+///     }
+/// });
+/// assert_eq!(num_terms, 3);
+/// ```
+pub fn visit_query(query: &dyn Query, visitor: &mut dyn QueryVisitor) {
+    visitor.visit(query);
+    for subquery in subqueries(query) {
+        visit_query(subquery, visitor);
+    }
+}
+
+/// Rewrites the tree of queries rooted at `query`, children first.
+///
+/// The subqueries of the built-in compound queries are rewritten before their parent
+/// is handed to `rewriter`. A compound query whose subqueries were all removed
+/// is removed as well.
+///
+/// Returns `None` if the whole tree was removed.
+///
+/// ```rust
+/// use tantivy::query::{
+///     rewrite_query, BooleanQuery, Occur, Query, QueryParser, TermQuery, WildcardQuery,
+/// };
+/// use tantivy::schema::{IndexRecordOption, Schema, STRING, TEXT};
+/// use tantivy::{Index, Term};
+///
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let tenant = schema_builder.add_text_field("tenant", STRING);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let query_parser = QueryParser::for_index(&index, vec![title]);
+/// let query = query_parser.parse_query("diary title:d*").unwrap();
+///
+/// // Strip the wildcard clauses, and restrict the query to a tenant.
+/// let query = rewrite_query(query, &mut |query: Box<dyn Query>| {
+///     if query.is::<WildcardQuery>() {
+///         None
+///     } else {
+///         Some(query)
+///     }
+/// })
+/// .unwrap();
+/// let tenant_filter = TermQuery::new(
+///     Term::from_field_text(tenant, "acme"),
+///     IndexRecordOption::Basic,
+/// );
+/// let query = BooleanQuery::new(vec![
+///     (Occur::Must, query),
+///     (Occur::Must, Box::new(tenant_filter)),
+/// ]);
+/// ```
+pub fn rewrite_query(
+    query: Box<dyn Query>,
+    rewriter: &mut dyn QueryRewriter,
+) -> Option<Box<dyn Query>> {
+    let query: Box<dyn Query> = match query.downcast::<BooleanQuery>() {
+        Ok(boolean_query) => {
+            let clauses: Vec<_> = boolean_query
+                .into_clauses()
+                .into_iter()
+                .filter_map(|(occur, subquery)| {
+                    rewrite_query(subquery, rewriter).map(|subquery| (occur, subquery))
+                })
+                .collect();
+            if clauses.is_empty() {
+                return None;
+            }
+            Box::new(BooleanQuery::new(clauses))
+        }
+        Err(query) => match query.downcast::<BoostQuery>() {
+            Ok(boost_query) => {
+                Box::new(boost_query.map_query(|subquery| rewrite_query(subquery, rewriter))?)
+            }
+            Err(query) => match query.downcast::<ConstScoreQuery>() {
+                Ok(const_score_query) => Box::new(
+                    const_score_query.map_query(|subquery| rewrite_query(subquery, rewriter))?,
+                ),
+                Err(query) => match query.downcast::<FunctionScoreQuery>() {
+                    Ok(function_score_query) => Box::new(
+                        function_score_query
+                            .map_query(|subquery| rewrite_query(subquery, rewriter))?,
+                    ),
+                    Err(query) => query,
+                },
+            },
+        },
+    };
+    rewriter.rewrite(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rewrite_query, subqueries, visit_query};
+    use crate::query::{
+        BooleanQuery, BoostQuery, ConstScoreQuery, Occur, PhraseQuery, Query, QueryParser,
+        RangeQuery, TermQuery,
+    };
+    use crate::schema::{IndexRecordOption, Schema, INDEXED, TEXT};
+    use crate::{Index, Term};
+
+    #[test]
+    fn test_visit_query() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_u64_field("year", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let query = query_parser
+            .parse_query("+diary^2 -\"young girl\" year:[1900 TO 2000]")
+            .unwrap();
+        assert_eq!(subqueries(query.as_ref()).len(), 3);
+        let mut kinds = Vec::new();
+        visit_query(query.as_ref(), &mut |query: &dyn Query| {
+            let kind = if query.is::<BooleanQuery>() {
+                "bool"
+            } else if query.is::<BoostQuery>() {
+                "boost"
+            } else if query.is::<TermQuery>() {
+                "term"
+            } else if query.is::<PhraseQuery>() {
+                "phrase"
+            } else if query.is::<RangeQuery>() {
+                "range"
+            } else {
+                "other"
+            };
+            kinds.push(kind);
+        });
+        assert_eq!(kinds, vec!["bool", "boost", "term", "phrase", "range"]);
+    }
+
+    #[test]
+    fn test_rewrite_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "diary", body => "girl"));
+        index_writer.add_document(doc!(title => "girl", body => "diary"));
+        index_writer.add_document(doc!(title => "cow", body => "diary"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let query = query_parser.parse_query("diary OR (girl -cow^2)")?;
+        assert_eq!(query.count(&searcher)?, 2);
+
+        // Rename the field `title` into `body`.
+        let mut rename_field = |query: Box<dyn Query>| -> Option<Box<dyn Query>> {
+            match query.downcast::<TermQuery>() {
+                Ok(term_query) => {
+                    let term = Term::from_field_text(body, term_query.term().text());
+                    Some(Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs)))
+                }
+                Err(query) => Some(query),
+            }
+        };
+        let renamed = rewrite_query(query.box_clone(), &mut rename_field).unwrap();
+        assert_eq!(renamed.count(&searcher)?, 3);
+
+        // Strip the clauses about `cow`, including the boost query wrapping it.
+        let cow = Term::from_field_text(title, "cow");
+        let mut strip_cow = |query: Box<dyn Query>| -> Option<Box<dyn Query>> {
+            match query.downcast_ref::<TermQuery>() {
+                Some(term_query) if term_query.term() == &cow => None,
+                _ => Some(query),
+            }
+        };
+        let stripped = rewrite_query(query.box_clone(), &mut strip_cow).unwrap();
+        let term_query = |text: &str| -> Box<dyn Query> {
+            let term = Term::from_field_text(title, text);
+            Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))
+        };
+        let expected = BooleanQuery::new(vec![
+            (Occur::Should, term_query("diary")),
+            (
+                Occur::Should,
+                Box::new(BooleanQuery::new(vec![(Occur::Should, term_query("girl"))])),
+            ),
+        ]);
+        assert_eq!(format!("{:?}", stripped), format!("{:?}", expected));
+        assert_eq!(stripped.count(&searcher)?, 2);
+
+        // Removing all of the leaves removes the whole tree.
+        let wrapped: Box<dyn Query> = Box::new(ConstScoreQuery::new(query, 1.0));
+        assert!(rewrite_query(wrapped, &mut |query: Box<dyn Query>| {
+            if query.is::<TermQuery>() {
+                None
+            } else {
+                Some(query)
+            }
+        })
+        .is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+use crate::fieldnorm::FieldNormReader;
+use crate::query::Explanation;
+use crate::schema::{Field, FieldType, Schema, Similarity, TextFieldIndexing};
+use crate::Score;
+use crate::Searcher;
+use crate::Term;
+
+pub(crate) fn idf(doc_freq: u64, doc_count: u64) -> Score {
+    assert!(doc_count >= doc_freq, "{} >= {}", doc_count, doc_freq);
+    let x = ((doc_count - doc_freq) as Score + 0.5) / (doc_freq as Score + 0.5);
+    (1.0 + x).ln()
+}
+
+fn tfidf_idf(doc_freq: u64, doc_count: u64) -> Score {
+    assert!(doc_count >= doc_freq, "{} >= {}", doc_count, doc_freq);
+    1.0 + ((doc_count + 1) as Score / (doc_freq + 1) as Score).ln()
+}
+
+fn cached_tf_component(
+    similarity: Similarity,
+    length_normalization: bool,
+    fieldnorm: u32,
+    average_fieldnorm: Score,
+) -> Score {
+    match similarity {
+        Similarity::BM25 { k1, .. } if !length_normalization => k1,
+        Similarity::BM25 { k1, b } => k1 * (1.0 - b + b * fieldnorm as Score / average_fieldnorm),
+        Similarity::TfIdf if !length_normalization => 1.0,
+        Similarity::TfIdf => 1.0 / (fieldnorm.max(1) as Score).sqrt(),
+    }
+}
+
+fn compute_tf_cache(
+    similarity: Similarity,
+    length_normalization: bool,
+    average_fieldnorm: Score,
+) -> [Score; 256] {
+    let mut cache: [Score; 256] = [0.0; 256];
+    for (fieldnorm_id, cache_mut) in cache.iter_mut().enumerate() {
+        let fieldnorm = FieldNormReader::id_to_fieldnorm(fieldnorm_id as u8);
+        *cache_mut = cached_tf_component(
+            similarity,
+            length_normalization,
+            fieldnorm,
+            average_fieldnorm,
+        );
+    }
+    cache
+}
+
+/// Returns the `Similarity` defined in the schema for the given field.
+pub(crate) fn field_similarity(schema: &Schema, field: Field) -> Similarity {
+    match schema.get_field_entry(field).field_type() {
+        FieldType::Str(text_options) => text_options
+            .get_indexing_options()
+            .map(TextFieldIndexing::similarity)
+            .unwrap_or_default(),
+        _ => Similarity::default(),
+    }
+}
+
+#[derive(Clone)]
+pub struct SimilarityWeight {
+    similarity: Similarity,
+    length_normalization: bool,
+    idf_explain: Explanation,
+    weight: Score,
+    cache: [Score; 256],
+    average_fieldnorm: Score,
+    max_tf_factor: Score,
+}
+
+impl SimilarityWeight {
+    pub fn boost_by(&self, boost: Score) -> SimilarityWeight {
+        SimilarityWeight {
+            similarity: self.similarity,
+            length_normalization: self.length_normalization,
+            idf_explain: self.idf_explain.clone(),
+            weight: self.weight * boost,
+            cache: self.cache,
+            average_fieldnorm: self.average_fieldnorm,
+            max_tf_factor: self.max_tf_factor,
+        }
+    }
+
+    /// Creates the weight of a set of terms of the same field.
+    ///
+    /// Length normalization is disabled if `length_normalization` is false,
+    /// or if the field does not have field norms.
+    pub fn for_terms(
+        searcher: &Searcher,
+        terms: &[Term],
+        similarity: Similarity,
+        length_normalization: bool,
+    ) -> crate::Result<SimilarityWeight> {
+        assert!(!terms.is_empty(), "Scoring requires at least one term");
+        similarity.validate()?;
+        let field = terms[0].field();
+        for term in &terms[1..] {
+            assert_eq!(
+                term.field(),
+                field,
+                "All terms must belong to the same field."
+            );
+        }
+
+        let mut total_num_tokens = 0u64;
+        let mut total_num_docs = 0u64;
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field)?;
+            total_num_tokens += inverted_index.total_num_tokens();
+            total_num_docs += u64::from(segment_reader.max_doc());
+        }
+        let average_fieldnorm = total_num_tokens as Score / total_num_docs as Score;
+        let length_normalization =
+            length_normalization && searcher.schema().get_field_entry(field).has_fieldnorms();
+
+        if terms.len() == 1 {
+            let term_doc_freq = searcher.doc_freq(&terms[0])?;
+            Ok(SimilarityWeight::for_one_term_with_similarity(
+                similarity,
+                length_normalization,
+                term_doc_freq,
+                total_num_docs,
+                average_fieldnorm,
+            ))
+        } else {
+            let mut idf_sum: Score = 0.0;
+            for term in terms {
+                let term_doc_freq = searcher.doc_freq(term)?;
+                idf_sum += match similarity {
+                    Similarity::BM25 { .. } => idf(term_doc_freq, total_num_docs),
+                    Similarity::TfIdf => tfidf_idf(term_doc_freq, total_num_docs),
+                };
+            }
+            let idf_explain = Explanation::new("idf", idf_sum);
+            Ok(SimilarityWeight::new(
+                similarity,
+                length_normalization,
+                idf_explain,
+                average_fieldnorm,
+            ))
+        }
+    }
+
+    /// Creates the weight of a single term, using the default BM25 similarity.
+    pub fn for_one_term(
+        term_doc_freq: u64,
+        total_num_docs: u64,
+        avg_fieldnorm: Score,
+    ) -> SimilarityWeight {
+        SimilarityWeight::for_one_term_with_similarity(
+            Similarity::default(),
+            true,
+            term_doc_freq,
+            total_num_docs,
+            avg_fieldnorm,
+        )
+    }
+
+    fn for_one_term_with_similarity(
+        similarity: Similarity,
+        length_normalization: bool,
+        term_doc_freq: u64,
+        total_num_docs: u64,
+        avg_fieldnorm: Score,
+    ) -> SimilarityWeight {
+        let mut idf_explain = match similarity {
+            Similarity::BM25 { .. } => Explanation::new(
+                "idf, computed as log(1 + (N - n + 0.5) / (n + 0.5))",
+                idf(term_doc_freq, total_num_docs),
+            ),
+            Similarity::TfIdf => Explanation::new(
+                "idf, computed as 1 + log((N + 1) / (n + 1))",
+                tfidf_idf(term_doc_freq, total_num_docs),
+            ),
+        };
+        idf_explain.add_const(
+            "n, number of docs containing this term",
+            term_doc_freq as Score,
+        );
+        idf_explain.add_const("N, total number of docs", total_num_docs as Score);
+        SimilarityWeight::new(similarity, length_normalization, idf_explain, avg_fieldnorm)
+    }
+
+    fn new(
+        similarity: Similarity,
+        length_normalization: bool,
+        idf_explain: Explanation,
+        average_fieldnorm: Score,
+    ) -> SimilarityWeight {
+        let weight = match similarity {
+            Similarity::BM25 { k1, .. } => idf_explain.value() * (1.0 + k1),
+            Similarity::TfIdf => idf_explain.value(),
+        };
+        let mut similarity_weight = SimilarityWeight {
+            similarity,
+            length_normalization,
+            idf_explain,
+            weight,
+            cache: compute_tf_cache(similarity, length_normalization, average_fieldnorm),
+            average_fieldnorm,
+            max_tf_factor: 0.0,
+        };
+        similarity_weight.max_tf_factor = similarity_weight.compute_max_tf_factor();
+        similarity_weight
+    }
+
+    fn compute_max_tf_factor(&self) -> Score {
+        match self.similarity {
+            Similarity::BM25 { .. } => self.tf_factor(255u8, 2_013_265_944),
+            Similarity::TfIdf => {
+                // The TF-IDF term frequency factor is not bounded by itself.
+                // The term frequency is however bounded by the actual length of the field,
+                // which is itself lower than the fieldnorm of the next fieldnorm id.
+                (0..=255u8)
+                    .map(|fieldnorm_id| {
+                        let max_term_freq = if fieldnorm_id == 255u8 {
+                            u32::MAX
+                        } else {
+                            FieldNormReader::id_to_fieldnorm(fieldnorm_id + 1)
+                        };
+                        self.tf_factor(fieldnorm_id, max_term_freq)
+                    })
+                    .fold(0.0, Score::max)
+            }
+        }
+    }
+
+    /// Returns the similarity used to compute the scores.
+    pub fn similarity(&self) -> Similarity {
+        self.similarity
+    }
+
+    /// Returns true iff the block wand information stored in the skip lists
+    /// can be used to compute block max scores.
+    ///
+    /// This information is computed at indexing time with the default BM25 similarity,
+    /// using the field norms.
+    pub(crate) fn has_block_wand_support(&self) -> bool {
+        self.similarity.is_default() && self.length_normalization
+    }
+
+    #[inline(always)]
+    pub fn score(&self, fieldnorm_id: u8, term_freq: u32) -> Score {
+        self.weight * self.tf_factor(fieldnorm_id, term_freq)
+    }
+
+    pub fn max_score(&self) -> Score {
+        self.weight * self.max_tf_factor
+    }
+
+    #[inline(always)]
+    pub(crate) fn tf_factor(&self, fieldnorm_id: u8, term_freq: u32) -> Score {
+        let term_freq = term_freq as Score;
+        let norm = self.cache[fieldnorm_id as usize];
+        match self.similarity {
+            Similarity::BM25 { .. } => term_freq / (term_freq + norm),
+            Similarity::TfIdf => term_freq.sqrt() * norm,
+        }
+    }
+
+    pub fn explain(&self, fieldnorm_id: u8, term_freq: u32) -> Explanation {
+        let score = self.score(fieldnorm_id, term_freq);
+        let tf_factor = self.tf_factor(fieldnorm_id, term_freq);
+        let fieldnorm = FieldNormReader::id_to_fieldnorm(fieldnorm_id) as Score;
+        let term_freq = term_freq as Score;
+        let mut explanation = Explanation::new("TermQuery, product of...", score);
+        match self.similarity {
+            Similarity::BM25 { k1, b } => {
+                let b = if self.length_normalization { b } else { 0.0 };
+                // The explain format is directly copied from Lucene's.
+                // (So, Kudos to Lucene)
+                let mut tf_explanation =
+                    Explanation::new("freq / (freq + k1 * (1 - b + b * dl / avgdl))", tf_factor);
+                tf_explanation.add_const("freq, occurrences of term within document", term_freq);
+                tf_explanation.add_const("k1, term saturation parameter", k1);
+                tf_explanation.add_const("b, length normalization parameter", b);
+                tf_explanation.add_const("dl, length of field", fieldnorm);
+                tf_explanation.add_const("avgdl, average length of field", self.average_fieldnorm);
+                explanation.add_detail(Explanation::new("(K1+1)", k1 + 1.0));
+                explanation.add_detail(self.idf_explain.clone());
+                explanation.add_detail(tf_explanation);
+            }
+            Similarity::TfIdf if !self.length_normalization => {
+                let mut tf_explanation = Explanation::new("sqrt(freq)", tf_factor);
+                tf_explanation.add_const("freq, occurrences of term within document", term_freq);
+                explanation.add_detail(self.idf_explain.clone());
+                explanation.add_detail(tf_explanation);
+            }
+            Similarity::TfIdf => {
+                let mut tf_explanation = Explanation::new("sqrt(freq) / sqrt(dl)", tf_factor);
+                tf_explanation.add_const("freq, occurrences of term within document", term_freq);
+                tf_explanation.add_const("dl, length of field", fieldnorm);
+                explanation.add_detail(self.idf_explain.clone());
+                explanation.add_detail(tf_explanation);
+            }
+        }
+        explanation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::idf;
+    use crate::{assert_nearly_equals, Score};
+
+    #[test]
+    fn test_idf() {
+        let score: Score = 2.0;
+        assert_nearly_equals!(idf(1, 2), score.ln());
+    }
+}
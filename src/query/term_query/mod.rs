@@ -13,7 +13,9 @@ mod tests {
     use crate::docset::DocSet;
     use crate::postings::compression::COMPRESSION_BLOCK_SIZE;
     use crate::query::{Query, QueryParser, Scorer, TermQuery};
-    use crate::schema::{Field, IndexRecordOption, Schema, STRING, TEXT};
+    use crate::schema::{
+        Field, IndexRecordOption, Schema, Similarity, TextFieldIndexing, TextOptions, STRING, TEXT,
+    };
     use crate::{assert_nearly_equals, DocAddress};
     use crate::{Index, Term, TERMINATED};
 
@@ -215,4 +217,154 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_term_query_similarity() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let indexing = TextFieldIndexing::default().set_index_option(IndexRecordOption::WithFreqs);
+        let title = schema_builder.add_text_field(
+            "title",
+            TextOptions::default().set_indexing_options(
+                indexing
+                    .clone()
+                    .set_similarity(Similarity::bm25(2.0, 0.0).unwrap()),
+            ),
+        );
+        let body = schema_builder.add_text_field(
+            "body",
+            TextOptions::default().set_indexing_options(indexing.set_similarity(Similarity::TfIdf)),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "a", body => "a"));
+        index_writer.add_document(doc!(title => "a a b c", body => "a a b c"));
+        index_writer.add_document(doc!(title => "b", body => "b"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let score = |query: &TermQuery, doc: u32| -> crate::Result<f32> {
+            Ok(query.explain(&searcher, DocAddress(0u32, doc))?.value())
+        };
+
+        let bm25_idf = (1.0f32 + 1.5 / 2.5).ln();
+        let title_query = TermQuery::new(
+            Term::from_field_text(title, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        assert_nearly_equals!(score(&title_query, 0)?, bm25_idf);
+        assert_nearly_equals!(score(&title_query, 1)?, bm25_idf * 1.5);
+        let explanation = title_query.explain(&searcher, DocAddress(0u32, 1u32))?;
+        let explanation_json = explanation.to_pretty_json();
+        assert!(explanation_json.contains(
+            r#""value": 2.0,
+          "description": "k1, term saturation parameter""#
+        ));
+        assert!(explanation_json.contains(
+            r#""value": 0.0,
+          "description": "b, length normalization parameter""#
+        ));
+
+        let tfidf_idf = 1.0f32 + (4.0f32 / 3.0).ln();
+        let body_query = TermQuery::new(
+            Term::from_field_text(body, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        assert_nearly_equals!(score(&body_query, 0)?, tfidf_idf);
+        assert_nearly_equals!(score(&body_query, 1)?, tfidf_idf * 0.5f32.sqrt());
+
+        // The similarity of the schema can be overridden at query time.
+        let title_query = title_query.with_similarity(Similarity::TfIdf);
+        assert_nearly_equals!(score(&title_query, 1)?, tfidf_idf * 0.5f32.sqrt());
+        let top_docs = searcher.search(&title_query, &TopDocs::with_limit(1))?;
+        assert_eq!(top_docs[0].1, DocAddress(0u32, 0u32));
+
+        // Invalid parameters are rejected, even if the variant is built directly.
+        let invalid_query = title_query.with_similarity(Similarity::BM25 { k1: -1.0, b: 0.5 });
+        assert!(matches!(
+            searcher.search(&invalid_query, &TopDocs::with_limit(1)),
+            Err(crate::TantivyError::InvalidArgument(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_term_query_tfidf_block_max() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        for i in 0..(COMPRESSION_BLOCK_SIZE * 3) {
+            let mut text = "a ".repeat(1 + i % 7);
+            text.push_str(&"b ".repeat(i % 13));
+            index_writer.add_document(doc!(text_field => text));
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let term_query = TermQuery::new(
+            Term::from_field_text(text_field, "a"),
+            IndexRecordOption::WithFreqs,
+        )
+        .with_similarity(Similarity::TfIdf);
+        let weight = term_query.specialized_weight(&searcher, true)?;
+        let mut scorer = weight.specialized_scorer(searcher.segment_reader(0u32), 1.0)?;
+        let mut scores = Vec::new();
+        while scorer.doc() != TERMINATED {
+            scores.push(scorer.score());
+            scorer.advance();
+        }
+        scores.sort_by(|left, right| right.partial_cmp(left).unwrap());
+        let top_docs = searcher.search(&term_query, &TopDocs::with_limit(10))?;
+        for ((score, _), expected_score) in top_docs.iter().zip(scores.iter()) {
+            assert_nearly_equals!(*score, *expected_score);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_term_query_length_normalization() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let no_norms = schema_builder.add_text_field(
+            "no_norms",
+            TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_index_option(IndexRecordOption::WithFreqs)
+                    .set_fieldnorms(false),
+            ),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "a", no_norms => "a"));
+        index_writer.add_document(doc!(text => "a b c d", no_norms => "a b c d"));
+        index_writer.add_document(doc!(text => "b", no_norms => "b"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let fieldnorm_reader = segment_reader.get_fieldnorms_reader(no_norms)?;
+        assert_eq!(fieldnorm_reader.fieldnorm(1), 1);
+        assert_eq!(segment_reader.get_fieldnorms_reader(text)?.fieldnorm(1), 4);
+        let score = |query: &TermQuery, doc: u32| -> crate::Result<f32> {
+            Ok(query.explain(&searcher, DocAddress(0u32, doc))?.value())
+        };
+
+        let no_norms_query = TermQuery::new(
+            Term::from_field_text(no_norms, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        assert_nearly_equals!(score(&no_norms_query, 0)?, score(&no_norms_query, 1)?);
+
+        let text_query = TermQuery::new(
+            Term::from_field_text(text, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        assert!(score(&text_query, 0)? > score(&text_query, 1)?);
+        let text_query = text_query.with_length_normalization(false);
+        assert_nearly_equals!(score(&text_query, 0)?, score(&text_query, 1)?);
+        assert_nearly_equals!(score(&text_query, 1)?, score(&no_norms_query, 1)?);
+        let explanation = text_query.explain(&searcher, DocAddress(0u32, 1u32))?;
+        assert!(explanation.to_pretty_json().contains(
+            r#""value": 0.0,
+          "description": "b, length normalization parameter""#
+        ));
+        Ok(())
+    }
 }
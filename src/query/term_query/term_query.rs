@@ -1,8 +1,8 @@
 use super::term_weight::TermWeight;
-use crate::query::bm25::BM25Weight;
+use crate::query::similarity::{field_similarity, SimilarityWeight};
 use crate::query::Query;
 use crate::query::Weight;
-use crate::schema::IndexRecordOption;
+use crate::schema::{IndexRecordOption, Similarity};
 use crate::Searcher;
 use crate::Term;
 use std::collections::BTreeSet;
@@ -11,12 +11,9 @@ use std::fmt;
 /// A Term query matches all of the documents
 /// containing a specific term.
 ///
-/// The score associated is defined as
-/// `idf` *  sqrt(`term_freq` / `field norm`)
-/// in which :
-/// * `idf`        - inverse document frequency.
-/// * `term_freq`  - number of occurrences of the term in the field
-/// * `field norm` - number of tokens in the field.
+/// The score associated is computed by the [`Similarity`](../schema/enum.Similarity.html)
+/// defined in the schema for the field of the term (BM25 by default),
+/// unless it is overridden with `.with_similarity(...)`.
 ///
 /// ```rust
 /// use tantivy::collector::{Count, TopDocs};
@@ -60,6 +57,8 @@ use std::fmt;
 pub struct TermQuery {
     term: Term,
     index_record_option: IndexRecordOption,
+    similarity: Option<Similarity>,
+    length_normalization: bool,
 }
 
 impl fmt::Debug for TermQuery {
@@ -74,9 +73,27 @@ impl TermQuery {
         TermQuery {
             term,
             index_record_option: segment_postings_options,
+            similarity: None,
+            length_normalization: true,
         }
     }
 
+    /// Overrides the similarity defined in the schema for the field of the term.
+    pub fn with_similarity(mut self, similarity: Similarity) -> TermQuery {
+        self.similarity = Some(similarity);
+        self
+    }
+
+    /// Enables or disables length normalization when scoring the documents.
+    ///
+    /// When disabled, the length of the field (its field norm) is ignored,
+    /// as if the field norms of the field had been omitted in the schema.
+    /// Length normalization is enabled by default.
+    pub fn with_length_normalization(mut self, length_normalization: bool) -> TermQuery {
+        self.length_normalization = length_normalization;
+        self
+    }
+
     /// The `Term` this query is built out of.
     pub fn term(&self) -> &Term {
         &self.term
@@ -100,7 +117,11 @@ impl TermQuery {
                 field_entry.name()
             )));
         }
-        let bm25_weight = BM25Weight::for_terms(searcher, &[term])?;
+        let similarity = self
+            .similarity
+            .unwrap_or_else(|| field_similarity(searcher.schema(), term.field()));
+        let similarity_weight =
+            SimilarityWeight::for_terms(searcher, &[term], similarity, self.length_normalization)?;
         let index_record_option = if scoring_enabled {
             self.index_record_option
         } else {
@@ -109,7 +130,7 @@ impl TermQuery {
         Ok(TermWeight::new(
             self.term.clone(),
             index_record_option,
-            bm25_weight,
+            similarity_weight,
             scoring_enabled,
         ))
     }
@@ -0,0 +1,204 @@
+use crate::error::TantivyError;
+use crate::query::{AutomatonWeight, Query, Weight};
+use crate::schema::Field;
+use crate::Searcher;
+use std::sync::Arc;
+use tantivy_fst::Regex;
+
+/// Default maximum number of terms a `WildcardQuery` may match within a segment.
+const DEFAULT_MAX_EXPANSIONS: usize = 10_000;
+
+/// A Wildcard Query matches all of the documents
+/// containing a term that matches a wildcard pattern.
+///
+/// In the pattern, `*` matches any sequence of characters (including the empty one),
+/// and `?` matches exactly one character. All other characters are matched literally.
+///
+/// The pattern is not analyzed: it is matched against the terms
+/// as they were emitted by the tokenizer of the field.
+///
+/// Patterns starting with a wildcard require to scan the entire term dictionary,
+/// and are therefore rejected unless explicitly allowed with
+/// [`from_pattern_with_leading_wildcard`](#method.from_pattern_with_leading_wildcard).
+///
+/// To avoid very expensive queries, the query fails if it matches more than
+/// `max_expansions` terms in a segment.
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::query::WildcardQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+/// {
+///     let mut index_writer = index.writer(3_000_000)?;
+///     index_writer.add_document(doc!(
+///         title => "The Name of the Wind",
+///     ));
+///     index_writer.add_document(doc!(
+///         title => "The Diary of Muadib",
+///     ));
+///     index_writer.add_document(doc!(
+///         title => "A Dairy Cow",
+///     ));
+///     index_writer.add_document(doc!(
+///         title => "The Diary of a Young Girl",
+///     ));
+///     index_writer.commit().unwrap();
+/// }
+///
+/// let reader = index.reader()?;
+/// let searcher = reader.searcher();
+///
+/// let query = WildcardQuery::from_pattern("d??ry", title)?;
+/// let count = searcher.search(&query, &Count)?;
+/// assert_eq!(count, 3);
+///
+/// let query = WildcardQuery::from_pattern("w*", title)?;
+/// let count = searcher.search(&query, &Count)?;
+/// assert_eq!(count, 1);
+/// Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WildcardQuery {
+    field: Field,
+    pattern: String,
+    automaton: Arc<Regex>,
+    max_expansions: usize,
+}
+
+/// Returns true iff the wildcard pattern starts with `*` or `?`.
+pub(crate) fn has_leading_wildcard(pattern: &str) -> bool {
+    pattern.starts_with(&['*', '?'][..])
+}
+
+/// Translates a wildcard pattern into the equivalent regular expression.
+fn wildcard_to_regex(pattern: &str) -> String {
+    let mut regex_pattern = String::with_capacity(pattern.len());
+    let mut buffer = [0u8; 4];
+    for c in pattern.chars() {
+        match c {
+            '*' => regex_pattern.push_str(".*"),
+            '?' => regex_pattern.push('.'),
+            _ => regex_pattern.push_str(&regex::escape(c.encode_utf8(&mut buffer))),
+        }
+    }
+    regex_pattern
+}
+
+impl WildcardQuery {
+    /// Creates a new `WildcardQuery` from a given pattern.
+    ///
+    /// Returns an error if the pattern starts with a wildcard.
+    pub fn from_pattern(pattern: &str, field: Field) -> crate::Result<WildcardQuery> {
+        if has_leading_wildcard(pattern) {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Wildcard pattern {:?} starts with a wildcard",
+                pattern
+            )));
+        }
+        WildcardQuery::from_pattern_with_leading_wildcard(pattern, field)
+    }
+
+    /// Creates a new `WildcardQuery` from a given pattern, which may start with a wildcard.
+    pub fn from_pattern_with_leading_wildcard(
+        pattern: &str,
+        field: Field,
+    ) -> crate::Result<WildcardQuery> {
+        let automaton = Regex::new(&wildcard_to_regex(pattern))
+            .map_err(|_| TantivyError::InvalidArgument(pattern.to_string()))?;
+        Ok(WildcardQuery {
+            field,
+            pattern: pattern.to_string(),
+            automaton: Arc::new(automaton),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+        })
+    }
+
+    /// Sets the maximum number of terms the query may match within a segment.
+    ///
+    /// Defaults to 10,000.
+    pub fn with_max_expansions(mut self, max_expansions: usize) -> WildcardQuery {
+        self.max_expansions = max_expansions;
+        self
+    }
+
+    /// Field to search over
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Wildcard pattern of the query
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    fn specialized_weight(&self) -> AutomatonWeight<Regex> {
+        AutomatonWeight::new(self.field, self.automaton.clone())
+            .with_max_expansions(self.max_expansions)
+    }
+}
+
+impl Query for WildcardQuery {
+    fn weight(
+        &self,
+        _searcher: &Searcher,
+        _scoring_enabled: bool,
+    ) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(self.specialized_weight()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{wildcard_to_regex, WildcardQuery};
+    use crate::collector::Count;
+    use crate::schema::{Schema, STRING};
+    use crate::Index;
+
+    #[test]
+    fn test_wildcard_to_regex() {
+        assert_eq!(wildcard_to_regex("abc"), "abc");
+        assert_eq!(wildcard_to_regex("a*b?c"), "a.*b.c");
+        assert_eq!(wildcard_to_regex("a.b+(c)"), "a\\.b\\+\\(c\\)");
+        assert_eq!(wildcard_to_regex("é*"), "é.*");
+    }
+
+    #[test]
+    fn test_wildcard_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let sku = schema_builder.add_text_field("sku", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(sku => "ab.12"));
+        index_writer.add_document(doc!(sku => "ab.123"));
+        index_writer.add_document(doc!(sku => "abx12"));
+        index_writer.add_document(doc!(sku => "béa"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let count = |query: WildcardQuery| searcher.search(&query, &Count).unwrap();
+        assert_eq!(count(WildcardQuery::from_pattern("ab.*", sku)?), 2);
+        assert_eq!(count(WildcardQuery::from_pattern("ab?12", sku)?), 2);
+        assert_eq!(count(WildcardQuery::from_pattern("ab.12?", sku)?), 1);
+        assert_eq!(count(WildcardQuery::from_pattern("a*", sku)?), 3);
+        assert_eq!(count(WildcardQuery::from_pattern("b?a", sku)?), 1);
+        assert_eq!(count(WildcardQuery::from_pattern("ab.12", sku)?), 1);
+        assert_eq!(count(WildcardQuery::from_pattern("c*", sku)?), 0);
+
+        assert!(WildcardQuery::from_pattern("*12", sku).is_err());
+        assert!(WildcardQuery::from_pattern("?b", sku).is_err());
+        let query = WildcardQuery::from_pattern_with_leading_wildcard("*12", sku)?;
+        assert_eq!(count(query), 2);
+
+        let query = WildcardQuery::from_pattern("a*", sku)?.with_max_expansions(2);
+        assert!(searcher.search(&query, &Count).is_err());
+        Ok(())
+    }
+}
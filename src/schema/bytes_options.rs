@@ -1,13 +1,65 @@
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 use std::ops::BitOr;
 
 use super::flags::{FastFlag, IndexedFlag, SchemaFlagList, StoredFlag};
+use super::Cardinality;
 /// Define how an a bytes field should be handled by tantivy.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "BytesOptionsDef", try_from = "BytesOptionsDef")]
 pub struct BytesOptions {
+    indexed: bool,
+    fast: Option<Cardinality>,
+    stored: bool,
+}
+
+/// Serialized representation of `BytesOptions`.
+///
+/// The cardinality of the fast field is serialized as a `multivalued` flag,
+/// only present for multivalued fields, so that single-valued bytes fields
+/// keep the format of the previous versions.
+#[derive(Serialize, Deserialize)]
+struct BytesOptionsDef {
     indexed: bool,
     fast: bool,
     stored: bool,
+    #[serde(default, skip_serializing_if = "is_single_valued")]
+    multivalued: bool,
+}
+
+fn is_single_valued(multivalued: &bool) -> bool {
+    !*multivalued
+}
+
+impl From<BytesOptions> for BytesOptionsDef {
+    fn from(options: BytesOptions) -> BytesOptionsDef {
+        BytesOptionsDef {
+            indexed: options.indexed,
+            fast: options.fast.is_some(),
+            stored: options.stored,
+            multivalued: options.fast == Some(Cardinality::MultiValues),
+        }
+    }
+}
+
+impl TryFrom<BytesOptionsDef> for BytesOptions {
+    type Error = String;
+
+    fn try_from(options_def: BytesOptionsDef) -> Result<BytesOptions, String> {
+        let fast = match (options_def.fast, options_def.multivalued) {
+            (false, false) => None,
+            (false, true) => {
+                return Err("a multivalued bytes field must be a fast field".to_string());
+            }
+            (true, false) => Some(Cardinality::SingleValue),
+            (true, true) => Some(Cardinality::MultiValues),
+        };
+        Ok(BytesOptions {
+            indexed: options_def.indexed,
+            fast,
+            stored: options_def.stored,
+        })
+    }
 }
 
 impl BytesOptions {
@@ -18,7 +70,7 @@ impl BytesOptions {
 
     /// Returns true iff the value is a fast field.
     pub fn is_fast(&self) -> bool {
-        self.fast
+        self.fast.is_some()
     }
 
     /// Returns true iff the value is stored.
@@ -42,10 +94,27 @@ impl BytesOptions {
     /// If more than one value is associated to a fast field, only the last one is
     /// kept.
     pub fn set_fast(mut self) -> BytesOptions {
-        self.fast = true;
+        self.fast = Some(Cardinality::SingleValue);
+        self
+    }
+
+    /// Set the field as a fast field, with the given cardinality.
+    ///
+    /// A multivalued bytes fast field keeps all of the byte arrays
+    /// associated to a document, in the order in which they were added.
+    pub fn set_fast_with_cardinality(mut self, cardinality: Cardinality) -> BytesOptions {
+        self.fast = Some(cardinality);
         self
     }
 
+    /// Returns the cardinality of the fastfield.
+    ///
+    /// If the field has not been declared as a fastfield, then
+    /// the method returns None.
+    pub fn get_fastfield_cardinality(&self) -> Option<Cardinality> {
+        self.fast
+    }
+
     /// Set the field as stored.
     ///
     /// Only the fields that are set as *stored* are
@@ -60,7 +129,7 @@ impl Default for BytesOptions {
     fn default() -> BytesOptions {
         BytesOptions {
             indexed: false,
-            fast: false,
+            fast: None,
             stored: false,
         }
     }
@@ -74,7 +143,7 @@ impl<T: Into<BytesOptions>> BitOr<T> for BytesOptions {
         BytesOptions {
             indexed: self.indexed | other.indexed,
             stored: self.stored | other.stored,
-            fast: self.fast | other.fast,
+            fast: self.fast.or(other.fast),
         }
     }
 }
@@ -90,7 +159,7 @@ impl From<FastFlag> for BytesOptions {
         BytesOptions {
             indexed: false,
             stored: false,
-            fast: true,
+            fast: Some(Cardinality::SingleValue),
         }
     }
 }
@@ -100,7 +169,7 @@ impl From<StoredFlag> for BytesOptions {
         BytesOptions {
             indexed: false,
             stored: true,
-            fast: false,
+            fast: None,
         }
     }
 }
@@ -110,7 +179,7 @@ impl From<IndexedFlag> for BytesOptions {
         BytesOptions {
             indexed: true,
             stored: false,
-            fast: false,
+            fast: None,
         }
     }
 }
@@ -128,7 +197,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::schema::{BytesOptions, FAST, INDEXED, STORED};
+    use crate::schema::{BytesOptions, Cardinality, FAST, INDEXED, STORED};
 
     #[test]
     fn test_bytes_option_fast_flag() {
@@ -161,4 +230,40 @@ mod tests {
         assert!(BytesOptions::default().set_fast().is_fast());
         assert!(BytesOptions::default().set_indexed().is_indexed());
     }
+
+    #[test]
+    fn test_bytes_option_cardinality() {
+        assert_eq!(BytesOptions::default().get_fastfield_cardinality(), None);
+        assert_eq!(
+            BytesOptions::from(FAST).get_fastfield_cardinality(),
+            Some(Cardinality::SingleValue)
+        );
+        let options = BytesOptions::default().set_fast_with_cardinality(Cardinality::MultiValues);
+        assert!(options.is_fast());
+        assert_eq!(
+            options.get_fastfield_cardinality(),
+            Some(Cardinality::MultiValues)
+        );
+        let json = serde_json::to_string(&options).unwrap();
+        assert_eq!(
+            json,
+            r#"{"indexed":false,"fast":true,"stored":false,"multivalued":true}"#
+        );
+        let deserialized: BytesOptions = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, options);
+        let legacy: BytesOptions =
+            serde_json::from_str(r#"{"indexed":false,"fast":true,"stored":false}"#).unwrap();
+        assert_eq!(
+            legacy.get_fastfield_cardinality(),
+            Some(Cardinality::SingleValue)
+        );
+        assert!(serde_json::from_str::<BytesOptions>(
+            r#"{"indexed":false,"fast":false,"stored":false,"multivalued":true}"#
+        )
+        .is_err());
+        assert_eq!(
+            options.set_fast().get_fastfield_cardinality(),
+            Some(Cardinality::SingleValue)
+        );
+    }
 }
@@ -0,0 +1,189 @@
+use crate::schema::flags::{FastFlag, IndexedFlag, SchemaFlagList, StoredFlag};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::ops::BitOr;
+
+/// Define how a facet field should be handled by tantivy.
+///
+/// - An indexed facet field can be searched, with a `TermQuery` on a facet for instance.
+/// - A fast facet field records the facets of each document in a fast field,
+///   so that they can be counted with a `FacetCollector`.
+///   The facets of a fast field are also recorded in the term dictionary of the field,
+///   as the fast field refers to their ordinals.
+/// - A stored facet field is persisted into the doc store.
+///
+/// A facet field must be at least indexed, fast or stored.
+/// `FacetOptions::all()` defines a facet field which is indexed, fast and stored,
+/// which is what `SchemaBuilder::add_facet_field` creates.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "FacetOptionsDef")]
+pub struct FacetOptions {
+    indexed: bool,
+    stored: bool,
+    fast: bool,
+}
+
+/// Serialized representation of `FacetOptions`,
+/// validated when converted into `FacetOptions`.
+#[derive(Deserialize)]
+struct FacetOptionsDef {
+    indexed: bool,
+    stored: bool,
+    // The fast field used to be tied to the indexing of the facets.
+    fast: Option<bool>,
+}
+
+impl TryFrom<FacetOptionsDef> for FacetOptions {
+    type Error = String;
+
+    fn try_from(options_def: FacetOptionsDef) -> Result<FacetOptions, String> {
+        let options = FacetOptions {
+            indexed: options_def.indexed,
+            stored: options_def.stored,
+            fast: options_def.fast.unwrap_or(options_def.indexed),
+        };
+        if !options.is_valid() {
+            return Err("a facet field must be indexed, fast or stored".to_string());
+        }
+        Ok(options)
+    }
+}
+
+impl FacetOptions {
+    /// Facet options for a facet field which is indexed, fast and stored.
+    pub fn all() -> FacetOptions {
+        FacetOptions {
+            indexed: true,
+            stored: true,
+            fast: true,
+        }
+    }
+
+    /// Returns true iff the facets are indexed.
+    pub fn is_indexed(&self) -> bool {
+        self.indexed
+    }
+
+    /// Returns true iff the facets are stored.
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    /// Returns true iff the facets are recorded in a fast field.
+    pub fn is_fast(&self) -> bool {
+        self.fast
+    }
+
+    /// Returns true iff the facets are either indexed, fast or stored.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.indexed || self.fast || self.stored
+    }
+
+    /// Set the field as indexed.
+    ///
+    /// Indexed facets can be searched.
+    pub fn set_indexed(mut self) -> FacetOptions {
+        self.indexed = true;
+        self
+    }
+
+    /// Set the field as stored.
+    ///
+    /// Only the fields that are set as *stored* are
+    /// persisted into the Tantivy's store.
+    pub fn set_stored(mut self) -> FacetOptions {
+        self.stored = true;
+        self
+    }
+
+    /// Set the field as fast.
+    ///
+    /// Fast facets are accessible to the `FacetCollector`.
+    pub fn set_fast(mut self) -> FacetOptions {
+        self.fast = true;
+        self
+    }
+}
+
+impl<T: Into<FacetOptions>> BitOr<T> for FacetOptions {
+    type Output = FacetOptions;
+
+    fn bitor(self, other: T) -> FacetOptions {
+        let other = other.into();
+        FacetOptions {
+            indexed: self.indexed | other.indexed,
+            stored: self.stored | other.stored,
+            fast: self.fast | other.fast,
+        }
+    }
+}
+
+impl From<()> for FacetOptions {
+    fn from(_: ()) -> Self {
+        Self::default()
+    }
+}
+
+impl From<StoredFlag> for FacetOptions {
+    fn from(_: StoredFlag) -> Self {
+        FacetOptions::default().set_stored()
+    }
+}
+
+impl From<IndexedFlag> for FacetOptions {
+    fn from(_: IndexedFlag) -> Self {
+        FacetOptions::default().set_indexed()
+    }
+}
+
+impl From<FastFlag> for FacetOptions {
+    fn from(_: FastFlag) -> Self {
+        FacetOptions::default().set_fast()
+    }
+}
+
+impl<Head, Tail> From<SchemaFlagList<Head, Tail>> for FacetOptions
+where
+    Head: Clone,
+    Tail: Clone,
+    Self: BitOr<Output = Self> + From<Head> + From<Tail>,
+{
+    fn from(head_tail: SchemaFlagList<Head, Tail>) -> Self {
+        Self::from(head_tail.head) | Self::from(head_tail.tail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{FacetOptions, FAST, INDEXED, STORED};
+
+    #[test]
+    fn test_facet_options_flags() {
+        assert_eq!(FacetOptions::default().set_indexed(), INDEXED.into());
+        assert_eq!(FacetOptions::default().set_stored(), STORED.into());
+        assert_eq!(FacetOptions::default().set_fast(), FAST.into());
+        assert_eq!(
+            FacetOptions::default().set_stored().set_indexed(),
+            (STORED | INDEXED).into()
+        );
+        assert_eq!(FacetOptions::all(), (STORED | INDEXED | FAST).into());
+        assert!(!FacetOptions::default().is_indexed());
+        assert!(!FacetOptions::default().is_stored());
+        assert!(!FacetOptions::default().is_fast());
+    }
+
+    #[test]
+    fn test_facet_options_deserialization() {
+        let options: FacetOptions =
+            serde_json::from_str(r#"{"indexed":false,"stored":true,"fast":true}"#).unwrap();
+        assert_eq!(options, FacetOptions::from(STORED | FAST));
+        // Options serialized before the fast flag existed.
+        let options: FacetOptions =
+            serde_json::from_str(r#"{"indexed":true,"stored":false}"#).unwrap();
+        assert_eq!(options, FacetOptions::from(INDEXED | FAST));
+        assert!(serde_json::from_str::<FacetOptions>(
+            r#"{"indexed":false,"stored":false,"fast":false}"#
+        )
+        .is_err());
+    }
+}
@@ -1,7 +1,8 @@
-use crate::schema::TextOptions;
 use crate::schema::{is_valid_field_name, IntOptions};
+use crate::schema::{TextFieldIndexing, TextOptions};
 
 use crate::schema::bytes_options::BytesOptions;
+use crate::schema::FacetOptions;
 use crate::schema::FieldType;
 use serde::de::{self, MapAccess, Visitor};
 use serde::ser::SerializeStruct;
@@ -73,11 +74,20 @@ impl FieldEntry {
     }
 
     /// Creates a field entry for a facet.
-    pub fn new_facet(field_name: String) -> FieldEntry {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the facet field is neither indexed, fast nor stored.
+    pub fn new_facet(field_name: String, field_type: FacetOptions) -> FieldEntry {
         assert!(is_valid_field_name(&field_name));
+        assert!(
+            field_type.is_valid(),
+            "The facet field {:?} must be indexed, fast or stored.",
+            field_name
+        );
         FieldEntry {
             name: field_name,
-            field_type: FieldType::HierarchicalFacet,
+            field_type: FieldType::HierarchicalFacet(field_type),
         }
     }
 
@@ -107,11 +117,38 @@ impl FieldEntry {
             | FieldType::I64(ref options)
             | FieldType::F64(ref options)
             | FieldType::Date(ref options) => options.is_indexed(),
-            FieldType::HierarchicalFacet => true,
+            FieldType::HierarchicalFacet(ref options) => options.is_indexed(),
             FieldType::Bytes(ref options) => options.is_indexed(),
         }
     }
 
+    /// Returns true iff the field norms of the field are recorded.
+    ///
+    /// Field norms are recorded for all of the indexed fields,
+    /// unless they were disabled in the `TextFieldIndexing` of a text field.
+    pub fn has_fieldnorms(&self) -> bool {
+        match self.field_type {
+            FieldType::Str(ref options) => options
+                .get_indexing_options()
+                .map(TextFieldIndexing::fieldnorms)
+                .unwrap_or(false),
+            _ => self.is_indexed(),
+        }
+    }
+
+    /// Returns true iff the term vectors of the field are recorded.
+    ///
+    /// Only text fields can have term vectors.
+    pub fn has_term_vectors(&self) -> bool {
+        match self.field_type {
+            FieldType::Str(ref options) => options
+                .get_indexing_options()
+                .map(TextFieldIndexing::term_vectors)
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
     /// Returns true iff the field is a int (signed or unsigned) fast field
     pub fn is_fast(&self) -> bool {
         match self.field_type {
@@ -131,8 +168,7 @@ impl FieldEntry {
             | FieldType::F64(ref options)
             | FieldType::Date(ref options) => options.is_stored(),
             FieldType::Str(ref options) => options.is_stored(),
-            // TODO make stored hierarchical facet optional
-            FieldType::HierarchicalFacet => true,
+            FieldType::HierarchicalFacet(ref options) => options.is_stored(),
             FieldType::Bytes(ref options) => options.is_stored(),
         }
     }
@@ -167,8 +203,9 @@ impl Serialize for FieldEntry {
                 s.serialize_field("type", "date")?;
                 s.serialize_field("options", options)?;
             }
-            FieldType::HierarchicalFacet => {
+            FieldType::HierarchicalFacet(ref options) => {
                 s.serialize_field("type", "hierarchical_facet")?;
+                s.serialize_field("options", options)?;
             }
             FieldType::Bytes(ref options) => {
                 s.serialize_field("type", "bytes")?;
@@ -226,7 +263,10 @@ impl<'de> Deserialize<'de> for FieldEntry {
                             let type_string = map.next_value::<String>()?;
                             match type_string.as_str() {
                                 "hierarchical_facet" => {
-                                    field_type = Some(FieldType::HierarchicalFacet);
+                                    // Facets used to be defined without options, and were
+                                    // always indexed, fast and stored.
+                                    field_type =
+                                        Some(FieldType::HierarchicalFacet(FacetOptions::all()));
                                 }
                                 "text" | "u64" | "i64" | "f64" | "date" | "bytes" => {
                                     // These types require additional options to create a field_type
@@ -248,6 +288,10 @@ impl<'de> Deserialize<'de> for FieldEntry {
                                 "f64" => field_type = Some(FieldType::F64(map.next_value()?)),
                                 "date" => field_type = Some(FieldType::Date(map.next_value()?)),
                                 "bytes" => field_type = Some(FieldType::Bytes(map.next_value()?)),
+                                "hierarchical_facet" => {
+                                    field_type =
+                                        Some(FieldType::HierarchicalFacet(map.next_value()?))
+                                }
                                 _ => {
                                     let msg = format!("Unrecognised type {}", ty);
                                     return Err(de::Error::custom(msg));
@@ -272,7 +316,7 @@ impl<'de> Deserialize<'de> for FieldEntry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schema::TEXT;
+    use crate::schema::{INDEXED, TEXT};
     use serde_json;
 
     #[test]
@@ -309,4 +353,29 @@ mod tests {
             _ => panic!("expected FieldType::Str"),
         }
     }
+
+    #[test]
+    fn test_facet_json_serialization() {
+        let field_value = FieldEntry::new_facet(String::from("category"), INDEXED.into());
+        let expected = r#"{
+  "name": "category",
+  "type": "hierarchical_facet",
+  "options": {
+    "indexed": true,
+    "stored": false,
+    "fast": false
+  }
+}"#;
+        let field_value_json = serde_json::to_string_pretty(&field_value).unwrap();
+        assert_eq!(expected, &field_value_json);
+        let field_value: FieldEntry = serde_json::from_str(expected).unwrap();
+        assert!(field_value.is_indexed());
+        assert!(!field_value.is_stored());
+
+        // Facet fields serialized without options are indexed and stored.
+        let legacy = r#"{"name": "category", "type": "hierarchical_facet"}"#;
+        let field_value: FieldEntry = serde_json::from_str(legacy).unwrap();
+        assert!(field_value.is_indexed());
+        assert!(field_value.is_stored());
+    }
 }
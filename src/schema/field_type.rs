@@ -1,5 +1,6 @@
 use crate::schema::bytes_options::BytesOptions;
 use crate::schema::Facet;
+use crate::schema::FacetOptions;
 use crate::schema::IndexRecordOption;
 use crate::schema::TextFieldIndexing;
 use crate::schema::Value;
@@ -60,7 +61,7 @@ pub enum FieldType {
     /// Signed 64-bits Date 64 field type configuration,
     Date(IntOptions),
     /// Hierachical Facet
-    HierarchicalFacet,
+    HierarchicalFacet(FacetOptions),
     /// Bytes (one per document)
     Bytes(BytesOptions),
 }
@@ -74,7 +75,7 @@ impl FieldType {
             FieldType::I64(_) => Type::I64,
             FieldType::F64(_) => Type::F64,
             FieldType::Date(_) => Type::Date,
-            FieldType::HierarchicalFacet => Type::HierarchicalFacet,
+            FieldType::HierarchicalFacet(_) => Type::HierarchicalFacet,
             FieldType::Bytes(_) => Type::Bytes,
         }
     }
@@ -87,7 +88,7 @@ impl FieldType {
             | FieldType::I64(ref int_options)
             | FieldType::F64(ref int_options) => int_options.is_indexed(),
             FieldType::Date(ref date_options) => date_options.is_indexed(),
-            FieldType::HierarchicalFacet => true,
+            FieldType::HierarchicalFacet(ref facet_options) => facet_options.is_indexed(),
             FieldType::Bytes(ref bytes_options) => bytes_options.is_indexed(),
         }
     }
@@ -95,7 +96,7 @@ impl FieldType {
     /// Given a field configuration, return the maximal possible
     /// `IndexRecordOption` available.
     ///
-    /// If the field is not indexed, then returns `None`.
+    /// If the field is neither indexed nor a fast facet field, then returns `None`.
     pub fn get_index_record_option(&self) -> Option<IndexRecordOption> {
         match *self {
             FieldType::Str(ref text_options) => text_options
@@ -111,7 +112,15 @@ impl FieldType {
                     None
                 }
             }
-            FieldType::HierarchicalFacet => Some(IndexRecordOption::Basic),
+            FieldType::HierarchicalFacet(ref facet_options) => {
+                // The fast field of a facet field refers to the ordinals
+                // of its term dictionary, which is written with the postings.
+                if facet_options.is_indexed() || facet_options.is_fast() {
+                    Some(IndexRecordOption::Basic)
+                } else {
+                    None
+                }
+            }
             FieldType::Bytes(ref bytes_options) => {
                 if bytes_options.is_indexed() {
                     Some(IndexRecordOption::Basic)
@@ -144,7 +153,7 @@ impl FieldType {
                 FieldType::U64(_) | FieldType::I64(_) | FieldType::F64(_) => Err(
                     ValueParsingError::TypeError(format!("Expected an integer, got {:?}", json)),
                 ),
-                FieldType::HierarchicalFacet => Ok(Value::Facet(Facet::from(field_text))),
+                FieldType::HierarchicalFacet(_) => Ok(Value::Facet(Facet::from(field_text))),
                 FieldType::Bytes(_) => base64::decode(field_text).map(Value::Bytes).map_err(|_| {
                     ValueParsingError::InvalidBase64(format!(
                         "Expected base64 string, got {:?}",
@@ -177,7 +186,7 @@ impl FieldType {
                         Err(ValueParsingError::OverflowError(msg))
                     }
                 }
-                FieldType::Str(_) | FieldType::HierarchicalFacet | FieldType::Bytes(_) => {
+                FieldType::Str(_) | FieldType::HierarchicalFacet(_) | FieldType::Bytes(_) => {
                     let msg = format!("Expected a string, got {:?}", json);
                     Err(ValueParsingError::TypeError(msg))
                 }
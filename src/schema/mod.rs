@@ -112,10 +112,12 @@ mod field_type;
 mod field_value;
 
 mod bytes_options;
+mod facet_options;
 mod field;
 mod index_record_option;
 mod int_options;
 mod named_field_document;
+mod similarity;
 mod text_options;
 mod value;
 
@@ -138,12 +140,14 @@ pub use self::field_type::{FieldType, Type};
 pub use self::field_value::FieldValue;
 
 pub use self::index_record_option::IndexRecordOption;
+pub use self::similarity::Similarity;
 pub use self::text_options::TextFieldIndexing;
 pub use self::text_options::TextOptions;
 pub use self::text_options::STRING;
 pub use self::text_options::TEXT;
 
 pub use self::bytes_options::BytesOptions;
+pub use self::facet_options::FacetOptions;
 pub use self::flags::{FAST, INDEXED, STORED};
 pub use self::int_options::Cardinality;
 pub use self::int_options::IntOptions;
@@ -146,8 +146,28 @@ impl SchemaBuilder {
     }
 
     /// Adds a facet field to the schema.
+    ///
+    /// The facets are indexed, fast and stored.
+    /// (See `SchemaBuilder::add_facet_field_with_options`)
     pub fn add_facet_field(&mut self, field_name: &str) -> Field {
-        let field_entry = FieldEntry::new_facet(field_name.to_string());
+        self.add_facet_field_with_options(field_name, FacetOptions::all())
+    }
+
+    /// Adds a facet field to the schema, given its options.
+    ///
+    /// Facets must be indexed (with the `INDEXED` flag) to be searched,
+    /// fast (with the `FAST` flag) to be counted by the `FacetCollector`,
+    /// and stored (with the `STORED` flag) to be retrieved with the documents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the facets are neither indexed, fast nor stored.
+    pub fn add_facet_field_with_options<T: Into<FacetOptions>>(
+        &mut self,
+        field_name: &str,
+        field_options: T,
+    ) -> Field {
+        let field_entry = FieldEntry::new_facet(field_name.to_string(), field_options.into());
         self.add_field(field_entry)
     }
 
@@ -0,0 +1,132 @@
+use crate::Score;
+use crate::TantivyError;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// `Similarity` defines the scoring model used to rank the documents
+/// matching the terms of a text field.
+///
+/// It can be defined in the schema, for each text field
+/// (See [`TextFieldIndexing::set_similarity`](./struct.TextFieldIndexing.html#method.set_similarity)),
+/// or overridden at query time
+/// (See [`TermQuery::with_similarity`](../query/struct.TermQuery.html#method.with_similarity)).
+///
+/// The default similarity is BM25 with `k1 = 1.2` and `b = 0.75`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", try_from = "SimilarityDef")]
+pub enum Similarity {
+    /// Okapi BM25.
+    ///
+    /// The score of a term is
+    /// `idf * (k1 + 1) * tf / (tf + k1 * (1 - b + b * dl / avgdl))`.
+    #[serde(rename = "bm25")]
+    BM25 {
+        /// Term frequency saturation parameter.
+        /// The higher, the more repeated occurrences of a term contribute to the score.
+        k1: Score,
+        /// Length normalization parameter, between 0 and 1.
+        /// `0` disables length normalization, `1` applies it fully.
+        b: Score,
+    },
+    /// Classic TF-IDF, as in Lucene's `ClassicSimilarity`.
+    ///
+    /// The score of a term is `idf * sqrt(tf) / sqrt(dl)`
+    /// with `idf = 1 + log((N + 1) / (n + 1))`.
+    #[serde(rename = "tfidf")]
+    TfIdf,
+}
+
+impl Similarity {
+    /// BM25 with the default parameters, `k1 = 1.2` and `b = 0.75`.
+    pub const DEFAULT_BM25: Similarity = Similarity::BM25 { k1: 1.2, b: 0.75 };
+
+    /// Creates a BM25 similarity with custom parameters.
+    ///
+    /// Returns an error if `k1` is negative, or if `b` is not within `[0, 1]`.
+    pub fn bm25(k1: Score, b: Score) -> crate::Result<Similarity> {
+        let similarity = Similarity::BM25 { k1, b };
+        similarity.validate()?;
+        Ok(similarity)
+    }
+
+    /// Checks the parameters of the similarity.
+    ///
+    /// The `BM25` variant can be built directly, without going through
+    /// `Similarity::bm25`: the parameters are therefore checked again
+    /// when the weight of a query is built.
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        if let Similarity::BM25 { k1, b } = *self {
+            if !(k1.is_finite() && k1 >= 0.0) {
+                return Err(TantivyError::InvalidArgument(format!(
+                    "k1 must be positive, got {}",
+                    k1
+                )));
+            }
+            if !(0.0..=1.0).contains(&b) {
+                return Err(TantivyError::InvalidArgument(format!(
+                    "b must be in [0, 1], got {}",
+                    b
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Similarity::DEFAULT_BM25
+    }
+}
+
+impl Default for Similarity {
+    fn default() -> Similarity {
+        Similarity::DEFAULT_BM25
+    }
+}
+
+/// Serialized representation of a `Similarity`,
+/// validated when converted into a `Similarity`.
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum SimilarityDef {
+    #[serde(rename = "bm25")]
+    BM25 { k1: Score, b: Score },
+    #[serde(rename = "tfidf")]
+    TfIdf,
+}
+
+impl TryFrom<SimilarityDef> for Similarity {
+    type Error = TantivyError;
+
+    fn try_from(similarity_def: SimilarityDef) -> crate::Result<Similarity> {
+        match similarity_def {
+            SimilarityDef::BM25 { k1, b } => Similarity::bm25(k1, b),
+            SimilarityDef::TfIdf => Ok(Similarity::TfIdf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Similarity;
+
+    #[test]
+    fn test_bm25_invalid_parameters() {
+        assert!(Similarity::bm25(1.2, 0.75).is_ok());
+        assert!(Similarity::bm25(0.0, 1.0).is_ok());
+        assert!(Similarity::bm25(-1.0, 0.75).is_err());
+        assert!(Similarity::bm25(1.2, 1.5).is_err());
+        assert!(Similarity::bm25(1.2, -0.1).is_err());
+    }
+
+    #[test]
+    fn test_similarity_deserialization_is_validated() {
+        let similarity: Similarity =
+            serde_json::from_str(r#"{"type": "bm25", "k1": 2.0, "b": 0.5}"#).unwrap();
+        assert_eq!(similarity, Similarity::bm25(2.0, 0.5).unwrap());
+        let tfidf: Similarity = serde_json::from_str(r#"{"type": "tfidf"}"#).unwrap();
+        assert_eq!(tfidf, Similarity::TfIdf);
+        let err = serde_json::from_str::<Similarity>(r#"{"type": "bm25", "k1": 1.2, "b": 2.0}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("b must be in [0, 1]"));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::schema::flags::SchemaFlagList;
 use crate::schema::flags::StoredFlag;
 use crate::schema::IndexRecordOption;
+use crate::schema::Similarity;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::ops::BitOr;
@@ -51,10 +52,34 @@ impl Default for TextOptions {
 /// - the amount of information that should be stored about the presence of a term in a document.
 /// Essentially, should we store the term frequency and/or the positions (See [`IndexRecordOption`](./enum.IndexRecordOption.html)).
 /// - the name of the `Tokenizer` that should be used to process the field.
+/// - the `Similarity` used to score the documents matching a term of the field.
+/// - whether the field norms of the field should be recorded.
+/// - whether the term vectors of the field should be recorded.
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct TextFieldIndexing {
     record: IndexRecordOption,
     tokenizer: Cow<'static, str>,
+    #[serde(default, skip_serializing_if = "Similarity::is_default")]
+    similarity: Similarity,
+    #[serde(
+        default = "default_fieldnorms",
+        skip_serializing_if = "is_default_fieldnorms"
+    )]
+    fieldnorms: bool,
+    #[serde(default, skip_serializing_if = "is_default_term_vectors")]
+    term_vectors: bool,
+}
+
+fn default_fieldnorms() -> bool {
+    true
+}
+
+fn is_default_fieldnorms(fieldnorms: &bool) -> bool {
+    *fieldnorms
+}
+
+fn is_default_term_vectors(term_vectors: &bool) -> bool {
+    !*term_vectors
 }
 
 impl Default for TextFieldIndexing {
@@ -62,6 +87,9 @@ impl Default for TextFieldIndexing {
         TextFieldIndexing {
             tokenizer: Cow::Borrowed("default"),
             record: IndexRecordOption::Basic,
+            similarity: Similarity::DEFAULT_BM25,
+            fieldnorms: true,
+            term_vectors: false,
         }
     }
 }
@@ -92,6 +120,49 @@ impl TextFieldIndexing {
     pub fn index_option(&self) -> IndexRecordOption {
         self.record
     }
+
+    /// Sets the similarity used to score the documents matching this field.
+    ///
+    /// See [Similarity](./enum.Similarity.html) for more detail.
+    pub fn set_similarity(mut self, similarity: Similarity) -> TextFieldIndexing {
+        self.similarity = similarity;
+        self
+    }
+
+    /// Returns the similarity used to score the documents matching this field.
+    pub fn similarity(&self) -> Similarity {
+        self.similarity
+    }
+
+    /// Sets whether the field norms (the number of tokens of the field, for each document)
+    /// should be recorded. They are recorded by default.
+    ///
+    /// Omitting them saves one byte per document, but disables length normalization
+    /// when scoring the documents matching this field.
+    pub fn set_fieldnorms(mut self, fieldnorms: bool) -> TextFieldIndexing {
+        self.fieldnorms = fieldnorms;
+        self
+    }
+
+    /// Returns true iff the field norms of this field are recorded.
+    pub fn fieldnorms(&self) -> bool {
+        self.fieldnorms
+    }
+
+    /// Sets whether the term vectors of the field should be recorded.
+    /// They are not recorded by default.
+    ///
+    /// The term vector of a document lists the terms of the field in this document,
+    /// with their positions. (See `SegmentReader::term_vector`)
+    pub fn set_term_vectors(mut self, term_vectors: bool) -> TextFieldIndexing {
+        self.term_vectors = term_vectors;
+        self
+    }
+
+    /// Returns true iff the term vectors of this field are recorded.
+    pub fn term_vectors(&self) -> bool {
+        self.term_vectors
+    }
 }
 
 /// The field will be untokenized and indexed
@@ -99,6 +170,9 @@ pub const STRING: TextOptions = TextOptions {
     indexing: Some(TextFieldIndexing {
         tokenizer: Cow::Borrowed("raw"),
         record: IndexRecordOption::Basic,
+        similarity: Similarity::DEFAULT_BM25,
+        fieldnorms: true,
+        term_vectors: false,
     }),
     stored: false,
 };
@@ -108,6 +182,9 @@ pub const TEXT: TextOptions = TextOptions {
     indexing: Some(TextFieldIndexing {
         tokenizer: Cow::Borrowed("default"),
         record: IndexRecordOption::WithFreqsAndPositions,
+        similarity: Similarity::DEFAULT_BM25,
+        fieldnorms: true,
+        term_vectors: false,
     }),
     stored: false,
 };
@@ -173,5 +250,70 @@ mod tests {
     fn test_cmp_index_record_option() {
         assert!(IndexRecordOption::WithFreqsAndPositions > IndexRecordOption::WithFreqs);
         assert!(IndexRecordOption::WithFreqs > IndexRecordOption::Basic);
+        assert!(
+            IndexRecordOption::WithFreqsAndPositionsAndOffsets
+                > IndexRecordOption::WithFreqsAndPositions
+        );
+    }
+
+    #[test]
+    fn test_text_field_indexing_offsets_serialization() {
+        let indexing = TextFieldIndexing::default()
+            .set_index_option(IndexRecordOption::WithFreqsAndPositionsAndOffsets);
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert_eq!(json, r#"{"record":"offsets","tokenizer":"default"}"#);
+        let deserialized: TextFieldIndexing = serde_json::from_str(&json).unwrap();
+        assert!(deserialized.index_option().has_offsets());
+        assert!(deserialized.index_option().has_positions());
+    }
+
+    #[test]
+    fn test_text_field_indexing_similarity_serialization() {
+        let indexing = TextFieldIndexing::default();
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert_eq!(json, r#"{"record":"basic","tokenizer":"default"}"#);
+        let deserialized: TextFieldIndexing = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.similarity(), Similarity::default());
+
+        let indexing = indexing.set_similarity(Similarity::bm25(1.5, 0.3).unwrap());
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert_eq!(
+            json,
+            r#"{"record":"basic","tokenizer":"default","similarity":{"type":"bm25","k1":1.5,"b":0.3}}"#
+        );
+        let deserialized: TextFieldIndexing = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, indexing);
+
+        let indexing = indexing.set_similarity(Similarity::TfIdf);
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert!(json.ends_with(r#""similarity":{"type":"tfidf"}}"#));
+    }
+
+    #[test]
+    fn test_text_field_indexing_fieldnorms_serialization() {
+        let indexing = TextFieldIndexing::default();
+        assert!(indexing.fieldnorms());
+        let indexing = indexing.set_fieldnorms(false);
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert_eq!(
+            json,
+            r#"{"record":"basic","tokenizer":"default","fieldnorms":false}"#
+        );
+        let deserialized: TextFieldIndexing = serde_json::from_str(&json).unwrap();
+        assert!(!deserialized.fieldnorms());
+    }
+
+    #[test]
+    fn test_text_field_indexing_term_vectors_serialization() {
+        let indexing = TextFieldIndexing::default();
+        assert!(!indexing.term_vectors());
+        let indexing = indexing.set_term_vectors(true);
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert_eq!(
+            json,
+            r#"{"record":"basic","tokenizer":"default","term_vectors":true}"#
+        );
+        let deserialized: TextFieldIndexing = serde_json::from_str(&json).unwrap();
+        assert!(deserialized.term_vectors());
     }
 }
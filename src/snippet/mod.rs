@@ -1,16 +1,20 @@
+use crate::docset::DocSet;
+use crate::postings::Postings;
 use crate::query::Query;
-use crate::schema::Field;
 use crate::schema::Value;
+use crate::schema::{Field, FieldType, IndexRecordOption, Term};
 use crate::tokenizer::{TextAnalyzer, Token};
 use crate::Searcher;
-use crate::{Document, Score};
+use crate::{DocAddress, Document, Score, TantivyError};
 use htmlescape::encode_minimal;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
-use std::collections::BTreeSet;
+use std::collections::HashMap;
 
 const DEFAULT_MAX_NUM_CHARS: usize = 150;
 
+const DEFAULT_FRAGMENT_SEPARATOR: &str = " ... ";
+
 #[derive(Debug)]
 pub struct HighlightSection {
     start: usize,
@@ -28,6 +32,24 @@ impl HighlightSection {
     }
 }
 
+/// A phrase of the query, with the text of its terms
+/// and their offsets within the phrase.
+#[derive(Debug)]
+struct HighlightPhrase {
+    terms: Vec<(usize, String)>,
+    score: Score,
+}
+
+/// A part of the text which is added to a fragment as a whole.
+///
+/// It is either a single token, or all of the tokens of a
+/// matching phrase, in which case it is highlighted as a whole.
+struct TextUnit {
+    offset_from: usize,
+    offset_to: usize,
+    score: Option<Score>,
+}
+
 #[derive(Debug)]
 pub struct FragmentCandidate {
     score: Score,
@@ -55,22 +77,25 @@ impl FragmentCandidate {
 
     /// Updates `score` and `highlighted` fields of the objects.
     ///
-    /// taking the token and terms, the token is added to the fragment.
-    /// if the token is one of the terms, the score
+    /// The unit is added to the fragment. If it matched
+    /// one of the terms or one of the phrases of the query, the score
     /// and highlighted fields are updated in the fragment.
-    fn try_add_token(&mut self, token: &Token, terms: &BTreeMap<String, Score>) {
-        self.stop_offset = token.offset_to;
+    fn try_add_unit(&mut self, unit: &TextUnit) {
+        self.stop_offset = unit.offset_to;
 
-        if let Some(&score) = terms.get(&token.text.to_lowercase()) {
+        if let Some(score) = unit.score {
             self.score += score;
             self.highlighted
-                .push(HighlightSection::new(token.offset_from, token.offset_to));
+                .push(HighlightSection::new(unit.offset_from, unit.offset_to));
         }
     }
 }
 
 /// `Snippet`
 /// Contains a fragment of a document, and some highlighed parts inside it.
+///
+/// If several fragments were requested, they are joined by the fragment separator
+/// of the `SnippetGenerator`.
 #[derive(Debug)]
 pub struct Snippet {
     fragments: String,
@@ -91,14 +116,22 @@ impl Snippet {
 
     /// Returns a hignlightned html from the `Snippet`.
     pub fn to_html(&self) -> String {
+        self.to_html_with_tags(HIGHLIGHTEN_PREFIX, HIGHLIGHTEN_POSTFIX)
+    }
+
+    /// Returns a highlighted html from the `Snippet`, surrounding
+    /// the highlighted parts by `pre_tag` and `post_tag`.
+    ///
+    /// The text of the fragments is html-escaped, but the tags are not.
+    pub fn to_html_with_tags(&self, pre_tag: &str, post_tag: &str) -> String {
         let mut html = String::new();
         let mut start_from: usize = 0;
 
         for item in self.highlighted.iter() {
             html.push_str(&encode_minimal(&self.fragments[start_from..item.start]));
-            html.push_str(HIGHLIGHTEN_PREFIX);
+            html.push_str(pre_tag);
             html.push_str(&encode_minimal(&self.fragments[item.start..item.stop]));
-            html.push_str(HIGHLIGHTEN_POSTFIX);
+            html.push_str(post_tag);
             start_from = item.stop;
         }
         html.push_str(&encode_minimal(
@@ -118,6 +151,105 @@ impl Snippet {
     }
 }
 
+/// Splits the text into the units fragments are made of.
+///
+/// Tokens matching a term are scored with the score of the term.
+/// The tokens of an occurrence of a phrase are merged into a single unit,
+/// scored with the score of the phrase. Overlapping occurrences are ignored.
+fn text_units(
+    tokenizer: &TextAnalyzer,
+    text: &str,
+    terms: &BTreeMap<String, Score>,
+    phrases: &[HighlightPhrase],
+) -> Vec<TextUnit> {
+    let mut tokens: Vec<Token> = Vec::new();
+    tokenizer
+        .token_stream(text)
+        .process(&mut |token| tokens.push(token.clone()));
+    let token_texts: Vec<String> = tokens
+        .iter()
+        .map(|token| token.text.to_lowercase())
+        .collect();
+    // (first token, last token, score) of the matches.
+    let mut matches: Vec<(usize, usize, Score)> = token_texts
+        .iter()
+        .enumerate()
+        .flat_map(|(token_id, token_text)| {
+            terms
+                .get(token_text)
+                .map(|&score| (token_id, token_id, score))
+        })
+        .collect();
+    if !phrases.is_empty() {
+        let mut tokens_at_position: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (token_id, token) in tokens.iter().enumerate() {
+            tokens_at_position
+                .entry(token.position)
+                .or_default()
+                .push(token_id);
+        }
+        for phrase in phrases {
+            let (first_offset, first_text) = &phrase.terms[0];
+            for (token_id, token) in tokens.iter().enumerate() {
+                if &token_texts[token_id] != first_text {
+                    continue;
+                }
+                let phrase_token_ids: Option<Vec<usize>> = phrase
+                    .terms
+                    .iter()
+                    .map(|(offset, text)| {
+                        let position = token.position + offset - first_offset;
+                        tokens_at_position.get(&position).and_then(|token_ids| {
+                            token_ids
+                                .iter()
+                                .cloned()
+                                .find(|&other_id| &token_texts[other_id] == text)
+                        })
+                    })
+                    .collect();
+                if let Some(phrase_token_ids) = phrase_token_ids {
+                    let first = phrase_token_ids.iter().cloned().min().unwrap_or(token_id);
+                    let last = phrase_token_ids.iter().cloned().max().unwrap_or(token_id);
+                    matches.push((first, last, phrase.score));
+                }
+            }
+        }
+        matches.sort_by_key(|&(first, last, _)| (first, last));
+    }
+    let mut units = Vec::with_capacity(tokens.len());
+    let mut matches_it = matches.into_iter().peekable();
+    let mut token_id = 0;
+    while token_id < tokens.len() {
+        // Matches overlapping with a previous match are skipped.
+        while let Some(&(first, _, _)) = matches_it.peek() {
+            if first >= token_id {
+                break;
+            }
+            matches_it.next();
+        }
+        match matches_it.peek() {
+            Some(&(first, last, score)) if first == token_id => {
+                units.push(TextUnit {
+                    offset_from: tokens[first].offset_from,
+                    offset_to: tokens[last].offset_to,
+                    score: Some(score),
+                });
+                matches_it.next();
+                token_id = last + 1;
+            }
+            _ => {
+                units.push(TextUnit {
+                    offset_from: tokens[token_id].offset_from,
+                    offset_to: tokens[token_id].offset_to,
+                    score: None,
+                });
+                token_id += 1;
+            }
+        }
+    }
+    units
+}
+
 /// Returns a non-empty list of "good" fragments.
 ///
 /// If no target term is within the text, then the function
@@ -143,19 +275,26 @@ fn search_fragments<'a>(
     tokenizer: &TextAnalyzer,
     text: &'a str,
     terms: &BTreeMap<String, Score>,
+    phrases: &[HighlightPhrase],
     max_num_chars: usize,
 ) -> Vec<FragmentCandidate> {
-    let mut token_stream = tokenizer.token_stream(text);
+    let units = text_units(tokenizer, text, terms, phrases);
+    fragments_from_units(units, max_num_chars)
+}
+
+/// Groups consecutive units into fragments of at most `max_num_chars`,
+/// and only keeps the fragments containing a match.
+fn fragments_from_units(units: Vec<TextUnit>, max_num_chars: usize) -> Vec<FragmentCandidate> {
     let mut fragment = FragmentCandidate::new(0);
     let mut fragments: Vec<FragmentCandidate> = vec![];
-    while let Some(next) = token_stream.next() {
-        if (next.offset_to - fragment.start_offset) > max_num_chars {
+    for unit in units {
+        if (unit.offset_to - fragment.start_offset) > max_num_chars {
             if fragment.score > 0.0 {
                 fragments.push(fragment)
             };
-            fragment = FragmentCandidate::new(next.offset_from);
+            fragment = FragmentCandidate::new(unit.offset_from);
         }
-        fragment.try_add_token(next, &terms);
+        fragment.try_add_unit(&unit);
     }
     if fragment.score > 0.0 {
         fragments.push(fragment)
@@ -164,50 +303,124 @@ fn search_fragments<'a>(
     fragments
 }
 
+/// Maps an offset recorded in the index to an offset in the text of the snippet,
+/// given the `(indexed offset, text offset)` of each value of the field.
+///
+/// An offset that is the end of a token belongs to the value preceding it.
+fn offset_in_text(value_offsets: &[(usize, usize)], offset: usize, is_end: bool) -> usize {
+    value_offsets
+        .iter()
+        .rev()
+        .find(|&&(indexed_start, _)| indexed_start < offset || (!is_end && indexed_start == offset))
+        .map(|&(indexed_start, text_start)| offset - indexed_start + text_start)
+        .unwrap_or(offset)
+}
+
+/// Splits the text into units, given the `(offset_from, offset_to, score)`
+/// of the matches of the query in the text.
+///
+/// The text between matches is split on whitespaces. Matches overlapping with
+/// a previous match are ignored.
+fn units_from_matches(text: &str, mut matches: Vec<(usize, usize, Score)>) -> Vec<TextUnit> {
+    matches.sort_by_key(|&(offset_from, offset_to, _)| (offset_from, offset_to));
+    let mut units = Vec::new();
+    let mut offset = 0;
+    for (offset_from, offset_to, score) in matches {
+        if offset_from < offset || offset_to > text.len() {
+            continue;
+        }
+        push_word_units(text, offset, offset_from, &mut units);
+        units.push(TextUnit {
+            offset_from,
+            offset_to,
+            score: Some(score),
+        });
+        offset = offset_to;
+    }
+    push_word_units(text, offset, text.len(), &mut units);
+    units
+}
+
+/// Pushes the whitespace separated words of `text[start..stop]` as units.
+fn push_word_units(text: &str, start: usize, stop: usize, units: &mut Vec<TextUnit>) {
+    let mut word_start: Option<usize> = None;
+    for (offset, c) in text[start..stop].char_indices() {
+        let offset = start + offset;
+        if c.is_whitespace() {
+            if let Some(offset_from) = word_start.take() {
+                units.push(TextUnit {
+                    offset_from,
+                    offset_to: offset,
+                    score: None,
+                });
+            }
+        } else if word_start.is_none() {
+            word_start = Some(offset);
+        }
+    }
+    if let Some(offset_from) = word_start {
+        units.push(TextUnit {
+            offset_from,
+            offset_to: stop,
+            score: None,
+        });
+    }
+}
+
 /// Returns a Snippet
 ///
 /// Takes a vector of `FragmentCandidate`s and the text.
-/// Figures out the best fragment from it and creates a snippet.
-fn select_best_fragment_combination(fragments: &[FragmentCandidate], text: &str) -> Snippet {
-    let best_fragment_opt = fragments.iter().max_by(|left, right| {
-        let cmp_score = left
+/// Figures out the `num_fragments` best fragments from it and creates a snippet,
+/// joining them by `separator` in the order in which they appear in the text.
+fn select_best_fragment_combination(
+    fragments: &[FragmentCandidate],
+    text: &str,
+    num_fragments: usize,
+    separator: &str,
+) -> Snippet {
+    let mut best_fragments: Vec<&FragmentCandidate> = fragments.iter().collect();
+    best_fragments.sort_by(|left, right| {
+        let cmp_score = right
             .score
-            .partial_cmp(&right.score)
+            .partial_cmp(&left.score)
             .unwrap_or(Ordering::Equal);
         if cmp_score == Ordering::Equal {
-            (right.start_offset, right.stop_offset).cmp(&(left.start_offset, left.stop_offset))
+            (left.start_offset, left.stop_offset).cmp(&(right.start_offset, right.stop_offset))
         } else {
             cmp_score
         }
     });
-    if let Some(fragment) = best_fragment_opt {
-        let fragment_text = &text[fragment.start_offset..fragment.stop_offset];
-        let highlighted = fragment
+    best_fragments.truncate(num_fragments);
+    best_fragments.sort_by_key(|fragment| fragment.start_offset);
+    // when there no fragments to chose from,
+    // for now create a empty snippet
+    let mut snippet = Snippet::empty();
+    for (fragment_ord, fragment) in best_fragments.into_iter().enumerate() {
+        if fragment_ord > 0 {
+            snippet.fragments.push_str(separator);
+        }
+        let fragment_start = snippet.fragments.len();
+        snippet
+            .fragments
+            .push_str(&text[fragment.start_offset..fragment.stop_offset]);
+        snippet
             .highlighted
-            .iter()
-            .map(|item| {
+            .extend(fragment.highlighted.iter().map(|item| {
                 HighlightSection::new(
-                    item.start - fragment.start_offset,
-                    item.stop - fragment.start_offset,
+                    fragment_start + item.start - fragment.start_offset,
+                    fragment_start + item.stop - fragment.start_offset,
                 )
-            })
-            .collect();
-        Snippet {
-            fragments: fragment_text.to_string(),
-            highlighted,
-        }
-    } else {
-        // when there no fragments to chose from,
-        // for now create a empty snippet
-        Snippet {
-            fragments: String::new(),
-            highlighted: vec![],
-        }
+            }));
     }
+    snippet
 }
 
 /// `SnippetGenerator`
 ///
+/// The terms of the query are highlighted wherever they appear in the text,
+/// while the terms of a phrase query are only highlighted where the whole
+/// phrase appears.
+///
 /// # Example
 ///
 /// ```rust
@@ -244,14 +457,27 @@ fn select_best_fragment_combination(fragments: &[FragmentCandidate], text: &str)
 /// let snippet = snippet_generator.snippet_from_doc(&doc);
 /// let snippet_html: String = snippet.to_html();
 /// assert_eq!(snippet_html, "Comme je descendais des Fleuves impassibles,\n  Je ne me sentis plus guidé par les <b>haleurs</b> :\n Des");
+///
+/// // Several fragments can be returned, joined by a separator.
+/// snippet_generator.set_max_num_chars(50);
+/// snippet_generator.set_num_fragments(2);
+/// snippet_generator.set_fragment_separator(" [...] ");
+/// let snippet = snippet_generator.snippet_from_doc(&doc);
+/// assert_eq!(
+///     snippet.to_html_with_tags("<em>", "</em>"),
+///     "ne me sentis plus guidé par les <em>haleurs</em> :\n Des [...] de blés <em>flamands</em> ou de cotons anglais.\n Quand"
+/// );
 /// #    Ok(())
 /// # }
 /// ```
 pub struct SnippetGenerator {
     terms_text: BTreeMap<String, Score>,
+    phrases: Vec<HighlightPhrase>,
     tokenizer: TextAnalyzer,
     field: Field,
     max_num_chars: usize,
+    num_fragments: usize,
+    fragment_separator: String,
 }
 
 impl SnippetGenerator {
@@ -261,25 +487,43 @@ impl SnippetGenerator {
         query: &dyn Query,
         field: Field,
     ) -> crate::Result<SnippetGenerator> {
-        let mut terms = BTreeSet::new();
-        query.query_terms(&mut terms);
+        let mut query_phrases = Vec::new();
+        query.query_phrases(&mut query_phrases);
         let mut terms_text: BTreeMap<String, Score> = Default::default();
-        for term in terms {
-            if term.field() != field {
+        let mut phrases = Vec::new();
+        'phrases: for query_phrase in query_phrases {
+            if query_phrase.iter().any(|(_, term)| term.field() != field) {
                 continue;
             }
-            let doc_freq = searcher.doc_freq(&term)?;
-            if doc_freq > 0 {
-                let score = 1.0 / (1.0 + doc_freq as Score);
-                terms_text.insert(term.text().to_string(), score);
+            let mut score = 0.0;
+            let mut phrase_terms = Vec::with_capacity(query_phrase.len());
+            for (offset, term) in query_phrase {
+                let doc_freq = searcher.doc_freq(&term)?;
+                if doc_freq == 0 {
+                    continue 'phrases;
+                }
+                score += 1.0 / (1.0 + doc_freq as Score);
+                phrase_terms.push((offset, term.text().to_string()));
+            }
+            if phrase_terms.len() == 1 {
+                let (_, term_text) = phrase_terms.pop().unwrap();
+                terms_text.insert(term_text, score);
+            } else {
+                phrases.push(HighlightPhrase {
+                    terms: phrase_terms,
+                    score,
+                });
             }
         }
         let tokenizer = searcher.index().tokenizer_for_field(field)?;
         Ok(SnippetGenerator {
             terms_text,
+            phrases,
             tokenizer,
             field,
             max_num_chars: DEFAULT_MAX_NUM_CHARS,
+            num_fragments: 1,
+            fragment_separator: DEFAULT_FRAGMENT_SEPARATOR.to_string(),
         })
     }
 
@@ -288,6 +532,26 @@ impl SnippetGenerator {
         self.max_num_chars = max_num_chars;
     }
 
+    /// Sets the maximum number of fragments of the snippets.
+    ///
+    /// The best fragments are joined by the fragment separator, in the order
+    /// in which they appear in the text. Each of them has at most
+    /// the maximum number of chars. Defaults to 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_fragments` is 0.
+    pub fn set_num_fragments(&mut self, num_fragments: usize) {
+        assert!(num_fragments > 0, "The number of fragments must be > 0.");
+        self.num_fragments = num_fragments;
+    }
+
+    /// Sets the separator inserted between the fragments of a snippet.
+    /// Defaults to `" ... "`.
+    pub fn set_fragment_separator(&mut self, fragment_separator: &str) {
+        self.fragment_separator = fragment_separator.to_string();
+    }
+
     #[cfg(test)]
     pub fn terms_text(&self) -> &BTreeMap<String, Score> {
         &self.terms_text
@@ -306,11 +570,153 @@ impl SnippetGenerator {
         self.snippet(&text)
     }
 
+    /// Generates a snippet for the document at the given `DocAddress`,
+    /// using the offsets recorded in the index rather than tokenizing its text again.
+    ///
+    /// The field must be stored, and indexed with
+    /// `IndexRecordOption::WithFreqsAndPositionsAndOffsets`.
+    /// The values of a multivalued field are joined with a space, as in `snippet_from_doc`.
+    pub fn snippet_from_indexed_offsets(
+        &self,
+        searcher: &Searcher,
+        doc_address: DocAddress,
+    ) -> crate::Result<Snippet> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        let has_offsets = match field_entry.field_type() {
+            FieldType::Str(ref text_options) => text_options
+                .get_indexing_options()
+                .map(|indexing_options| indexing_options.index_option().has_offsets())
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !has_offsets {
+            return Err(TantivyError::SchemaError(format!(
+                "Field {:?} is not indexed with offsets.",
+                field_entry.name()
+            )));
+        }
+        let DocAddress(segment_ord, doc_id) = doc_address;
+        let inverted_index = searcher
+            .segment_reader(segment_ord)
+            .inverted_index(self.field)?;
+        // (position, offset_from, offset_to) of the occurrences of each term in the document.
+        let mut occurrences: HashMap<&str, Vec<(u32, u32, u32)>> = HashMap::new();
+        let phrase_terms = self
+            .phrases
+            .iter()
+            .flat_map(|phrase| phrase.terms.iter().map(|(_, text)| text));
+        let mut positions = Vec::new();
+        let mut offsets = Vec::new();
+        for term_text in self.terms_text.keys().chain(phrase_terms) {
+            if occurrences.contains_key(term_text.as_str()) {
+                continue;
+            }
+            let term = Term::from_field_text(self.field, term_text);
+            let mut term_occurrences = Vec::new();
+            if let Some(mut postings) = inverted_index
+                .read_postings(&term, IndexRecordOption::WithFreqsAndPositionsAndOffsets)?
+            {
+                if postings.seek(doc_id) == doc_id {
+                    postings.positions(&mut positions);
+                    postings.offsets(&mut offsets);
+                    term_occurrences.extend(positions.iter().zip(offsets.iter()).map(
+                        |(&position, &(offset_from, offset_to))| (position, offset_from, offset_to),
+                    ));
+                }
+            }
+            occurrences.insert(term_text, term_occurrences);
+        }
+        let mut matches: Vec<(usize, usize, Score)> = Vec::new();
+        for (term_text, &score) in &self.terms_text {
+            matches.extend(occurrences[term_text.as_str()].iter().map(
+                |&(_, offset_from, offset_to)| (offset_from as usize, offset_to as usize, score),
+            ));
+        }
+        for phrase in &self.phrases {
+            let (first_offset, first_text) = &phrase.terms[0];
+            for &(position, _, _) in &occurrences[first_text.as_str()] {
+                let phrase_occurrences: Option<Vec<(u32, u32, u32)>> = phrase
+                    .terms
+                    .iter()
+                    .map(|(offset, text)| {
+                        let term_position = position + *offset as u32 - *first_offset as u32;
+                        occurrences[text.as_str()]
+                            .iter()
+                            .cloned()
+                            .find(|&(other_position, _, _)| other_position == term_position)
+                    })
+                    .collect();
+                if let Some(phrase_occurrences) = phrase_occurrences {
+                    let offset_from = phrase_occurrences.iter().map(|occ| occ.1).min();
+                    let offset_to = phrase_occurrences.iter().map(|occ| occ.2).max();
+                    if let (Some(offset_from), Some(offset_to)) = (offset_from, offset_to) {
+                        matches.push((offset_from as usize, offset_to as usize, phrase.score));
+                    }
+                }
+            }
+        }
+        let doc = searcher.doc(doc_address)?;
+        // The offsets were recorded as if the values were concatenated without any separator.
+        // `value_offsets` contains, for each value, its offset in this concatenation
+        // and its offset in the text of the snippet.
+        let mut text = String::new();
+        let mut value_offsets: Vec<(usize, usize)> = Vec::new();
+        let mut indexed_offset = 0;
+        for value in doc.get_all(self.field) {
+            let value_text = match value {
+                Value::Str(ref value_text) => value_text.as_str(),
+                Value::PreTokStr(ref tok_str) => {
+                    let len = tok_str
+                        .tokens
+                        .last()
+                        .map(|last_token| last_token.offset_to)
+                        .unwrap_or(0);
+                    &tok_str.text[..len]
+                }
+                _ => continue,
+            };
+            if !value_offsets.is_empty() {
+                text.push(' ');
+            }
+            value_offsets.push((indexed_offset, text.len()));
+            indexed_offset += value_text.len();
+            text.push_str(value_text);
+        }
+        let matches = matches
+            .into_iter()
+            .map(|(offset_from, offset_to, score)| {
+                (
+                    offset_in_text(&value_offsets, offset_from, false),
+                    offset_in_text(&value_offsets, offset_to, true),
+                    score,
+                )
+            })
+            .collect();
+        let units = units_from_matches(&text, matches);
+        let fragment_candidates = fragments_from_units(units, self.max_num_chars);
+        Ok(select_best_fragment_combination(
+            &fragment_candidates[..],
+            &text,
+            self.num_fragments,
+            &self.fragment_separator,
+        ))
+    }
+
     /// Generates a snippet for the given text.
     pub fn snippet(&self, text: &str) -> Snippet {
-        let fragment_candidates =
-            search_fragments(&self.tokenizer, &text, &self.terms_text, self.max_num_chars);
-        select_best_fragment_combination(&fragment_candidates[..], &text)
+        let fragment_candidates = search_fragments(
+            &self.tokenizer,
+            text,
+            &self.terms_text,
+            &self.phrases,
+            self.max_num_chars,
+        );
+        select_best_fragment_combination(
+            &fragment_candidates[..],
+            text,
+            self.num_fragments,
+            &self.fragment_separator,
+        )
     }
 }
 
@@ -318,10 +724,11 @@ impl SnippetGenerator {
 mod tests {
     use super::{search_fragments, select_best_fragment_combination};
     use crate::query::QueryParser;
-    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions, TEXT};
+    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions, STORED, TEXT};
     use crate::tokenizer::SimpleTokenizer;
     use crate::Index;
     use crate::SnippetGenerator;
+    use crate::{DocAddress, TantivyError};
     use maplit::btreemap;
     use std::collections::BTreeMap;
     use std::iter::Iterator;
@@ -346,14 +753,14 @@ Survey in 2016, 2017, and 2018."#;
             String::from("rust") => 1.0,
             String::from("language") => 0.9
         };
-        let fragments = search_fragments(&From::from(SimpleTokenizer), TEST_TEXT, &terms, 100);
+        let fragments = search_fragments(&From::from(SimpleTokenizer), TEST_TEXT, &terms, &[], 100);
         assert_eq!(fragments.len(), 7);
         {
             let first = &fragments[0];
             assert_eq!(first.score, 1.9);
             assert_eq!(first.stop_offset, 89);
         }
-        let snippet = select_best_fragment_combination(&fragments[..], &TEST_TEXT);
+        let snippet = select_best_fragment_combination(&fragments[..], &TEST_TEXT, 1, "");
         assert_eq!(
             snippet.fragments,
             "Rust is a systems programming language sponsored by\n\
@@ -373,13 +780,14 @@ Survey in 2016, 2017, and 2018."#;
                 String::from("rust") =>1.0,
                 String::from("language") => 0.9
             };
-            let fragments = search_fragments(&From::from(SimpleTokenizer), TEST_TEXT, &terms, 20);
+            let fragments =
+                search_fragments(&From::from(SimpleTokenizer), TEST_TEXT, &terms, &[], 20);
             {
                 let first = &fragments[0];
                 assert_eq!(first.score, 1.0);
                 assert_eq!(first.stop_offset, 17);
             }
-            let snippet = select_best_fragment_combination(&fragments[..], &TEST_TEXT);
+            let snippet = select_best_fragment_combination(&fragments[..], &TEST_TEXT, 1, "");
             assert_eq!(snippet.to_html(), "<b>Rust</b> is a systems")
         }
         {
@@ -387,14 +795,15 @@ Survey in 2016, 2017, and 2018."#;
                 String::from("rust") =>0.9,
                 String::from("language") => 1.0
             };
-            let fragments = search_fragments(&From::from(SimpleTokenizer), TEST_TEXT, &terms, 20);
+            let fragments =
+                search_fragments(&From::from(SimpleTokenizer), TEST_TEXT, &terms, &[], 20);
             //assert_eq!(fragments.len(), 7);
             {
                 let first = &fragments[0];
                 assert_eq!(first.score, 0.9);
                 assert_eq!(first.stop_offset, 17);
             }
-            let snippet = select_best_fragment_combination(&fragments[..], &TEST_TEXT);
+            let snippet = select_best_fragment_combination(&fragments[..], &TEST_TEXT, 1, "");
             assert_eq!(snippet.to_html(), "programming <b>language</b>")
         }
     }
@@ -406,7 +815,7 @@ Survey in 2016, 2017, and 2018."#;
         let mut terms = BTreeMap::new();
         terms.insert(String::from("c"), 1.0);
 
-        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, 3);
+        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, &[], 3);
 
         assert_eq!(fragments.len(), 1);
         {
@@ -416,7 +825,7 @@ Survey in 2016, 2017, and 2018."#;
             assert_eq!(first.stop_offset, 7);
         }
 
-        let snippet = select_best_fragment_combination(&fragments[..], &text);
+        let snippet = select_best_fragment_combination(&fragments[..], &text, 1, "");
         assert_eq!(snippet.fragments, "c d");
         assert_eq!(snippet.to_html(), "<b>c</b> d");
     }
@@ -428,7 +837,7 @@ Survey in 2016, 2017, and 2018."#;
         let mut terms = BTreeMap::new();
         terms.insert(String::from("f"), 1.0);
 
-        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, 3);
+        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, &[], 3);
 
         assert_eq!(fragments.len(), 2);
         {
@@ -438,7 +847,7 @@ Survey in 2016, 2017, and 2018."#;
             assert_eq!(first.start_offset, 8);
         }
 
-        let snippet = select_best_fragment_combination(&fragments[..], &text);
+        let snippet = select_best_fragment_combination(&fragments[..], &text, 1, "");
         assert_eq!(snippet.fragments, "e f");
         assert_eq!(snippet.to_html(), "e <b>f</b>");
     }
@@ -451,7 +860,7 @@ Survey in 2016, 2017, and 2018."#;
         terms.insert(String::from("f"), 1.0);
         terms.insert(String::from("a"), 0.9);
 
-        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, 7);
+        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, &[], 7);
 
         assert_eq!(fragments.len(), 2);
         {
@@ -461,7 +870,7 @@ Survey in 2016, 2017, and 2018."#;
             assert_eq!(first.start_offset, 0);
         }
 
-        let snippet = select_best_fragment_combination(&fragments[..], &text);
+        let snippet = select_best_fragment_combination(&fragments[..], &text, 1, "");
         assert_eq!(snippet.fragments, "e f g");
         assert_eq!(snippet.to_html(), "e <b>f</b> g");
     }
@@ -473,11 +882,11 @@ Survey in 2016, 2017, and 2018."#;
         let mut terms = BTreeMap::new();
         terms.insert(String::from("z"), 1.0);
 
-        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, 3);
+        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, &[], 3);
 
         assert_eq!(fragments.len(), 0);
 
-        let snippet = select_best_fragment_combination(&fragments[..], &text);
+        let snippet = select_best_fragment_combination(&fragments[..], &text, 1, "");
         assert_eq!(snippet.fragments, "");
         assert_eq!(snippet.to_html(), "");
     }
@@ -487,14 +896,143 @@ Survey in 2016, 2017, and 2018."#;
         let text = "a b c d";
 
         let terms = BTreeMap::new();
-        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, 3);
+        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, &[], 3);
         assert_eq!(fragments.len(), 0);
 
-        let snippet = select_best_fragment_combination(&fragments[..], &text);
+        let snippet = select_best_fragment_combination(&fragments[..], &text, 1, "");
         assert_eq!(snippet.fragments, "");
         assert_eq!(snippet.to_html(), "");
     }
 
+    #[test]
+    fn test_snippet_with_several_fragments() {
+        let text = "a b c d e f g h";
+
+        let mut terms = BTreeMap::new();
+        terms.insert(String::from("b"), 0.5);
+        terms.insert(String::from("e"), 1.0);
+        terms.insert(String::from("g"), 0.8);
+
+        let fragments = search_fragments(&From::from(SimpleTokenizer), &text, &terms, &[], 3);
+        assert_eq!(fragments.len(), 3);
+
+        let snippet = select_best_fragment_combination(&fragments[..], &text, 2, " ... ");
+        assert_eq!(snippet.fragments, "e f ... g h");
+        assert_eq!(snippet.to_html(), "<b>e</b> f ... <b>g</b> h");
+        assert_eq!(
+            snippet.to_html_with_tags("<em class=\"hl\">", "</em>"),
+            "<em class=\"hl\">e</em> f ... <em class=\"hl\">g</em> h"
+        );
+
+        let snippet = select_best_fragment_combination(&fragments[..], &text, 5, "|");
+        assert_eq!(snippet.to_html(), "a <b>b</b>|<b>e</b> f|<b>g</b> h");
+    }
+
+    #[test]
+    fn test_snippet_generator_phrase() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let text = "Alan just got a part time job. \
+                    This is my favorite part of the job, time flies.";
+        {
+            let mut index_writer = index.writer_for_tests().unwrap();
+            index_writer.add_document(doc!(text_field => text));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        let snippet_html = |query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            let snippet_generator =
+                SnippetGenerator::create(&searcher, &*query, text_field).unwrap();
+            snippet_generator.snippet(text).to_html()
+        };
+        assert_eq!(
+            snippet_html("\"part time\""),
+            "Alan just got a <b>part time</b> job. This is my favorite part of the job, time flies"
+        );
+        assert_eq!(
+            snippet_html("\"part time\" flies"),
+            "Alan just got a <b>part time</b> job. This is my favorite part of the job, time <b>flies</b>"
+        );
+        assert_eq!(
+            snippet_html("part time"),
+            "Alan just got a <b>part</b> <b>time</b> job. This is my favorite <b>part</b> of the job, <b>time</b> flies"
+        );
+        assert_eq!(snippet_html("\"time part\""), "");
+    }
+
+    #[test]
+    fn test_snippet_from_indexed_offsets() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer("default")
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositionsAndOffsets),
+            )
+            .set_stored();
+        let text_field = schema_builder.add_text_field("text", text_options);
+        let title_field = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_for_tests()?;
+            index_writer.add_document(doc!(
+                title_field => "part time",
+                text_field => "Alan just got a part time job.",
+                text_field => "This is my favorite part of the job, time flies."
+            ));
+            index_writer.commit()?;
+        }
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        let snippet_html = |query: &str| -> crate::Result<String> {
+            let query = query_parser.parse_query(query)?;
+            let snippet_generator = SnippetGenerator::create(&searcher, &*query, text_field)?;
+            let snippet =
+                snippet_generator.snippet_from_indexed_offsets(&searcher, DocAddress(0, 0))?;
+            Ok(snippet.to_html())
+        };
+        assert_eq!(
+            snippet_html("\"part time\"")?,
+            "Alan just got a <b>part time</b> job. This is my favorite part of the job, time flies."
+        );
+        assert_eq!(
+            snippet_html("\"part time\" flies")?,
+            "Alan just got a <b>part time</b> job. This is my favorite part of the job, time <b>flies</b>."
+        );
+        assert_eq!(
+            snippet_html("part time")?,
+            "Alan just got a <b>part</b> <b>time</b> job. This is my favorite <b>part</b> of the job, <b>time</b> flies."
+        );
+        // The values of the field are not part of the same phrase.
+        assert_eq!(snippet_html("\"job this\"")?, "");
+        assert_eq!(snippet_html("\"time part\"")?, "");
+
+        // The highlights of the second value are shifted by the separator.
+        let query = query_parser.parse_query("job this")?;
+        let mut snippet_generator = SnippetGenerator::create(&searcher, &*query, text_field)?;
+        snippet_generator.set_max_num_chars(20);
+        let snippet =
+            snippet_generator.snippet_from_indexed_offsets(&searcher, DocAddress(0, 0))?;
+        assert_eq!(snippet.fragments(), "time job. This is my");
+        let highlighted: Vec<(usize, usize)> = snippet
+            .highlighted()
+            .iter()
+            .map(|highlight_section| highlight_section.bounds())
+            .collect();
+        assert_eq!(highlighted, vec![(5, 8), (10, 14)]);
+
+        let query = query_parser.parse_query("part")?;
+        let snippet_generator = SnippetGenerator::create(&searcher, &*query, title_field)?;
+        assert!(matches!(
+            snippet_generator.snippet_from_indexed_offsets(&searcher, DocAddress(0, 0)),
+            Err(TantivyError::SchemaError(_))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_snippet_generator_term_score() {
         let mut schema_builder = Schema::builder();
@@ -74,6 +74,7 @@ pub struct SegmentSpaceUsage {
     fieldnorms: PerFieldSpaceUsage,
 
     store: StoreSpaceUsage,
+    term_vectors: ByteCount,
 
     deletes: ByteCount,
 
@@ -91,6 +92,7 @@ impl SegmentSpaceUsage {
         fast_fields: PerFieldSpaceUsage,
         fieldnorms: PerFieldSpaceUsage,
         store: StoreSpaceUsage,
+        term_vectors: ByteCount,
         deletes: ByteCount,
     ) -> SegmentSpaceUsage {
         let total = termdict.total()
@@ -99,6 +101,7 @@ impl SegmentSpaceUsage {
             + fast_fields.total()
             + fieldnorms.total()
             + store.total()
+            + term_vectors
             + deletes;
         SegmentSpaceUsage {
             num_docs,
@@ -109,6 +112,7 @@ impl SegmentSpaceUsage {
             fast_fields,
             fieldnorms,
             store,
+            term_vectors,
             deletes,
             total,
         }
@@ -129,6 +133,7 @@ impl SegmentSpaceUsage {
             FIELDNORMS => PerField(self.fieldnorms().clone()),
             TERMS => PerField(self.termdict().clone()),
             STORE => Store(self.store().clone()),
+            TERMVECTORS => Basic(self.term_vectors()),
             DELETE => Basic(self.deletes()),
         }
     }
@@ -173,6 +178,11 @@ impl SegmentSpaceUsage {
         &self.store
     }
 
+    /// Space usage for term vectors
+    pub fn term_vectors(&self) -> ByteCount {
+        self.term_vectors
+    }
+
     /// Space usage for document deletions
     pub fn deletes(&self) -> ByteCount {
         self.deletes
@@ -0,0 +1,269 @@
+//! Term vectors list, for each document, the terms of a field
+//! along with their positions.
+//!
+//! They make it possible to access the terms of a document without walking
+//! through the posting lists of the entire term dictionary. This is useful
+//! to build more-like-this queries, to extract keywords, or to compute
+//! per-document statistics.
+//!
+//! Term vectors are only recorded for the text fields for which they were enabled
+//! (See `TextFieldIndexing::set_term_vectors`).
+//!
+//! Like the doc store, they are written in the `SegmentComponent::TERMVECTORS` file
+//! as documents are being added. The file contains the term vectors of each document,
+//! followed by the offsets of the documents' data, and by the number of documents.
+//! Segments without any field recording term vectors do not have such a file.
+//!
+//! The data of a document is a sequence of fields, each of them encoded as
+//! - the field id, as a `VInt`
+//! - the number of terms, as a `VInt`
+//! - for each term, sorted in the lexicographic order of their bytes,
+//!   the length of its bytes and its bytes, its term frequency and the deltas of its positions,
+//!   all of the numbers being encoded as `VInt`s.
+mod reader;
+mod serializer;
+mod writer;
+
+pub(crate) use self::reader::TermVectorsReader;
+pub(crate) use self::serializer::TermVectorsSerializer;
+pub(crate) use self::writer::TermVectorsWriter;
+
+use crate::schema::Term;
+
+/// A term of a term vector, with its positions in the document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TermVectorEntry {
+    term: Term,
+    positions: Vec<u32>,
+}
+
+impl TermVectorEntry {
+    /// Returns the term.
+    pub fn term(&self) -> &Term {
+        &self.term
+    }
+
+    /// Returns the number of occurrences of the term in the document.
+    pub fn term_freq(&self) -> u32 {
+        self.positions.len() as u32
+    }
+
+    /// Returns the positions of the occurrences of the term in the document,
+    /// in increasing order.
+    pub fn positions(&self) -> &[u32] {
+        &self.positions[..]
+    }
+}
+
+/// The terms of a field of a document, with their positions.
+///
+/// The entries are sorted by term.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TermVector {
+    entries: Vec<TermVectorEntry>,
+}
+
+impl TermVector {
+    /// Returns the entries of the term vector, sorted by term.
+    pub fn entries(&self) -> &[TermVectorEntry] {
+        &self.entries[..]
+    }
+
+    /// Returns the number of distinct terms in the term vector.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true iff the term vector contains no term.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entry associated to the given term, if the
+    /// term appears in the document.
+    pub fn get(&self, term: &Term) -> Option<&TermVectorEntry> {
+        self.entries
+            .binary_search_by(|entry| entry.term.value_bytes().cmp(term.value_bytes()))
+            .ok()
+            .map(|ord| &self.entries[ord])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TermVectorsReader, TermVectorsSerializer};
+    use crate::common::VInt;
+    use crate::directory::error::OpenReadError;
+    use crate::directory::{Directory, FileSlice, RAMDirectory};
+    use crate::schema::{IndexRecordOption, Schema, Term, TextFieldIndexing, TextOptions, TEXT};
+    use crate::{DocSet, Index, Postings, SegmentComponent, TantivyError};
+    use std::path::Path;
+
+    fn term_vector_options() -> TextOptions {
+        TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("default")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+                .set_term_vectors(true),
+        )
+    }
+
+    #[test]
+    fn test_term_vector() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", term_vector_options());
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "hello happy tax payer", title => "hello"));
+        index_writer.add_document(doc!(title => "no text"));
+        index_writer.add_document(doc!(text => "happy happy", text => "tax payer happy"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+
+        let term_vector = segment_reader.term_vector(0, text)?;
+        let terms: Vec<(String, Vec<u32>)> = term_vector
+            .entries()
+            .iter()
+            .map(|entry| (entry.term().text().to_string(), entry.positions().to_vec()))
+            .collect();
+        assert_eq!(
+            terms,
+            vec![
+                ("happy".to_string(), vec![1]),
+                ("hello".to_string(), vec![0]),
+                ("payer".to_string(), vec![3]),
+                ("tax".to_string(), vec![2]),
+            ]
+        );
+        assert!(segment_reader.term_vector(1, text)?.is_empty());
+
+        let term_vector = segment_reader.term_vector(2, text)?;
+        assert_eq!(term_vector.len(), 3);
+        let happy = term_vector
+            .get(&Term::from_field_text(text, "happy"))
+            .unwrap();
+        assert_eq!(happy.term_freq(), 3);
+        assert_eq!(happy.positions(), &[0, 1, 5]);
+        assert!(term_vector
+            .get(&Term::from_field_text(text, "hello"))
+            .is_none());
+
+        // The term vectors are consistent with the postings.
+        let inverted_index = segment_reader.inverted_index(text)?;
+        let mut postings = inverted_index
+            .read_postings(
+                &Term::from_field_text(text, "happy"),
+                IndexRecordOption::WithFreqsAndPositions,
+            )?
+            .unwrap();
+        assert_eq!(postings.seek(2), 2);
+        let mut positions = Vec::new();
+        postings.positions(&mut positions);
+        assert_eq!(&positions[..], happy.positions());
+
+        assert!(matches!(
+            segment_reader.term_vector(0, title),
+            Err(TantivyError::SchemaError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_term_vector_merge() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", term_vector_options());
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "a b"));
+        index_writer.add_document(doc!(text => "c"));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "d d"));
+        index_writer.add_document(doc!(text => "e"));
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(text, "c"));
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        futures::executor::block_on(index_writer.merge(&segment_ids))?;
+        index_writer.wait_merging_threads()?;
+
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        let segment_reader = searcher.segment_reader(0u32);
+        assert_eq!(segment_reader.max_doc(), 3);
+        let term_texts = |doc| -> crate::Result<Vec<String>> {
+            Ok(segment_reader
+                .term_vector(doc, text)?
+                .entries()
+                .iter()
+                .map(|entry| entry.term().text().to_string())
+                .collect())
+        };
+        assert_eq!(term_texts(0)?, vec!["a", "b"]);
+        assert_eq!(term_texts(1)?, vec!["d"]);
+        assert_eq!(term_texts(2)?, vec!["e"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_term_vectors_file() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "hello"));
+        index_writer.commit()?;
+        let segment = index.searchable_segments()?[0].clone();
+        assert!(matches!(
+            segment.open_read(SegmentComponent::TERMVECTORS),
+            Err(OpenReadError::FileDoesNotExist(_))
+        ));
+        let searcher = index.reader()?.searcher();
+        assert!(searcher.segment_reader(0).term_vector(0, text).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_term_vectors_corrupted() -> crate::Result<()> {
+        assert!(matches!(
+            TermVectorsReader::open(FileSlice::from(vec![1u8, 2u8])),
+            Err(TantivyError::DataCorruption(_))
+        ));
+        // The footer announces more documents than the file contains.
+        assert!(matches!(
+            TermVectorsReader::open(FileSlice::from(vec![
+                0u8, 0u8, 0u8, 0u8, 3u8, 0u8, 0u8, 0u8
+            ])),
+            Err(TantivyError::DataCorruption(_))
+        ));
+        // A term announcing more bytes than available.
+        let mut doc_data = Vec::new();
+        VInt(0).serialize_into_vec(&mut doc_data);
+        VInt(1).serialize_into_vec(&mut doc_data);
+        VInt(100).serialize_into_vec(&mut doc_data);
+        doc_data.extend_from_slice(b"abc");
+        let directory = RAMDirectory::create();
+        let path = Path::new("termvectors");
+        let mut serializer = TermVectorsSerializer::from_write(directory.open_write(path)?);
+        serializer.store_doc(&doc_data)?;
+        serializer.close()?;
+        let reader = TermVectorsReader::open(directory.open_read(path)?)?;
+        let field = Schema::builder().add_text_field("text", TEXT);
+        assert!(matches!(
+            reader.term_vector(0, field),
+            Err(TantivyError::DataCorruption(_))
+        ));
+        // A truncated document.
+        let directory = RAMDirectory::create();
+        let mut serializer = TermVectorsSerializer::from_write(directory.open_write(path)?);
+        serializer.store_doc(&doc_data[..1])?;
+        serializer.close()?;
+        let reader = TermVectorsReader::open(directory.open_read(path)?)?;
+        assert!(matches!(
+            reader.term_vector(0, field),
+            Err(TantivyError::DataCorruption(_))
+        ));
+        Ok(())
+    }
+}
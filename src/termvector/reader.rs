@@ -0,0 +1,124 @@
+use super::{TermVector, TermVectorEntry};
+use crate::common::{BinarySerializable, HasLen, VInt};
+use crate::directory::{FileSlice, OwnedBytes};
+use crate::error::DataCorruption;
+use crate::schema::{Field, Term};
+use crate::space_usage::ByteCount;
+use crate::DocId;
+use std::mem::size_of;
+
+fn corrupted(comment: &str) -> crate::TantivyError {
+    DataCorruption::comment_only(format!("Term vectors are corrupted: {}", comment)).into()
+}
+
+fn read_vint(cursor: &mut &[u8]) -> crate::Result<u64> {
+    VInt::deserialize(cursor)
+        .map(|vint| vint.val())
+        .map_err(|_| corrupted("truncated variable length integer"))
+}
+
+/// Reads the term vectors of the documents of a segment.
+#[derive(Clone)]
+pub struct TermVectorsReader {
+    data: FileSlice,
+    doc_offsets: OwnedBytes,
+    num_docs: DocId,
+}
+
+impl TermVectorsReader {
+    /// Opens the term vectors of a segment.
+    pub fn open(file: FileSlice) -> crate::Result<TermVectorsReader> {
+        let footer_offset = file
+            .len()
+            .checked_sub(size_of::<DocId>())
+            .ok_or_else(|| corrupted("the file is too short"))?;
+        let num_docs = DocId::deserialize(&mut file.slice_from(footer_offset).read_bytes()?)?;
+        let doc_offsets_len = (num_docs as usize + 1) * size_of::<u64>();
+        let doc_offsets_start = footer_offset
+            .checked_sub(doc_offsets_len)
+            .ok_or_else(|| corrupted("the document offsets are truncated"))?;
+        Ok(TermVectorsReader {
+            data: file.slice(0, doc_offsets_start),
+            doc_offsets: file.slice(doc_offsets_start, footer_offset).read_bytes()?,
+            num_docs,
+        })
+    }
+
+    /// Returns a reader without any term vector.
+    ///
+    /// It is used for the segments that were written without a term vectors file.
+    pub fn empty() -> TermVectorsReader {
+        TermVectorsReader {
+            data: FileSlice::empty(),
+            doc_offsets: OwnedBytes::empty(),
+            num_docs: 0,
+        }
+    }
+
+    fn doc_offset(&self, doc: DocId) -> crate::Result<usize> {
+        let start = doc as usize * size_of::<u64>();
+        let mut offset_bytes = &self.doc_offsets.as_slice()[start..start + size_of::<u64>()];
+        Ok(u64::deserialize(&mut offset_bytes)? as usize)
+    }
+
+    /// Returns the encoded term vectors of all of the fields of a document.
+    pub(crate) fn doc_data(&self, doc: DocId) -> crate::Result<OwnedBytes> {
+        if doc >= self.num_docs {
+            return Ok(OwnedBytes::empty());
+        }
+        let start = self.doc_offset(doc)?;
+        let stop = self.doc_offset(doc + 1)?;
+        if start > stop || stop > self.data.len() {
+            return Err(corrupted("invalid document offsets"));
+        }
+        Ok(self.data.read_bytes_slice(start, stop)?)
+    }
+
+    /// Returns the term vector of a field of a document.
+    ///
+    /// The term vector is empty if the document does not contain the field.
+    ///
+    /// Returns a `DataCorruption` error if the term vectors cannot be decoded.
+    pub fn term_vector(&self, doc: DocId, field: Field) -> crate::Result<TermVector> {
+        let doc_data = self.doc_data(doc)?;
+        let mut cursor = doc_data.as_slice();
+        while !cursor.is_empty() {
+            let field_id = read_vint(&mut cursor)? as u32;
+            let num_terms = read_vint(&mut cursor)? as usize;
+            let is_requested_field = field_id == field.field_id();
+            let mut entries = Vec::new();
+            for _ in 0..num_terms {
+                let term_len = read_vint(&mut cursor)? as usize;
+                if term_len > cursor.len() {
+                    return Err(corrupted("the term is truncated"));
+                }
+                let (term_bytes, remaining) = cursor.split_at(term_len);
+                cursor = remaining;
+                let term_freq = read_vint(&mut cursor)? as usize;
+                let mut positions = Vec::new();
+                let mut position = 0u32;
+                for _ in 0..term_freq {
+                    let position_delta = read_vint(&mut cursor)? as u32;
+                    position = position
+                        .checked_add(position_delta)
+                        .ok_or_else(|| corrupted("invalid position"))?;
+                    positions.push(position);
+                }
+                if is_requested_field {
+                    let mut term = Term::for_field(field);
+                    term.set_bytes(term_bytes);
+                    entries.push(TermVectorEntry { term, positions });
+                }
+            }
+            if is_requested_field {
+                return Ok(TermVector { entries });
+            }
+        }
+        Ok(TermVector::default())
+    }
+
+    /// Returns the space usage of the term vectors.
+    pub fn space_usage(&self) -> ByteCount {
+        self.data.len() + self.doc_offsets.len()
+    }
+}
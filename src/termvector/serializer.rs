@@ -0,0 +1,42 @@
+use crate::common::{BinarySerializable, CountingWriter};
+use crate::directory::{TerminatingWrite, WritePtr};
+use crate::DocId;
+use std::io::{self, Write};
+
+/// The term vectors serializer writes the term vectors of
+/// the documents as they are added, and their offsets
+/// once the segment is closed.
+pub struct TermVectorsSerializer {
+    writer: CountingWriter<WritePtr>,
+    doc_offsets: Vec<u64>,
+}
+
+impl TermVectorsSerializer {
+    /// Constructor
+    pub fn from_write(write: WritePtr) -> TermVectorsSerializer {
+        TermVectorsSerializer {
+            writer: CountingWriter::wrap(write),
+            doc_offsets: Vec::new(),
+        }
+    }
+
+    /// Writes the encoded term vectors of a new document.
+    ///
+    /// The document id is implicitely the number of times
+    /// this method has been called.
+    pub fn store_doc(&mut self, doc_data: &[u8]) -> io::Result<()> {
+        self.doc_offsets.push(self.writer.written_bytes());
+        self.writer.write_all(doc_data)
+    }
+
+    /// Writes the offsets of the documents, and closes the file.
+    pub fn close(mut self) -> io::Result<()> {
+        let num_docs = self.doc_offsets.len() as DocId;
+        self.doc_offsets.push(self.writer.written_bytes());
+        for doc_offset in &self.doc_offsets {
+            doc_offset.serialize(&mut self.writer)?;
+        }
+        num_docs.serialize(&mut self.writer)?;
+        self.writer.terminate()
+    }
+}
@@ -0,0 +1,74 @@
+use super::TermVectorsSerializer;
+use crate::common::VInt;
+use crate::schema::{Field, Schema};
+use crate::tokenizer::{Token, MAX_TOKEN_LEN};
+use std::collections::BTreeMap;
+use std::io;
+
+/// The `TermVectorsWriter` is in charge of encoding the term vectors
+/// of the document being indexed.
+///
+/// Once all of the fields of the document have been recorded,
+/// its term vectors are handed over to the `TermVectorsSerializer`.
+pub struct TermVectorsWriter {
+    fields_with_term_vectors: Vec<bool>,
+    doc_buffer: Vec<u8>,
+}
+
+impl TermVectorsWriter {
+    /// Initialize with state for tracking the term vectors of the fields of a schema.
+    pub fn for_schema(schema: &Schema) -> TermVectorsWriter {
+        let fields_with_term_vectors = schema
+            .fields()
+            .map(|(_, field_entry)| field_entry.has_term_vectors())
+            .collect();
+        TermVectorsWriter {
+            fields_with_term_vectors,
+            doc_buffer: Vec::new(),
+        }
+    }
+
+    /// Returns true iff the term vectors of the field should be recorded.
+    pub fn has_term_vectors(&self, field: Field) -> bool {
+        self.fields_with_term_vectors
+            .get(field.field_id() as usize)
+            .cloned()
+            .unwrap_or(false)
+    }
+
+    /// Records the term vector of a field of the current document,
+    /// given all of its tokens.
+    ///
+    /// Like in the postings, tokens that are too long are ignored.
+    pub fn record(&mut self, field: Field, tokens: &[Token]) {
+        let mut term_positions: BTreeMap<&[u8], Vec<u32>> = BTreeMap::new();
+        for token in tokens {
+            if token.text.len() <= MAX_TOKEN_LEN {
+                term_positions
+                    .entry(token.text.as_bytes())
+                    .or_default()
+                    .push(token.position as u32);
+            }
+        }
+        VInt(u64::from(field.field_id())).serialize_into_vec(&mut self.doc_buffer);
+        VInt(term_positions.len() as u64).serialize_into_vec(&mut self.doc_buffer);
+        for (term_bytes, positions) in term_positions {
+            VInt(term_bytes.len() as u64).serialize_into_vec(&mut self.doc_buffer);
+            self.doc_buffer.extend_from_slice(term_bytes);
+            VInt(positions.len() as u64).serialize_into_vec(&mut self.doc_buffer);
+            let mut prev_position = 0u32;
+            for position in positions {
+                VInt(u64::from(position - prev_position)).serialize_into_vec(&mut self.doc_buffer);
+                prev_position = position;
+            }
+        }
+    }
+
+    /// Serializes the term vectors of the current document,
+    /// and prepares the writer for the next document.
+    pub fn serialize_doc(&mut self, serializer: &mut TermVectorsSerializer) -> io::Result<()> {
+        serializer.store_doc(&self.doc_buffer[..])?;
+        self.doc_buffer.clear();
+        Ok(())
+    }
+}
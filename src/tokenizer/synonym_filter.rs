@@ -0,0 +1,211 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let mut synonyms = SynonymMap::new();
+//! synonyms.add_synonyms(&["car", "automobile"]);
+//!
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .filter(LowerCaser)
+//!   .filter(SynonymFilter::new(synonyms));
+//!
+//! let mut stream = tokenizer.token_stream("Red car");
+//! assert_eq!(stream.next().unwrap().text, "red");
+//! {
+//!     let token = stream.next().unwrap();
+//!     assert_eq!(token.text, "car");
+//!     assert_eq!(token.position, 1);
+//! }
+//! {
+//!     let token = stream.next().unwrap();
+//!     assert_eq!(token.text, "automobile");
+//!     assert_eq!(token.position, 1);
+//! }
+//! assert!(stream.next().is_none());
+//! ```
+use super::{Token, TokenFilter, TokenStream};
+use crate::tokenizer::BoxTokenStream;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn normalize(words: &str) -> String {
+    words.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Map associating words, or sequences of words, to their synonyms.
+///
+/// Entries are matched against the tokens emitted by the tokenizer,
+/// so they should be expressed in their tokenized form. (e.g. lowercased)
+/// Words are separated by whitespaces.
+///
+/// The same map can be used at query time (see `QueryParser::set_field_synonyms`)
+/// and at indexing time with a `SynonymFilter`.
+#[derive(Clone, Debug, Default)]
+pub struct SynonymMap {
+    synonyms: HashMap<String, Vec<String>>,
+}
+
+impl SynonymMap {
+    /// Creates an empty `SynonymMap`.
+    pub fn new() -> SynonymMap {
+        SynonymMap::default()
+    }
+
+    /// Registers `synonym` as a synonym of `words`.
+    ///
+    /// The relation is one-way: `words` is not registered as a synonym of `synonym`.
+    pub fn add_synonym(&mut self, words: &str, synonym: &str) {
+        let words = normalize(words);
+        let synonym = normalize(synonym);
+        if words.is_empty() || synonym.is_empty() || words == synonym {
+            return;
+        }
+        let synonyms = self.synonyms.entry(words).or_default();
+        if !synonyms.contains(&synonym) {
+            synonyms.push(synonym);
+        }
+    }
+
+    /// Registers a group of equivalent words:
+    /// each of them becomes a synonym of all of the others.
+    pub fn add_synonyms(&mut self, equivalent_words: &[&str]) {
+        for &words in equivalent_words {
+            for &synonym in equivalent_words {
+                self.add_synonym(words, synonym);
+            }
+        }
+    }
+
+    /// Returns the synonyms of `words`.
+    pub fn synonyms(&self, words: &str) -> &[String] {
+        self.synonyms
+            .get(&normalize(words))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Returns true iff the map does not contain any entry.
+    pub fn is_empty(&self) -> bool {
+        self.synonyms.is_empty()
+    }
+}
+
+/// `TokenFilter` that emits the synonyms of each token at the same position.
+///
+/// Only the single word entries of the `SynonymMap` are applied:
+/// multi-word entries are ignored at indexing time.
+#[derive(Clone)]
+pub struct SynonymFilter {
+    synonyms: Arc<SynonymMap>,
+}
+
+impl SynonymFilter {
+    /// Creates a `SynonymFilter` given a map of synonyms.
+    pub fn new(synonyms: SynonymMap) -> SynonymFilter {
+        SynonymFilter {
+            synonyms: Arc::new(synonyms),
+        }
+    }
+}
+
+impl TokenFilter for SynonymFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(SynonymFilterStream {
+            synonyms: self.synonyms.clone(),
+            tail: token_stream,
+            pending: Vec::new(),
+            synonym_token: Token::default(),
+            is_synonym: false,
+        })
+    }
+}
+
+pub struct SynonymFilterStream<'a> {
+    synonyms: Arc<SynonymMap>,
+    tail: BoxTokenStream<'a>,
+    // Synonyms of the current token that remain to be emitted, in reverse order.
+    pending: Vec<String>,
+    synonym_token: Token,
+    is_synonym: bool,
+}
+
+impl<'a> TokenStream for SynonymFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        if let Some(synonym) = self.pending.pop() {
+            self.synonym_token.text = synonym;
+            self.is_synonym = true;
+            return true;
+        }
+        self.is_synonym = false;
+        if !self.tail.advance() {
+            return false;
+        }
+        let token = self.tail.token();
+        if let Some(synonyms) = self.synonyms.synonyms.get(&token.text) {
+            self.pending.extend(
+                synonyms
+                    .iter()
+                    .rev()
+                    .filter(|synonym| !synonym.contains(' '))
+                    .cloned(),
+            );
+            if !self.pending.is_empty() {
+                self.synonym_token.clone_from(token);
+            }
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        if self.is_synonym {
+            &self.synonym_token
+        } else {
+            self.tail.token()
+        }
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        if self.is_synonym {
+            &mut self.synonym_token
+        } else {
+            self.tail.token_mut()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SynonymFilter, SynonymMap};
+    use crate::tokenizer::tests::assert_token;
+    use crate::tokenizer::{SimpleTokenizer, TextAnalyzer, Token};
+
+    #[test]
+    fn test_synonym_map() {
+        let mut synonyms = SynonymMap::new();
+        synonyms.add_synonyms(&["nyc", "new  york", "big apple"]);
+        synonyms.add_synonym("car", "automobile");
+        synonyms.add_synonym("car", "automobile");
+        assert_eq!(synonyms.synonyms("nyc"), &["new york", "big apple"]);
+        assert_eq!(synonyms.synonyms("new york"), &["nyc", "big apple"]);
+        assert_eq!(synonyms.synonyms("car"), &["automobile"]);
+        assert!(synonyms.synonyms("automobile").is_empty());
+        assert!(synonyms.synonyms("york").is_empty());
+    }
+
+    #[test]
+    fn test_synonym_filter() {
+        let mut synonyms = SynonymMap::new();
+        synonyms.add_synonyms(&["nyc", "new york", "gotham"]);
+        synonyms.add_synonym("car", "auto");
+        let tokenizer = TextAnalyzer::from(SimpleTokenizer).filter(SynonymFilter::new(synonyms));
+        let mut tokens: Vec<Token> = vec![];
+        let mut token_stream = tokenizer.token_stream("car in nyc");
+        token_stream.process(&mut |token: &Token| tokens.push(token.clone()));
+        assert_eq!(tokens.len(), 5);
+        assert_token(&tokens[0], 0, "car", 0, 3);
+        assert_token(&tokens[1], 0, "auto", 0, 3);
+        assert_token(&tokens[2], 1, "in", 4, 6);
+        assert_token(&tokens[3], 2, "nyc", 7, 10);
+        assert_token(&tokens[4], 2, "gotham", 7, 10);
+    }
+}